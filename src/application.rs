@@ -25,12 +25,13 @@ mod imp {
     use adw::prelude::*;
     use adw::subclass::prelude::*;
     use gtk::glib::property::PropertySet;
-    use gtk::glib;
     use gtk::subclass::prelude::{
         ApplicationImpl, ApplicationImplExt, GtkApplicationImpl, ObjectImpl, ObjectSubclassExt,
     };
+    use gtk::{gio, glib};
     use gtk::{glib::object_subclass, subclass::prelude::ObjectSubclass};
 
+    use crate::window::new_launcher::NewFileDialog;
     use crate::window::DMWindow;
 
     #[derive(Debug, Default)]
@@ -49,6 +50,17 @@ mod imp {
     impl ObjectImpl for DMApplication {}
 
     impl ApplicationImpl for DMApplication {
+        fn startup(&self) {
+            self.parent_startup();
+
+            let obj = self.obj();
+            let new_file_action = gio::ActionEntry::builder("new-file")
+                .activate(|app: &super::DMApplication, _, _| app.imp().show_new_file_dialog())
+                .build();
+            obj.add_action_entries([new_file_action]);
+            obj.set_accels_for_action("app.new-file", &["<Control>n"]);
+        }
+
         fn activate(&self) {
             self.parent_activate();
             let additional_search_paths = self.additional_search_paths.replace(Vec::new());
@@ -75,6 +87,23 @@ mod imp {
         }
     }
     impl GtkApplicationImpl for DMApplication {}
+
+    impl DMApplication {
+        /// Ask for the basics of a new desktop file, which is then opened in the active window
+        fn show_new_file_dialog(&self) {
+            let Some(window) = self.obj().active_window().and_downcast::<DMWindow>() else {
+                return;
+            };
+
+            let dialog = NewFileDialog::new();
+            dialog.connect_launcher_created(glib::clone!(
+                #[weak]
+                window,
+                move |path| window.open_created_file(path)
+            ));
+            dialog.present(Some(&window));
+        }
+    }
     impl AdwApplicationImpl for DMApplication {}
 }
 
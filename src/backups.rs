@@ -0,0 +1,281 @@
+/*
+* Copyright © 2025 Alessandro Balducci
+*
+* This file is part of Desktop File Editor.
+* Desktop File Editor is free software: you can redistribute it and/or modify it under the terms of the
+* GNU General Public License as published by the Free Software Foundation,
+* either version 3 of the License, or (at your option) any later version.
+* Desktop File Editor is distributed in the hope that it will be useful, but WITHOUT ANY WARRANTY;
+* without even the implied warranty of MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.
+* See the GNU General Public License for more details.
+* You should have received a copy of the GNU General Public License along with Desktop File Editor. If not, see <https://www.gnu.org/licenses/>.
+*/
+
+//! Copies of the previous versions of the edited files, taken on each save and kept under the
+//! user config directory, so that a file can be brought back to how it was
+
+use std::{
+    fs,
+    io::{self, Write},
+    path::{Path, PathBuf},
+};
+
+use adw::prelude::*;
+use gtk::glib::{self, clone};
+
+use crate::{change_journal::line_diff, settings::settings};
+
+/// Copies kept for each file, older ones are removed
+const MAX_BACKUPS: usize = 20;
+
+/// Format of the names of the copies, they sort in the order they were taken. Copies taken within
+/// the same second get a number after the time
+const TIME_FORMAT: &str = "%Y%m%d-%H%M%S";
+
+/// A copy of a file as it was before one of its saves
+#[derive(Debug, Clone, PartialEq)]
+pub struct Backup {
+    pub path: PathBuf,
+    /// When the copy was taken, as year, month, day, hour, minute and second
+    pub time: (i32, i32, i32, i32, i32, i32),
+    /// Tells apart the copies taken within the same second, in the order they were taken
+    sequence: u32,
+}
+
+impl Backup {
+    /// The time of the copy in the format of the user locale
+    pub fn display_time(&self) -> String {
+        let (year, month, day, hour, minute, second) = self.time;
+        glib::DateTime::from_local(year, month, day, hour, minute, second.into())
+            .and_then(|time| time.format("%x %X"))
+            .map(|time| time.to_string())
+            .unwrap_or_default()
+    }
+}
+
+/// Directory with the copies of the file at `path`, named after its escaped path
+fn backup_dir(path: &Path) -> PathBuf {
+    let escaped = glib::Uri::escape_string(&path.to_string_lossy(), None, false);
+    glib::user_config_dir()
+        .join("desktop-file-editor")
+        .join("backups")
+        .join(escaped.as_str())
+}
+
+/// Keep a copy of `contents`, what the file at `path` contained before being saved, if backups
+/// are enabled in the settings
+pub fn back_up(path: &Path, contents: &str) {
+    if !settings().keep_backups() {
+        return;
+    }
+
+    let Ok(name) = glib::DateTime::now_local().and_then(|now| now.format(TIME_FORMAT)) else {
+        return;
+    };
+    let dir = backup_dir(path);
+    let result = fs::create_dir_all(&dir).and_then(|()| write_new_backup(&dir, &name, contents));
+    if let Err(e) = result {
+        eprintln!("Failed to back up {}: {e}", path.display());
+        return;
+    }
+
+    for backup in backups(path).into_iter().skip(MAX_BACKUPS) {
+        if let Err(e) = fs::remove_file(&backup.path) {
+            eprintln!("Failed to remove old backup {}: {e}", backup.path.display());
+        }
+    }
+}
+
+/// Writes `contents` to a file of `dir` named after `time`, numbering it when a copy taken in the
+/// same second already exists so that no copy is overwritten
+fn write_new_backup(dir: &Path, time: &str, contents: &str) -> io::Result<()> {
+    for sequence in 0.. {
+        let name = match sequence {
+            0 => format!("{time}.desktop"),
+            _ => format!("{time}-{sequence}.desktop"),
+        };
+        let file = fs::OpenOptions::new()
+            .write(true)
+            .create_new(true)
+            .open(dir.join(name));
+        match file {
+            Ok(mut file) => return file.write_all(contents.as_bytes()),
+            Err(e) if e.kind() == io::ErrorKind::AlreadyExists => continue,
+            Err(e) => return Err(e),
+        }
+    }
+    unreachable!("A free name is found before running out of numbers")
+}
+
+/// The copies of the file at `path`, the most recent first
+pub fn backups(path: &Path) -> Vec<Backup> {
+    let Ok(dir_entries) = fs::read_dir(backup_dir(path)) else {
+        return Vec::new();
+    };
+    let mut backups: Vec<Backup> = dir_entries
+        .filter_map(Result::ok)
+        .filter_map(|dir_entry| {
+            let path = dir_entry.path();
+            let (time, sequence) = parse_backup_name(path.file_name()?.to_str()?)?;
+            Some(Backup {
+                path,
+                time,
+                sequence,
+            })
+        })
+        .collect();
+    backups.sort_by(|a, b| (b.time, b.sequence).cmp(&(a.time, a.sequence)));
+    backups
+}
+
+/// Reads the time and the sequence number back from the name of a copy, as written with
+/// [`TIME_FORMAT`]
+fn parse_backup_name(name: &str) -> Option<((i32, i32, i32, i32, i32, i32), u32)> {
+    let stem = name.strip_suffix(".desktop")?;
+    let (date, rest) = stem.split_once('-')?;
+    let (time, sequence) = match rest.split_once('-') {
+        Some((time, sequence)) => (time, sequence.parse().ok()?),
+        None => (rest, 0),
+    };
+    if date.len() != 8 || time.len() != 6 {
+        return None;
+    }
+    let number = |text: &str, range: std::ops::Range<usize>| text.get(range)?.parse().ok();
+    let time = (
+        number(date, 0..4)?,
+        number(date, 4..6)?,
+        number(date, 6..8)?,
+        number(time, 0..2)?,
+        number(time, 2..4)?,
+        number(time, 4..6)?,
+    );
+    Some((time, sequence))
+}
+
+/// Lists the copies of the file at `path`, each with the changes restoring it would make to
+/// `current`. The contents of the chosen copy are passed to `restore`
+pub fn show_backups_dialog(
+    parent: &impl IsA<gtk::Widget>,
+    path: &Path,
+    current: &str,
+    restore: impl Fn(String) + Clone + 'static,
+) {
+    let backups = backups(path);
+
+    let dialog = adw::Dialog::builder()
+        .title("Previous Versions")
+        .content_width(560)
+        .content_height(560)
+        .build();
+
+    let content: gtk::Widget = if backups.is_empty() {
+        let description = if settings().keep_backups() {
+            "A copy of the file is kept each time it is saved"
+        } else {
+            "Enable Keep Backups in the preferences to keep the previous versions of saved files"
+        };
+        adw::StatusPage::builder()
+            .icon_name("document-open-recent-symbolic")
+            .title("No Previous Versions")
+            .description(description)
+            .build()
+            .upcast()
+    } else {
+        let page = adw::PreferencesPage::new();
+        let group = adw::PreferencesGroup::builder()
+            .description("The changes shown are those restoring a version would make")
+            .build();
+        for backup in backups {
+            group.add(&backup_row(&dialog, &backup, current, restore.clone()));
+        }
+        page.add(&group);
+        page.upcast()
+    };
+
+    let toolbar_view = adw::ToolbarView::new();
+    toolbar_view.add_top_bar(&adw::HeaderBar::new());
+    toolbar_view.set_content(Some(&content));
+    dialog.set_child(Some(&toolbar_view));
+
+    dialog.present(Some(parent));
+}
+
+fn backup_row(
+    dialog: &adw::Dialog,
+    backup: &Backup,
+    current: &str,
+    restore: impl Fn(String) + 'static,
+) -> adw::ExpanderRow {
+    let contents = match fs::read_to_string(&backup.path) {
+        Ok(contents) => contents,
+        Err(e) => {
+            eprintln!("Failed to read backup {}: {e}", backup.path.display());
+            String::new()
+        }
+    };
+    let diff = line_diff(current, &contents);
+
+    let row = adw::ExpanderRow::builder()
+        .title(backup.display_time())
+        .subtitle(if diff.is_empty() {
+            "Same as the current version"
+        } else {
+            "Differs from the current version"
+        })
+        .build();
+
+    let restore_button = gtk::Button::builder()
+        .label("Restore")
+        .valign(gtk::Align::Center)
+        .sensitive(!diff.is_empty())
+        .build();
+    restore_button.connect_clicked(clone!(
+        #[weak]
+        dialog,
+        move |_| {
+            restore(contents.clone());
+            dialog.close();
+        }
+    ));
+    row.add_suffix(&restore_button);
+
+    let diff_label = gtk::Label::builder()
+        .label(diff.trim_end())
+        .xalign(0.0)
+        .wrap(true)
+        .wrap_mode(gtk::pango::WrapMode::WordChar)
+        .selectable(true)
+        .margin_top(6)
+        .margin_bottom(6)
+        .margin_start(12)
+        .margin_end(12)
+        .css_classes(["monospace"])
+        .build();
+    row.add_row(
+        &gtk::ListBoxRow::builder()
+            .activatable(false)
+            .child(&diff_label)
+            .build(),
+    );
+    row
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn backup_names() {
+        assert_eq!(
+            parse_backup_name("20250102-103005.desktop"),
+            Some(((2025, 1, 2, 10, 30, 5), 0))
+        );
+        assert_eq!(
+            parse_backup_name("20250102-103005-2.desktop"),
+            Some(((2025, 1, 2, 10, 30, 5), 2))
+        );
+        assert_eq!(parse_backup_name("20250102-103005-x.desktop"), None);
+        assert_eq!(parse_backup_name("20250102-103005.txt"), None);
+        assert_eq!(parse_backup_name("2025-01-02.desktop"), None);
+    }
+}
@@ -0,0 +1,262 @@
+/*
+* Copyright © 2025 Alessandro Balducci
+*
+* This file is part of Desktop File Editor.
+* Desktop File Editor is free software: you can redistribute it and/or modify it under the terms of the
+* GNU General Public License as published by the Free Software Foundation,
+* either version 3 of the License, or (at your option) any later version.
+* Desktop File Editor is distributed in the hope that it will be useful, but WITHOUT ANY WARRANTY;
+* without even the implied warranty of MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.
+* See the GNU General Public License for more details.
+* You should have received a copy of the GNU General Public License along with Desktop File Editor. If not, see <https://www.gnu.org/licenses/>.
+*/
+
+//! Optional record of every save, kept as plain text under the user data directory so that the
+//! changes made with the editor can be reviewed later, or put under version control
+
+use std::{
+    fs,
+    io::{self, Write},
+    path::{Path, PathBuf},
+};
+
+use adw::prelude::*;
+use gtk::glib;
+
+use crate::{settings::settings, util};
+
+/// Starts the header line of each record, followed by the time and the path of the file
+const RECORD_PREFIX: &str = "=== ";
+
+/// Records shown in the dialog, older ones stay in the file
+const SHOWN_RECORDS: usize = 200;
+
+/// A save of a file, as written in the journal
+#[derive(Debug, Clone, PartialEq)]
+pub struct JournalRecord {
+    /// Time of the save in ISO 8601 format
+    pub time: String,
+    pub path: PathBuf,
+    /// Removed lines start with `-`, added lines with `+`
+    pub diff: String,
+}
+
+fn file_path() -> PathBuf {
+    glib::user_data_dir()
+        .join("desktop-file-editor")
+        .join("journal.txt")
+}
+
+/// Add a record of `path` being saved with the contents `after`, if recording is enabled in the
+/// settings. `before` is what the file contained until then, `None` if it didn't exist
+pub fn record(path: &Path, before: Option<&str>, after: &str) {
+    if !settings().record_changes() {
+        return;
+    }
+
+    let diff = line_diff(before.unwrap_or_default(), after);
+    if diff.is_empty() {
+        return;
+    }
+
+    let time = glib::DateTime::now_local()
+        .and_then(|now| now.format_iso8601())
+        .map(|time| time.to_string())
+        .unwrap_or_default();
+    let record = format!("{RECORD_PREFIX}{time} {}\n{diff}", path.to_string_lossy());
+
+    let journal_path = file_path();
+    let result = journal_path
+        .parent()
+        .map_or(Ok(()), fs::create_dir_all)
+        .and_then(|_| {
+            fs::OpenOptions::new()
+                .create(true)
+                .append(true)
+                .open(&journal_path)
+        })
+        .and_then(|mut file| file.write_all(record.as_bytes()));
+
+    if let Err(e) = result {
+        eprintln!("Failed to record change to journal: {e}");
+    }
+}
+
+/// Every record in the journal, the most recent first
+pub fn read_journal() -> io::Result<Vec<JournalRecord>> {
+    match fs::read_to_string(file_path()) {
+        Ok(contents) => Ok(parse_journal(&contents)),
+        Err(e) if e.kind() == io::ErrorKind::NotFound => Ok(Vec::new()),
+        Err(e) => Err(e),
+    }
+}
+
+fn parse_journal(contents: &str) -> Vec<JournalRecord> {
+    let mut records: Vec<JournalRecord> = Vec::new();
+    for line in contents.lines() {
+        if let Some(header) = line.strip_prefix(RECORD_PREFIX) {
+            let (time, path) = header.split_once(' ').unwrap_or((header, ""));
+            records.push(JournalRecord {
+                time: time.to_string(),
+                path: PathBuf::from(path),
+                diff: String::new(),
+            });
+        } else if let Some(record) = records.last_mut() {
+            record.diff.push_str(line);
+            record.diff.push('\n');
+        }
+    }
+    records.reverse();
+    records
+}
+
+/// Lines removed from `before` and added in `after`, found through their longest common
+/// subsequence. Unchanged lines are left out
+pub fn line_diff(before: &str, after: &str) -> String {
+    let before: Vec<&str> = before.lines().collect();
+    let after: Vec<&str> = after.lines().collect();
+
+    // lengths[i][j] is the length of the common subsequence of before[i..] and after[j..]
+    let mut lengths = vec![vec![0usize; after.len() + 1]; before.len() + 1];
+    for i in (0..before.len()).rev() {
+        for j in (0..after.len()).rev() {
+            lengths[i][j] = if before[i] == after[j] {
+                lengths[i + 1][j + 1] + 1
+            } else {
+                lengths[i + 1][j].max(lengths[i][j + 1])
+            };
+        }
+    }
+
+    let mut diff = String::new();
+    let (mut i, mut j) = (0, 0);
+    while i < before.len() || j < after.len() {
+        let removed =
+            j == after.len() || (i < before.len() && lengths[i + 1][j] >= lengths[i][j + 1]);
+        if i < before.len() && j < after.len() && before[i] == after[j] {
+            i += 1;
+            j += 1;
+        } else if removed {
+            diff.push_str(&format!("-{}\n", before[i]));
+            i += 1;
+        } else {
+            diff.push_str(&format!("+{}\n", after[j]));
+            j += 1;
+        }
+    }
+    diff
+}
+
+/// Shows the recorded changes, only those of the file at `path` if given
+pub fn show_change_journal_dialog(parent: &impl IsA<gtk::Widget>, path: Option<&Path>) {
+    let records = match read_journal() {
+        Ok(records) => records,
+        Err(e) => {
+            eprintln!("Failed to read change journal: {e}");
+            Vec::new()
+        }
+    };
+    let records: Vec<JournalRecord> = records
+        .into_iter()
+        .filter(|record| path.is_none_or(|path| record.path == path))
+        .take(SHOWN_RECORDS)
+        .collect();
+
+    let dialog = adw::Dialog::builder()
+        .title("Recorded Changes")
+        .content_width(560)
+        .content_height(560)
+        .build();
+
+    let content: gtk::Widget = if records.is_empty() {
+        let description = if settings().record_changes() {
+            "Changes are recorded each time a file is saved"
+        } else {
+            "Enable Record Changes in the preferences to keep track of saved files"
+        };
+        adw::StatusPage::builder()
+            .icon_name("document-open-recent-symbolic")
+            .title("No Changes Recorded")
+            .description(description)
+            .build()
+            .upcast()
+    } else {
+        let page = adw::PreferencesPage::new();
+        let group = adw::PreferencesGroup::new();
+        for record in records {
+            group.add(&record_row(&record));
+        }
+        page.add(&group);
+        page.upcast()
+    };
+
+    let toolbar_view = adw::ToolbarView::new();
+    toolbar_view.add_top_bar(&adw::HeaderBar::new());
+    toolbar_view.set_content(Some(&content));
+    dialog.set_child(Some(&toolbar_view));
+
+    dialog.present(Some(parent));
+}
+
+fn record_row(record: &JournalRecord) -> adw::ExpanderRow {
+    let time = glib::DateTime::from_iso8601(&record.time, None)
+        .and_then(|time| time.to_local())
+        .and_then(|time| time.format("%x %X"))
+        .map(|time| time.to_string())
+        .unwrap_or_else(|_| record.time.clone());
+    let row = adw::ExpanderRow::builder()
+        .title(util::desktop_file_id(&record.path))
+        .subtitle(format!(
+            "{time}, {}",
+            util::display_path(&record.path).to_string_lossy()
+        ))
+        .use_markup(false)
+        .build();
+
+    let diff_label = gtk::Label::builder()
+        .label(record.diff.trim_end())
+        .xalign(0.0)
+        .wrap(true)
+        .wrap_mode(gtk::pango::WrapMode::WordChar)
+        .selectable(true)
+        .margin_top(6)
+        .margin_bottom(6)
+        .margin_start(12)
+        .margin_end(12)
+        .css_classes(["monospace"])
+        .build();
+    row.add_row(
+        &gtk::ListBoxRow::builder()
+            .activatable(false)
+            .child(&diff_label)
+            .build(),
+    );
+    row
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn diff_changed_lines() {
+        let before = "[Desktop Entry]\nName=Old\nExec=app\nIcon=app\n";
+        let after = "[Desktop Entry]\nName=New\nExec=app\nTerminal=true\n";
+        assert_eq!(
+            line_diff(before, after),
+            "-Name=Old\n+Name=New\n-Icon=app\n+Terminal=true\n"
+        );
+        assert_eq!(line_diff(after, after), "");
+    }
+
+    #[test]
+    fn parse_records() {
+        let journal = "=== 2025-01-01T10:00:00Z /a b/one.desktop\n-Name=A\n+Name=B\n\
+                       === 2025-01-02T10:00:00Z /two.desktop\n+Exec=two\n";
+        let records = parse_journal(journal);
+        assert_eq!(records.len(), 2);
+        assert_eq!(records[0].path, Path::new("/two.desktop"));
+        assert_eq!(records[1].path, Path::new("/a b/one.desktop"));
+        assert_eq!(records[1].diff, "-Name=A\n+Name=B\n");
+    }
+}
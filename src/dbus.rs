@@ -0,0 +1,243 @@
+/*
+* Copyright © 2025 Alessandro Balducci
+*
+* This file is part of Desktop File Editor.
+* Desktop File Editor is free software: you can redistribute it and/or modify it under the terms of the
+* GNU General Public License as published by the Free Software Foundation,
+* either version 3 of the License, or (at your option) any later version.
+* Desktop File Editor is distributed in the hope that it will be useful, but WITHOUT ANY WARRANTY;
+* without even the implied warranty of MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.
+* See the GNU General Public License for more details.
+* You should have received a copy of the GNU General Public License along with Desktop File Editor. If not, see <https://www.gnu.org/licenses/>.
+*/
+
+use std::collections::HashMap;
+
+use zbus::{fdo, names::BusName, proxy, zvariant::OwnedValue, Connection};
+
+const MAX_NAME_LENGTH: usize = 255;
+
+/// Check that `name` is a well-formed D-Bus interface name, returns a description of the problem
+/// if it is not.
+pub fn interface_name_error(name: &str) -> Option<String> {
+    name_error(name, false)
+}
+
+/// Check that `name` is a well-formed D-Bus well-known bus name, returns a description of the
+/// problem if it is not.
+pub fn bus_name_error(name: &str) -> Option<String> {
+    name_error(name, true)
+}
+
+fn name_error(name: &str, allow_hyphen: bool) -> Option<String> {
+    if name.is_empty() {
+        return Some("The name is empty".to_string());
+    }
+
+    if name.len() > MAX_NAME_LENGTH {
+        return Some(format!(
+            "The name is longer than {MAX_NAME_LENGTH} characters"
+        ));
+    }
+
+    let elements: Vec<&str> = name.split('.').collect();
+    if elements.len() < 2 {
+        return Some(
+            "The name must contain at least two elements separated by a dot (.)".to_string(),
+        );
+    }
+
+    for element in elements {
+        let mut chars = element.chars();
+        let first = match chars.next() {
+            Some(first) => first,
+            None => {
+                return Some(
+                    "The name contains an empty element (\"..\" or a leading/trailing dot)"
+                        .to_string(),
+                )
+            }
+        };
+
+        if first.is_ascii_digit() {
+            return Some(format!("The element \"{element}\" starts with a digit"));
+        }
+
+        let valid_char =
+            |c: char| c.is_ascii_alphanumeric() || c == '_' || (allow_hyphen && c == '-');
+        if let Some(c) = std::iter::once(first)
+            .chain(chars)
+            .find(|&c| !valid_char(c))
+        {
+            return Some(format!(
+                "The element \"{element}\" contains the invalid character '{c}'"
+            ));
+        }
+    }
+
+    None
+}
+
+/// Turn `name` into a valid bus name by replacing the invalid characters, returns `None` if it
+/// does not have the reverse-DNS structure a bus name needs
+pub fn suggested_bus_name(name: &str) -> Option<String> {
+    let elements: Vec<String> = name
+        .split('.')
+        .filter(|element| !element.is_empty())
+        .map(|element| {
+            let element: String = element
+                .chars()
+                .map(|c| {
+                    if c.is_ascii_alphanumeric() || c == '_' || c == '-' {
+                        c
+                    } else {
+                        '_'
+                    }
+                })
+                .collect();
+            if element.starts_with(|c: char| c.is_ascii_digit()) {
+                format!("_{element}")
+            } else {
+                element
+            }
+        })
+        .collect();
+
+    let suggestion = elements.join(".");
+    (elements.len() >= 2 && bus_name_error(&suggestion).is_none()).then_some(suggestion)
+}
+
+/// Object path an application is expected to be exported at according to the Desktop Entry
+/// Specification's D-Bus activation section.
+pub fn object_path_for_bus_name(bus_name: &str) -> String {
+    format!("/{}", bus_name.replace('.', "/").replace('-', "_"))
+}
+
+pub enum ExportedInterfaces {
+    /// The application does not currently own the bus name, it is not activated to avoid
+    /// launching it as a side effect of the check.
+    NotRunning,
+    Interfaces(Vec<String>),
+}
+
+/// Query the interfaces exported by a running application through introspection.
+pub async fn exported_interfaces(bus_name: &str) -> zbus::Result<ExportedInterfaces> {
+    let connection = Connection::session().await?;
+    let name = BusName::try_from(bus_name)?;
+
+    let dbus_proxy = fdo::DBusProxy::new(&connection).await?;
+    if !dbus_proxy.name_has_owner(name.clone()).await? {
+        return Ok(ExportedInterfaces::NotRunning);
+    }
+
+    let introspectable = fdo::IntrospectableProxy::builder(&connection)
+        .destination(name)?
+        .path(object_path_for_bus_name(bus_name))?
+        .build()
+        .await?;
+    let xml = introspectable.introspect().await?;
+
+    Ok(ExportedInterfaces::Interfaces(
+        xml.split("<interface name=\"")
+            .skip(1)
+            .filter_map(|rest| rest.split('"').next())
+            .map(String::from)
+            .collect(),
+    ))
+}
+
+#[proxy(
+    interface = "net.hadess.SwitcherooControl",
+    default_service = "net.hadess.SwitcherooControl",
+    default_path = "/net/hadess/SwitcherooControl"
+)]
+trait SwitcherooControl {
+    #[zbus(property, name = "GPUs")]
+    fn gpus(&self) -> zbus::Result<Vec<HashMap<String, OwnedValue>>>;
+}
+
+#[derive(Debug, Clone)]
+pub struct Gpu {
+    pub name: String,
+    pub default: bool,
+}
+
+/// Query the GPUs available on the system through switcheroo-control
+pub async fn gpus() -> zbus::Result<Vec<Gpu>> {
+    let connection = Connection::system().await?;
+    let proxy = SwitcherooControlProxy::new(&connection).await?;
+
+    Ok(proxy
+        .gpus()
+        .await?
+        .iter()
+        .map(|gpu| Gpu {
+            name: gpu
+                .get("Name")
+                .and_then(|name| <&str>::try_from(name).ok())
+                .unwrap_or("Unknown GPU")
+                .to_string(),
+            default: gpu
+                .get("Default")
+                .and_then(|default| bool::try_from(default).ok())
+                .unwrap_or(false),
+        })
+        .collect())
+}
+
+#[cfg(test)]
+mod test {
+    use super::{
+        bus_name_error, interface_name_error, object_path_for_bus_name, suggested_bus_name,
+    };
+
+    #[test]
+    fn valid_interface() {
+        assert!(interface_name_error("org.freedesktop.FileManager1").is_none());
+        assert!(interface_name_error("org.gnome.Shell.SearchProvider2").is_none());
+    }
+
+    #[test]
+    fn interface_missing_dot() {
+        assert!(interface_name_error("orgfreedesktopFileManager1").is_some());
+    }
+
+    #[test]
+    fn interface_empty_element() {
+        assert!(interface_name_error("org..FileManager1").is_some());
+        assert!(interface_name_error("org.FileManager1.").is_some());
+    }
+
+    #[test]
+    fn interface_invalid_chars() {
+        assert!(interface_name_error("org.gnome-shell.Search").is_some());
+        assert!(interface_name_error("org.1gnome.Search").is_some());
+    }
+
+    #[test]
+    fn bus_name_hyphen() {
+        assert!(bus_name_error("com.argoware.desktop-file-editor").is_none());
+        assert!(bus_name_error("firefox").is_some());
+    }
+
+    #[test]
+    fn bus_name_suggestion() {
+        assert_eq!(
+            suggested_bus_name("org.example.2048 game").as_deref(),
+            Some("org.example._2048_game")
+        );
+        assert_eq!(
+            suggested_bus_name("org..example").as_deref(),
+            Some("org.example")
+        );
+        assert_eq!(suggested_bus_name("firefox"), None);
+    }
+
+    #[test]
+    fn object_path() {
+        assert_eq!(
+            object_path_for_bus_name("com.argoware.desktop-file-editor"),
+            "/com/argoware/desktop_file_editor"
+        );
+    }
+}
@@ -0,0 +1,155 @@
+/*
+* Copyright © 2025 Alessandro Balducci
+*
+* This file is part of Desktop File Editor.
+* Desktop File Editor is free software: you can redistribute it and/or modify it under the terms of the 
+* GNU General Public License as published by the Free Software Foundation, 
+* either version 3 of the License, or (at your option) any later version.
+* Desktop File Editor is distributed in the hope that it will be useful, but WITHOUT ANY WARRANTY;
+* without even the implied warranty of MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.
+* See the GNU General Public License for more details.
+* You should have received a copy of the GNU General Public License along with Desktop File Editor. If not, see <https://www.gnu.org/licenses/>.
+*/
+
+use adw::subclass::prelude::*;
+use gtk::glib;
+
+mod imp {
+
+    use std::cell::RefCell;
+
+    use adw::{prelude::*, subclass::prelude::*};
+    use gtk::glib::{closure, Object};
+    use gtk::{
+        glib::{self, clone, Properties},
+        Entry,
+    };
+
+    use crate::desktop_file_view::desktop_entry_ext::group_name_error;
+    use crate::desktop_file_view::util::connect_self_fn;
+
+    #[derive(Default, Properties)]
+    #[properties(wrapper_type = super::AddGroupDialog)]
+    pub struct AddGroupDialog {
+        pub entry: RefCell<gtk::Entry>,
+        pub error_label: gtk::Label,
+
+        /// Names of the groups already in the file
+        pub existing_groups: RefCell<Vec<String>>,
+
+        #[property(get, set)]
+        group: RefCell<String>,
+    }
+
+    #[glib::object_subclass]
+    impl ObjectSubclass for AddGroupDialog {
+        const NAME: &'static str = "AddGroupDialog";
+        type Type = super::AddGroupDialog;
+        type ParentType = adw::AlertDialog;
+    }
+
+    #[glib::derived_properties]
+    impl ObjectImpl for AddGroupDialog {
+        fn constructed(&self) {
+            let obj = self.obj();
+
+            obj.set_heading(Some("Add Group"));
+            obj.set_body(
+                "Custom groups hold settings of other programs, their name must start with \"X-\"",
+            );
+
+            let container = gtk::Box::builder()
+                .spacing(6)
+                .orientation(gtk::Orientation::Vertical)
+                .build();
+
+            let entry = gtk::Entry::new();
+            entry.set_placeholder_text(Some("Group Name"));
+            entry.set_text("X-");
+
+            // Connect entry signals
+            entry.connect_changed(connect_self_fn!(self.on_entry_changed(entry)));
+            entry.connect_activate(connect_self_fn!(self.on_entry_activated(entry)));
+
+            self.error_label.set_halign(gtk::Align::Start);
+            self.error_label.set_wrap(true);
+            self.error_label.add_css_class("caption");
+            self.error_label.add_css_class("error");
+
+            container.append(&entry);
+            container.append(&self.error_label);
+            obj.set_extra_child(Some(&container));
+
+            obj.add_responses(&[("cancel", "Cancel"), ("add", "Add")]);
+            obj.set_response_appearance("add", adw::ResponseAppearance::Suggested);
+            obj.set_response_enabled("add", false);
+
+            entry
+                .property_expression_weak("text")
+                .chain_closure::<String>(closure!(|_: Option<Object>, s: &str| {
+                    s.trim().to_string()
+                }))
+                .bind(&obj.clone(), "group", Object::NONE);
+
+            self.entry.replace(entry);
+
+            obj.connect_map(|dialog| {
+                let entry = dialog.imp().entry.borrow();
+                entry.grab_focus();
+                entry.set_position(-1);
+            });
+        }
+    }
+
+    impl AdwAlertDialogImpl for AddGroupDialog {}
+    impl AdwDialogImpl for AddGroupDialog {}
+    impl WidgetImpl for AddGroupDialog {}
+
+    impl AddGroupDialog {
+        fn error(&self) -> Option<String> {
+            let existing_groups = self.existing_groups.borrow();
+            group_name_error(&self.obj().group(), |name| {
+                existing_groups.iter().any(|group| group == name)
+            })
+        }
+
+        fn on_entry_changed(&self, _entry: &Entry) {
+            let error = self.error();
+
+            // Don't complain while the user has not typed anything yet
+            let untouched = self.obj().group() == "X-";
+            self.error_label
+                .set_label(error.as_deref().filter(|_| !untouched).unwrap_or_default());
+            self.obj().set_response_enabled("add", error.is_none());
+        }
+
+        fn on_entry_activated(&self, _entry: &Entry) {
+            let obj = self.obj();
+            if self.error().is_none() {
+                obj.set_close_response("add");
+                if !obj.close() {
+                    eprintln!(
+                        "Failed to close add group dialog, closing forcefully, please report this bug!"
+                    );
+                    obj.force_close();
+                }
+                obj.set_close_response("cancel");
+            }
+        }
+    }
+}
+
+glib::wrapper! {
+    pub struct AddGroupDialog(ObjectSubclass<imp::AddGroupDialog>)
+        @extends adw::AlertDialog, adw::Dialog, gtk::Widget,
+        @implements gtk::Accessible, gtk::Buildable, gtk::ConstraintTarget, gtk::ShortcutManager;
+}
+
+impl AddGroupDialog {
+    /// `existing_groups` are the groups of the file, which can't be added again
+    pub fn new(existing_groups: Vec<String>) -> Self {
+        let dialog: Self = glib::Object::builder().build();
+        dialog.imp().existing_groups.replace(existing_groups);
+        dialog
+    }
+}
@@ -18,20 +18,21 @@ mod imp {
 
     use adw::{prelude::*, subclass::prelude::*};
     use gtk::{
-        gdk::{Key, ModifierType},
-        glib::{self, clone, closure, GString, Object, Propagation, Properties},
-        Entry, EventControllerKey, Label,
+        glib::{self, clone, closure, Object, Properties},
+        Entry, Label,
     };
 
     use crate::desktop_file_view::{
         languages::LANGUAGES_LOCALE_MAP,
-        util::{connect_self_fn, entry_popup_completion_handle_escape_key_pressed},
+        suggestion_entry::{Suggestion, SuggestionEntry},
+        util::connect_self_fn,
     };
 
     #[derive(Default, Properties)]
     #[properties(wrapper_type = super::AddLocaleDialog)]
     pub struct AddLocaleDialog {
         entry: RefCell<gtk::Entry>,
+        suggestion_entry: RefCell<SuggestionEntry>,
         language_label: RefCell<gtk::Label>,
         locale_exists_label: RefCell<gtk::Label>,
 
@@ -62,17 +63,13 @@ mod imp {
                 .orientation(gtk::Orientation::Vertical)
                 .build();
 
-            let entry = gtk::Entry::new();
+            let suggestion_entry = SuggestionEntry::new();
+            let entry = suggestion_entry.entry();
             entry.set_placeholder_text(Some("Locale"));
 
             // Connect entry signals
             entry.connect_changed(connect_self_fn!(self.on_entry_changed(entry)));
             entry.connect_activate(connect_self_fn!(self.on_entry_activated(entry)));
-            let entry_controller_key = EventControllerKey::new();
-            entry_controller_key.connect_key_pressed(connect_self_fn!(
-                self.on_entry_key_pressed(controller, key, code, modifier) -> Propagation::Proceed
-            ));
-            entry.add_controller(entry_controller_key);
 
             let language_label = Label::builder()
                 .halign(gtk::Align::Center)
@@ -89,7 +86,7 @@ mod imp {
                 .css_classes(["error"])
                 .build();
 
-            container.append(&entry);
+            container.append(&suggestion_entry);
             container.append(&language_label);
             container.append(&locale_exists_label);
             obj.set_extra_child(Some(&container));
@@ -106,10 +103,11 @@ mod imp {
                 .bind(&obj.clone(), "locale", Object::NONE);
 
             self.entry.replace(entry);
+            self.suggestion_entry.replace(suggestion_entry);
             self.language_label.replace(language_label);
             self.locale_exists_label.replace(locale_exists_label);
 
-            self.init_completion();
+            self.init_suggestions();
             obj.connect_map(|dialog| {
                 dialog.imp().entry.borrow().grab_focus();
             });
@@ -121,64 +119,20 @@ mod imp {
     impl WidgetImpl for AddLocaleDialog {}
 
     impl AddLocaleDialog {
-        #[allow(deprecated)]
-        fn init_completion(&self) {
-            let entry = self.entry.borrow().clone();
-            let entry_model = gtk::ListStore::new(&[glib::Type::STRING, glib::Type::STRING]);
-            for (locale, language) in LANGUAGES_LOCALE_MAP.iter() {
-                entry_model.set(
-                    &entry_model.append(),
-                    &[(0, &format!("[{locale}] {language}")), (1, locale)],
-                );
-            }
-
-            let completion = gtk::EntryCompletion::builder()
-                .model(&entry_model)
-                .text_column(0)
-                .minimum_key_length(2)
-                .popup_completion(false)
-                .build();
-
-            let completion_cell = gtk::CellRendererText::new();
-            completion_cell.set_xpad(6);
-            completion.pack_start(&completion_cell, false);
-            completion.add_attribute(&completion_cell, "text", 0);
-            completion.set_match_func(clone!(
-                #[weak(rename_to=model)]
-                entry_model,
-                #[upgrade_or]
-                false,
-                move |_completion, s, iter| {
-                    let iter_text =
-                        TreeModelExtManual::get::<GString>(&model, iter, 0).to_lowercase();
-                    s.split_whitespace().all(|word| iter_text.contains(word))
-                }
-            ));
-
-            completion.connect_match_selected(clone!(
-                #[weak]
-                entry,
-                #[upgrade_or]
-                Propagation::Proceed,
-                move |_completion, model, iter| {
-                    let locale = TreeModelExtManual::get::<GString>(model, iter, 1);
-                    // Set text does not properly send the property modified signal, hence the use
-                    // of delete and insert
-                    entry.delete_text(0, entry.text().len() as i32);
-                    entry.insert_text(&locale, &mut 0);
-                    entry.select_region(0, locale.len() as i32);
-                    Propagation::Stop
-                }
-            ));
-
-            entry.set_completion(Some(&completion));
+        fn init_suggestions(&self) {
+            let suggestion_entry = self.suggestion_entry.borrow();
+            suggestion_entry.set_minimum_key_length(2);
+
+            let mut languages: Vec<_> = LANGUAGES_LOCALE_MAP.iter().collect();
+            languages.sort_by_key(|(_, language)| **language);
+            let suggestions: Vec<Suggestion> = languages
+                .into_iter()
+                .map(|(locale, language)| Suggestion::new(locale, language, Some(*locale)))
+                .collect();
+            suggestion_entry.set_suggestions(&suggestions);
         }
 
-        #[allow(deprecated)]
         fn on_entry_changed(&self, entry: &Entry) {
-            // Make sure to enable completion on change
-            entry.completion().unwrap().set_popup_completion(true);
-
             let text = entry.text();
             let locale = text.trim();
 
@@ -234,17 +188,6 @@ mod imp {
                 obj.set_close_response("cancel");
             }
         }
-
-        fn on_entry_key_pressed(
-            &self,
-            _controller: &gtk::EventControllerKey,
-            key: Key,
-            _code: u32,
-            modifier: ModifierType,
-        ) -> Propagation {
-            entry_popup_completion_handle_escape_key_pressed(&self.entry.borrow(), key, modifier)
-                .into()
-        }
     }
 }
 
@@ -0,0 +1,259 @@
+/*
+* Copyright © 2025 Alessandro Balducci
+*
+* This file is part of Desktop File Editor.
+* Desktop File Editor is free software: you can redistribute it and/or modify it under the terms of the
+* GNU General Public License as published by the Free Software Foundation,
+* either version 3 of the License, or (at your option) any later version.
+* Desktop File Editor is distributed in the hope that it will be useful, but WITHOUT ANY WARRANTY;
+* without even the implied warranty of MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.
+* See the GNU General Public License for more details.
+* You should have received a copy of the GNU General Public License along with Desktop File Editor. If not, see <https://www.gnu.org/licenses/>.
+*/
+
+//! Categories registered by the Desktop Menu Specification, which desktops use to place
+//! applications in their menus
+
+/// A category of the `Categories` key
+pub struct Category {
+    pub name: &'static str,
+    pub description: &'static str,
+    /// Main categories make up the top level of menus, every entry should have one of them
+    pub main: bool,
+}
+
+const fn category(name: &'static str, description: &'static str, main: bool) -> Category {
+    Category {
+        name,
+        description,
+        main,
+    }
+}
+
+/// Main and additional categories, in the order the specification lists them. Others must start
+/// with `X-`
+pub const CATEGORIES: &[Category] = &[
+    category(
+        "AudioVideo",
+        "Application for presenting, creating, or processing multimedia (audio/video)",
+        true,
+    ),
+    category("Audio", "An audio application", true),
+    category("Video", "A video application", true),
+    category("Development", "An application for development", true),
+    category("Education", "Educational software", true),
+    category("Game", "A game", true),
+    category("Graphics", "Application for viewing, creating, or processing graphics", true),
+    category("Network", "Network application such as a web browser", true),
+    category("Office", "An office type application", true),
+    category("Science", "Scientific software", true),
+    category("Settings", "Settings applications", true),
+    category(
+        "System",
+        "System application, \"System Tools\" such as say a log viewer or network monitor",
+        true,
+    ),
+    category("Utility", "Small utility application, \"Accessories\"", true),
+    category("Building", "A tool to build applications", false),
+    category("Debugger", "A tool to debug applications", false),
+    category("IDE", "IDE application", false),
+    category("GUIDesigner", "A GUI designer application", false),
+    category("Profiling", "A profiling tool", false),
+    category("RevisionControl", "Applications like cvs or subversion", false),
+    category("Translation", "A translation tool", false),
+    category("Calendar", "Calendar application", false),
+    category("ContactManagement", "E.g. an address book", false),
+    category("Database", "Application to manage a database", false),
+    category("Dictionary", "A dictionary", false),
+    category("Chart", "Chart application", false),
+    category("Email", "Email application", false),
+    category("Finance", "Application to manage your finance", false),
+    category("FlowChart", "A flowchart application", false),
+    category("PDA", "Tool to manage your PDA", false),
+    category("ProjectManagement", "Project management application", false),
+    category("Presentation", "Presentation software", false),
+    category("Spreadsheet", "A spreadsheet", false),
+    category("WordProcessor", "A word processor", false),
+    category("2DGraphics", "2D based graphical application", false),
+    category(
+        "VectorGraphics",
+        "Application for viewing, creating, or processing vector graphics",
+        false,
+    ),
+    category(
+        "RasterGraphics",
+        "Application for viewing, creating, or processing raster (bitmap) graphics",
+        false,
+    ),
+    category("3DGraphics", "Application for viewing, creating, or processing 3-D graphics", false),
+    category("Scanning", "Tool to scan a file/text", false),
+    category("OCR", "Optical character recognition application", false),
+    category("Photography", "Camera tools, etc.", false),
+    category("Publishing", "Desktop Publishing applications and Color Management tools", false),
+    category("Viewer", "Tool to view e.g. a graphic or pdf file", false),
+    category("TextTools", "A text tool utility", false),
+    category("DesktopSettings", "Configuration tool for the GUI", false),
+    category(
+        "HardwareSettings",
+        "A tool to manage hardware components, like sound cards, video cards or printers",
+        false,
+    ),
+    category("Printing", "A tool to manage printers", false),
+    category("PackageManager", "A package manager application", false),
+    category("Dialup", "A dial-up program", false),
+    category("InstantMessaging", "An instant messaging client", false),
+    category("Chat", "A chat client", false),
+    category("IRCClient", "An IRC client", false),
+    category("Feed", "RSS, podcast and other subscription based contents", false),
+    category("FileTransfer", "Tools like FTP or P2P programs", false),
+    category("HamRadio", "HAM radio software", false),
+    category("News", "A news reader or a news ticker", false),
+    category("P2P", "A P2P program", false),
+    category("RemoteAccess", "A tool to remotely manage your PC", false),
+    category("Telephony", "Telephony via PC", false),
+    category("TelephonyTools", "Telephony tools, to dial a number, manage PBX, ...", false),
+    category("VideoConference", "Video Conference software", false),
+    category("WebBrowser", "A web browser", false),
+    category("WebDevelopment", "A tool for web developers", false),
+    category("Midi", "An app related to MIDI", false),
+    category("Mixer", "Just a mixer", false),
+    category("Sequencer", "A sequencer", false),
+    category("Tuner", "A tuner", false),
+    category("TV", "A TV application", false),
+    category("AudioVideoEditing", "Application to edit audio/video files", false),
+    category("Player", "Application to play audio/video files", false),
+    category("Recorder", "Application to record audio/video files", false),
+    category("DiscBurning", "Application to burn a disc", false),
+    category("ActionGame", "An action game", false),
+    category("AdventureGame", "Adventure style game", false),
+    category("ArcadeGame", "Arcade style game", false),
+    category("BoardGame", "A board game", false),
+    category("BlocksGame", "Falling blocks game", false),
+    category("CardGame", "A card game", false),
+    category("KidsGame", "A game for kids", false),
+    category("LogicGame", "Logic games like puzzles, etc", false),
+    category("RolePlaying", "A role playing game", false),
+    category("Shooter", "A shooter game", false),
+    category("Simulation", "A simulation game", false),
+    category("SportsGame", "A sports game", false),
+    category("StrategyGame", "A strategy game", false),
+    category("Art", "Software to teach arts", false),
+    category("Construction", "Construction", false),
+    category("Music", "Musical software", false),
+    category("Languages", "Software to learn foreign languages", false),
+    category("ArtificialIntelligence", "Artificial Intelligence software", false),
+    category("Astronomy", "Astronomy software", false),
+    category("Biology", "Biology software", false),
+    category("Chemistry", "Chemistry software", false),
+    category("ComputerScience", "Computer science software", false),
+    category("DataVisualization", "Data visualization software", false),
+    category("Economy", "Economy software", false),
+    category("Electricity", "Electricity software", false),
+    category("Geography", "Geography software", false),
+    category("Geology", "Geology software", false),
+    category("Geoscience", "Geoscience software, GIS", false),
+    category("History", "History software", false),
+    category("Humanities", "Software for philosophy, psychology and other humanities", false),
+    category("ImageProcessing", "Image Processing software", false),
+    category("Literature", "Literature software", false),
+    category("Maps", "Software for viewing maps, navigation, mapping, GPS", false),
+    category("Math", "Math software", false),
+    category("NumericalAnalysis", "Numerical analysis software", false),
+    category("MedicalSoftware", "Medical software", false),
+    category("Physics", "Physics software", false),
+    category("Robotics", "Robotics software", false),
+    category("Spirituality", "Religious and spiritual software, theology", false),
+    category("Sports", "Sports software", false),
+    category("ParallelComputing", "Parallel computing software", false),
+    category("Amusement", "A simple amusement", false),
+    category("Archiving", "A tool to archive/backup data", false),
+    category("Compression", "A tool to manage compressed data/archives", false),
+    category("Electronics", "Electronics software, e.g. a circuit designer", false),
+    category("Emulator", "Emulator of another platform, such as a DOS emulator", false),
+    category("Engineering", "Engineering software, e.g. CAD programs", false),
+    category("FileTools", "A file tool utility", false),
+    category("FileManager", "A file manager", false),
+    category("TerminalEmulator", "A terminal emulator application", false),
+    category("Filesystem", "A file system tool", false),
+    category(
+        "Monitor",
+        "Monitor application/applet that monitors some resource or activity",
+        false,
+    ),
+    category("Security", "A security tool", false),
+    category("Accessibility", "Accessibility", false),
+    category("Calculator", "A calculator", false),
+    category("Clock", "A clock application/applet", false),
+    category("TextEditor", "A text editor", false),
+    category("Documentation", "Help or documentation", false),
+    category("Adult", "Application handles adult or explicit material", false),
+    category(
+        "Core",
+        "Important application, core to correct functioning of the desktop environment",
+        false,
+    ),
+    category("KDE", "Application based on KDE libraries", false),
+    category("GNOME", "Application based on GNOME libraries", false),
+    category("XFCE", "Application based on XFCE libraries", false),
+    category("DDE", "Application based on DDE libraries", false),
+    category("GTK", "Application based on GTK libraries", false),
+    category("Qt", "Application based on Qt libraries", false),
+    category("Motif", "Application based on Motif libraries", false),
+    category("Java", "Application based on Java GUI libraries, such as AWT or Swing", false),
+    category(
+        "ConsoleOnly",
+        "Application that only works inside a terminal (text-based or command line application)",
+        false,
+    ),
+    category(
+        "Screensaver",
+        "A screen saver (launching this desktop entry should activate the screen saver)",
+        false,
+    ),
+    category(
+        "TrayIcon",
+        "An application that is primarily an icon for the \"system tray\" or \"notification area\"",
+        false,
+    ),
+    category(
+        "Applet",
+        "An applet that will run inside a panel or another such application, likely desktop environment-specific",
+        false,
+    ),
+    category(
+        "Shell",
+        "A shell (an actual specific shell such as bash or tcsh, not a TerminalEmulator)",
+        false,
+    ),
+];
+
+pub fn is_registered(name: &str) -> bool {
+    CATEGORIES.iter().any(|category| category.name == name)
+}
+
+/// Why `name` should not be used as a category, if it is neither registered nor custom
+pub fn category_issue(name: &str) -> Option<String> {
+    if name.starts_with("X-") || is_registered(name) {
+        None
+    } else {
+        Some(format!(
+            "{name} is not a registered category, custom ones start with X-"
+        ))
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::{category_issue, CATEGORIES};
+
+    #[test]
+    fn issues() {
+        assert_eq!(category_issue("Utility"), None);
+        assert_eq!(category_issue("X-GNOME-Utilities"), None);
+        assert!(category_issue("Utilities").is_some());
+        assert_eq!(
+            CATEGORIES.iter().filter(|category| category.main).count(),
+            13
+        );
+    }
+}
@@ -11,29 +11,127 @@
 * You should have received a copy of the GNU General Public License along with Desktop File Editor. If not, see <https://www.gnu.org/licenses/>.
 */
 
+use std::cell::Cell;
+use std::rc::Rc;
+
 use adw::prelude::*;
 use gtk::gio::Cancellable;
+use gtk::glib;
+
+use crate::util::display_path;
+
+use super::DesktopFileView;
 
-pub fn show_close_confirm_dialog<F, U>(parent: &impl IsA<gtk::Widget>, cancel: F, discard: U)
-where
+pub fn show_close_confirm_dialog<F, U, S>(
+    parent: &impl IsA<gtk::Widget>,
+    cancel: F,
+    discard: U,
+    save: S,
+) where
     F: Fn() + 'static,
     U: Fn() + 'static,
+    S: Fn() + 'static,
 {
     let dialog = adw::AlertDialog::builder()
-        .heading("Close File?")
-        .body("Unsaved changes will be discarded")
+        .heading("Save Changes?")
+        .body("Unsaved changes will be lost if you don't save them")
         .close_response("cancel")
-        .default_response("cancel")
+        .default_response("save")
         .build();
     dialog.add_response("cancel", "Cancel");
     dialog.add_response("discard", "Discard");
+    dialog.add_response("save", "Save");
+    dialog.set_response_appearance("discard", adw::ResponseAppearance::Destructive);
+    dialog.set_response_appearance("save", adw::ResponseAppearance::Suggested);
+
+    dialog.choose(parent, None::<&Cancellable>, move |response| match response.as_str() {
+        "discard" => discard(),
+        "save" => save(),
+        _ => cancel(),
+    });
+}
+
+/// Asks which of the given editors should be saved before closing, `close` is called once
+/// the selected ones have been saved successfully
+pub fn show_unsaved_changes_dialog<F>(
+    parent: &impl IsA<gtk::Widget>,
+    desktop_file_views: Vec<DesktopFileView>,
+    close: F,
+) where
+    F: Fn() + 'static,
+{
+    let body = match desktop_file_views.len() {
+        1 => "A file has unsaved changes, choose whether to save it before closing".to_string(),
+        n => format!("{n} files have unsaved changes, choose which ones to save before closing"),
+    };
+
+    let dialog = adw::AlertDialog::builder()
+        .heading("Save Changes?")
+        .body(body)
+        .close_response("cancel")
+        .default_response("save")
+        .build();
+    dialog.add_response("cancel", "Cancel");
+    dialog.add_response("discard", "Discard All");
+    dialog.add_response("save", "Save Selected");
     dialog.set_response_appearance("discard", adw::ResponseAppearance::Destructive);
+    dialog.set_response_appearance("save", adw::ResponseAppearance::Suggested);
+
+    let list_box = gtk::ListBox::builder()
+        .selection_mode(gtk::SelectionMode::None)
+        .css_classes(["boxed-list"])
+        .build();
+
+    let choices: Vec<(DesktopFileView, adw::SwitchRow)> = desktop_file_views
+        .into_iter()
+        .map(|desktop_file_view| {
+            let row = adw::SwitchRow::builder()
+                .title(desktop_file_view.title())
+                .subtitle(display_path(&desktop_file_view.path()).to_string_lossy())
+                .active(true)
+                .build();
+            list_box.append(&row);
+            (desktop_file_view, row)
+        })
+        .collect();
+    dialog.set_extra_child(Some(&list_box));
+
+    // Saving nothing would close the window like discarding, without saying so
+    let active_rows = Rc::new(Cell::new(choices.len()));
+    for (_, row) in choices.iter() {
+        row.connect_active_notify(glib::clone!(
+            #[weak]
+            dialog,
+            #[strong]
+            active_rows,
+            move |row| {
+                if row.is_active() {
+                    active_rows.set(active_rows.get() + 1);
+                } else {
+                    active_rows.set(active_rows.get() - 1);
+                }
+                dialog.set_response_enabled("save", active_rows.get() > 0);
+            }
+        ));
+    }
+
+    dialog.choose(parent, None::<&Cancellable>, move |response| match response.as_str() {
+        "discard" => close(),
+        "save" => {
+            glib::spawn_future_local(async move {
+                let mut all_saved = true;
+                for (desktop_file_view, row) in choices.iter() {
+                    if row.is_active() {
+                        all_saved &= desktop_file_view.save().await;
+                    }
+                }
 
-    dialog.choose(parent, None::<&Cancellable>, move |response| {
-        if response == "discard" {
-            discard();
-        } else {
-            cancel();
+                // Keep the window open if something could not be written
+                if all_saved {
+                    close();
+                }
+            });
         }
+        _ => {}
     });
 }
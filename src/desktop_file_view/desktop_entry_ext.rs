@@ -13,11 +13,11 @@
 
 use std::{
     cmp::Ordering,
-    collections::{btree_map::Entry, BTreeMap, BTreeSet},
+    collections::{btree_map::Entry, BTreeMap, BTreeSet, HashMap, HashSet},
     fmt::Write,
 };
 
-use freedesktop_desktop_entry::{DesktopEntry, GroupName, Key, LocaleMap, Value};
+use freedesktop_desktop_entry::{DecodeError, DesktopEntry, GroupName, Key, LocaleMap, Value};
 
 use crate::window::file_entry::ToGIcon;
 
@@ -26,8 +26,9 @@ pub const DEFAULT_LOCALE: &str = "Default";
 
 const GROUPS_ORDER: [&str; 1] = ["Desktop Entry"];
 
-/// Defines the order the keymap entries will be displayed in.
-const KEYMAP_ORDER: [&str; 25] = [
+/// Defines the order the keymap entries will be displayed in, unless the user configured a
+/// different one in the preferences.
+pub const KEYMAP_ORDER: [&str; 25] = [
     "Name",
     "GenericName",
     "Comment",
@@ -55,9 +56,52 @@ const KEYMAP_ORDER: [&str; 25] = [
     "Actions",
 ];
 
-fn fixed_order_comparator(fixed_order: &[&str], a: &str, b: &str) -> Ordering {
-    let a_fixed_order = fixed_order.iter().position(|key| *key == a);
-    let b_fixed_order = fixed_order.iter().position(|key| *key == b);
+/// Returns the locales a localized value is looked up in for the given locale, following the
+/// matching order defined by the Desktop Entry Specification:
+/// `lang_COUNTRY@MODIFIER`, `lang_COUNTRY`, `lang@MODIFIER`, `lang`.
+/// The encoding part of the locale, if any, is ignored. The default (unlocalized) value is not
+/// part of the returned list, it is always the last fallback.
+pub fn locale_fallback_chain(locale: &str) -> Vec<String> {
+    let (locale, modifier) = match locale.split_once('@') {
+        Some((locale, modifier)) => (locale, Some(modifier)),
+        None => (locale, None),
+    };
+    let locale = locale.split('.').next().unwrap_or_default();
+    let (lang, country) = match locale.split_once('_') {
+        Some((lang, country)) => (lang, Some(country)),
+        None => (locale, None),
+    };
+
+    let mut chain = Vec::new();
+    if lang.is_empty() {
+        return chain;
+    }
+
+    if let (Some(country), Some(modifier)) = (country, modifier) {
+        chain.push(format!("{lang}_{country}@{modifier}"));
+    }
+    if let Some(country) = country {
+        chain.push(format!("{lang}_{country}"));
+    }
+    if let Some(modifier) = modifier {
+        chain.push(format!("{lang}@{modifier}"));
+    }
+    chain.push(lang.to_string());
+    chain
+}
+
+/// The first of `available` that values are read from for the user `locale`, following
+/// [`locale_fallback_chain`]
+pub fn matching_locale<'a>(locale: &str, available: &'a [String]) -> Option<&'a str> {
+    locale_fallback_chain(locale)
+        .into_iter()
+        .find_map(|fallback| available.iter().find(|locale| **locale == fallback))
+        .map(String::as_str)
+}
+
+fn fixed_order_comparator(fixed_order: &[impl AsRef<str>], a: &str, b: &str) -> Ordering {
+    let a_fixed_order = fixed_order.iter().position(|key| key.as_ref() == a);
+    let b_fixed_order = fixed_order.iter().position(|key| key.as_ref() == b);
     match (a_fixed_order, b_fixed_order) {
         (Some(a_pos), Some(b_pos)) => a_pos.cmp(&b_pos),
         (Some(_), None) => Ordering::Less,
@@ -66,44 +110,344 @@ fn fixed_order_comparator(fixed_order: &[&str], a: &str, b: &str) -> Ordering {
     }
 }
 
+/// Turn the `\n` escape sequences of a value into actual line breaks, other escape sequences are
+/// kept as they are
+pub fn unescape_line_breaks(value: &str) -> String {
+    let mut unescaped = String::with_capacity(value.len());
+    let mut chars = value.chars();
+    while let Some(c) = chars.next() {
+        if c != '\\' {
+            unescaped.push(c);
+            continue;
+        }
+
+        match chars.next() {
+            Some('n') => unescaped.push('\n'),
+            Some(next) => {
+                unescaped.push('\\');
+                unescaped.push(next);
+            }
+            None => unescaped.push('\\'),
+        }
+    }
+    unescaped
+}
+
+/// Inverse of [`unescape_line_breaks`]
+pub fn escape_line_breaks(text: &str) -> String {
+    text.replace('\n', "\\n")
+}
+
+/// Check that `name` can be used for a new custom group, returns a description of the problem if
+/// it can not. `taken` tells whether a group with that name already exists
+pub fn group_name_error(name: &str, taken: impl Fn(&str) -> bool) -> Option<String> {
+    if name.is_empty() {
+        return Some("The name is empty".to_string());
+    }
+
+    if let Some(c) = name
+        .chars()
+        .find(|&c| !c.is_ascii() || c.is_ascii_control() || c == '[' || c == ']')
+    {
+        return Some(format!("The name contains the invalid character '{c}'"));
+    }
+
+    if !name.starts_with("X-") {
+        return Some("Custom group names must start with \"X-\"".to_string());
+    }
+
+    if taken(name) {
+        return Some("A group with this name already exists".to_string());
+    }
+
+    None
+}
+
+/// Append `name` with a numeric suffix until `taken` returns false for it
+fn unique_name(name: &str, taken: impl Fn(&str) -> bool) -> String {
+    if !taken(name) {
+        return name.to_string();
+    }
+
+    (2..)
+        .map(|n| format!("{name}-{n}"))
+        .find(|candidate| !taken(candidate))
+        .unwrap()
+}
+
 pub type VecKeyMap = Vec<(Key, (Value, LocaleMap))>;
 
+// Code adapted from Display implementation of DesktopEntry
+fn write_group(result: &mut String, group_name: &str, keymap: VecKeyMap, raw: &[RawBlock]) {
+    let _ = writeln!(result, "[{group_name}]");
+
+    for (key, (value, localizations)) in keymap {
+        let _ = writeln!(result, "{key}={value}");
+        for (locale, localized) in localizations {
+            let _ = writeln!(result, "{key}[{locale}]={localized}");
+        }
+    }
+    for block in raw {
+        if block.placement == RawPlacement::Group(group_name.to_string()) {
+            write_raw_block(result, block);
+        }
+    }
+    let _ = writeln!(result);
+}
+
+/// Where a block of content that can't be decoded is written back
+#[derive(Debug, Clone, PartialEq)]
+pub enum RawPlacement {
+    /// Before the first group
+    Start,
+    /// Among the values of a group
+    Group(GroupName),
+    /// After all the groups, the block starts with its own group header
+    End,
+}
+
+/// Consecutive lines of a desktop file that `DesktopEntry` can't decode. They are kept aside
+/// and written back as they are, unless the user edits them
+#[derive(Debug, Clone, PartialEq)]
+pub struct RawBlock {
+    pub placement: RawPlacement,
+    pub reason: &'static str,
+    /// Index of the first line of the block in the original file
+    pub first_line: usize,
+    /// Number of lines of the original file the block replaces
+    pub line_count: usize,
+    pub text: String,
+}
+
+impl RawBlock {
+    fn contains_line(&self, index: usize) -> bool {
+        (self.first_line..self.first_line + self.line_count).contains(&index)
+    }
+}
+
+fn write_raw_block(result: &mut String, block: &RawBlock) {
+    ensure_newline(result);
+    result.push_str(&block.text);
+    ensure_newline(result);
+}
+
+/// Keys may only contain the characters allowed by the specification
+fn is_valid_key(key: &str) -> bool {
+    !key.is_empty() && key.chars().all(|c| c.is_ascii_alphanumeric() || c == '-')
+}
+
+/// Why a group header can't be decoded, if it can't
+fn group_header_error(name: &str, seen: &HashSet<&str>) -> Option<&'static str> {
+    if seen.contains(name) {
+        Some("Duplicated group")
+    } else if name.is_empty() || name.contains(['[', ']']) || name.chars().any(char::is_control) {
+        Some("Invalid group name")
+    } else {
+        None
+    }
+}
+
+/// Whether the text decodes as a desktop entry with a main group, like the contents of a file
+/// copied from elsewhere
+pub fn is_desktop_entry_text(text: &str) -> bool {
+    let (decodable, _) = split_unrecognized(text);
+    DesktopEntry::from_str("pasted.desktop", &decodable, None::<&[&str]>)
+        .is_ok_and(|entry| entry.groups.0.contains_key("Desktop Entry"))
+}
+
+/// Separate the content of a desktop file that can be decoded from the content that can't.
+/// Returns `contents` without the lines that can't be decoded, and those lines as blocks
+pub fn split_unrecognized(contents: &str) -> (String, Vec<RawBlock>) {
+    let lines: Vec<&str> = contents.split_inclusive('\n').collect();
+
+    // Translations can only be decoded if the key has a default value as well
+    let mut default_keys: HashSet<(&str, &str)> = HashSet::new();
+    let mut group: Option<&str> = None;
+    for line in lines.iter() {
+        let trimmed = line.trim();
+        if trimmed.starts_with('[') && trimmed.ends_with(']') {
+            group = Some(&trimmed[1..trimmed.len() - 1]);
+        } else if let (Some(group), Some((key, _))) = (group, trimmed.split_once('=')) {
+            if let (key, None) = split_locale(key.trim()) {
+                default_keys.insert((group, key));
+            }
+        }
+    }
+
+    let mut decodable = String::new();
+    let mut blocks: Vec<RawBlock> = Vec::new();
+    let mut seen_groups: HashSet<&str> = HashSet::new();
+    let mut group: Option<&str> = None;
+    // Set while inside a group that can't be decoded as a whole
+    let mut group_error: Option<&'static str> = None;
+
+    for (index, line) in lines.iter().enumerate() {
+        let trimmed = line.trim();
+
+        let raw = if trimmed.starts_with('[') && trimmed.ends_with(']') {
+            let name = &trimmed[1..trimmed.len() - 1];
+            group = Some(name);
+            group_error = group_header_error(name, &seen_groups);
+            seen_groups.insert(name);
+            group_error.map(|reason| (RawPlacement::End, reason))
+        } else if let Some(reason) = group_error {
+            Some((RawPlacement::End, reason))
+        } else if trimmed.is_empty() || trimmed.starts_with('#') {
+            None
+        } else {
+            let reason = match (group, trimmed.split_once('=')) {
+                (_, None) => Some("Not a key-value pair"),
+                (None, Some(_)) => Some("Value outside of any group"),
+                (Some(group), Some((key, _))) => match split_locale(key.trim()) {
+                    (key, _) if !is_valid_key(key) => Some("Invalid key name"),
+                    (key, Some(_)) if !default_keys.contains(&(group, key)) => {
+                        Some("Translation of a key without a default value")
+                    }
+                    _ => None,
+                },
+            };
+            let placement = match group {
+                Some(group) => RawPlacement::Group(group.to_string()),
+                None => RawPlacement::Start,
+            };
+            reason.map(|reason| (placement, reason))
+        };
+
+        let Some((placement, reason)) = raw else {
+            decodable.push_str(line);
+            continue;
+        };
+
+        match blocks.last_mut() {
+            Some(block)
+                if block.first_line + block.line_count == index
+                    && block.placement == placement
+                    && block.reason == reason =>
+            {
+                block.line_count += 1;
+                block.text.push_str(line);
+            }
+            _ => blocks.push(RawBlock {
+                placement,
+                reason,
+                first_line: index,
+                line_count: 1,
+                text: line.to_string(),
+            }),
+        }
+    }
+
+    (decodable, blocks)
+}
+
 pub trait DesktopEntryExt {
-    fn sorted_keymap(&self, group: &str) -> Option<VecKeyMap>;
-    fn sorted_groups(&self) -> Vec<(GroupName, VecKeyMap)>;
+    /// Keys of `group` in `key_order`, keys not in it come after in alphabetical order
+    fn sorted_keymap(&self, group: &str, key_order: &[impl AsRef<str>]) -> Option<VecKeyMap>;
+    fn sorted_groups(&self, key_order: &[impl AsRef<str>]) -> Vec<(GroupName, VecKeyMap)>;
     fn locales(&self) -> Vec<String>;
 
     /// Convert the desktop entry to a `String`, with entries sorted by key, following the
-    /// `KEYMAP_ORDER` fixed priority list. If a key is not in the defined fixed order they will be
-    /// written alphabetically after the items present in the array. Groups are sorted the same way following
-    /// `GROUPS_ORDER`.
+    /// `key_order`, usually the one from the settings. If a key is not in the defined fixed
+    /// order they will be written alphabetically after the items present in the array. Groups
+    /// are sorted the same way following `GROUPS_ORDER`.
     ///
     /// This function also adds the `X-Ubuntu-Gettext-Domain` entry, which is removed by
     /// the `DesktopEntry` decoder.
-    fn to_sorted_entry_string(&self) -> String {
+    fn to_sorted_entry_string(&self, key_order: &[impl AsRef<str>]) -> String {
+        self.to_sorted_entry_string_with_raw(&[], key_order)
+    }
+
+    /// Same as `to_sorted_entry_string`, also writing back the `raw` blocks the entry could not
+    /// decode. Blocks of groups that no longer exist are dropped
+    fn to_sorted_entry_string_with_raw(
+        &self,
+        raw: &[RawBlock],
+        key_order: &[impl AsRef<str>],
+    ) -> String {
         let mut result = String::new();
 
-        // Code adapted from Display implementation of DesktopEntry
-        for (group_name, keymap) in self.sorted_groups() {
-            let _ = writeln!(&mut result, "[{group_name}]");
+        for block in raw
+            .iter()
+            .filter(|block| block.placement == RawPlacement::Start)
+        {
+            write_raw_block(&mut result, block);
+        }
 
-            for (key, (value, localizations)) in keymap {
-                let _ = writeln!(&mut result, "{key}={value}");
-                for (locale, localized) in localizations {
-                    let _ = writeln!(&mut result, "{key}[{locale}]={localized}");
-                }
-            }
-            let _ = writeln!(&mut result);
+        for (group_name, keymap) in self.sorted_groups(key_order) {
+            write_group(&mut result, &group_name, keymap, raw);
+        }
+
+        for block in raw
+            .iter()
+            .filter(|block| block.placement == RawPlacement::End)
+        {
+            write_raw_block(&mut result, block);
         }
 
         result
     }
 
+    /// Convert the desktop entry to a `String` by editing `original`, the text it was loaded from.
+    /// Only the lines of values that changed are rewritten, every other line is kept
+    /// byte-identical. Removed values are dropped, new values are added at the end of their group
+    /// and new groups at the end of the file. The lines of the `raw` blocks are replaced by the
+    /// text of the blocks. New values are written in `key_order`.
+    fn to_minimal_diff_string(
+        &self,
+        original: &str,
+        raw: &[RawBlock],
+        key_order: &[impl AsRef<str>],
+    ) -> String;
+
+    /// Convert a single group to a `String`, in the same format used by `to_sorted_entry_string`
+    fn group_to_string(&self, group_name: &str, key_order: &[impl AsRef<str>]) -> Option<String> {
+        let keymap = self.sorted_keymap(group_name, key_order)?;
+        let mut result = String::new();
+        write_group(&mut result, group_name, keymap, &[]);
+        Some(result)
+    }
+
+    /// Parse `text` as desktop file content and add all of its groups to the entry.
+    /// Groups whose name is already taken are renamed, and pasted actions are added to the
+    /// `Actions` list. The keys of a pasted main group are merged into the main group instead,
+    /// replacing the values it had. Returns the names of the added or changed groups.
+    fn paste_groups(&mut self, text: &str) -> Result<Vec<GroupName>, DecodeError>;
+
     fn entry(&self, group_name: &str, key: &str, locale: Option<&str>) -> Option<&str>;
+
+    /// Find the value a localized entry would fall back to if it had no value for `locale`,
+    /// along with the locale the value comes from (`None` for the default value).
+    fn inherited_entry(
+        &self,
+        group_name: &str,
+        key: &str,
+        locale: &str,
+    ) -> Option<(&str, Option<String>)>;
+
     fn add_entry(&mut self, group_name: String, key: String) -> bool;
+
+    /// Set the value of a key along with all of its translations, creating the group if needed
+    fn set_entry_with_localizations(
+        &mut self,
+        group_name: String,
+        key: String,
+        value: (Value, LocaleMap),
+    );
+
     fn add_group(&mut self, name: String);
     fn remove_group(&mut self, name: String);
 
+    /// Give the group `from` the name `to`, keeping its keys
+    fn rename_group(&mut self, from: &str, to: String);
+
+    /// Move `key` with all of its translations from the group `from` to the group `to`, replacing
+    /// what `to` had for it. Returns false if `from` does not have the key
+    fn move_entry(&mut self, from: &str, to: &str, key: &str) -> bool;
+
+    /// Remove every localized value, except for the locales in `keep`.
+    /// Returns the number of removed values.
+    fn strip_translations(&mut self, keep: &[String]) -> usize;
+
     fn add_action(&mut self, name: &str) {
         self.add_group(format!("Desktop Action {name}"));
     }
@@ -113,10 +457,199 @@ pub trait DesktopEntryExt {
     }*/
 
     fn remove_entry(&mut self, group: String, key: String);
+
+    /// Set the value of `key`, or its translation for `locale`, creating the group if needed
+    fn set_value(&mut self, group_name: &str, key: &str, locale: Option<&str>, value: String);
+}
+
+/// Splits a key as written in a file into the key itself and its locale, e.g. `Name[de]`
+pub fn split_locale(key: &str) -> (&str, Option<&str>) {
+    match key.strip_suffix(']').and_then(|key| key.split_once('[')) {
+        Some((key, locale)) => (key, Some(locale)),
+        None => (key, None),
+    }
+}
+
+/// The semicolon separated `list` with the `items` it doesn't have yet appended, `None` if it
+/// already has all of them
+pub fn extend_list<'a>(list: &str, items: impl IntoIterator<Item = &'a str>) -> Option<String> {
+    let mut entries: Vec<&str> = list.split(';').filter(|entry| !entry.is_empty()).collect();
+    let listed = entries.len();
+    for item in items {
+        if !entries.contains(&item) {
+            entries.push(item);
+        }
+    }
+    (entries.len() > listed).then(|| entries.iter().map(|entry| format!("{entry};")).collect())
+}
+
+/// Key, locale and group of a value, identifying a line of a desktop file
+type LineId = (GroupName, Key, Option<String>);
+
+/// A key defined more than once in the same group of a file
+#[derive(Debug, Clone, PartialEq)]
+pub struct DuplicateKey {
+    pub group: GroupName,
+    pub key: Key,
+    pub locale: Option<String>,
+    /// Line numbers, starting from 1, and values of every definition in file order. Only the
+    /// last one is used, the others are silently discarded when the file is decoded
+    pub values: Vec<(usize, String)>,
+}
+
+/// Find the keys defined more than once in the same group of the desktop file `contents`
+pub fn find_duplicate_keys(contents: &str) -> Vec<DuplicateKey> {
+    let mut definitions: Vec<DuplicateKey> = Vec::new();
+    let mut group: Option<&str> = None;
+
+    for (index, line) in contents.lines().enumerate() {
+        let trimmed = line.trim();
+        if trimmed.is_empty() || trimmed.starts_with('#') {
+            continue;
+        }
+
+        if trimmed.starts_with('[') && trimmed.ends_with(']') {
+            group = Some(&trimmed[1..trimmed.len() - 1]);
+            continue;
+        }
+
+        let (Some(group), Some((key_text, value))) = (group, line.split_once('=')) else {
+            continue;
+        };
+        let (key, locale) = split_locale(key_text.trim());
+        let definition = (index + 1, value.trim().to_string());
+
+        let existing = definitions.iter_mut().find(|duplicate| {
+            duplicate.group == group
+                && duplicate.key == key
+                && duplicate.locale.as_deref() == locale
+        });
+        match existing {
+            Some(duplicate) => duplicate.values.push(definition),
+            None => definitions.push(DuplicateKey {
+                group: group.to_string(),
+                key: key.to_string(),
+                locale: locale.map(String::from),
+                values: vec![definition],
+            }),
+        }
+    }
+
+    definitions.retain(|duplicate| duplicate.values.len() > 1);
+    definitions
+}
+
+/// Append the values of `group_name` that are not among the `written` ones
+fn write_new_values(
+    result: &mut String,
+    entry: &DesktopEntry,
+    group_name: &str,
+    written: &HashSet<LineId>,
+    key_order: &[impl AsRef<str>],
+) {
+    let Some(keymap) = entry.sorted_keymap(group_name, key_order) else {
+        return;
+    };
+
+    let is_written = |key: &str, locale: Option<&str>| {
+        written.contains(&(
+            group_name.to_string(),
+            key.to_string(),
+            locale.map(String::from),
+        ))
+    };
+
+    for (key, (value, localizations)) in keymap {
+        let has_value = !value.is_empty() || localizations.is_empty();
+        if has_value && !is_written(&key, None) {
+            ensure_newline(result);
+            let _ = writeln!(result, "{key}={value}");
+        }
+        for (locale, localized) in localizations {
+            if !is_written(&key, Some(&locale)) {
+                ensure_newline(result);
+                let _ = writeln!(result, "{key}[{locale}]={localized}");
+            }
+        }
+    }
+}
+
+/// Makes sure the next write starts on its own line, the original file might lack a final newline
+fn ensure_newline(result: &mut String) {
+    if !result.is_empty() && !result.ends_with('\n') {
+        result.push('\n');
+    }
+}
+
+/// Every value of `entry`, by the line it would be written on
+fn line_values(entry: &DesktopEntry) -> HashMap<LineId, String> {
+    let mut values = HashMap::new();
+    for (group_name, group) in &entry.groups.0 {
+        for (key, (value, localized)) in &group.0 {
+            values.insert((group_name.clone(), key.clone(), None), value.clone());
+            for (locale, value) in localized {
+                let id = (group_name.clone(), key.clone(), Some(locale.clone()));
+                values.insert(id, value.clone());
+            }
+        }
+    }
+    values
+}
+
+/// Three-way merge of desktop entries: starting from `theirs`, applies every change `ours` made
+/// to `base`. When both sides changed the same value, ours wins
+pub fn merge_entries(
+    base: &DesktopEntry,
+    ours: &DesktopEntry,
+    theirs: &DesktopEntry,
+) -> DesktopEntry {
+    let mut merged = theirs.clone();
+    let base_values = line_values(base);
+    let our_values = line_values(ours);
+
+    for ((group, key, locale), value) in &our_values {
+        let id = (group.clone(), key.clone(), locale.clone());
+        if base_values.get(&id) != Some(value) {
+            merged.set_value(group, key, locale.as_deref(), value.clone());
+        }
+    }
+
+    for (group, key, locale) in base_values.keys() {
+        let id = (group.clone(), key.clone(), locale.clone());
+        if our_values.contains_key(&id) {
+            continue;
+        }
+        match locale {
+            None => merged.remove_entry(group.clone(), key.clone()),
+            Some(locale) => {
+                if let Some((_, localized)) = merged
+                    .groups
+                    .0
+                    .get_mut(group)
+                    .and_then(|group| group.0.get_mut(key))
+                {
+                    localized.remove(locale);
+                }
+            }
+        }
+    }
+
+    for group in base.groups.0.keys() {
+        if !ours.groups.0.contains_key(group) {
+            merged.remove_group(group.clone());
+        }
+    }
+    for group in ours.groups.0.keys() {
+        if !base.groups.0.contains_key(group) {
+            merged.add_group(group.clone());
+        }
+    }
+
+    merged
 }
 
 impl DesktopEntryExt for DesktopEntry {
-    fn sorted_keymap(&self, group_name: &str) -> Option<VecKeyMap> {
+    fn sorted_keymap(&self, group_name: &str, key_order: &[impl AsRef<str>]) -> Option<VecKeyMap> {
         let keymap = self.groups.group(group_name)?.0.clone();
         let mut keymap = Vec::from_iter(keymap);
 
@@ -129,14 +662,14 @@ impl DesktopEntryExt for DesktopEntry {
             keymap.push((ubuntu_gettext_domain_key, ubuntu_gettext_domain_value));
         }
 
-        keymap.sort_by(|(a, _), (b, _)| fixed_order_comparator(&KEYMAP_ORDER, a, b));
+        keymap.sort_by(|(a, _), (b, _)| fixed_order_comparator(key_order, a, b));
         Some(keymap)
     }
 
-    fn sorted_groups(&self) -> Vec<(GroupName, VecKeyMap)> {
+    fn sorted_groups(&self, key_order: &[impl AsRef<str>]) -> Vec<(GroupName, VecKeyMap)> {
         let mut groups = Vec::new();
         for group in self.groups.0.keys() {
-            let vec_keymap = self.sorted_keymap(group).unwrap();
+            let vec_keymap = self.sorted_keymap(group, key_order).unwrap();
             groups.push((group.clone(), vec_keymap))
         }
 
@@ -144,6 +677,135 @@ impl DesktopEntryExt for DesktopEntry {
         groups
     }
 
+    fn to_minimal_diff_string(
+        &self,
+        original: &str,
+        raw: &[RawBlock],
+        key_order: &[impl AsRef<str>],
+    ) -> String {
+        let mut result = String::new();
+        let mut written: HashSet<LineId> = HashSet::new();
+        let mut original_groups: HashSet<&str> = HashSet::new();
+
+        let mut group: Option<&str> = None;
+        let mut group_removed = false;
+
+        // Blank lines are held back, so values added at the end of a group come before the blank
+        // lines separating it from the next one
+        let mut blank_lines = String::new();
+
+        for (index, line) in original.split_inclusive('\n').enumerate() {
+            if let Some(block) = raw.iter().find(|block| block.contains_line(index)) {
+                // Values of removed groups are dropped along with them
+                let removed = group_removed && matches!(block.placement, RawPlacement::Group(_));
+                if index == block.first_line && !removed {
+                    result.push_str(&blank_lines);
+                    blank_lines.clear();
+                    write_raw_block(&mut result, block);
+                }
+                continue;
+            }
+
+            let trimmed = line.trim();
+
+            if trimmed.is_empty() {
+                if !group_removed {
+                    blank_lines.push_str(line);
+                }
+                continue;
+            }
+
+            if trimmed.starts_with('[') && trimmed.ends_with(']') {
+                if let (Some(group), false) = (group, group_removed) {
+                    write_new_values(&mut result, self, group, &written, key_order);
+                }
+                result.push_str(&blank_lines);
+                blank_lines.clear();
+
+                let name = &trimmed[1..trimmed.len() - 1];
+                group = Some(name);
+                group_removed = self.groups.group(name).is_none();
+                original_groups.insert(name);
+                if !group_removed {
+                    result.push_str(line);
+                }
+                continue;
+            }
+
+            if group_removed {
+                continue;
+            }
+
+            result.push_str(&blank_lines);
+            blank_lines.clear();
+
+            // Comments, and anything else that is not a value, are kept as they are
+            let (Some(group), Some((key_text, old_value))) = (group, line.split_once('=')) else {
+                result.push_str(line);
+                continue;
+            };
+            if trimmed.starts_with('#') {
+                result.push_str(line);
+                continue;
+            }
+
+            let (key, locale) = split_locale(key_text.trim());
+            let id = (group.to_string(), key.to_string(), locale.map(String::from));
+            if written.contains(&id) {
+                // Duplicated line, the value was already written for the first one
+                continue;
+            }
+
+            let value = if group == "Desktop Entry"
+                && key == "X-Ubuntu-Gettext-Domain"
+                && locale.is_none()
+            {
+                self.ubuntu_gettext_domain.as_deref()
+            } else {
+                self.entry(group, key, locale)
+            };
+
+            // Only the whitespace after `=` is insignificant, whitespace at the end of the line
+            // is part of the value
+            let old_value = old_value.trim_end_matches(['\n', '\r']).trim_start();
+            match value {
+                Some(value) if value == old_value => result.push_str(line),
+                Some(value) => {
+                    let newline = if line.ends_with("\r\n") {
+                        "\r\n"
+                    } else if line.ends_with('\n') {
+                        "\n"
+                    } else {
+                        ""
+                    };
+                    let _ = write!(result, "{key_text}={value}{newline}");
+                }
+                // Removed value
+                None => continue,
+            }
+            written.insert(id);
+        }
+
+        if let (Some(group), false) = (group, group_removed) {
+            write_new_values(&mut result, self, group, &written, key_order);
+        }
+        result.push_str(&blank_lines);
+
+        for (group_name, keymap) in self.sorted_groups(key_order) {
+            if original_groups.contains(group_name.as_str()) {
+                continue;
+            }
+
+            ensure_newline(&mut result);
+            if !result.is_empty() && !result.ends_with("\n\n") {
+                result.push('\n');
+            }
+            write_group(&mut result, &group_name, keymap, raw);
+        }
+
+        result
+    }
+
     fn locales(&self) -> Vec<String> {
         self.groups.0
             .iter()
@@ -172,6 +834,30 @@ impl DesktopEntryExt for DesktopEntry {
         None
     }
 
+    fn inherited_entry(
+        &self,
+        group_name: &str,
+        key: &str,
+        locale: &str,
+    ) -> Option<(&str, Option<String>)> {
+        let (value, localized_values) = self.groups.group(group_name)?.0.get(key)?;
+
+        // The first item of the chain is the locale itself, which is not inherited
+        for fallback_locale in locale_fallback_chain(locale).into_iter().skip(1) {
+            if let Some(localized_value) = localized_values.get(&fallback_locale) {
+                if !localized_value.is_empty() {
+                    return Some((localized_value, Some(fallback_locale)));
+                }
+            }
+        }
+
+        if value.is_empty() {
+            None
+        } else {
+            Some((value, None))
+        }
+    }
+
     fn add_entry(&mut self, group_name: String, key: String) -> bool {
         self.groups.0
             .get_mut(&group_name)
@@ -183,6 +869,15 @@ impl DesktopEntryExt for DesktopEntry {
             .unwrap_or(false)
     }
 
+    fn set_entry_with_localizations(
+        &mut self,
+        group_name: String,
+        key: String,
+        value: (Value, LocaleMap),
+    ) {
+        self.groups.0.entry(group_name).or_default().0.insert(key, value);
+    }
+
     fn add_group(&mut self, name: String) {
         self.groups.0.entry(name).or_default();
     }
@@ -191,6 +886,77 @@ impl DesktopEntryExt for DesktopEntry {
         self.groups.0.remove(&name);
     }
 
+    fn rename_group(&mut self, from: &str, to: String) {
+        if let Some(group) = self.groups.0.remove(from) {
+            self.groups.0.insert(to, group);
+        }
+    }
+
+    fn move_entry(&mut self, from: &str, to: &str, key: &str) -> bool {
+        let value = self
+            .groups
+            .0
+            .get_mut(from)
+            .and_then(|group| group.0.remove(key));
+        let Some(value) = value else {
+            return false;
+        };
+        self.set_entry_with_localizations(to.to_string(), key.to_string(), value);
+        true
+    }
+
+    fn strip_translations(&mut self, keep: &[String]) -> usize {
+        let mut removed = 0;
+        for group in self.groups.0.values_mut() {
+            group.0.retain(|_, (value, localized)| {
+                let before = localized.len();
+                localized.retain(|locale, _| keep.contains(locale));
+                removed += before - localized.len();
+
+                // Keys that only existed as translations are dropped entirely
+                before == 0 || !value.is_empty() || !localized.is_empty()
+            });
+        }
+        removed
+    }
+
+    fn paste_groups(&mut self, text: &str) -> Result<Vec<GroupName>, DecodeError> {
+        let pasted = DesktopEntry::from_str(self.path.clone(), text, None::<&[&str]>)?;
+
+        let mut added = Vec::new();
+        for (name, group) in pasted.groups.0 {
+            // A second main group would be invalid, its keys go to the one the entry has. The
+            // Actions list is kept up to date with the pasted actions below
+            if name == "Desktop Entry" {
+                let desktop_entry = self.groups.0.entry(name.clone()).or_default();
+                for (key, value) in group.0 {
+                    if key != "Actions" {
+                        desktop_entry.0.insert(key, value);
+                    }
+                }
+                added.push(name);
+                continue;
+            }
+
+            let name = unique_name(&name, |candidate| self.groups.0.contains_key(candidate));
+
+            if let Some(action) = name.strip_prefix("Desktop Action ") {
+                let desktop_entry = self.groups.0.entry("Desktop Entry".to_string()).or_default();
+                let (actions, _) = desktop_entry.0.entry("Actions".to_string()).or_default();
+                if !actions.is_empty() && !actions.ends_with(';') {
+                    actions.push(';');
+                }
+                actions.push_str(action);
+                actions.push(';');
+            }
+
+            self.groups.0.insert(name.clone(), group);
+            added.push(name);
+        }
+
+        Ok(added)
+    }
+
     fn remove_entry(&mut self, group_name: String, key: String) {
         self.groups.0.entry(group_name).and_modify(|group| {
             if let Entry::Occupied(entry) = group.0.entry(key) {
@@ -198,6 +964,17 @@ impl DesktopEntryExt for DesktopEntry {
             }
         });
     }
+
+    fn set_value(&mut self, group_name: &str, key: &str, locale: Option<&str>, value: String) {
+        let group = self.groups.0.entry(group_name.to_string()).or_default();
+        let (default_value, localized) = group.0.entry(key.to_string()).or_default();
+        match locale {
+            Some(locale) => {
+                localized.insert(locale.to_string(), value);
+            }
+            None => *default_value = value,
+        }
+    }
 }
 
 impl ToGIcon for DesktopEntry {
@@ -205,3 +982,239 @@ impl ToGIcon for DesktopEntry {
         self.icon().map(|icon| icon.to_string())
     }
 }
+
+#[cfg(test)]
+mod test {
+    use freedesktop_desktop_entry::DesktopEntry;
+
+    use super::{
+        escape_line_breaks, extend_list, find_duplicate_keys, group_name_error,
+        is_desktop_entry_text, locale_fallback_chain, matching_locale, merge_entries,
+        split_unrecognized, unescape_line_breaks, unique_name, DesktopEntryExt, RawPlacement,
+        KEYMAP_ORDER,
+    };
+
+    #[test]
+    fn strip_translations_keeps_allowlist() {
+        let input = "[Desktop Entry]\nName=App\nName[de]=Anw\nName[it]=Appl\nComment[de]=K\n";
+        let mut entry = DesktopEntry::from_str("/tmp/app.desktop", input, None::<&[&str]>).unwrap();
+
+        assert_eq!(entry.strip_translations(&["it".to_string()]), 2);
+        assert_eq!(entry.locales(), vec!["it"]);
+        assert_eq!(entry.entry("Desktop Entry", "Name", None), Some("App"));
+    }
+
+    #[test]
+    fn minimal_diff_keeps_formatting() {
+        let input = "# Vendored\n[Desktop Entry]\nName = App\nName[it]=Appl\nName[de]=Anw\n\n[X-Tool]\nA=1\n";
+        let mut entry = DesktopEntry::from_str("/tmp/app.desktop", input, None::<&[&str]>).unwrap();
+        assert_eq!(
+            entry.to_minimal_diff_string(input, &[], &KEYMAP_ORDER),
+            input
+        );
+
+        entry.strip_translations(&["it".to_string()]);
+        assert_eq!(
+            entry.to_minimal_diff_string(input, &[], &KEYMAP_ORDER),
+            "# Vendored\n[Desktop Entry]\nName = App\nName[it]=Appl\n\n[X-Tool]\nA=1\n"
+        );
+    }
+
+    #[test]
+    fn merge_keeps_both_sides() {
+        let parse = |input: &str| {
+            DesktopEntry::from_str("/tmp/app.desktop", input, None::<&[&str]>).unwrap()
+        };
+        let base = parse("[Desktop Entry]\nName=App\nName[de]=Anw\nExec=app\nIcon=app\n");
+        let ours = parse("[Desktop Entry]\nName=Mine\nExec=app\nIcon=app\n");
+        let theirs = parse("[Desktop Entry]\nName=App\nName[de]=Anw\nExec=app --new\nIcon=x\n");
+
+        let merged = merge_entries(&base, &ours, &theirs);
+        assert_eq!(merged.entry("Desktop Entry", "Name", None), Some("Mine"));
+        assert_eq!(
+            merged.entry("Desktop Entry", "Exec", None),
+            Some("app --new")
+        );
+        assert_eq!(merged.entry("Desktop Entry", "Icon", None), Some("x"));
+        assert!(merged.locales().is_empty());
+    }
+
+    #[test]
+    fn move_entry_keeps_translations() {
+        let input =
+            "[Desktop Entry]\nName=App\n\n[Desktop Action new]\nName=N\nName[de]=Neu\nExec=x\n";
+        let mut entry = DesktopEntry::from_str("/tmp/app.desktop", input, None::<&[&str]>).unwrap();
+
+        assert!(entry.move_entry("Desktop Action new", "Desktop Entry", "Name"));
+        assert_eq!(entry.entry("Desktop Entry", "Name", None), Some("N"));
+        assert_eq!(
+            entry.entry("Desktop Entry", "Name", Some("de")),
+            Some("Neu")
+        );
+        assert_eq!(entry.entry("Desktop Action new", "Name", None), None);
+        assert!(!entry.move_entry("Desktop Action new", "Desktop Entry", "Name"));
+    }
+
+    #[test]
+    fn duplicate_keys() {
+        let input = "[Desktop Entry]\nName=App\nExec=a\n# Exec=c\nName[de]=Anw\nExec = b\n\n\
+                     [X-Tool]\nExec=c\nName[de]=X\nName[de]=Y\n";
+        let duplicates = find_duplicate_keys(input);
+
+        assert_eq!(duplicates.len(), 2);
+        assert_eq!(duplicates[0].key, "Exec");
+        assert_eq!(
+            duplicates[0].values,
+            vec![(3, "a".to_string()), (6, "b".to_string())]
+        );
+        assert_eq!(duplicates[1].group, "X-Tool");
+        assert_eq!(duplicates[1].locale.as_deref(), Some("de"));
+    }
+
+    #[test]
+    fn unrecognized_content_is_preserved() {
+        let input = "Stray=1\n[Desktop Entry]\nName=App\nBad Key=x\nName[de]=Anw\n\
+                     Comment[it]=C\n\n[X-Tool]\nA=1\n[X-Tool]\nB=2\n";
+        let (decodable, raw) = split_unrecognized(input);
+        assert_eq!(
+            decodable,
+            "[Desktop Entry]\nName=App\nName[de]=Anw\n\n[X-Tool]\nA=1\n"
+        );
+
+        let placements: Vec<&RawPlacement> = raw.iter().map(|block| &block.placement).collect();
+        assert_eq!(
+            placements,
+            vec![
+                &RawPlacement::Start,
+                &RawPlacement::Group("Desktop Entry".to_string()),
+                &RawPlacement::Group("Desktop Entry".to_string()),
+                &RawPlacement::End,
+            ]
+        );
+        assert_eq!(raw[3].text, "[X-Tool]\nB=2\n");
+
+        let entry =
+            DesktopEntry::from_str("/tmp/app.desktop", &decodable, None::<&[&str]>).unwrap();
+        assert_eq!(
+            entry.to_minimal_diff_string(input, &raw, &KEYMAP_ORDER),
+            input
+        );
+    }
+
+    #[test]
+    fn paste_main_group_merges() {
+        let input = "[Desktop Entry]\nName=App\nExec=app\nActions=new;\n\n\
+                     [Desktop Action new]\nName=New\n";
+        let mut entry = DesktopEntry::from_str("/tmp/app.desktop", input, None::<&[&str]>).unwrap();
+
+        let pasted = "[Desktop Entry]\nExec=app --safe\nComment=Hi\nActions=new;\n\n\
+                      [Desktop Action new]\nName=New Copy\n";
+        let added = entry.paste_groups(pasted).unwrap();
+
+        assert_eq!(added, ["Desktop Action new-2", "Desktop Entry"]);
+        assert!(!entry.groups.0.contains_key("Desktop Entry-2"));
+        assert_eq!(entry.entry("Desktop Entry", "Name", None), Some("App"));
+        assert_eq!(
+            entry.entry("Desktop Entry", "Exec", None),
+            Some("app --safe")
+        );
+        assert_eq!(entry.entry("Desktop Entry", "Comment", None), Some("Hi"));
+        assert_eq!(
+            entry.entry("Desktop Entry", "Actions", None),
+            Some("new;new-2;")
+        );
+    }
+
+    #[test]
+    fn unique_name_free() {
+        assert_eq!(unique_name("Desktop Action new", |_| false), "Desktop Action new");
+    }
+
+    #[test]
+    fn unique_name_taken() {
+        let taken = ["Desktop Action new", "Desktop Action new-2"];
+        assert_eq!(
+            unique_name("Desktop Action new", |name| taken.contains(&name)),
+            "Desktop Action new-3"
+        );
+    }
+
+    #[test]
+    fn group_name_valid() {
+        assert!(group_name_error("X-MyTool Settings", |_| false).is_none());
+    }
+
+    #[test]
+    fn group_name_invalid() {
+        assert!(group_name_error("", |_| false).is_some());
+        assert!(group_name_error("X-Tool]", |_| false).is_some());
+        assert!(group_name_error("X-Réglages", |_| false).is_some());
+        assert!(group_name_error("MyTool", |_| false).is_some());
+        assert!(group_name_error("X-Tool", |name| name == "X-Tool").is_some());
+    }
+
+    #[test]
+    fn fallback_lang() {
+        assert_eq!(locale_fallback_chain("de"), vec!["de"]);
+    }
+
+    #[test]
+    fn fallback_lang_country() {
+        assert_eq!(locale_fallback_chain("pt_BR"), vec!["pt_BR", "pt"]);
+    }
+
+    #[test]
+    fn fallback_lang_modifier() {
+        assert_eq!(locale_fallback_chain("sr@latin"), vec!["sr@latin", "sr"]);
+    }
+
+    #[test]
+    fn fallback_full() {
+        assert_eq!(
+            locale_fallback_chain("sr_RS.UTF-8@latin"),
+            vec!["sr_RS@latin", "sr_RS", "sr@latin", "sr"]
+        );
+    }
+
+    #[test]
+    fn fallback_empty() {
+        assert!(locale_fallback_chain("").is_empty());
+    }
+
+    #[test]
+    fn matching_locales() {
+        let available = ["de".to_string(), "pt".to_string(), "pt_BR".to_string()];
+        assert_eq!(matching_locale("pt_BR.UTF-8", &available), Some("pt_BR"));
+        assert_eq!(matching_locale("pt_PT.UTF-8", &available), Some("pt"));
+        assert_eq!(matching_locale("de_AT@euro", &available), Some("de"));
+        assert_eq!(matching_locale("fr_FR.UTF-8", &available), None);
+    }
+
+    #[test]
+    fn line_breaks_round_trip() {
+        let value = "First line\\nSecond line\\\\nstill second\\ttab\\";
+        let unescaped = unescape_line_breaks(value);
+
+        assert_eq!(unescaped, "First line\nSecond line\\\\nstill second\\ttab\\");
+        assert_eq!(escape_line_breaks(&unescaped), value);
+    }
+
+    #[test]
+    fn lists_are_extended_once() {
+        assert_eq!(
+            extend_list("new;", ["new", "private"]),
+            Some("new;private;".into())
+        );
+        assert_eq!(extend_list("new;private", ["private"]), None);
+        assert_eq!(extend_list("", ["new", "new"]), Some("new;".into()));
+    }
+
+    #[test]
+    fn desktop_entry_text() {
+        assert!(is_desktop_entry_text(
+            "[Desktop Entry]\nType=Application\nName=App\nExec=app\n"
+        ));
+        assert!(!is_desktop_entry_text("[Settings]\nkey=value\n"));
+        assert!(!is_desktop_entry_text("Just some text"));
+    }
+}
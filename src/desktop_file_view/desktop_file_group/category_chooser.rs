@@ -0,0 +1,159 @@
+/*
+* Copyright © 2025 Alessandro Balducci
+*
+* This file is part of Desktop File Editor.
+* Desktop File Editor is free software: you can redistribute it and/or modify it under the terms of the
+* GNU General Public License as published by the Free Software Foundation,
+* either version 3 of the License, or (at your option) any later version.
+* Desktop File Editor is distributed in the hope that it will be useful, but WITHOUT ANY WARRANTY;
+* without even the implied warranty of MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.
+* See the GNU General Public License for more details.
+* You should have received a copy of the GNU General Public License along with Desktop File Editor. If not, see <https://www.gnu.org/licenses/>.
+*/
+
+//! Popover to pick the categories of an entry among the registered ones
+
+use std::cell::Cell;
+use std::rc::Rc;
+
+use adw::prelude::*;
+use gtk::glib::clone;
+
+use crate::desktop_file_view::categories::{Category, CATEGORIES};
+
+use super::tagged_entry_row::TaggedEntryRow;
+
+/// Button opening the list of registered categories with their descriptions. Checking a category
+/// adds it to `row`, unchecking it removes it
+pub fn category_chooser_button(row: &TaggedEntryRow) -> gtk::MenuButton {
+    let search_entry = gtk::SearchEntry::builder()
+        .placeholder_text("Search Categories")
+        .build();
+
+    let list = gtk::ListBox::builder()
+        .selection_mode(gtk::SelectionMode::None)
+        .build();
+    let mut checks = Vec::new();
+    for category in CATEGORIES {
+        let check = gtk::CheckButton::new();
+        let category_row = adw::ActionRow::builder()
+            .title(category.name)
+            .subtitle(category.description)
+            .activatable_widget(&check)
+            .build();
+        category_row.add_prefix(&check);
+        list.append(&category_row);
+        checks.push(check);
+    }
+
+    list.set_filter_func(clone!(
+        #[weak]
+        search_entry,
+        #[upgrade_or]
+        true,
+        move |list_row| {
+            let query = search_entry.text().to_lowercase();
+            category_at(list_row).is_some_and(|category| {
+                category.name.to_lowercase().contains(&query)
+                    || category.description.to_lowercase().contains(&query)
+            })
+        }
+    ));
+    list.set_header_func(|list_row, before| {
+        let main = category_at(list_row).is_some_and(|category| category.main);
+        let before_main = before.and_then(category_at).map(|category| category.main);
+        if before_main == Some(main) {
+            list_row.set_header(gtk::Widget::NONE);
+            return;
+        }
+
+        let title = if main {
+            "Main Categories"
+        } else {
+            "Additional Categories"
+        };
+        let header = gtk::Label::builder()
+            .label(title)
+            .halign(gtk::Align::Start)
+            .margin_start(12)
+            .margin_top(12)
+            .margin_bottom(6)
+            .css_classes(["heading"])
+            .build();
+        list_row.set_header(Some(&header));
+    });
+    search_entry.connect_search_changed(clone!(
+        #[weak]
+        list,
+        move |_| {
+            list.invalidate_filter();
+            list.invalidate_headers();
+        }
+    ));
+
+    // Checks are updated from the tags when the popover opens, that is not a change by the user
+    let syncing = Rc::new(Cell::new(false));
+    for (check, category) in checks.iter().zip(CATEGORIES) {
+        check.connect_toggled(clone!(
+            #[weak]
+            row,
+            #[strong]
+            syncing,
+            move |check| {
+                if syncing.get() {
+                    return;
+                }
+                if check.is_active() {
+                    row.add_value(category.name);
+                } else {
+                    row.remove_value(category.name);
+                }
+            }
+        ));
+    }
+
+    let scrolled_window = gtk::ScrolledWindow::builder()
+        .hscrollbar_policy(gtk::PolicyType::Never)
+        .propagate_natural_height(true)
+        .max_content_height(400)
+        .min_content_width(360)
+        .child(&list)
+        .build();
+
+    let content = gtk::Box::new(gtk::Orientation::Vertical, 6);
+    content.append(&search_entry);
+    content.append(&scrolled_window);
+
+    let popover = gtk::Popover::builder().child(&content).build();
+    popover.connect_show(clone!(
+        #[weak]
+        row,
+        #[weak]
+        search_entry,
+        move |_| {
+            let values = row.values();
+            syncing.set(true);
+            for (check, category) in checks.iter().zip(CATEGORIES) {
+                check.set_active(values.iter().any(|value| value == category.name));
+            }
+            syncing.set(false);
+            search_entry.grab_focus();
+        }
+    ));
+
+    gtk::MenuButton::builder()
+        .icon_name("view-list-symbolic")
+        .halign(gtk::Align::Center)
+        .valign(gtk::Align::Center)
+        .popover(&popover)
+        .css_classes(["circular"])
+        .tooltip_text("Choose Categories")
+        .build()
+}
+
+/// The category shown by a row of the list, rows are in the same order as [`CATEGORIES`]
+fn category_at(list_row: &gtk::ListBoxRow) -> Option<&'static Category> {
+    usize::try_from(list_row.index())
+        .ok()
+        .and_then(|index| CATEGORIES.get(index))
+}
@@ -0,0 +1,309 @@
+/*
+* Copyright © 2025 Alessandro Balducci
+*
+* This file is part of Desktop File Editor.
+* Desktop File Editor is free software: you can redistribute it and/or modify it under the terms of the
+* GNU General Public License as published by the Free Software Foundation,
+* either version 3 of the License, or (at your option) any later version.
+* Desktop File Editor is distributed in the hope that it will be useful, but WITHOUT ANY WARRANTY;
+* without even the implied warranty of MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.
+* See the GNU General Public License for more details.
+* You should have received a copy of the GNU General Public License along with Desktop File Editor. If not, see <https://www.gnu.org/licenses/>.
+*/
+
+use gtk::glib;
+
+use crate::shellparse::{self, Command};
+use crate::util;
+
+mod imp {
+    use std::cell::{Cell, RefCell};
+
+    use adw::prelude::*;
+    use adw::subclass::prelude::*;
+    use gtk::glib::{self, clone, Properties};
+
+    use crate::shellparse::Command;
+
+    #[derive(Default, Properties)]
+    #[properties(wrapper_type = super::ExecEntryRow)]
+    pub struct ExecEntryRow {
+        /// The command line as stored in the desktop file
+        #[property(get, set = ExecEntryRow::set_text)]
+        pub text: RefCell<String>,
+
+        pub command_row: RefCell<adw::EntryRow>,
+        pub piece_rows: RefCell<Vec<adw::EntryRow>>,
+        /// How many of the piece rows, at the start, are environment variables
+        pub variable_count: Cell<usize>,
+        pub detail_rows: RefCell<Vec<gtk::Widget>>,
+        pub binary_warning: RefCell<Option<gtk::Image>>,
+
+        /// Set while the command line is rebuilt from its pieces, so the pieces being edited are
+        /// not replaced
+        pub syncing: Cell<bool>,
+    }
+
+    #[glib::object_subclass]
+    impl ObjectSubclass for ExecEntryRow {
+        const NAME: &'static str = "ExecEntryRow";
+        type Type = super::ExecEntryRow;
+        type ParentType = adw::ExpanderRow;
+    }
+
+    #[glib::derived_properties()]
+    impl ObjectImpl for ExecEntryRow {
+        fn constructed(&self) {
+            self.parent_constructed();
+            let obj = self.obj();
+
+            obj.set_use_markup(false);
+            obj.set_subtitle_lines(1);
+
+            let command_row = adw::EntryRow::builder().title("Command Line").build();
+            command_row.connect_text_notify(clone!(
+                #[weak(rename_to=this)]
+                self,
+                move |command_row| {
+                    let text = command_row.text().to_string();
+                    if *this.text.borrow() == text {
+                        return;
+                    }
+
+                    this.text.replace(text);
+                    this.obj().set_subtitle(&this.text.borrow());
+                    if !this.syncing.get() {
+                        this.rebuild_pieces();
+                    }
+                    this.obj().notify_text();
+                }
+            ));
+            obj.add_row(&command_row);
+
+            self.command_row.replace(command_row);
+        }
+    }
+
+    impl ExecEntryRow {
+        fn set_text(&self, text: &str) {
+            if *self.text.borrow() == text {
+                return;
+            }
+
+            // Updating the entry also stores the text and notifies the change
+            self.command_row.borrow().set_text(text);
+        }
+
+        /// Replaces the rows editing the pieces of the command with the ones of the current
+        /// command line
+        fn rebuild_pieces(&self) {
+            let obj = self.obj();
+            for row in self.piece_rows.take() {
+                obj.remove(&row);
+            }
+
+            let command = super::split_command(&self.text.borrow());
+            self.binary_warning.take();
+            if let Some(command) = &command {
+                let mut rows = Vec::new();
+                for (var, value) in command.variables.iter() {
+                    rows.push(self.piece_row("Environment Variable", &format!("{var}={value}")));
+                }
+                self.variable_count.set(command.variables.len());
+
+                let binary_warning = gtk::Image::builder()
+                    .icon_name("dialog-warning-symbolic")
+                    .tooltip_text("The binary was not found in PATH")
+                    .css_classes(["warning"])
+                    .build();
+                let binary_row = self.piece_row("Binary", &command.command);
+                binary_row.add_suffix(&binary_warning);
+                self.binary_warning.replace(Some(binary_warning));
+                rows.push(binary_row);
+
+                for (i, arg) in command.args.iter().enumerate() {
+                    rows.push(self.piece_row(&format!("Argument {}", i + 1), arg));
+                }
+
+                for row in rows.iter() {
+                    obj.add_row(row);
+                }
+                self.piece_rows.replace(rows);
+            }
+
+            self.update_details(command.as_ref());
+        }
+
+        fn piece_row(&self, title: &str, text: &str) -> adw::EntryRow {
+            let row = adw::EntryRow::builder().title(title).text(text).build();
+            row.connect_text_notify(clone!(
+                #[weak(rename_to=this)]
+                self,
+                move |_| this.apply_pieces()
+            ));
+            row
+        }
+
+        /// Puts the command line back together after one of its pieces was edited
+        fn apply_pieces(&self) {
+            let command = {
+                let rows = self.piece_rows.borrow();
+                let (variables, words) = rows.split_at(self.variable_count.get());
+                let texts = |rows: &[adw::EntryRow]| {
+                    rows.iter().map(|row| row.text().to_string()).collect()
+                };
+                super::join_pieces(texts(variables), texts(words))
+            };
+
+            self.syncing.set(true);
+            match &command {
+                Some(command) => self.obj().set_text(command.to_exec_string_with_codes()),
+                None => self.obj().set_text(""),
+            }
+            self.syncing.set(false);
+
+            self.update_details(command.as_ref());
+        }
+
+        /// Updates the warning about the binary and the explanations of the field codes
+        fn update_details(&self, command: Option<&Command>) {
+            let obj = self.obj();
+            for row in self.detail_rows.take() {
+                obj.remove(&row);
+            }
+
+            if let (Some(binary_warning), Some(command)) = (&*self.binary_warning.borrow(), command)
+            {
+                binary_warning.set_visible(!super::binary_found(&command.command));
+            }
+
+            let mut rows: Vec<gtk::Widget> = Vec::new();
+            match command {
+                Some(command) => {
+                    for code in command.field_codes() {
+                        let row = adw::ActionRow::builder()
+                            .title(format!("%{code}"))
+                            .subtitle(super::field_code_description(code))
+                            .build();
+                        rows.push(row.upcast());
+                    }
+                }
+                None if !self.text.borrow().is_empty() => {
+                    let row = adw::ActionRow::builder()
+                        .title("The command line could not be split into pieces")
+                        .css_classes(["error"])
+                        .build();
+                    rows.push(row.upcast());
+                }
+                None => {}
+            }
+
+            for row in rows.iter() {
+                obj.add_row(row);
+            }
+            self.detail_rows.replace(rows);
+        }
+    }
+
+    impl ExpanderRowImpl for ExecEntryRow {}
+    impl PreferencesRowImpl for ExecEntryRow {}
+    impl ListBoxRowImpl for ExecEntryRow {}
+    impl WidgetImpl for ExecEntryRow {}
+}
+
+glib::wrapper! {
+    /// Row editing the Exec key, both as a whole command line and as its environment variables,
+    /// binary and arguments
+    pub struct ExecEntryRow(ObjectSubclass<imp::ExecEntryRow>)
+        @extends adw::ExpanderRow, adw::PreferencesRow, gtk::ListBoxRow, gtk::Widget,
+        @implements gtk::Accessible, gtk::Actionable, gtk::Buildable, gtk::ConstraintTarget;
+}
+
+impl ExecEntryRow {
+    pub fn new(key: &str, value: &str) -> Self {
+        let row: Self = glib::Object::builder().property("title", key).build();
+        row.set_text(value);
+        row
+    }
+}
+
+/// Splits a command line into pieces. Variables set through `env` are shown as variables, unless
+/// `env` is also given options, which would be lost when the pieces are put back together
+fn split_command(text: &str) -> Option<Command> {
+    let mut command = shellparse::parse(text)?;
+    let has_env_options = command
+        .args
+        .iter()
+        .take_while(|arg| arg.starts_with('-') || arg.contains('='))
+        .any(|arg| arg.starts_with('-'));
+    if !has_env_options {
+        command.flatten_env();
+    }
+    Some(command)
+}
+
+/// Builds a command from the texts of its pieces, the first word being the binary. Pieces left
+/// empty are dropped
+fn join_pieces(variables: Vec<String>, words: Vec<String>) -> Option<Command> {
+    let variables = variables
+        .iter()
+        .filter(|variable| !variable.is_empty())
+        .map(|variable| {
+            let (var, value) = variable.split_once('=').unwrap_or((variable, ""));
+            (var.to_string(), value.to_string())
+        })
+        .collect();
+
+    let mut words = words.into_iter().filter(|word| !word.is_empty());
+    Some(Command {
+        command: words.next()?,
+        args: words.collect(),
+        variables,
+    })
+}
+
+fn binary_found(binary: &str) -> bool {
+    which::which_in_global(binary, util::binary_search_paths())
+        .map(|mut found| found.next().is_some())
+        .unwrap_or(false)
+}
+
+/// What a field code of the Exec key is replaced with when the application is launched
+fn field_code_description(code: char) -> &'static str {
+    match code {
+        'f' => "A single file, the application is launched once for each file that is opened",
+        'F' => "A list of files, all opened by a single launch of the application",
+        'u' => "A single file or URL, the application is launched once for each of them",
+        'U' => "A list of files or URLs, all opened by a single launch of the application",
+        'i' => "The Icon key, passed as --icon followed by the icon name",
+        'c' => "The translated name of the application",
+        'k' => "The location of the desktop file",
+        'd' | 'D' | 'n' | 'N' | 'v' | 'm' => "Deprecated, launchers ignore it",
+        _ => "Not a field code, write %% for a percent sign",
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn pieces_round_trip() {
+        let command = split_command("env LANG=C app --file %f").unwrap();
+        assert_eq!(command.variables, [("LANG".to_string(), "C".to_string())]);
+
+        let words = std::iter::once(command.command.clone())
+            .chain(command.args.clone())
+            .chain(std::iter::once(String::new()))
+            .collect();
+        let joined = join_pieces(vec!["LANG=C".to_string()], words).unwrap();
+        assert_eq!(joined, command);
+        assert_eq!(
+            joined.to_exec_string_with_codes(),
+            "env LANG=C app --file %f"
+        );
+
+        let command = split_command("env -u HOME app").unwrap();
+        assert_eq!(command.command, "env");
+    }
+}
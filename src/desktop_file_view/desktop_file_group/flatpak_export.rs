@@ -0,0 +1,106 @@
+/*
+* Copyright © 2025 Alessandro Balducci
+*
+* This file is part of Desktop File Editor.
+* Desktop File Editor is free software: you can redistribute it and/or modify it under the terms of the
+* GNU General Public License as published by the Free Software Foundation,
+* either version 3 of the License, or (at your option) any later version.
+* Desktop File Editor is distributed in the hope that it will be useful, but WITHOUT ANY WARRANTY;
+* without even the implied warranty of MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.
+* See the GNU General Public License for more details.
+* You should have received a copy of the GNU General Public License along with Desktop File Editor. If not, see <https://www.gnu.org/licenses/>.
+*/
+
+//! Finding out what the wrapper scripts flatpak exports for its applications launch. Desktop
+//! files of flatpak applications often run them instead of `flatpak run`
+
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use gtk::glib;
+
+use crate::util;
+
+/// An application installed with flatpak, as found from its exported wrapper
+#[derive(Debug)]
+pub struct ExportedApp {
+    pub app_id: String,
+    /// Directory of the flatpak installation, e.g. `/var/lib/flatpak`
+    pub installation: PathBuf,
+    pub arch: Option<String>,
+    pub branch: Option<String>,
+    /// Runtime reference, e.g. `org.gnome.Platform/x86_64/47`
+    pub runtime: Option<String>,
+}
+
+/// Resolve the application behind `program` if it is a wrapper exported by flatpak, either
+/// written as a path or found in the binary search paths
+pub fn exported_app(program: &str) -> Option<ExportedApp> {
+    let path = if Path::new(program).is_absolute() {
+        PathBuf::from(program)
+    } else {
+        which::which_in_global(program, util::binary_search_paths())
+            .ok()?
+            .next()?
+    };
+
+    let bin_dir = path.parent()?;
+    if !bin_dir.ends_with("exports/bin") {
+        return None;
+    }
+    let installation = bin_dir.parent()?.parent()?.to_path_buf();
+    let app_id = path.file_name()?.to_str()?.to_string();
+
+    // `current` links to the deployed arch and branch, e.g. `x86_64/stable`
+    let app_dir = installation.join("app").join(&app_id);
+    let current = fs::read_link(app_dir.join("current")).ok();
+    let (arch, branch) = current
+        .as_deref()
+        .and_then(Path::to_str)
+        .and_then(split_current_link)
+        .map_or((None, None), |(arch, branch)| (Some(arch), Some(branch)));
+
+    let runtime = app_runtime(&app_dir.join("current/active/metadata"));
+
+    Some(ExportedApp {
+        app_id,
+        installation,
+        arch,
+        branch,
+        runtime,
+    })
+}
+
+/// Arch and branch from the target of the `current` link of an application
+fn split_current_link(target: &str) -> Option<(String, String)> {
+    let (arch, branch) = target.trim_end_matches('/').split_once('/')?;
+    (!arch.is_empty() && !branch.is_empty() && !branch.contains('/'))
+        .then(|| (arch.to_string(), branch.to_string()))
+}
+
+/// Runtime the application runs in, as written in its metadata file
+fn app_runtime(metadata: &Path) -> Option<String> {
+    let key_file = glib::KeyFile::new();
+    key_file
+        .load_from_file(metadata, glib::KeyFileFlags::NONE)
+        .ok()?;
+    key_file
+        .string("Application", "runtime")
+        .ok()
+        .map(String::from)
+}
+
+#[cfg(test)]
+mod test {
+    use super::split_current_link;
+
+    #[test]
+    fn current_link() {
+        assert_eq!(
+            split_current_link("x86_64/stable"),
+            Some(("x86_64".to_string(), "stable".to_string()))
+        );
+        assert_eq!(split_current_link("stable"), None);
+        assert_eq!(split_current_link("a/b/c"), None);
+    }
+}
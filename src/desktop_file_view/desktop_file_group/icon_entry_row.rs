@@ -11,37 +11,63 @@
 * You should have received a copy of the GNU General Public License along with Desktop File Editor. If not, see <https://www.gnu.org/licenses/>.
 */
 
+use std::fs;
+use std::path::{Path, PathBuf};
+
 use adw::prelude::*;
-use gtk::glib;
 use gtk::glib::subclass::types::ObjectSubclassIsExt;
+use gtk::{gdk, gio, glib};
 
+use crate::util::{hicolor_dir, hicolor_size_dir, HICOLOR_EXTENSIONS};
 use crate::window::file_entry::ToGIcon;
 
 mod imp {
-    use std::{cell::RefCell, rc::Rc};
+    use std::{
+        cell::{Cell, RefCell},
+        path::{Path, PathBuf},
+        rc::Rc,
+    };
 
     use adw::prelude::*;
     use adw::subclass::prelude::*;
     use gtk::{
-        gio::Cancellable,
+        gio::{self, Cancellable},
         glib::{self, clone, closure_local, object::Cast, property::PropertySet},
-        Align, ClosureExpression, Expression, FileDialog, FileFilter, FilterListModel, GridView,
-        IconTheme, Image, ListItem, NoSelection, Orientation, Revealer,
-        RevealerTransitionType, ScrolledWindow, SearchEntry, SignalListItemFactory, StringFilter,
-        StringFilterMatchMode, StringList, StringObject,
+        Align, ClosureExpression, CustomFilter, EveryFilter, Expression, FileDialog, FileFilter,
+        FilterChange, FilterListModel, GridView, Image, ListItem, NoSelection, Orientation, Revealer, RevealerTransitionType,
+        ScrolledWindow, SearchEntry, SignalListItemFactory, StringFilter, StringFilterMatchMode,
+        StringObject,
     };
 
     use crate::util::display_path;
+    use crate::window::DMWindow;
+
+    use super::super::icon_names::{connect_icon_names_loaded, icon_names_model};
+
+    const SYSTEM_ICON_FOLDERS: [&str; 2] = ["/usr/share/icons", "/usr/share/pixmaps"];
 
     const POPOVER_SIZE_SMALL: f64 = 85.0;
     const POPOVER_SIZE_LARGE: f64 = 360.0;
     const POPOVER_ANIM_DURATION: u32 = 325;
 
-    #[derive(Default)]
     pub struct IconEntryRow {
         pub icon_image: RefCell<gtk::Image>,
         pub edit_button: RefCell<gtk::Button>,
         icon_search_filter: Rc<RefCell<StringFilter>>,
+        symbolic_filter: RefCell<CustomFilter>,
+        show_symbolic: Cell<bool>,
+    }
+
+    impl Default for IconEntryRow {
+        fn default() -> Self {
+            Self {
+                icon_image: Default::default(),
+                edit_button: Default::default(),
+                icon_search_filter: Default::default(),
+                symbolic_filter: Default::default(),
+                show_symbolic: Cell::new(true),
+            }
+        }
     }
 
     #[glib::object_subclass]
@@ -89,6 +115,29 @@ mod imp {
         }
     }
 
+    /// Existing folders where icon files are usually located. The folder of the current icon
+    /// comes first when the icon is an absolute path
+    fn icon_file_folders(current_icon: &str) -> Vec<PathBuf> {
+        let current_icon = Path::new(current_icon);
+        let current_folder = current_icon
+            .is_absolute()
+            .then(|| current_icon.parent())
+            .flatten()
+            .map(Path::to_path_buf);
+
+        let mut folders: Vec<PathBuf> = current_folder.into_iter().collect();
+        folders.push(glib::user_data_dir().join("icons"));
+        folders.extend(SYSTEM_ICON_FOLDERS.iter().map(PathBuf::from));
+
+        let mut unique_folders = Vec::with_capacity(folders.len());
+        for folder in folders {
+            if folder.is_dir() && !unique_folders.contains(&folder) {
+                unique_folders.push(folder);
+            }
+        }
+        unique_folders
+    }
+
     impl EntryRowImpl for IconEntryRow {}
     impl PreferencesRowImpl for IconEntryRow {}
     impl ListBoxRowImpl for IconEntryRow {}
@@ -129,6 +178,17 @@ mod imp {
                 .css_classes(["flat", "icon_chooser_menu_button"])
                 .build();
 
+            let install_button = gtk::Button::builder()
+                .child(
+                    &gtk::Label::builder()
+                        .label("Install in Icon Theme")
+                        .halign(Align::Start)
+                        .build(),
+                )
+                .tooltip_text("Use the icon by name, so that it follows the icon theme")
+                .css_classes(["flat", "icon_chooser_menu_button"])
+                .build();
+
             let remove_button = gtk::Button::builder()
                 .child(
                     &gtk::Label::builder()
@@ -144,6 +204,7 @@ mod imp {
                 .build();
             options_box.append(&file_button);
             options_box.append(&icon_button);
+            options_box.append(&install_button);
             options_box.append(&remove_button);
             options_box.append(&description_button);
 
@@ -158,6 +219,33 @@ mod imp {
 
             let obj = self.obj();
 
+            // Install, only icons given as a path need it
+            popover.connect_show(clone!(
+                #[weak]
+                obj,
+                #[weak]
+                install_button,
+                move |_| {
+                    install_button.set_visible(Path::new(obj.text().as_str()).is_absolute());
+                }
+            ));
+            install_button.connect_clicked(clone!(
+                #[weak]
+                obj,
+                #[weak]
+                popover,
+                move |_| {
+                    popover.set_visible(false);
+                    glib::spawn_future_local(clone!(
+                        #[weak]
+                        obj,
+                        async move {
+                            obj.imp().install_in_theme().await;
+                        }
+                    ));
+                }
+            ));
+
             // Remove
             remove_button.set_detailed_action_name("desktop_file_group.remove_entry('Icon')");
             remove_button.connect_clicked(clone!(
@@ -179,44 +267,74 @@ mod imp {
             ));
 
             // File
+            let locations_box = gtk::Box::builder()
+                .orientation(Orientation::Vertical)
+                .build();
+
+            let locations_nav_page = adw::NavigationPage::builder()
+                .title("Icon File")
+                .child(&locations_box)
+                .build();
+
             file_button.connect_clicked(clone!(
                 #[weak]
                 obj,
                 #[weak]
                 popover,
-                move |button| {
-                    popover.set_visible(false);
+                #[weak]
+                nav_view,
+                #[weak]
+                locations_box,
+                #[weak]
+                locations_nav_page,
+                move |_| {
+                    // Offer the folders where icons are usually found as shortcuts, the first
+                    // one being the most relevant. Browsing from the default folder is always
+                    // possible, in case none of them exists or has the icon
+                    while let Some(child) = locations_box.first_child() {
+                        locations_box.remove(&child);
+                    }
 
-                    let filter = FileFilter::new();
-                    filter.set_name(Some("Image File"));
-                    filter.add_mime_type("image/png");
-                    filter.add_mime_type("image/jpeg");
-                    filter.add_mime_type("image/svg+xml");
-                    filter.add_mime_type("image/bmp");
-                    filter.add_mime_type("image/webp");
-                    let dialog = FileDialog::builder()
-                        .title("Choose Icon")
-                        .default_filter(&filter)
-                        .build();
-
-                    dialog.open(
-                        button
-                            .root()
-                            .map(|root| root.downcast::<gtk::Window>().unwrap())
-                            .as_ref(),
-                        Some(&Cancellable::new()),
-                        clone!(
+                    let folders = icon_file_folders(&obj.text()).into_iter().map(Some);
+                    for folder in folders.chain([None]) {
+                        let label = match &folder {
+                            Some(folder) => display_path(folder).to_string_lossy().into_owned(),
+                            None => "Browse…".to_string(),
+                        };
+                        let location_button = gtk::Button::builder()
+                            .child(
+                                &gtk::Label::builder()
+                                    .label(label)
+                                    .halign(Align::Start)
+                                    .build(),
+                            )
+                            .css_classes(["flat", "icon_chooser_menu_button"])
+                            .build();
+
+                        location_button.connect_clicked(clone!(
                             #[weak]
                             obj,
-                            move |res| {
-                                if let Ok(res) = res {
-                                    let path = display_path(&res.path().unwrap());
-                                    obj.set_text(path.to_str().unwrap());
-                                    obj.activate();
-                                }
+                            #[weak]
+                            popover,
+                            #[weak]
+                            nav_view,
+                            move |button| {
+                                popover.set_visible(false);
+                                nav_view.pop();
+                                obj.imp().open_icon_file_dialog(
+                                    button
+                                        .root()
+                                        .and_downcast::<gtk::Window>()
+                                        .as_ref(),
+                                    folder.as_deref(),
+                                );
                             }
-                        ),
-                    );
+                        ));
+
+                        locations_box.append(&location_button);
+                    }
+
+                    nav_view.push(&locations_nav_page);
                 }
             ));
 
@@ -258,8 +376,21 @@ mod imp {
                 .width_request(0)
                 .build();
 
+            // Shown while the icon names are still being loaded
+            let loading_spinner = adw::Spinner::builder()
+                .width_request(32)
+                .height_request(32)
+                .halign(Align::Center)
+                .valign(Align::Center)
+                .can_target(false)
+                .visible(false)
+                .build();
+
+            let grid_overlay = gtk::Overlay::builder().child(&scrolled_window).build();
+            grid_overlay.add_overlay(&loading_spinner);
+
             let grid_revealer = Revealer::builder()
-                .child(&scrolled_window)
+                .child(&grid_overlay)
                 .transition_type(RevealerTransitionType::SlideLeft)
                 .transition_duration(250)
                 .build();
@@ -288,6 +419,26 @@ mod imp {
                 .width_request(0)
                 .build();
 
+            let symbolic_toggle = gtk::ToggleButton::builder()
+                .icon_name("applications-graphics-symbolic")
+                .tooltip_text("Show Symbolic Icons")
+                .active(self.show_symbolic.get())
+                .build();
+            symbolic_toggle.connect_toggled(clone!(
+                #[weak(rename_to = row)]
+                self.obj(),
+                move |toggle| {
+                    let this = row.imp();
+                    this.show_symbolic.set(toggle.is_active());
+                    this.symbolic_filter.borrow().changed(if toggle.is_active() {
+                        FilterChange::LessStrict
+                    } else {
+                        FilterChange::MoreStrict
+                    });
+                }
+            ));
+            header_bar.pack_end(&symbolic_toggle);
+
             let toolbar_view = adw::ToolbarView::new();
             toolbar_view.add_top_bar(&header_bar);
             toolbar_view.set_content(Some(&grid_revealer));
@@ -338,7 +489,13 @@ mod imp {
                 anim_width,
                 #[weak]
                 anim_height,
-                move |_, _| {
+                #[weak]
+                icons_nav_page,
+                move |_, page| {
+                    if *page != icons_nav_page {
+                        return;
+                    }
+
                     search_entry_revealer.set_reveal_child(false);
                     grid_revealer.set_reveal_child(false);
                     anim_width.set_easing(adw::Easing::EaseInQuad);
@@ -355,9 +512,35 @@ mod imp {
                 nav_view,
                 #[strong]
                 icons_nav_page,
+                #[weak]
+                grid_view,
+                #[weak]
+                loading_spinner,
                 move |_| {
                     nav_view.push(&icons_nav_page);
 
+                    // The icon names are only loaded the first time the grid is shown
+                    let filter_model = grid_view
+                        .model()
+                        .and_downcast::<NoSelection>()
+                        .and_then(|selection_model| selection_model.model())
+                        .and_downcast::<FilterListModel>()
+                        .expect("Needs to be FilterListModel");
+                    if filter_model.model().is_none() {
+                        let display = grid_view.display();
+                        filter_model.set_model(Some(&icon_names_model(&display)));
+
+                        loading_spinner.set_visible(true);
+                        connect_icon_names_loaded(
+                            &display,
+                            clone!(
+                                #[weak]
+                                loading_spinner,
+                                move || loading_spinner.set_visible(false)
+                            ),
+                        );
+                    }
+
                     anim_width.set_easing(adw::Easing::EaseOutQuad);
                     anim_width.set_reverse(false);
                     anim_width.play();
@@ -370,15 +553,140 @@ mod imp {
             nav_view.into()
         }
 
-        fn make_icon_grid(&self) -> GridView {
-            let icon_theme = IconTheme::for_display(&self.obj().display());
-            let binding = icon_theme.icon_names();
+        /// Copy the icon file of the row to the icon theme of the user and refer to it by name.
+        /// Icons already installed with the same name are only replaced if the user agrees
+        async fn install_in_theme(&self) {
+            let obj = self.obj();
+            let path = PathBuf::from(obj.text().as_str());
+            let icon = match super::HicolorIcon::new(&path) {
+                Ok(icon) => icon,
+                Err(e) => return self.show_install_error(&e),
+            };
+
+            let installed = icon.installed_files();
+            if !installed.is_empty() {
+                let dialog = adw::AlertDialog::builder()
+                    .heading("Replace Icon?")
+                    .body(format!(
+                        "An icon named \u{201c}{}\u{201d} is already installed:\n\n{}",
+                        icon.name,
+                        installed
+                            .iter()
+                            .map(|file| display_path(file).to_string_lossy().into_owned())
+                            .collect::<Vec<_>>()
+                            .join("\n")
+                    ))
+                    .close_response("cancel")
+                    .default_response("cancel")
+                    .build();
+                dialog.add_response("cancel", "Cancel");
+                dialog.add_response("replace", "Replace");
+                dialog.set_response_appearance("replace", adw::ResponseAppearance::Destructive);
+                if dialog.choose_future(&*obj).await.as_str() != "replace" {
+                    return;
+                }
+            }
+
+            match icon.install(&path, &installed) {
+                Ok(()) => {
+                    // The theme only notices the new file once it looks through its folders again
+                    let icon_theme = gtk::IconTheme::for_display(&obj.display());
+                    let search_path = icon_theme.search_path();
+                    let search_path: Vec<&Path> =
+                        search_path.iter().map(PathBuf::as_path).collect();
+                    icon_theme.set_search_path(&search_path);
+
+                    obj.set_text(&icon.name);
+                    obj.activate();
+                }
+                Err(e) => self.show_install_error(&e),
+            }
+        }
+
+        fn show_install_error(&self, e: &glib::Error) {
+            let dialog = adw::AlertDialog::builder()
+                .heading("Failed to Install Icon")
+                .body(format!("The icon could not be added to the theme: {e}"))
+                .build();
+            dialog.add_response("close", "Close");
+            dialog.present(Some(&*self.obj()));
+        }
+
+        /// Tell the user the chosen file can't be used, with a button to choose another one
+        /// starting from the default folder
+        fn offer_browsing(&self, title: &str) {
+            let obj = self.obj();
+            let Some(window) = obj.root().and_downcast::<DMWindow>() else {
+                return;
+            };
+
+            let toast = adw::Toast::builder()
+                .title(title)
+                .button_label("Browse…")
+                .build();
+            toast.connect_button_clicked(clone!(
+                #[weak]
+                obj,
+                #[weak]
+                window,
+                move |_| {
+                    obj.imp()
+                        .open_icon_file_dialog(Some(window.upcast_ref::<gtk::Window>()), None);
+                }
+            ));
+            window.add_toast(toast);
+        }
 
-            let mut icon_names: Vec<_> = binding.iter().map(|name| name.as_str()).collect();
-            icon_names.sort();
+        fn open_icon_file_dialog(
+            &self,
+            parent: Option<&gtk::Window>,
+            initial_folder: Option<&Path>,
+        ) {
+            let filter = FileFilter::new();
+            filter.set_name(Some("Image File"));
+            filter.add_mime_type("image/png");
+            filter.add_mime_type("image/jpeg");
+            filter.add_mime_type("image/svg+xml");
+            filter.add_mime_type("image/bmp");
+            filter.add_mime_type("image/webp");
+            let dialog = FileDialog::builder()
+                .title("Choose Icon")
+                .default_filter(&filter)
+                .build();
+            if let Some(initial_folder) = initial_folder {
+                dialog.set_initial_folder(Some(&gio::File::for_path(initial_folder)));
+            }
 
-            let model = StringList::new(&icon_names);
+            let obj = self.obj();
+            dialog.open(
+                parent,
+                Some(&Cancellable::new()),
+                clone!(
+                    #[weak]
+                    obj,
+                    move |res| {
+                        let Ok(res) = res else {
+                            return;
+                        };
+                        // Portals may hand out files that can't be referred to by a path
+                        let Some(path) = res.path() else {
+                            obj.imp()
+                                .offer_browsing("The chosen file has no local path");
+                            return;
+                        };
+                        let Some(path) = display_path(&path).to_str().map(str::to_owned) else {
+                            obj.imp()
+                                .offer_browsing("The path of the chosen file is not valid UTF-8");
+                            return;
+                        };
+                        obj.set_text(&path);
+                        obj.activate();
+                    }
+                ),
+            );
+        }
 
+        fn make_icon_grid(&self) -> GridView {
             let factory = SignalListItemFactory::new();
             factory.connect_setup(move |_, list_item| {
                 let image = gtk::Image::builder()
@@ -386,10 +694,24 @@ mod imp {
                     .css_classes(["lowres-icon"])
                     .build();
 
+                let label = gtk::Label::builder()
+                    .ellipsize(gtk::pango::EllipsizeMode::Middle)
+                    .max_width_chars(10)
+                    .width_chars(10)
+                    .css_classes(["caption", "dim-label"])
+                    .build();
+
+                let cell_box = gtk::Box::builder()
+                    .orientation(Orientation::Vertical)
+                    .spacing(4)
+                    .build();
+                cell_box.append(&image);
+                cell_box.append(&label);
+
                 list_item
                     .downcast_ref::<ListItem>()
                     .expect("Needs to be ListItem")
-                    .set_child(Some(&image));
+                    .set_child(Some(&cell_box));
             });
 
             factory.connect_bind(move |_, list_item| {
@@ -400,20 +722,54 @@ mod imp {
                     .and_downcast::<StringObject>()
                     .expect("Needs to be StringObject");
 
-                let image = list_item
+                let cell_box = list_item
                     .downcast_ref::<ListItem>()
                     .expect("Needs to be ListItem")
                     .child()
+                    .and_downcast::<gtk::Box>()
+                    .expect("Needs to be Box");
+
+                let image = cell_box
+                    .first_child()
                     .and_downcast::<Image>()
                     .expect("Needs to be Image");
 
+                let label = cell_box
+                    .last_child()
+                    .and_downcast::<gtk::Label>()
+                    .expect("Needs to be Label");
+
                 let icon_name = string_object.string();
                 image.set_icon_name(Some(&icon_name));
-                image.set_tooltip_text(Some(&icon_name));
+                cell_box.set_tooltip_text(Some(&icon_name));
+                label.set_label(&icon_name);
             });
 
-            let filter_model =
-                FilterListModel::new(Some(model), Some(self.icon_search_filter.borrow().clone()));
+            // Hide symbolic variants unless requested
+            let symbolic_filter = CustomFilter::new(clone!(
+                #[weak(rename_to = row)]
+                self.obj(),
+                #[upgrade_or]
+                true,
+                move |entry| {
+                    row.imp().show_symbolic.get()
+                        || !entry
+                            .downcast_ref::<StringObject>()
+                            .expect("Needs to be StringObject")
+                            .string()
+                            .ends_with("-symbolic")
+                }
+            ));
+            self.symbolic_filter.set(symbolic_filter.clone());
+
+            let filter = EveryFilter::new();
+            filter.append(self.icon_search_filter.borrow().clone());
+            filter.append(symbolic_filter);
+
+            // The model is set once the grid is first shown, filtering is done incrementally to
+            // avoid blocking the UI on big themes
+            let filter_model = FilterListModel::new(None::<gtk::gio::ListModel>, Some(filter));
+            filter_model.set_incremental(true);
             let selection_model = NoSelection::new(Some(filter_model));
 
             GridView::builder()
@@ -445,6 +801,90 @@ impl IconEntryRow {
     }
 }
 
+/// An icon file to be copied to the hicolor theme in the user data directory. Bitmaps are
+/// converted to PNG, the only format themes have in common with the files that may be chosen
+struct HicolorIcon {
+    /// Name the icon can be looked up with
+    name: String,
+    texture: Option<gdk::Texture>,
+    target: PathBuf,
+}
+
+impl HicolorIcon {
+    fn new(path: &Path) -> Result<Self, glib::Error> {
+        let name = path
+            .file_stem()
+            .map(|stem| stem.to_string_lossy().into_owned())
+            .filter(|stem| !stem.is_empty())
+            .ok_or_else(|| glib::Error::new(gio::IOErrorEnum::InvalidFilename, "No file name"))?;
+        let is_svg = path
+            .extension()
+            .is_some_and(|extension| extension.eq_ignore_ascii_case("svg"));
+        let texture = if is_svg {
+            None
+        } else {
+            Some(gdk::Texture::from_filename(path)?)
+        };
+
+        let size_dir = hicolor_size_dir(texture.as_ref().map(|texture| texture.width()));
+        let extension = if texture.is_some() { "png" } else { "svg" };
+        let target = hicolor_dir()
+            .join(size_dir)
+            .join("apps")
+            .join(format!("{name}.{extension}"));
+        Ok(Self {
+            name,
+            texture,
+            target,
+        })
+    }
+
+    /// Files of the user hicolor theme that already provide an icon with the same name, in any
+    /// size. The theme would keep picking them for some sizes if they were left in place
+    fn installed_files(&self) -> Vec<PathBuf> {
+        let Ok(size_dirs) = fs::read_dir(hicolor_dir()) else {
+            return Vec::new();
+        };
+        let mut files: Vec<PathBuf> = size_dirs
+            .flatten()
+            .flat_map(|size_dir| {
+                HICOLOR_EXTENSIONS.map(|extension| {
+                    size_dir
+                        .path()
+                        .join("apps")
+                        .join(format!("{}.{extension}", self.name))
+                })
+            })
+            .filter(|file| file.is_file())
+            .collect();
+        files.sort();
+        files
+    }
+
+    /// Copy the icon file at `path` to the theme, then remove the `replaced` files it doesn't
+    /// overwrite. They are kept if the copy fails
+    fn install(&self, path: &Path, replaced: &[PathBuf]) -> Result<(), glib::Error> {
+        let io_error =
+            |e: std::io::Error| glib::Error::new(gio::IOErrorEnum::Failed, &e.to_string());
+
+        let dir = self.target.parent().expect("The target is in a directory");
+        fs::create_dir_all(dir).map_err(io_error)?;
+        match &self.texture {
+            Some(texture) => texture
+                .save_to_png(&self.target)
+                .map_err(|e| glib::Error::new(gio::IOErrorEnum::Failed, &e.to_string()))?,
+            None => {
+                fs::copy(path, &self.target).map_err(io_error)?;
+            }
+        }
+
+        for file in replaced.iter().filter(|file| **file != self.target) {
+            fs::remove_file(file).map_err(io_error)?;
+        }
+        Ok(())
+    }
+}
+
 impl ToGIcon for IconEntryRow {
     fn icon_string(&self) -> Option<String> {
         Some(self.text().to_string())
@@ -0,0 +1,121 @@
+/*
+* Copyright © 2025 Alessandro Balducci
+*
+* This file is part of Desktop File Editor.
+* Desktop File Editor is free software: you can redistribute it and/or modify it under the terms of the
+* GNU General Public License as published by the Free Software Foundation,
+* either version 3 of the License, or (at your option) any later version.
+* Desktop File Editor is distributed in the hope that it will be useful, but WITHOUT ANY WARRANTY;
+* without even the implied warranty of MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.
+* See the GNU General Public License for more details.
+* You should have received a copy of the GNU General Public License along with Desktop File Editor. If not, see <https://www.gnu.org/licenses/>.
+*/
+
+use std::{
+    cell::{Cell, RefCell},
+    rc::Rc,
+};
+
+use gtk::{
+    gdk,
+    glib::{self, ControlFlow, WeakRef},
+    prelude::*,
+    IconTheme, StringList,
+};
+
+/// Number of icon names appended to the model in a single main loop iteration
+const PAGE_SIZE: usize = 500;
+
+struct IconNames {
+    display: WeakRef<gdk::Display>,
+    model: StringList,
+    loaded: Cell<bool>,
+    loaded_callbacks: RefCell<Vec<Box<dyn FnOnce()>>>,
+}
+
+thread_local! {
+    // Building the list of icon names is expensive for big themes, hence the model is built only
+    // once per display and shared by every icon chooser
+    static ICON_NAMES: RefCell<Vec<Rc<IconNames>>> = const { RefCell::new(Vec::new()) };
+}
+
+fn icon_names(display: &gdk::Display) -> Rc<IconNames> {
+    let (icon_names, is_new) = ICON_NAMES.with_borrow_mut(|cache| {
+        // Forget models of displays that no longer exist
+        cache.retain(|icon_names| icon_names.display.upgrade().is_some());
+
+        if let Some(icon_names) = cache
+            .iter()
+            .find(|icon_names| icon_names.display.upgrade().as_ref() == Some(display))
+        {
+            return (icon_names.clone(), false);
+        }
+
+        let weak_display = WeakRef::new();
+        weak_display.set(Some(display));
+        let icon_names = Rc::new(IconNames {
+            display: weak_display,
+            model: StringList::new(&[]),
+            loaded: Cell::new(false),
+            loaded_callbacks: RefCell::new(Vec::new()),
+        });
+        cache.push(icon_names.clone());
+        (icon_names, true)
+    });
+
+    if is_new {
+        populate(icon_names.clone(), display);
+    }
+
+    icon_names
+}
+
+/// Fills the model a page at a time from the main loop, so that big themes don't block the UI
+fn populate(icon_names: Rc<IconNames>, display: &gdk::Display) {
+    let icon_theme = IconTheme::for_display(display);
+    let mut names: Vec<String> = icon_theme
+        .icon_names()
+        .iter()
+        .map(|name| name.to_string())
+        .collect();
+    names.sort();
+
+    let mut remaining = names.into_iter();
+    glib::idle_add_local(move || {
+        let page: Vec<String> = remaining.by_ref().take(PAGE_SIZE).collect();
+        if !page.is_empty() {
+            let page: Vec<&str> = page.iter().map(String::as_str).collect();
+            icon_names
+                .model
+                .splice(icon_names.model.n_items(), 0, &page);
+            return ControlFlow::Continue;
+        }
+
+        icon_names.loaded.set(true);
+        let callbacks = icon_names.loaded_callbacks.take();
+        for callback in callbacks {
+            callback();
+        }
+        ControlFlow::Break
+    });
+}
+
+/// Returns the sorted list of icon names provided by the icon theme of the given display.
+/// The model is filled asynchronously, use [`connect_icon_names_loaded`] to know when it is complete
+pub fn icon_names_model(display: &gdk::Display) -> StringList {
+    icon_names(display).model.clone()
+}
+
+/// Calls `callback` once the icon names model of the given display is completely populated,
+/// immediately if it already is
+pub fn connect_icon_names_loaded(display: &gdk::Display, callback: impl FnOnce() + 'static) {
+    let icon_names = icon_names(display);
+    if icon_names.loaded.get() {
+        callback();
+    } else {
+        icon_names
+            .loaded_callbacks
+            .borrow_mut()
+            .push(Box::new(callback));
+    }
+}
@@ -0,0 +1,215 @@
+/*
+* Copyright © 2025 Alessandro Balducci
+*
+* This file is part of Desktop File Editor.
+* Desktop File Editor is free software: you can redistribute it and/or modify it under the terms of the
+* GNU General Public License as published by the Free Software Foundation,
+* either version 3 of the License, or (at your option) any later version.
+* Desktop File Editor is distributed in the hope that it will be useful, but WITHOUT ANY WARRANTY;
+* without even the implied warranty of MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.
+* See the GNU General Public License for more details.
+* You should have received a copy of the GNU General Public License along with Desktop File Editor. If not, see <https://www.gnu.org/licenses/>.
+*/
+
+use adw::prelude::*;
+use gtk::glib;
+use gtk::glib::property::PropertySet;
+use gtk::glib::subclass::types::ObjectSubclassIsExt;
+
+use crate::desktop_file_view::desktop_entry_ext::{escape_line_breaks, unescape_line_breaks};
+use crate::desktop_file_view::string_entry_row::inherited_from_tooltip;
+
+/// Values longer than this many characters are edited in a [`LongTextRow`]
+const LONG_VALUE_LENGTH: usize = 60;
+
+mod imp {
+    use std::cell::{Cell, RefCell};
+
+    use adw::prelude::*;
+    use adw::subclass::prelude::*;
+    use gtk::glib::{self, clone, closure, Object, Properties};
+    use gtk::{Label, Widget};
+
+    use crate::desktop_file_view::languages::LANGUAGES_LOCALE_MAP;
+
+    #[derive(Default, Properties)]
+    #[properties(wrapper_type = super::LongTextRow)]
+    pub struct LongTextRow {
+        #[property(get, set)]
+        pub localizable: Cell<bool>,
+
+        #[property(get, set)]
+        pub locale: RefCell<Option<String>>,
+
+        /// The value as stored in the desktop file, with line breaks escaped
+        #[property(get, set = LongTextRow::set_text)]
+        pub text: RefCell<String>,
+
+        pub text_view: RefCell<gtk::TextView>,
+        pub inherited: RefCell<Option<String>>,
+    }
+
+    #[glib::object_subclass]
+    impl ObjectSubclass for LongTextRow {
+        const NAME: &'static str = "LongTextRow";
+        type Type = super::LongTextRow;
+        type ParentType = adw::ExpanderRow;
+    }
+
+    #[glib::derived_properties()]
+    impl ObjectImpl for LongTextRow {
+        fn constructed(&self) {
+            self.parent_constructed();
+            let obj = self.obj();
+
+            obj.add_css_class("longtextrow");
+            obj.set_use_markup(false);
+            obj.set_subtitle_lines(1);
+
+            let locale_label = Label::builder()
+                .tooltip_text("Locale")
+                .valign(gtk::Align::Center)
+                .css_classes(["locale-emblem"])
+                .build();
+            obj.add_prefix(&locale_label);
+
+            obj.property_expression_weak("locale")
+                .chain_closure::<bool>(closure!(|_: Option<Object>, locale: Option<String>| {
+                    locale.is_some()
+                }))
+                .bind(&locale_label, "visible", Widget::NONE);
+
+            obj.property_expression_weak("locale")
+                .bind(&locale_label, "label", Widget::NONE);
+
+            obj.property_expression_weak("locale")
+                .chain_closure::<String>(closure!(|_: Option<Object>, locale: Option<String>| {
+                    LANGUAGES_LOCALE_MAP
+                        .get(&locale.unwrap_or_default()[..])
+                        .unwrap_or(&"Locale")
+                        .to_string()
+                }))
+                .bind(&locale_label, "tooltip_text", Widget::NONE);
+
+            let text_view = gtk::TextView::builder()
+                .wrap_mode(gtk::WrapMode::WordChar)
+                .accepts_tab(false)
+                .top_margin(12)
+                .bottom_margin(12)
+                .left_margin(12)
+                .right_margin(12)
+                .build();
+            text_view.buffer().connect_changed(clone!(
+                #[weak(rename_to=this)]
+                self,
+                move |buffer| {
+                    let (start, end) = buffer.bounds();
+                    let text = super::escape_line_breaks(&buffer.text(&start, &end, false));
+                    if *this.text.borrow() != text {
+                        this.text.replace(text);
+                        this.obj().notify_text();
+                    }
+                    this.update_subtitle();
+                }
+            ));
+
+            let row = gtk::ListBoxRow::builder()
+                .activatable(false)
+                .child(&text_view)
+                .build();
+            obj.add_row(&row);
+
+            self.text_view.replace(text_view);
+        }
+    }
+
+    impl LongTextRow {
+        fn set_text(&self, text: &str) {
+            if *self.text.borrow() == text {
+                return;
+            }
+
+            // Updating the buffer also stores the text and notifies the change
+            self.text_view
+                .borrow()
+                .buffer()
+                .set_text(&super::unescape_line_breaks(text));
+        }
+
+        /// Show the first line of the value while the row is collapsed, or the inherited value if
+        /// there is none
+        pub fn update_subtitle(&self) {
+            let text = self.text.borrow();
+            let subtitle = match (text.is_empty(), self.inherited.borrow().as_ref()) {
+                (true, Some(inherited)) => inherited.clone(),
+                _ => super::unescape_line_breaks(&text)
+                    .lines()
+                    .next()
+                    .unwrap_or_default()
+                    .to_string(),
+            };
+
+            let obj = self.obj();
+            if obj.subtitle().as_str() != subtitle {
+                obj.set_subtitle(&subtitle);
+            }
+        }
+    }
+
+    impl ExpanderRowImpl for LongTextRow {}
+    impl PreferencesRowImpl for LongTextRow {}
+    impl ListBoxRowImpl for LongTextRow {}
+    impl WidgetImpl for LongTextRow {}
+}
+
+glib::wrapper! {
+    /// Row editing a single value in a multi-line text view, for values too long to comfortably
+    /// edit in an entry
+    pub struct LongTextRow(ObjectSubclass<imp::LongTextRow>)
+        @extends adw::ExpanderRow, adw::PreferencesRow, gtk::ListBoxRow, gtk::Widget,
+        @implements gtk::Accessible, gtk::Actionable, gtk::Buildable, gtk::ConstraintTarget;
+}
+
+impl LongTextRow {
+    pub fn new(localizable: bool, locale: Option<String>) -> Self {
+        glib::Object::builder()
+            .property("localizable", localizable)
+            .property("locale", locale)
+            .build()
+    }
+
+    pub fn with_default_locale(localizable: bool) -> Self {
+        Self::new(localizable, None)
+    }
+
+    pub fn change_locale(&self, locale: Option<String>, localized_text: &str) {
+        if !self.localizable() {
+            return;
+        }
+
+        let freeze_guard = self.freeze_notify();
+        let imp = self.imp();
+        imp.locale.set(locale);
+        self.notify_locale();
+        self.set_text(localized_text);
+        drop(freeze_guard);
+    }
+
+    /// Show the value the row falls back to when it has no value for the current locale
+    pub fn set_inherited_value(&self, inherited: Option<(&str, Option<&str>)>) {
+        let imp = self.imp();
+        let tooltip = inherited.map(|(_, from_locale)| inherited_from_tooltip(from_locale));
+        imp.inherited
+            .replace(inherited.map(|(value, _)| unescape_line_breaks(value)));
+
+        if self.tooltip_text().as_deref() != tooltip.as_deref() {
+            self.set_tooltip_text(tooltip.as_deref());
+        }
+        imp.update_subtitle();
+    }
+}
+
+/// Whether a value is long enough to be edited in a [`LongTextRow`] instead of a single line entry
+pub fn is_long_value(value: &str) -> bool {
+    value.chars().count() > LONG_VALUE_LENGTH || value.contains("\\n")
+}
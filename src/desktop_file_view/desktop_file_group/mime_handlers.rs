@@ -0,0 +1,299 @@
+/*
+* Copyright © 2025 Alessandro Balducci
+*
+* This file is part of Desktop File Editor.
+* Desktop File Editor is free software: you can redistribute it and/or modify it under the terms of the
+* GNU General Public License as published by the Free Software Foundation,
+* either version 3 of the License, or (at your option) any later version.
+* Desktop File Editor is distributed in the hope that it will be useful, but WITHOUT ANY WARRANTY;
+* without even the implied warranty of MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.
+* See the GNU General Public License for more details.
+* You should have received a copy of the GNU General Public License along with Desktop File Editor. If not, see <https://www.gnu.org/licenses/>.
+*/
+
+//! Popover showing which applications are registered for the MIME types of an entry, and making
+//! the entry their default application
+
+use std::cell::RefCell;
+use std::rc::Rc;
+
+use adw::prelude::*;
+use gtk::gio;
+use gtk::glib::{self, clone};
+
+use crate::desktop_file_view::DesktopFileView;
+
+use super::tagged_entry_row::TaggedEntryRow;
+
+/// A MIME type the entry could become the default application of
+struct DefaultCandidate {
+    mime_type: String,
+    current_default: Option<gio::AppInfo>,
+    check: gtk::CheckButton,
+}
+
+/// Button opening the applications that currently handle each MIME type listed in `row`, the
+/// list is read again every time it opens so it follows the edits. The types can be checked to
+/// make the saved file of `desktop_file_view` their default application
+pub fn mime_handlers_button(
+    row: &TaggedEntryRow,
+    desktop_file_view: glib::WeakRef<DesktopFileView>,
+) -> gtk::MenuButton {
+    let content = gtk::Box::builder()
+        .orientation(gtk::Orientation::Vertical)
+        .spacing(18)
+        .margin_start(6)
+        .margin_end(6)
+        .margin_top(6)
+        .margin_bottom(6)
+        .build();
+
+    let scrolled_window = gtk::ScrolledWindow::builder()
+        .hscrollbar_policy(gtk::PolicyType::Never)
+        .propagate_natural_height(true)
+        .max_content_height(400)
+        .min_content_width(360)
+        .child(&content)
+        .build();
+
+    let make_default_button = gtk::Button::builder()
+        .label("Make Default…")
+        .css_classes(["suggested-action"])
+        .build();
+
+    let popover_content = gtk::Box::new(gtk::Orientation::Vertical, 6);
+    popover_content.append(&scrolled_window);
+    popover_content.append(&make_default_button);
+
+    let candidates: Rc<RefCell<Vec<DefaultCandidate>>> = Rc::default();
+    let popover = gtk::Popover::builder().child(&popover_content).build();
+    popover.connect_show(clone!(
+        #[weak]
+        row,
+        #[weak]
+        content,
+        #[weak]
+        make_default_button,
+        #[strong]
+        candidates,
+        #[strong]
+        desktop_file_view,
+        move |_| {
+            while let Some(child) = content.first_child() {
+                content.remove(&child);
+            }
+            candidates.borrow_mut().clear();
+            make_default_button.set_visible(false);
+
+            let mime_types = row.values();
+            if mime_types.is_empty() {
+                let label = gtk::Label::builder()
+                    .label("No MIME types listed")
+                    .css_classes(["dim-label"])
+                    .build();
+                content.append(&label);
+                return;
+            }
+
+            let app_info = desktop_file_view
+                .upgrade()
+                .and_then(|view| saved_app_info(&view));
+            for mime_type in &mime_types {
+                let (group, candidate) = mime_type_group(mime_type, app_info.as_ref());
+                content.append(&group);
+                if let Some(candidate) = candidate {
+                    candidate.check.connect_toggled(clone!(
+                        #[weak]
+                        make_default_button,
+                        #[weak]
+                        candidates,
+                        move |_| {
+                            let any_checked = candidates
+                                .borrow()
+                                .iter()
+                                .any(|candidate| candidate.check.is_active());
+                            make_default_button.set_sensitive(any_checked);
+                        }
+                    ));
+                    candidates.borrow_mut().push(candidate);
+                }
+            }
+
+            make_default_button.set_visible(!candidates.borrow().is_empty());
+            make_default_button.set_sensitive(false);
+        }
+    ));
+
+    make_default_button.connect_clicked(clone!(
+        #[weak]
+        row,
+        #[weak]
+        popover,
+        move |_| {
+            let Some(app_info) = desktop_file_view
+                .upgrade()
+                .and_then(|view| saved_app_info(&view))
+            else {
+                return;
+            };
+            let chosen: Vec<(String, Option<gio::AppInfo>)> = candidates
+                .borrow()
+                .iter()
+                .filter(|candidate| candidate.check.is_active())
+                .map(|candidate| {
+                    (
+                        candidate.mime_type.clone(),
+                        candidate.current_default.clone(),
+                    )
+                })
+                .collect();
+            popover.popdown();
+            confirm_make_default(row.upcast(), app_info, chosen);
+        }
+    ));
+
+    gtk::MenuButton::builder()
+        .icon_name("application-x-addon-symbolic")
+        .halign(gtk::Align::Center)
+        .valign(gtk::Align::Center)
+        .popover(&popover)
+        .css_classes(["circular"])
+        .tooltip_text("Current Handlers")
+        .build()
+}
+
+/// The application of the file of the view as it is saved, only saved files can be registered
+/// as handlers
+fn saved_app_info(view: &DesktopFileView) -> Option<gio::DesktopAppInfo> {
+    let path = view.path();
+    if path.as_os_str().is_empty() {
+        return None;
+    }
+    gio::DesktopAppInfo::from_filename(path)
+}
+
+/// Group listing the applications registered for `mime_type`, the default one is marked. When
+/// `app_info` is not the default yet, the group can be checked to make it so
+fn mime_type_group(
+    mime_type: &str,
+    app_info: Option<&gio::DesktopAppInfo>,
+) -> (adw::PreferencesGroup, Option<DefaultCandidate>) {
+    let group = adw::PreferencesGroup::builder()
+        .title(mime_type)
+        .description(gio::content_type_get_description(mime_type))
+        .build();
+
+    let default = gio::AppInfo::default_for_type(mime_type, false);
+    let candidate = app_info
+        .filter(|app_info| !default.as_ref().is_some_and(|d| d.equal(*app_info)))
+        .map(|_| {
+            let check = gtk::CheckButton::builder()
+                .label("Make Default")
+                .valign(gtk::Align::Center)
+                .build();
+            group.set_header_suffix(Some(&check));
+            DefaultCandidate {
+                mime_type: mime_type.to_string(),
+                current_default: default.clone(),
+                check,
+            }
+        });
+
+    let handlers = gio::AppInfo::all_for_type(mime_type);
+    if handlers.is_empty() {
+        let row = adw::ActionRow::builder()
+            .title("No applications handle this type yet")
+            .css_classes(["dim-label"])
+            .build();
+        group.add(&row);
+        return (group, candidate);
+    }
+
+    for app_info in handlers {
+        let row = adw::ActionRow::builder()
+            .title(app_info.display_name())
+            .subtitle(app_info.id().unwrap_or_default())
+            .build();
+        let icon = app_info
+            .icon()
+            .unwrap_or_else(|| gio::ThemedIcon::new("application-x-executable").upcast());
+        row.add_prefix(
+            &gtk::Image::builder()
+                .gicon(&icon)
+                .icon_size(gtk::IconSize::Large)
+                .build(),
+        );
+        if default.as_ref().is_some_and(|d| d.equal(&app_info)) {
+            let label = gtk::Label::builder()
+                .label("Default")
+                .valign(gtk::Align::Center)
+                .css_classes(["accent"])
+                .build();
+            row.add_suffix(&label);
+        }
+        group.add(&row);
+    }
+
+    (group, candidate)
+}
+
+/// Show what changes for each chosen MIME type, then write the new defaults to mimeapps.list
+fn confirm_make_default(
+    parent: gtk::Widget,
+    app_info: gio::DesktopAppInfo,
+    chosen: Vec<(String, Option<gio::AppInfo>)>,
+) {
+    let summary: Vec<String> = chosen
+        .iter()
+        .map(|(mime_type, current_default)| match current_default {
+            Some(current_default) => {
+                let name = current_default.display_name();
+                format!("• {mime_type}, instead of {name}")
+            }
+            None => format!("• {mime_type}, which has no default yet"),
+        })
+        .collect();
+
+    let dialog = adw::AlertDialog::builder()
+        .heading("Make Default Application?")
+        .body(format!(
+            "{} will open these types of files:\n\n{}",
+            app_info.display_name(),
+            summary.join("\n")
+        ))
+        .build();
+    dialog.add_responses(&[("cancel", "Cancel"), ("apply", "Make Default")]);
+    dialog.set_response_appearance("apply", adw::ResponseAppearance::Suggested);
+    dialog.set_default_response(Some("apply"));
+    dialog.set_close_response("cancel");
+
+    dialog.connect_response(
+        Some("apply"),
+        clone!(
+            #[weak]
+            parent,
+            move |_, _| {
+                let failures: Vec<String> = chosen
+                    .iter()
+                    .filter_map(|(mime_type, _)| {
+                        let e = app_info.set_as_default_for_type(mime_type).err()?;
+                        eprintln!("Failed to set the default application for {mime_type}: {e}");
+                        Some(format!("{mime_type}: {e}"))
+                    })
+                    .collect();
+                if failures.is_empty() {
+                    return;
+                }
+
+                let dialog = adw::AlertDialog::builder()
+                    .heading("Failed to Set Default")
+                    .body(failures.join("\n"))
+                    .build();
+                dialog.add_response("close", "Close");
+                dialog.present(Some(&parent));
+            }
+        ),
+    );
+
+    dialog.present(Some(&parent));
+}
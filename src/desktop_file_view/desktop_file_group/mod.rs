@@ -11,28 +11,46 @@
 * You should have received a copy of the GNU General Public License along with Desktop File Editor. If not, see <https://www.gnu.org/licenses/>.
 */
 
+mod category_chooser;
+mod exec_entry_row;
+mod flatpak_export;
 mod icon_entry_row;
+mod icon_names;
+mod long_text_row;
+mod mime_handlers;
 mod new_entry_dialog;
 mod tagged_entry_row;
 mod util;
 
 use std::borrow::Borrow;
-use std::collections::btree_map::Entry;
+use std::path::Path;
 
 use adw::{prelude::*, SwitchRow};
+use category_chooser::category_chooser_button;
+use exec_entry_row::ExecEntryRow;
+use freedesktop_desktop_entry::DesktopEntry;
 use gtk::gio::Cancellable;
 use gtk::glib::clone::Downgrade;
 use gtk::glib::{self, property::PropertySet, subclass::types::ObjectSubclassIsExt};
 use gtk::glib::{clone, closure_local, SignalHandlerId};
 use icon_entry_row::IconEntryRow;
+use long_text_row::LongTextRow;
+use mime_handlers::mime_handlers_button;
 use once_cell::sync::Lazy;
 use regex::Regex;
 use tagged_entry_row::TaggedEntryRow;
-use util::{add_additional_options_buttons, make_additional_options_menu};
+use util::{
+    add_additional_options_buttons, make_additional_option_button, make_additional_options_menu,
+};
 
+use crate::dbus::{self, ExportedInterfaces};
+use crate::desktop_file_view::categories::category_issue;
 use crate::desktop_file_view::desktop_entry_ext::DesktopEntryExt;
 use crate::desktop_file_view::imp::DesktopEntryCell;
+use crate::desktop_file_view::known_entries::{key_info, ValueType, NOTE_KEY};
 use crate::desktop_file_view::string_entry_row::StringEntryRow;
+use crate::settings::settings;
+use crate::shellparse::{self, quote_exec_arg};
 use crate::window::file_entry::ToGIcon;
 
 use super::DesktopFileView;
@@ -41,13 +59,12 @@ static DESKTOP_ACTION_RE: Lazy<Regex> =
     Lazy::new(|| Regex::new("^Desktop Action (.+)$").expect("Failed to compile regex"));
 
 mod imp {
-    use crate::desktop_file_view::known_entries::KEYS_DESCRIPTIONS;
+    use crate::desktop_file_view::known_entries::{key_info, GroupKind};
     use crate::desktop_file_view::string_entry_row::StringEntryRow;
-    use std::borrow::Borrow;
     use std::cell::{Cell, RefCell};
 
+    use adw::prelude::*;
     use adw::subclass::prelude::*;
-    use adw::{prelude::*, AlertDialog};
 
     use gtk::gio::Cancellable;
     use gtk::glib::clone;
@@ -56,11 +73,12 @@ mod imp {
     use gtk::glib::Properties;
     use gtk::{glib, template_callbacks, CompositeTemplate};
 
-    use crate::desktop_file_view::imp::DesktopEntryCell;
     use crate::desktop_file_view::DesktopFileView;
 
+    use super::long_text_row::LongTextRow;
     use super::new_entry_dialog::NewEntryDialog;
     use super::tagged_entry_row::TaggedEntryRow;
+    use super::util::{make_custom_key_dialog, make_key_info_dialog};
     use super::RowWidgetExt;
 
     #[derive(CompositeTemplate, Default, Properties)]
@@ -104,13 +122,14 @@ mod imp {
                 Some(&String::static_variant_type()),
                 |group, _action, args| {
                     let variant = args.expect("Missing action parameter");
-                    if let Some(key) = String::from_variant(variant) {
-                        let info = KEYS_DESCRIPTIONS[&key[..]];
-                        let dialog = AlertDialog::builder().heading(&key).body(info).build();
-
-                        dialog.add_response("close", "Close");
-                        dialog.present(Some(group));
-                    }
+                    let Some(key) = String::from_variant(variant) else {
+                        return;
+                    };
+                    let dialog = match key_info(&key) {
+                        Some(info) => make_key_info_dialog(info, GroupKind::of(&group.name())),
+                        None => make_custom_key_dialog(&key),
+                    };
+                    dialog.present(Some(group));
                 },
             );
 
@@ -174,9 +193,48 @@ mod imp {
                 },
             );
 
+            klass.install_action(
+                "desktop_file_group.move_entry",
+                Some(&String::static_variant_type()),
+                |group, _action, args| {
+                    let variant = args.expect("Missing action parameter");
+                    if let Some(key) = String::from_variant(variant) {
+                        group.show_move_entry_dialog(key);
+                    }
+                },
+            );
+
             klass.install_action("desktop_file_group.edit", None, |group, _action, _args| {
                 group.show_edit_dialog();
             });
+
+            klass.install_action("desktop_file_group.copy", None, |group, _action, _args| {
+                group.copy_to_clipboard();
+            });
+
+            klass.install_action_async(
+                "desktop_file_group.check_implements",
+                None,
+                |group, _action, _args| async move {
+                    group.check_implemented_interfaces().await;
+                },
+            );
+
+            klass.install_action(
+                "desktop_file_group.show_effective_command",
+                None,
+                |group, _action, _args| {
+                    group.show_effective_command();
+                },
+            );
+
+            klass.install_action(
+                "desktop_file_group.show_launch_environment",
+                None,
+                |group, _action, _args| {
+                    group.show_launch_environment();
+                },
+            );
         }
 
         fn instance_init(obj: &InitializingObject<Self>) {
@@ -266,14 +324,9 @@ mod imp {
             // Automatically modify the desktop entry state to be in sync with the group name
             if let Some(desktop_file_view) = self.desktop_file_view.borrow().as_ref() {
                 if let Some(desktop_file_view) = desktop_file_view.upgrade() {
-                    let desktop_entry_rc = &desktop_file_view.desktop_entry();
-                    let desktop_entry_cell: &DesktopEntryCell = desktop_entry_rc.borrow();
-                    let mut desktop_entry = desktop_entry_cell.borrow_mut();
-
-                    let value = desktop_entry.groups.0.remove(&old_name);
-                    if let Some(value) = value {
-                        desktop_entry.groups.0.insert(name.to_string(), value);
-                    }
+                    desktop_file_view
+                        .imp()
+                        .edit_entry(|editor| editor.rename_group(&old_name, name.to_string()));
                     desktop_file_view.set_content_changed(true);
                 }
             }
@@ -283,6 +336,7 @@ mod imp {
     pub enum LocalizedWidget {
         StringEntry(StringEntryRow),
         StringList(TaggedEntryRow),
+        LongText(LongTextRow),
     }
 
     impl LocalizedWidget {
@@ -294,6 +348,23 @@ mod imp {
                 LocalizedWidget::StringList(tagged_entry_row) => {
                     tagged_entry_row.change_locale(locale, localized_value)
                 }
+                LocalizedWidget::LongText(long_text_row) => {
+                    long_text_row.change_locale(locale, localized_value)
+                }
+            }
+        }
+
+        pub fn set_inherited_value(&self, inherited: Option<(&str, Option<&str>)>) {
+            match self {
+                LocalizedWidget::StringEntry(string_entry_row) => {
+                    string_entry_row.set_inherited_value(inherited)
+                }
+                LocalizedWidget::StringList(tagged_entry_row) => {
+                    tagged_entry_row.set_inherited_value(inherited)
+                }
+                LocalizedWidget::LongText(long_text_row) => {
+                    long_text_row.set_inherited_value(inherited)
+                }
             }
         }
 
@@ -301,6 +372,7 @@ mod imp {
             match self {
                 LocalizedWidget::StringEntry(string_entry_row) => string_entry_row.entry_key(),
                 LocalizedWidget::StringList(tagged_entry_row) => tagged_entry_row.entry_key(),
+                LocalizedWidget::LongText(long_text_row) => long_text_row.entry_key(),
             }
         }
     }
@@ -316,6 +388,12 @@ mod imp {
             Self::StringList(value)
         }
     }
+
+    impl From<LongTextRow> for LocalizedWidget {
+        fn from(value: LongTextRow) -> Self {
+            Self::LongText(value)
+        }
+    }
 }
 
 glib::wrapper! {
@@ -348,7 +426,8 @@ impl DesktopFileGroup {
                 let desktop_entry_cell: &DesktopEntryCell = desktop_entry_rc.borrow();
                 let desktop_entry = desktop_entry_cell.borrow();
 
-                if let Some(keymap) = desktop_entry.sorted_keymap(&self.name()) {
+                let key_order = settings().key_order_list();
+                if let Some(keymap) = desktop_entry.sorted_keymap(&self.name(), &key_order) {
                     for (key, val) in keymap.iter() {
                         let val = &val.0;
                         let entry_widget = self.make_entry_widget(key, val);
@@ -375,19 +454,33 @@ impl DesktopFileGroup {
         let desktop_file_view = imp.desktop_file_view.borrow().as_ref().unwrap().upgrade();
         if let Some(desktop_file_view) = desktop_file_view {
             for widget in imp.localized_widgets.borrow().iter() {
-                let value = {
+                let (value, inherited) = {
                     let desktop_entry_rc = &desktop_file_view.desktop_entry();
                     let desktop_entry_cell: &DesktopEntryCell = desktop_entry_rc.borrow();
                     let desktop_entry = desktop_entry_cell.borrow();
 
                     let entry_key = widget.entry_key();
 
-                    desktop_entry
+                    let value = desktop_entry
                         .entry(&self.name(), &entry_key, locale)
                         .unwrap_or_default()
-                        .to_string()
+                        .to_string();
+
+                    // Value the entry resolves to when it is not translated for this locale
+                    let inherited = locale.and_then(|locale| {
+                        desktop_entry
+                            .inherited_entry(&self.name(), &entry_key, locale)
+                            .map(|(value, from_locale)| (value.to_string(), from_locale))
+                    });
+
+                    (value, inherited)
                 };
                 widget.change_locale(locale.map(|s| s.to_owned()), &value);
+                widget.set_inherited_value(
+                    inherited
+                        .as_ref()
+                        .map(|(value, from_locale)| (&value[..], from_locale.as_deref())),
+                );
             }
         }
     }
@@ -396,23 +489,27 @@ impl DesktopFileGroup {
         let imp = self.imp();
         let desktop_file_view = imp.desktop_file_view.borrow().as_ref().unwrap().upgrade();
         // Try to add the entry to the Desktop Entry object state
-        let added = if let Some(desktop_file_view) = desktop_file_view {
-            let desktop_entry_rc = &desktop_file_view.desktop_entry();
-            let desktop_entry_cell: &DesktopEntryCell = desktop_entry_rc.borrow();
-            let mut desktop_entry = desktop_entry_cell.borrow_mut();
-
-            let added = desktop_entry.add_entry(self.name(), key.to_string());
-            desktop_file_view.set_content_changed(added);
-            added
+        let default_value = if let Some(desktop_file_view) = desktop_file_view {
+            let default_value = desktop_file_view.imp().edit_entry(|editor| {
+                let added = editor.add_entry(self.name(), key.to_string());
+
+                // Set the default value for the appropriate type
+                added.then(|| {
+                    EntryWidgetType::from_entry_key(key).default_value(
+                        key,
+                        &self.name(),
+                        editor.entry(),
+                    )
+                })
+            });
+            desktop_file_view.set_content_changed(default_value.is_some());
+            default_value
         } else {
-            false
+            None
         };
 
-        if added {
-            // Set the default value for the appropriate type
-            let widget_type = &EntryWidgetType::from_entry_key(key);
-            let default_value = widget_type.default_value();
-            self.set_entry_value(key.to_string(), default_value.to_string(), None);
+        if let Some(default_value) = default_value {
+            self.set_entry_value(key.to_string(), default_value, None);
 
             // Update the UI
             self.populate();
@@ -426,6 +523,22 @@ impl DesktopFileGroup {
         }
     }
 
+    /// Moves the focus to the row of `key`, adding the entry first if the group doesn't have it
+    pub fn focus_entry(&self, key: &str) {
+        match self.find_entry_widget(key) {
+            Some(row) => {
+                row.grab_focus();
+            }
+            None => self.add_entry(key),
+        }
+
+        // Long values can only be edited once their row is expanded
+        let row = self.find_entry_widget(key);
+        if let Some(row) = row.and_downcast_ref::<adw::ExpanderRow>() {
+            row.set_expanded(true);
+        }
+    }
+
     fn find_entry_widget(&self, key: &str) -> Option<adw::PreferencesRow> {
         let imp = self.imp();
         let mut child = imp.entry_list.first_child();
@@ -506,19 +619,385 @@ impl DesktopFileGroup {
             .unwrap()
             .upgrade();
         if let Some(desktop_file_view) = desktop_file_view {
+            desktop_file_view
+                .imp()
+                .edit_entry(|editor| editor.remove_entry(self.name(), key));
+            desktop_file_view.set_content_changed(true);
+        }
+
+        self.populate()
+    }
+
+    /// Ask which group `key` belongs to, then move it there with all of its translations
+    fn show_move_entry_dialog(&self, key: String) {
+        let desktop_file_view = self
+            .imp()
+            .desktop_file_view
+            .borrow()
+            .as_ref()
+            .and_then(|view| view.upgrade());
+        let Some(desktop_file_view) = desktop_file_view else {
+            return;
+        };
+
+        let group_name = self.name();
+        let other_groups: Vec<String> = {
             let desktop_entry_rc = &desktop_file_view.desktop_entry();
             let desktop_entry_cell: &DesktopEntryCell = desktop_entry_rc.borrow();
-            let mut desktop_entry = desktop_entry_cell.borrow_mut();
+            let desktop_entry = desktop_entry_cell.borrow();
+            desktop_entry
+                .groups
+                .0
+                .keys()
+                .filter(|name| **name != group_name)
+                .cloned()
+                .collect()
+        };
 
-            desktop_entry.remove_entry(self.name(), key);
-            desktop_file_view.set_content_changed(true);
+        let dialog = adw::AlertDialog::builder()
+            .heading(format!("Move {key}"))
+            .body(format!(
+                "The value and all the translations of {key} are moved to the chosen group, \
+                 replacing the ones it already has"
+            ))
+            .build();
+        dialog.add_responses(&[("cancel", "Cancel"), ("move", "Move")]);
+        dialog.set_response_appearance("move", adw::ResponseAppearance::Suggested);
+        dialog.set_default_response(Some("move"));
+        dialog.set_close_response("cancel");
+
+        if other_groups.is_empty() {
+            dialog.set_body("This file has no other group");
+            dialog.set_response_enabled("move", false);
+            dialog.present(Some(self));
+            return;
         }
 
-        self.populate()
+        let names: Vec<&str> = other_groups.iter().map(String::as_str).collect();
+        let dropdown = gtk::DropDown::from_strings(&names);
+        dialog.set_extra_child(Some(&dropdown));
+
+        dialog.choose(
+            self,
+            Cancellable::NONE,
+            clone!(
+                #[weak]
+                desktop_file_view,
+                move |response| {
+                    if response != "move" {
+                        return;
+                    }
+                    let Some(target) = other_groups.get(dropdown.selected() as usize) else {
+                        return;
+                    };
+
+                    let moved = desktop_file_view
+                        .imp()
+                        .edit_entry(|editor| editor.move_entry(&group_name, target, &key));
+                    if moved {
+                        desktop_file_view.set_content_changed(true);
+                        desktop_file_view.refresh_groups();
+                    }
+                }
+            ),
+        );
+    }
+
+    /// Check whether the application actually exports the interfaces listed in the `Implements`
+    /// key, this is only possible for D-Bus activatable applications that are currently running
+    async fn check_implemented_interfaces(&self) {
+        let desktop_file_view = match self.imp().desktop_file_view.borrow().as_ref() {
+            Some(desktop_file_view) => desktop_file_view.upgrade(),
+            None => None,
+        };
+        let desktop_file_view = match desktop_file_view {
+            Some(desktop_file_view) => desktop_file_view,
+            None => return,
+        };
+
+        let (dbus_activatable, interfaces) = {
+            let desktop_entry_rc = &desktop_file_view.desktop_entry();
+            let desktop_entry_cell: &DesktopEntryCell = desktop_entry_rc.borrow();
+            let desktop_entry = desktop_entry_cell.borrow();
+
+            let dbus_activatable =
+                desktop_entry.entry(&self.name(), "DBusActivatable", None) == Some("true");
+            let interfaces: Vec<String> = desktop_entry
+                .entry(&self.name(), "Implements", None)
+                .unwrap_or_default()
+                .split(';')
+                .map(str::trim)
+                .filter(|interface| !interface.is_empty())
+                .map(String::from)
+                .collect();
+            (dbus_activatable, interfaces)
+        };
+
+        let path = desktop_file_view.path();
+        let bus_name = crate::util::desktop_file_id(&path)
+            .trim_end_matches(".desktop")
+            .to_string();
+
+        let body = if !dbus_activatable {
+            "Exported interfaces can only be checked for applications with DBusActivatable enabled"
+                .to_string()
+        } else if let Some(error) = dbus::bus_name_error(&bus_name) {
+            format!("The file name \"{bus_name}\" is not a valid bus name: {error}")
+        } else {
+            match dbus::exported_interfaces(&bus_name).await {
+                Ok(ExportedInterfaces::NotRunning) => format!(
+                    "{bus_name} is not running, start the application and check again"
+                ),
+                Ok(ExportedInterfaces::Interfaces(exported)) => interfaces
+                    .iter()
+                    .map(|interface| {
+                        if exported.contains(interface) {
+                            format!("✓ {interface}")
+                        } else {
+                            format!("✗ {interface} is not exported")
+                        }
+                    })
+                    .collect::<Vec<_>>()
+                    .join("\n"),
+                Err(e) => format!("Failed to introspect {bus_name}: {e}"),
+            }
+        };
+
+        let dialog = adw::AlertDialog::builder()
+            .heading("Implemented Interfaces")
+            .body(body)
+            .build();
+        dialog.add_response("close", "Close");
+        dialog.present(Some(self));
+    }
+
+    /// Show what the Exec key of the group eventually runs, going through the wrapper programs
+    /// that only set up how the actual command is run
+    fn show_effective_command(&self) {
+        let desktop_file_view = match self.imp().desktop_file_view.borrow().as_ref() {
+            Some(desktop_file_view) => desktop_file_view.upgrade(),
+            None => None,
+        };
+        let Some(desktop_file_view) = desktop_file_view else {
+            return;
+        };
+
+        let command = {
+            let desktop_entry_rc = &desktop_file_view.desktop_entry();
+            let desktop_entry_cell: &DesktopEntryCell = desktop_entry_rc.borrow();
+            let desktop_entry = desktop_entry_cell.borrow();
+            desktop_entry
+                .entry(&self.name(), "Exec", None)
+                .and_then(shellparse::parse)
+        };
+
+        let dialog = adw::AlertDialog::builder()
+            .heading("Effective Command")
+            .build();
+        dialog.add_response("close", "Close");
+
+        let Some(command) = command else {
+            dialog.set_body("The Exec key could not be parsed");
+            dialog.present(Some(self));
+            return;
+        };
+
+        let unwrapped = command.unwrap_wrappers();
+        let list = gtk::ListBox::builder()
+            .selection_mode(gtk::SelectionMode::None)
+            .css_classes(["boxed-list"])
+            .build();
+        let add_row = |title: &str, subtitle: &str| {
+            let row = adw::ActionRow::builder()
+                .title(title)
+                .subtitle(subtitle)
+                .subtitle_selectable(true)
+                .use_markup(false)
+                .css_classes(["property"])
+                .build();
+            list.append(&row);
+        };
+
+        for wrapper in unwrapped.wrappers.iter() {
+            let invocation = std::iter::once(&wrapper.program)
+                .chain(wrapper.args.iter())
+                .map(|arg| quote_exec_arg(arg))
+                .collect::<Vec<_>>()
+                .join(" ");
+            add_row(wrapper.kind.description(), &invocation);
+        }
+
+        let command = unwrapped.command;
+        if !command.variables.is_empty() {
+            let variables = command
+                .variables
+                .iter()
+                .map(|(var, value)| format!("{var}={value}"))
+                .collect::<Vec<_>>()
+                .join("\n");
+            add_row("Variables", &variables);
+        }
+        add_row("Binary", &command.command);
+        if !command.args.is_empty() {
+            add_row("Arguments", &command.args.join("\n"));
+        }
+
+        // The wrappers exported by flatpak only tell the application by their file name
+        let exported_app = flatpak_export::exported_app(&command.command);
+        if let Some(app) = &exported_app {
+            add_row("Flatpak Application", &app.app_id);
+            if let (Some(branch), Some(arch)) = (&app.branch, &app.arch) {
+                add_row("Branch", &format!("{branch} ({arch})"));
+            }
+            if let Some(runtime) = &app.runtime {
+                add_row("Runtime", runtime);
+            }
+            add_row("Installation", &app.installation.to_string_lossy());
+        }
+
+        if exported_app.is_some() {
+            dialog.set_body("The binary is the wrapper flatpak exports to run the application");
+        } else if unwrapped.wrappers.is_empty() {
+            dialog.set_body("The command is not run through any wrapper");
+        }
+        dialog.set_extra_child(Some(&list));
+        dialog.present(Some(self));
+    }
+
+    /// Sum up what the application is run with: the command and the variables set by Exec, the
+    /// working directory from Path and whether it gets a terminal
+    fn show_launch_environment(&self) {
+        let desktop_file_view = match self.imp().desktop_file_view.borrow().as_ref() {
+            Some(desktop_file_view) => desktop_file_view.upgrade(),
+            None => None,
+        };
+        let Some(desktop_file_view) = desktop_file_view else {
+            return;
+        };
+
+        // Path and Terminal only exist in the main group, they apply to the actions as well
+        let (command, working_dir, terminal) = {
+            let desktop_entry_rc = &desktop_file_view.desktop_entry();
+            let desktop_entry_cell: &DesktopEntryCell = desktop_entry_rc.borrow();
+            let desktop_entry = desktop_entry_cell.borrow();
+            let main_value = |key: &str| {
+                desktop_entry
+                    .entry("Desktop Entry", key, None)
+                    .map(str::trim)
+                    .filter(|value| !value.is_empty())
+                    .map(String::from)
+            };
+            (
+                desktop_entry
+                    .entry(&self.name(), "Exec", None)
+                    .and_then(shellparse::parse),
+                main_value("Path"),
+                main_value("Terminal").as_deref() == Some("true"),
+            )
+        };
+
+        let dialog = adw::AlertDialog::builder()
+            .heading("Launch Environment")
+            .body("What the application runs with when it is launched from the desktop")
+            .build();
+        dialog.add_response("close", "Close");
+
+        let Some(command) = command else {
+            dialog.set_body("The Exec key could not be parsed");
+            dialog.present(Some(self));
+            return;
+        };
+
+        let list = gtk::ListBox::builder()
+            .selection_mode(gtk::SelectionMode::None)
+            .css_classes(["boxed-list"])
+            .build();
+        let add_row = |title: &str, subtitle: &str| {
+            let row = adw::ActionRow::builder()
+                .title(title)
+                .subtitle(subtitle)
+                .subtitle_selectable(true)
+                .use_markup(false)
+                .css_classes(["property"])
+                .build();
+            list.append(&row);
+        };
+
+        let unwrapped = command.unwrap_wrappers();
+        let command = unwrapped.command;
+        let invocation = std::iter::once(&command.command)
+            .chain(command.args.iter())
+            .map(|arg| quote_exec_arg(arg))
+            .collect::<Vec<_>>()
+            .join(" ");
+        add_row("Command", &invocation);
+        if !unwrapped.wrappers.is_empty() {
+            let wrappers = unwrapped
+                .wrappers
+                .iter()
+                .map(|wrapper| wrapper.kind.description())
+                .collect::<Vec<_>>()
+                .join("\n");
+            add_row("Run Through", &wrappers);
+        }
+
+        let variables = if command.variables.is_empty() {
+            "None, the variables of the session are passed on as they are".to_string()
+        } else {
+            command
+                .variables
+                .iter()
+                .map(|(var, value)| format!("{var}={value}"))
+                .collect::<Vec<_>>()
+                .join("\n")
+        };
+        add_row("Variables", &variables);
+
+        let working_dir = match working_dir {
+            Some(path) if Path::new(&path).is_dir() => path,
+            Some(path) => format!("{path}, which does not exist"),
+            None => "Not set, usually the home directory".to_string(),
+        };
+        add_row("Working Directory", &working_dir);
+
+        add_row(
+            "Terminal",
+            if terminal {
+                "Runs in a terminal emulator"
+            } else {
+                "Runs without a terminal"
+            },
+        );
+
+        dialog.set_extra_child(Some(&list));
+        dialog.present(Some(self));
+    }
+
+    /// Copy the group, serialized as desktop file text, to the clipboard
+    fn copy_to_clipboard(&self) {
+        let desktop_file_view = self.imp().desktop_file_view.borrow().as_ref().unwrap().upgrade();
+        let Some(desktop_file_view) = desktop_file_view else {
+            return;
+        };
+
+        let text = {
+            let desktop_entry_rc = &desktop_file_view.desktop_entry();
+            let desktop_entry_cell: &DesktopEntryCell = desktop_entry_rc.borrow();
+            let desktop_entry = desktop_entry_cell.borrow();
+            desktop_entry.group_to_string(&self.name(), &settings().key_order_list())
+        };
+
+        if let Some(text) = text {
+            self.clipboard().set_text(&text);
+        }
     }
 
     /// Creates a widget suitable for the given key-value pair
     fn make_entry_widget(&self, key: &str, value: &str) -> gtk::Widget {
+        if key == NOTE_KEY {
+            return self.note_widget(value);
+        }
+
         let mut widget_type = EntryWidgetType::from_entry_key(key);
         if matches!(widget_type, EntryWidgetType::Unknown) {
             widget_type = EntryWidgetType::from_entry_value(value);
@@ -530,6 +1009,11 @@ impl DesktopFileGroup {
             EntryWidgetType::StringEntry => self.string_entry_widget(key, value, false),
             EntryWidgetType::StringList => self.string_list_widget(key, value, false),
             EntryWidgetType::LocalizedStringList => self.string_list_widget(key, value, true),
+            EntryWidgetType::LocalizedStringEntry | EntryWidgetType::Unknown
+                if long_text_row::is_long_value(value) && key != "Name" =>
+            {
+                self.long_text_widget(key, value)
+            }
             EntryWidgetType::LocalizedStringEntry | EntryWidgetType::Unknown => {
                 self.string_entry_widget(key, value, true)
             }
@@ -560,76 +1044,46 @@ impl DesktopFileGroup {
             .unwrap()
             .upgrade();
         if let Some(desktop_file_view) = desktop_file_view {
-            let desktop_entry_rc = &desktop_file_view.desktop_entry();
-            let desktop_entry_cell: &DesktopEntryCell = desktop_entry_rc.borrow();
-            let mut desktop_entry = desktop_entry_cell.borrow_mut();
-
             let group_name = self.name();
 
-            if key == "X-Ubuntu-Gettext-Domain" && group_name == "Desktop Entry" {
-                desktop_entry.ubuntu_gettext_domain = Some(value);
-                return;
-            }
+            let changed = desktop_file_view.imp().edit_entry(|editor| {
+                if key == "X-Ubuntu-Gettext-Domain" && group_name == "Desktop Entry" {
+                    editor.set_gettext_domain(Some(value));
+                    return None;
+                }
 
-            desktop_entry
-                .groups
-                .0
-                .entry(group_name)
-                .and_modify(|group| {
-                    group
-                        .0
-                        .entry(key)
-                        .and_modify(|(unlocalized_value, localized_values)| {
-                            let original_value = match locale {
-                                Some(ref locale) => {
-                                    localized_values.entry(locale.clone()).or_default()
-                                }
-                                None => unlocalized_value,
-                            };
+                let (unlocalized_value, localized_values) =
+                    editor.entry().groups.0.get(&group_name)?.0.get(&key)?;
+                let original_value = match locale {
+                    Some(ref locale) => localized_values.get(locale).map_or("", |v| &v[..]),
+                    None => &unlocalized_value[..],
+                };
+                let changed = *original_value != value;
 
-                            let desktop_file_view = self
-                                .imp()
-                                .desktop_file_view
-                                .borrow()
-                                .as_ref()
-                                .expect("Desktop File View not set")
-                                .upgrade();
-                            if let Some(desktop_file_view) = desktop_file_view {
-                                if !desktop_file_view.content_changed() && *original_value != value
-                                {
-                                    desktop_file_view.set_content_changed(true);
-                                }
-                            }
+                match locale {
+                    // If after the change the entry is empty, we can remove it altogether
+                    Some(ref locale) if value.is_empty() => {
+                        editor.remove_translation(&group_name, &key, locale)
+                    }
+                    _ => editor.set_value(&group_name, &key, locale.as_deref(), value),
+                }
+                Some(changed)
+            });
 
-                            *original_value = value;
-
-                            // If after the change the entry is empty, we can remove it
-                            // altogether
-                            if let Some(locale) = locale {
-                                if let Entry::Occupied(entry) =
-                                    localized_values.entry(locale)
-                                {
-                                    if entry.get().is_empty() {
-                                        entry.remove();
-                                    }
-                                }
-                            }
-                        });
-
-                    // If after the change the entry is empty, we can remove it
-                    // altogether
-                    /*
-                    if let Entry::Occupied(entry) = keymap.entry(key.into()) {
-                        let (value, localized_values) = entry.get();
-                        if value.is_empty() && localized_values.is_empty() {
-                            entry.remove();
-                        }
-                    }*/
-                });
+            if let Some(changed) = changed {
+                if !desktop_file_view.content_changed() && changed {
+                    desktop_file_view.set_content_changed(true);
+                }
+                desktop_file_view.queue_preview_update();
+            }
         }
     }
 
     fn string_entry_widget(&self, key: &str, value: &str, localizable: bool) -> gtk::Widget {
+        if key == "Exec" {
+            return self.exec_entry_widget(key, value);
+        }
+
         let entry_row = StringEntryRow::with_default_locale(localizable);
         entry_row.set_title(key);
         entry_row.set_text(value);
@@ -642,11 +1096,123 @@ impl DesktopFileGroup {
                 .push(entry_row.clone().into());
         }
 
+        if key == "StartupWMClass" {
+            self.add_wm_class_suggestion(&entry_row);
+        }
         entry_row.add_suffix(&make_additional_options_menu(key));
 
         entry_row.into()
     }
 
+    /// Offers a value guessed from the Exec key while StartupWMClass is empty, since the window
+    /// class of an application is rarely known
+    fn add_wm_class_suggestion(&self, entry_row: &StringEntryRow) {
+        let button = gtk::Button::builder()
+            .label("Suggest")
+            .valign(gtk::Align::Center)
+            .css_classes(["flat"])
+            .build();
+
+        let update = clone!(
+            #[weak(rename_to=this)]
+            self,
+            #[weak]
+            button,
+            move |entry_row: &StringEntryRow| {
+                let suggestion = entry_row
+                    .text()
+                    .is_empty()
+                    .then(|| this.suggested_wm_class())
+                    .flatten();
+                button.set_visible(suggestion.is_some());
+                if let Some(suggestion) = suggestion {
+                    button.set_tooltip_text(Some(&format!("Use “{suggestion}”")));
+                }
+            }
+        );
+        update(entry_row);
+        entry_row.connect_text_notify(update.clone());
+        // Exec may have been edited since the row was created
+        entry_row.connect_map(update);
+
+        button.connect_clicked(clone!(
+            #[weak(rename_to=this)]
+            self,
+            #[weak]
+            entry_row,
+            move |_| {
+                if let Some(suggestion) = this.suggested_wm_class() {
+                    entry_row.set_text(&suggestion);
+                }
+            }
+        ));
+        entry_row.add_suffix(&button);
+    }
+
+    fn suggested_wm_class(&self) -> Option<String> {
+        let desktop_file_view = self.imp().desktop_file_view.borrow().as_ref()?.upgrade()?;
+        let desktop_entry_rc = &desktop_file_view.desktop_entry();
+        let desktop_entry_cell: &DesktopEntryCell = desktop_entry_rc.borrow();
+        let desktop_entry = desktop_entry_cell.borrow();
+        desktop_entry
+            .entry("Desktop Entry", "Exec", None)
+            .and_then(shellparse::parse)?
+            .unwrap_wrappers()
+            .suggested_wm_class()
+    }
+
+    fn exec_entry_widget(&self, key: &str, value: &str) -> gtk::Widget {
+        let exec_row = ExecEntryRow::new(key, value);
+
+        self.add_state_change_listener(&exec_row);
+        exec_row.add_suffix(&make_additional_option_button(
+            "system-run-symbolic",
+            "Show effective command",
+            "desktop_file_group.show_effective_command",
+            None,
+            "",
+        ));
+        exec_row.add_suffix(&make_additional_option_button(
+            "dialog-information-symbolic",
+            "Show launch environment",
+            "desktop_file_group.show_launch_environment",
+            None,
+            "",
+        ));
+        exec_row.add_suffix(&make_additional_options_menu(key));
+
+        exec_row.into()
+    }
+
+    fn long_text_widget(&self, key: &str, value: &str) -> gtk::Widget {
+        let long_text_row = LongTextRow::with_default_locale(true);
+        long_text_row.set_title(key);
+        long_text_row.set_text(value);
+
+        self.add_state_change_listener(&long_text_row);
+        self.imp()
+            .localized_widgets
+            .borrow_mut()
+            .push(long_text_row.clone().into());
+
+        long_text_row.add_suffix(&make_additional_options_menu(key));
+
+        long_text_row.into()
+    }
+
+    /// The private note of the file, free text that is never translated and often spans multiple
+    /// lines, even while it is still short
+    fn note_widget(&self, value: &str) -> gtk::Widget {
+        let long_text_row = LongTextRow::with_default_locale(false);
+        long_text_row.set_title(NOTE_KEY);
+        long_text_row.set_text(value);
+
+        self.add_state_change_listener(&long_text_row);
+        long_text_row.add_suffix(&make_additional_options_menu(NOTE_KEY));
+
+        long_text_row.into()
+    }
+
     fn bool_switch_widget(&self, key: &str, value: &str) -> gtk::Widget {
         if value != "true" && value != "false" {
             // FIXME: How to handle this?
@@ -660,6 +1226,10 @@ impl DesktopFileGroup {
 
         switch_row.add_suffix(&make_additional_options_menu(key));
 
+        if key == "PrefersNonDefaultGPU" {
+            show_gpu_info(&switch_row);
+        }
+
         self.add_state_change_listener(&switch_row);
         switch_row.into()
     }
@@ -701,6 +1271,30 @@ impl DesktopFileGroup {
                 .push(tagged_entry_row.clone().into());
         }
 
+        if key == "Implements" {
+            tagged_entry_row.set_tag_validator(Some(Box::new(dbus::interface_name_error)));
+            tagged_entry_row.add_suffix(&make_additional_option_button(
+                "system-search-symbolic",
+                "Check exported interfaces",
+                "desktop_file_group.check_implements",
+                None,
+                "",
+            ));
+        }
+
+        if key == "Categories" {
+            tagged_entry_row.set_tag_warning_validator(Some(Box::new(category_issue)));
+            tagged_entry_row.add_suffix(&category_chooser_button(&tagged_entry_row));
+        }
+
+        if key == "MimeType" {
+            let desktop_file_view = self.imp().desktop_file_view.borrow().clone();
+            tagged_entry_row.add_suffix(&mime_handlers_button(
+                &tagged_entry_row,
+                desktop_file_view.unwrap_or_default(),
+            ));
+        }
+
         // tagged_entry_row.add_suffix(&make_additional_options_menu(key));
         add_additional_options_buttons(
             key,
@@ -717,6 +1311,43 @@ impl DesktopFileGroup {
     }
 }
 
+/// Show in the row subtitle which GPU the application would run on, depending on the switch state
+fn show_gpu_info(switch_row: &adw::SwitchRow) {
+    glib::spawn_future_local(clone!(
+        #[weak]
+        switch_row,
+        async move {
+            let gpus = dbus::gpus().await;
+            let update_subtitle = move |switch_row: &adw::SwitchRow| {
+                let subtitle = match &gpus {
+                    Ok(gpus) if gpus.len() > 1 => {
+                        let gpu = gpus
+                            .iter()
+                            .find(|gpu| gpu.default != switch_row.is_active())
+                            .or(gpus.first())
+                            .expect("At least one GPU should exist");
+                        format!("Runs on {}", gpu.name)
+                    }
+                    Ok(gpus) => match gpus.first() {
+                        Some(gpu) => format!(
+                            "Only one GPU detected ({}), this key has no effect",
+                            gpu.name
+                        ),
+                        None => "No GPU detected".to_string(),
+                    },
+                    Err(_) => {
+                        "GPU information unavailable, switcheroo-control is not running".to_string()
+                    }
+                };
+                switch_row.set_subtitle(&subtitle);
+            };
+
+            update_subtitle(&switch_row);
+            switch_row.connect_active_notify(update_subtitle);
+        }
+    ));
+}
+
 enum EntryWidgetType {
     StringEntry,
     LocalizedStringEntry,
@@ -732,29 +1363,19 @@ static STRING_LIST_RE: Lazy<Regex> =
 
 impl EntryWidgetType {
     pub fn from_entry_key(key: &str) -> Self {
-        match key {
-            "NoDisplay"
-            | "Hidden"
-            | "DBusActivatable"
-            | "Terminal"
-            | "StartupNotify"
-            | "PrefersNonDefaultGPU"
-            | "SingleMainWindow" => Self::BoolSwitch,
-            "Name" | "GenericName" | "Comment" | "StartupWMClass" | "URL" => {
-                Self::LocalizedStringEntry
-            }
-            "Type" | "Version" | "TryExec" | "Exec" | "Path" => Self::StringEntry,
-            "Icon" => Self::IconEntry,
-
-            // Lists
-            "OnlyShowIn" | "NotShowIn" | "Actions" | "MimeType" | "Categories" | "Implements" => {
-                Self::StringList
-            }
-            "Keywords" => Self::LocalizedStringList,
+        // Special keys, not part of the specification
+        if key == "X-Ubuntu-Gettext-Domain" {
+            return Self::StringEntry;
+        }
 
-            // Special keys
-            "X-Ubuntu-Gettext-Domain" => Self::StringEntry,
-            _ => Self::Unknown,
+        match key_info(key).map(|info| info.value_type) {
+            Some(ValueType::String) => Self::StringEntry,
+            Some(ValueType::LocaleString) => Self::LocalizedStringEntry,
+            Some(ValueType::IconString) => Self::IconEntry,
+            Some(ValueType::Boolean) => Self::BoolSwitch,
+            Some(ValueType::Strings) => Self::StringList,
+            Some(ValueType::LocaleStrings) => Self::LocalizedStringList,
+            None => Self::Unknown,
         }
     }
 
@@ -769,16 +1390,64 @@ impl EntryWidgetType {
         }
     }
 
-    pub fn default_value(&self) -> &str {
-        match self {
+    /// Value a new `key` entry of the group `group_name` starts with. Some keys get a value
+    /// derived from the rest of the desktop entry, the others the empty value of their type
+    pub fn default_value(
+        &self,
+        key: &str,
+        group_name: &str,
+        desktop_entry: &DesktopEntry,
+    ) -> String {
+        let action = DESKTOP_ACTION_RE
+            .captures(group_name)
+            .and_then(|captures| captures.get(1))
+            .map(|action| action.as_str());
+
+        let contextual = match (key, action) {
+            ("Type", None) => {
+                // Only links have a URL
+                let link = desktop_entry.entry(group_name, "URL", None).is_some();
+                Some(if link { "Link" } else { "Application" }.to_string())
+            }
+            ("TryExec", None) => desktop_entry
+                .entry(group_name, "Exec", None)
+                .and_then(shellparse::parse)
+                .map(|mut command| {
+                    command.flatten_env();
+                    command.command
+                }),
+            ("Actions", None) => {
+                let actions: String = desktop_entry
+                    .groups
+                    .0
+                    .keys()
+                    .filter_map(|name| DESKTOP_ACTION_RE.captures(name))
+                    .filter_map(|captures| captures.get(1))
+                    .map(|action| format!("{};", action.as_str()))
+                    .collect();
+                Some(actions)
+            }
+            ("Name", Some(action)) => Some(action.to_string()),
+            // Actions usually share the icon of the application
+            ("Icon", Some(_)) => desktop_entry
+                .entry("Desktop Entry", "Icon", None)
+                .map(String::from),
+            _ => None,
+        };
+
+        let registered = key_info(key)
+            .filter(|info| !info.default.is_empty())
+            .map(|info| info.default.to_string());
+
+        contextual.or(registered).unwrap_or_else(|| match self {
             EntryWidgetType::StringEntry
             | EntryWidgetType::LocalizedStringEntry
             | EntryWidgetType::IconEntry
             | EntryWidgetType::StringList
             | EntryWidgetType::LocalizedStringList
-            | EntryWidgetType::Unknown => "",
-            EntryWidgetType::BoolSwitch => "false",
-        }
+            | EntryWidgetType::Unknown => String::new(),
+            EntryWidgetType::BoolSwitch => "false".to_string(),
+        })
     }
 }
 
@@ -815,6 +1484,40 @@ impl RowWidgetExt for StringEntryRow {
     }
 }
 
+impl RowWidgetExt for ExecEntryRow {
+    fn connect_changed<F>(&self, f: F) -> SignalHandlerId
+    where
+        F: Fn(&Self) + 'static,
+    {
+        self.connect_text_notify(f)
+    }
+
+    fn entry_value(&self) -> String {
+        self.text()
+    }
+
+    fn entry_locale(&self) -> Option<String> {
+        None
+    }
+}
+
+impl RowWidgetExt for LongTextRow {
+    fn connect_changed<F>(&self, f: F) -> SignalHandlerId
+    where
+        F: Fn(&Self) + 'static,
+    {
+        self.connect_text_notify(f)
+    }
+
+    fn entry_value(&self) -> String {
+        self.text()
+    }
+
+    fn entry_locale(&self) -> Option<String> {
+        self.locale()
+    }
+}
+
 impl RowWidgetExt for IconEntryRow {
     fn connect_changed<F>(&self, f: F) -> SignalHandlerId
     where
@@ -20,7 +20,9 @@ use gtk::{
 };
 
 use crate::desktop_file_view::{
-    desktop_entry_ext::DesktopEntryExt, imp::DesktopEntryCell, DesktopFileView,
+    desktop_entry_ext::{DesktopEntryExt, KEYMAP_ORDER},
+    imp::DesktopEntryCell,
+    DesktopFileView,
 };
 use once_cell::sync::Lazy;
 use regex::Regex;
@@ -35,14 +37,14 @@ mod imp {
     use adw::{prelude::*, subclass::prelude::*};
     use gtk::glib::clone::Downgrade;
     use gtk::{
-        gdk::{Key, ModifierType},
-        glib::{self, clone, GString, Propagation, Properties},
-        Entry, EventControllerKey, Image, Label, ScrolledWindow,
+        glib::{self, clone, Properties},
+        Entry, Image, Label, ScrolledWindow,
     };
 
     use crate::desktop_file_view::{
-        known_entries::{KEYS_DESCRIPTIONS, KNOWN_KEYS},
-        util::{connect_self_fn, entry_popup_completion_handle_escape_key_pressed},
+        known_entries::{key_info, GroupKind, KEYS},
+        suggestion_entry::{Suggestion, SuggestionEntry},
+        util::connect_self_fn,
         DesktopFileView,
     };
 
@@ -50,6 +52,7 @@ mod imp {
     #[properties(wrapper_type = super::NewEntryDialog)]
     pub struct NewEntryDialog {
         pub entry: RefCell<gtk::Entry>,
+        suggestion_entry: RefCell<SuggestionEntry>,
         fail_label: RefCell<gtk::Label>,
         info_label: RefCell<gtk::Label>,
         info_box: RefCell<gtk::Box>,
@@ -79,17 +82,13 @@ mod imp {
                 .orientation(gtk::Orientation::Vertical)
                 .build();
 
-            let entry = gtk::Entry::new();
+            let suggestion_entry = SuggestionEntry::new();
+            let entry = suggestion_entry.entry();
             entry.set_placeholder_text(Some("Entry key"));
 
             // Connect entry signals
             entry.connect_changed(connect_self_fn!(self.on_entry_changed(entry)));
             entry.connect_activate(connect_self_fn!(self.on_entry_activated(entry)));
-            let entry_controller_key = EventControllerKey::new();
-            entry_controller_key.connect_key_pressed(connect_self_fn!(
-                self.on_entry_key_pressed(controller, key, code, modifier) -> Propagation::Proceed
-            ));
-            entry.add_controller(entry_controller_key);
 
             let fail_label = Label::builder()
                 .halign(gtk::Align::Center)
@@ -123,7 +122,7 @@ mod imp {
             info_box.append(&info_icon);
             info_box.append(&info_scroll);
 
-            container.append(&entry);
+            container.append(&suggestion_entry);
             container.append(&fail_label);
             container.append(&info_box);
             obj.set_extra_child(Some(&container));
@@ -133,11 +132,12 @@ mod imp {
             obj.set_response_enabled("add", false);
 
             self.entry.replace(entry);
+            self.suggestion_entry.replace(suggestion_entry);
             self.fail_label.replace(fail_label);
             self.info_label.replace(info_label);
             self.info_box.replace(info_box);
 
-            self.init_completion();
+            self.init_suggestions();
             obj.connect_map(|dialog| {
                 dialog.imp().entry.borrow().grab_focus();
             });
@@ -149,47 +149,19 @@ mod imp {
     impl WidgetImpl for NewEntryDialog {}
 
     impl NewEntryDialog {
-        #[allow(deprecated)]
-        fn init_completion(&self) {
-            // Setup key entry completion
-            let entry = self.entry.borrow();
-            let entry_model = gtk::ListStore::new(&[glib::Type::STRING]);
-            for key in KNOWN_KEYS {
-                entry_model.set(&entry_model.append(), &[(0, &key)]);
-            }
-
-            let completion = gtk::EntryCompletion::builder()
-                .model(&entry_model)
-                .text_column(0)
-                .minimum_key_length(0)
-                .popup_completion(false)
-                .build();
-
-            let completion_cell = gtk::CellRendererText::new();
-            completion_cell.set_xpad(6);
-            completion.pack_start(&completion_cell, false);
-            completion.add_attribute(&completion_cell, "text", 0);
-            completion.set_match_func(clone!(
-                #[weak(rename_to=model)]
-                entry_model,
-                #[upgrade_or]
-                false,
-                move |_completion, s, iter| {
-                    let iter_text = TreeModelExtManual::get::<GString>(&model, iter, 0);
-                    iter_text.to_lowercase().contains(s)
-                }
-            ));
-
-            entry.set_completion(Some(&completion));
+        fn init_suggestions(&self) {
+            // Only the keys that belong in the group are suggested
+            let group = GroupKind::of(&self.group_name.borrow());
+            let suggestions: Vec<Suggestion> = KEYS
+                .iter()
+                .filter(|info| info.allowed_in(group))
+                .map(|info| Suggestion::new(info.key, info.key, Some(info.short_description())))
+                .collect();
+            self.suggestion_entry.borrow().set_suggestions(&suggestions);
         }
 
-        #[allow(deprecated)]
         fn on_entry_changed(&self, entry: &Entry) {
             let obj = self.obj();
-
-            // Make sure to enable completion on change
-            entry.completion().unwrap().set_popup_completion(true);
-
             let key = obj.validated_key();
 
             let fail_label = self.fail_label.borrow();
@@ -217,10 +189,8 @@ mod imp {
             }
 
             // Update info
-            if let Some(description) =
-                KEYS_DESCRIPTIONS.get(key.as_ref().map(|s| &s[..]).unwrap_or(""))
-            {
-                info_label.set_text(description);
+            if let Some(info) = key.as_deref().ok().and_then(key_info) {
+                info_label.set_text(info.description);
                 info_box.set_visible(true);
             } else {
                 info_label.set_text("");
@@ -243,17 +213,6 @@ mod imp {
                 obj.set_close_response("cancel");
             }
         }
-
-        fn on_entry_key_pressed(
-            &self,
-            _controller: &gtk::EventControllerKey,
-            key: Key,
-            _code: u32,
-            modifier: ModifierType,
-        ) -> Propagation {
-            entry_popup_completion_handle_escape_key_pressed(&self.entry.borrow(), key, modifier)
-                .into()
-        }
     }
 }
 
@@ -286,7 +245,8 @@ impl NewEntryDialog {
             let desktop_entry_cell: &DesktopEntryCell = std::rc::Rc::borrow(desktop_entry_rc);
             let desktop_entry = desktop_entry_cell.borrow();
 
-            if let Some(keymap) = desktop_entry.sorted_keymap(&self.group_name()) {
+            // Only the keys matter here, not their order
+            if let Some(keymap) = desktop_entry.sorted_keymap(&self.group_name(), &KEYMAP_ORDER) {
                 keymap
                     .iter()
                     .map(|(existing_key, _)| existing_key)
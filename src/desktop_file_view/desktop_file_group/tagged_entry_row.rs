@@ -20,8 +20,19 @@ use gtk::glib::{self, clone, closure_local};
 use gtk::graphene::Point;
 use tag::Tag;
 
+use crate::desktop_file_view::string_entry_row::inherited_from_tooltip;
+
 pub const TAG_SPACING: i32 = 6;
 
+/// Distance from the edges of the scrolled view within which dragging a tag scrolls it
+const AUTOSCROLL_EDGE: f64 = 48.0;
+
+/// Pixels scrolled each frame while the pointer is at the very edge of the scrolled view
+const AUTOSCROLL_MAX_SPEED: f64 = 12.0;
+
+/// Validates a single tag value, returning a description of the problem if it is not valid
+pub type TagValidator = Box<dyn Fn(&str) -> Option<String>>;
+
 mod imp {
     use std::cell::{Cell, RefCell};
     use std::collections::HashMap;
@@ -36,13 +47,13 @@ mod imp {
     use gtk::glib::subclass::Signal;
     use gtk::glib::{self, clone, Properties};
     use gtk::glib::{closure, closure_local, Object};
-    use gtk::graphene::Point;
-    use gtk::GestureDrag;
+    use gtk::graphene::{Point, Rect};
+    use gtk::{GestureDrag, TickCallbackId, WidgetPaintable};
 
     use crate::desktop_file_view::languages::LANGUAGES_LOCALE_MAP;
 
     use super::tag::Tag;
-    use super::{DragSide, TAG_SPACING};
+    use super::{autoscroll_speed, DragSide, TAG_SPACING};
 
     #[derive(Default, Properties)]
     #[properties(wrapper_type = super::TaggedEntryRow)]
@@ -57,6 +68,11 @@ mod imp {
         pub add_button: RefCell<Tag>,
         pub suffixes: RefCell<gtk::Box>,
         pub locale_emblem: RefCell<gtk::Label>,
+        pub inherited_label: RefCell<gtk::Label>,
+        pub has_inherited_value: Cell<bool>,
+        pub tag_validator: RefCell<Option<super::TagValidator>>,
+        /// Style class of tags the validator finds a problem with
+        pub tag_issue_class: Cell<&'static str>,
 
         pub adding_tags: Cell<bool>,
         pub enter_anim: RefCell<Option<TimedAnimation>>,
@@ -68,6 +84,18 @@ mod imp {
         pub reorder_anim: RefCell<Option<(TimedAnimation, TimedAnimation)>>,
         pub drag_bounceback_anim: RefCell<Option<(SpringAnimation, SpringAnimation)>>,
         moveaside_tags_info: RefCell<HashMap<Tag, MoveasideAnimationInfo>>,
+
+        /// Copy of the dragged tag that follows the pointer, while the tag itself stays dimmed
+        /// in the place it would be dropped at
+        drag_ghost: RefCell<Option<WidgetPaintable>>,
+        drag_pointer: Cell<(f64, f64)>,
+        /// Where the pointer grabbed the dragged tag, relative to the tag
+        drag_grab_offset: Cell<(f64, f64)>,
+        /// Last offset reported by the drag gesture, and how much the view scrolled since
+        drag_offset: Cell<(f64, f64)>,
+        autoscrolled: Cell<f64>,
+        autoscroll_speed: Cell<f64>,
+        autoscroll_tick: RefCell<Option<TickCallbackId>>,
     }
 
     #[glib::object_subclass]
@@ -133,8 +161,16 @@ mod imp {
             tags_box.set_vexpand(true);
             tags_box.set_css_classes(&["tags"]);
 
+            let inherited_label = self.inherited_label.borrow().clone();
+            inherited_label.set_halign(gtk::Align::Start);
+            inherited_label.set_wrap(true);
+            inherited_label.set_xalign(0.0);
+            inherited_label.set_visible(false);
+            inherited_label.set_css_classes(&["dim-label", "caption"]);
+
             main_content.append(&title_box);
             main_content.append(&tags_box);
+            main_content.append(&inherited_label);
             container.append(&main_content);
             container.append(&suffixes);
             obj.set_child(Some(&container));
@@ -182,6 +218,15 @@ mod imp {
             self.leave_anim.replace(Some(leave_anim));
 
             self.init_tag_reordering();
+
+            // The inherited value is only relevant while there are no values for the locale
+            obj.connect_closure(
+                "changed",
+                true,
+                closure_local!(|this: &super::TaggedEntryRow| {
+                    this.update_inherited_label_visibility();
+                }),
+            );
         }
 
         fn signals() -> &'static [Signal] {
@@ -190,7 +235,13 @@ mod imp {
         }
     }
 
-    impl WidgetImpl for TaggedEntryRow {}
+    impl WidgetImpl for TaggedEntryRow {
+        fn snapshot(&self, snapshot: &gtk::Snapshot) {
+            self.parent_snapshot(snapshot);
+            self.snapshot_drag_feedback(snapshot);
+        }
+    }
+
     impl PreferencesRowImpl for TaggedEntryRow {}
     impl ListBoxRowImpl for TaggedEntryRow {}
 
@@ -251,6 +302,12 @@ mod imp {
                         }
                         let index = obj.find_tag_index(tag);
                         this.reordered_tag_begin_index.set(index);
+
+                        let pointer = Point::new(x as f32, y as f32);
+                        if let Some(grab) = obj.compute_point(tag, &pointer) {
+                            this.drag_grab_offset
+                                .set((grab.x() as f64, grab.y() as f64));
+                        }
                     }
 
                     this.reordered_tag.replace(tag);
@@ -260,13 +317,19 @@ mod imp {
             drag.connect_drag_update(clone!(
                 #[weak(rename_to=this)]
                 self,
-                move |drag, offset_x, offset_y| this.on_drag_update(drag, offset_x, offset_y)
+                move |drag, offset_x, offset_y| {
+                    // The offset already accounts for scrolling that happened before the event
+                    this.drag_offset.set((offset_x, offset_y));
+                    this.autoscrolled.set(0.0);
+                    this.on_drag_update(drag, offset_x, offset_y)
+                }
             ));
 
             drag.connect_drag_end(clone!(
                 #[weak(rename_to=this)]
                 self,
                 move |_drag, _x, _y| {
+                    this.stop_autoscroll();
                     let reordered_tag = match this.reordered_tag.replace(None) {
                         Some(tag) => tag,
                         None => return,
@@ -275,6 +338,11 @@ mod imp {
                     // Restore button sensitivity
                     reordered_tag.set_button_sensitive(true);
 
+                    if this.drag_ghost.replace(None).is_some() {
+                        reordered_tag.set_opacity(1.0);
+                        this.obj().queue_draw();
+                    }
+
                     // Show bounceback animation
                     let (bounceback_anim_x, bounceback_anim_y) =
                         match this.drag_bounceback_anim.borrow().clone() {
@@ -337,7 +405,15 @@ mod imp {
             let distance_travelled = (offset_x * offset_x + offset_y * offset_y).sqrt();
             if distance_travelled >= 10.0 {
                 reordered_tag.set_button_sensitive(false);
+
+                if self.drag_ghost.borrow().is_none() {
+                    self.drag_ghost.replace(Some(reordered_tag.drag_ghost()));
+                    reordered_tag.set_opacity(0.4);
+                }
             }
+            self.drag_pointer.set((x, y));
+            self.update_autoscroll(x, y);
+            obj.queue_draw();
 
             let (trans_value_to_x, trans_value_to_y) = if adw::is_animations_enabled(&obj) {
                 // Calculate pull animation based on cursor distance and angle
@@ -505,6 +581,105 @@ mod imp {
         }
     }
 
+    impl TaggedEntryRow {
+        /// Draws the copy of the dragged tag under the pointer, and a line where the tag would be
+        /// dropped
+        fn snapshot_drag_feedback(&self, snapshot: &gtk::Snapshot) {
+            let Some(ghost) = self.drag_ghost.borrow().clone() else {
+                return;
+            };
+            let Some(reordered_tag) = self.reordered_tag.borrow().clone() else {
+                return;
+            };
+            let obj = self.obj();
+
+            if let Some(bounds) = reordered_tag.compute_bounds(&*obj) {
+                let line = Rect::new(
+                    bounds.x() - TAG_SPACING as f32 / 2.0 - 1.0,
+                    bounds.y(),
+                    2.0,
+                    bounds.height(),
+                );
+                snapshot.append_color(&adw::StyleManager::default().accent_color_rgba(), &line);
+            }
+
+            let (x, y) = self.drag_pointer.get();
+            let (grab_x, grab_y) = self.drag_grab_offset.get();
+            let width = ghost.intrinsic_width() as f32;
+            let height = ghost.intrinsic_height() as f32;
+
+            snapshot.save();
+            snapshot.translate(&Point::new(
+                (x - grab_x) as f32 + width / 2.0,
+                (y - grab_y) as f32 + height / 2.0,
+            ));
+            snapshot.scale(1.1, 1.1);
+            snapshot.translate(&Point::new(-width / 2.0, -height / 2.0));
+            snapshot.push_opacity(0.8);
+            ghost.snapshot(snapshot, width as f64, height as f64);
+            snapshot.pop();
+            snapshot.restore();
+        }
+
+        /// Scrolls the view containing the row while a tag is dragged close to its edges, so tags
+        /// can be moved to lines that are not visible
+        fn update_autoscroll(&self, x: f64, y: f64) {
+            let obj = self.obj();
+            let speed = obj
+                .ancestor(gtk::ScrolledWindow::static_type())
+                .and_then(|scrolled_window| {
+                    let point =
+                        obj.compute_point(&scrolled_window, &Point::new(x as f32, y as f32))?;
+                    Some(autoscroll_speed(
+                        point.y() as f64,
+                        scrolled_window.height() as f64,
+                    ))
+                })
+                .unwrap_or(0.0);
+            self.autoscroll_speed.set(speed);
+
+            if speed == 0.0 {
+                self.stop_autoscroll();
+            } else if self.autoscroll_tick.borrow().is_none() {
+                let tick = obj.add_tick_callback(|obj, _clock| {
+                    obj.imp().autoscroll_step();
+                    glib::ControlFlow::Continue
+                });
+                self.autoscroll_tick.replace(Some(tick));
+            }
+        }
+
+        fn autoscroll_step(&self) {
+            let obj = self.obj();
+            let Some(scrolled_window) = obj
+                .ancestor(gtk::ScrolledWindow::static_type())
+                .and_downcast::<gtk::ScrolledWindow>()
+            else {
+                return;
+            };
+
+            let adjustment = scrolled_window.vadjustment();
+            let before = adjustment.value();
+            adjustment.set_value(before + self.autoscroll_speed.get());
+            let scrolled = adjustment.value() - before;
+            if scrolled == 0.0 {
+                return;
+            }
+
+            // The row moved under the pointer, which is now over a different part of it
+            self.autoscrolled.set(self.autoscrolled.get() + scrolled);
+            let (offset_x, offset_y) = self.drag_offset.get();
+            let drag = self.drag_gesture.borrow().clone();
+            self.on_drag_update(&drag, offset_x, offset_y + self.autoscrolled.get());
+        }
+
+        fn stop_autoscroll(&self) {
+            if let Some(tick) = self.autoscroll_tick.take() {
+                tick.remove();
+            }
+        }
+    }
+
     #[derive(Clone)]
     struct MoveasideAnimationInfo {
         value_from: Point,
@@ -622,6 +797,7 @@ impl TaggedEntryRow {
                     }
 
                     if !tag.label().is_empty() {
+                        obj.validate_tag(&tag);
                         obj.emit_by_name::<()>("changed", &[]);
                     }
                 }
@@ -640,6 +816,8 @@ impl TaggedEntryRow {
             ),
         );
 
+        self.validate_tag(&tag);
+
         if let Some(animation) = imp.enter_anim.borrow().clone() {
             animation.skip();
             let target = adw::PropertyAnimationTarget::new(&tag, "scale");
@@ -650,6 +828,54 @@ impl TaggedEntryRow {
         tag
     }
 
+    /// Set a function used to validate each tag, invalid tags are highlighted and show the
+    /// problem as their tooltip
+    pub fn set_tag_validator(&self, validator: Option<TagValidator>) {
+        self.imp().tag_issue_class.set("error");
+        self.replace_tag_validator(validator);
+    }
+
+    /// Like [`Self::set_tag_validator`], for values that are discouraged rather than wrong. Tags
+    /// with problems are highlighted as warnings
+    pub fn set_tag_warning_validator(&self, validator: Option<TagValidator>) {
+        self.imp().tag_issue_class.set("warning");
+        self.replace_tag_validator(validator);
+    }
+
+    fn replace_tag_validator(&self, validator: Option<TagValidator>) {
+        self.imp().tag_validator.replace(validator);
+        for tag in self.tags() {
+            self.validate_tag(&tag);
+        }
+    }
+
+    fn validate_tag(&self, tag: &Tag) {
+        let label = tag.label();
+        let error = match self.imp().tag_validator.borrow().as_ref() {
+            Some(validator) if !label.is_empty() => validator(&label),
+            _ => None,
+        };
+
+        tag.remove_css_class("error");
+        tag.remove_css_class("warning");
+        if error.is_some() {
+            tag.add_css_class(self.imp().tag_issue_class.get());
+        }
+        tag.set_tooltip_text(error.as_deref());
+    }
+
+    fn tags(&self) -> Vec<Tag> {
+        let tags_box = self.imp().tags_box.borrow();
+        let mut child = tags_box.first_child().expect("No child in tags box");
+        let add_button = self.imp().add_button.borrow().clone();
+        let mut tags = Vec::new();
+        while child != add_button {
+            tags.push(child.clone().downcast::<Tag>().expect("Child is not a Tag"));
+            child = child.next_sibling().expect("Next sibling does not exist");
+        }
+        tags
+    }
+
     fn add_tag_and_edit(&self) {
         let tag = self.add_tag("");
         tag.set_edit_mode(true);
@@ -677,6 +903,32 @@ impl TaggedEntryRow {
         res
     }
 
+    /// Add a tag for `value` unless there is one already, as if the user added it
+    pub fn add_value(&self, value: &str) {
+        if !self.values().iter().any(|existing| existing == value) {
+            self.add_tag(value);
+            self.emit_by_name::<()>("changed", &[]);
+        }
+    }
+
+    /// Remove the tags of `value` right away, as if the user removed them
+    pub fn remove_value(&self, value: &str) {
+        let tags = self
+            .tags()
+            .into_iter()
+            .filter(|tag| tag.label() == value)
+            .collect::<Vec<_>>();
+        if tags.is_empty() {
+            return;
+        }
+
+        let tags_box = self.imp().tags_box.borrow().clone();
+        for tag in tags {
+            tags_box.remove(&tag);
+        }
+        self.emit_by_name::<()>("changed", &[]);
+    }
+
     pub fn values_as_string_list(&self) -> String {
         let mut s = self.values().join(";");
         s.push(';');
@@ -699,19 +951,51 @@ impl TaggedEntryRow {
         imp.locale.set(locale);
         self.notify_locale();
 
-        self.clear();
-
-        self.push_string_list(localized_string_list);
+        // Rebuilding the tags loses focus and any tag being edited, only do it when needed
+        if self.values() != parse_string_list(localized_string_list) {
+            self.clear();
+            self.push_string_list(localized_string_list);
+        }
         drop(freeze_guard);
     }
 
-    fn push_string_list(&self, list: &str) {
-        for item in list.split(';') {
-            let item = item.trim();
-            if !item.is_empty() {
-                self.add_tag(item);
+    /// Show the list the entry falls back to when it has no values for the current locale
+    pub fn set_inherited_value(&self, inherited: Option<(&str, Option<&str>)>) {
+        let imp = self.imp();
+        let inherited_label = imp.inherited_label.borrow();
+        match inherited {
+            Some((value, from_locale)) => {
+                let values = value
+                    .split(';')
+                    .map(str::trim)
+                    .filter(|item| !item.is_empty())
+                    .collect::<Vec<_>>()
+                    .join(", ");
+                inherited_label.set_label(&values);
+                inherited_label.set_tooltip_text(Some(&inherited_from_tooltip(from_locale)));
+                imp.has_inherited_value.set(true);
+            }
+            None => {
+                inherited_label.set_label("");
+                inherited_label.set_tooltip_text(None);
+                imp.has_inherited_value.set(false);
             }
         }
+        drop(inherited_label);
+        self.update_inherited_label_visibility();
+    }
+
+    fn update_inherited_label_visibility(&self) {
+        let imp = self.imp();
+        imp.inherited_label
+            .borrow()
+            .set_visible(imp.has_inherited_value.get() && self.values().is_empty());
+    }
+
+    fn push_string_list(&self, list: &str) {
+        for item in parse_string_list(list) {
+            self.add_tag(&item);
+        }
     }
 
     pub fn clear(&self) {
@@ -821,11 +1105,35 @@ impl TaggedEntryRow {
     }
 }
 
+/// Items of a string list value, the way they are shown as tags
+fn parse_string_list(list: &str) -> Vec<String> {
+    list.split(';')
+        .map(str::trim)
+        .filter(|item| !item.is_empty())
+        .map(String::from)
+        .collect()
+}
+
 enum DragSide {
     Left,
     Right,
 }
 
+/// How many pixels to scroll each frame with the pointer at `y` in a scrolled view of `height`,
+/// negative to scroll up
+fn autoscroll_speed(y: f64, height: f64) -> f64 {
+    let edge = AUTOSCROLL_EDGE.min(height / 3.0);
+    if edge <= 0.0 {
+        0.0
+    } else if y < edge {
+        -AUTOSCROLL_MAX_SPEED * ((edge - y) / edge).min(1.0)
+    } else if y > height - edge {
+        AUTOSCROLL_MAX_SPEED * ((y - (height - edge)) / edge).min(1.0)
+    } else {
+        0.0
+    }
+}
+
 impl Default for TaggedEntryRow {
     fn default() -> Self {
         Self::new(false, None)
@@ -888,7 +1196,7 @@ mod tag {
             #[property(get, set, construct, default = 0.0)]
             translate_y: Cell<f32>,
 
-            container: RefCell<gtk::Box>,
+            pub container: RefCell<gtk::Box>,
             pub text_stack: RefCell<adw::ViewStack>,
             pub text: RefCell<Text>,
             pub button: RefCell<Button>,
@@ -1120,6 +1428,12 @@ mod tag {
             Object::builder().property("label", label).build()
         }
 
+        /// Paintable showing the tag, used as the image that follows the pointer while it is
+        /// dragged. It draws the contents only, so dimming the tag itself does not affect it
+        pub fn drag_ghost(&self) -> gtk::WidgetPaintable {
+            gtk::WidgetPaintable::new(Some(&*self.imp().container.borrow()))
+        }
+
         fn apply_edit(&self) {
             let imp = self.imp();
             let new_label = imp.text.borrow().text();
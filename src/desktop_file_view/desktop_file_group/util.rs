@@ -11,54 +11,48 @@
 * You should have received a copy of the GNU General Public License along with Desktop File Editor. If not, see <https://www.gnu.org/licenses/>.
 */
 
+use adw::prelude::*;
 use gtk::{gio::Menu, glib::variant::ToVariant};
 
-use crate::desktop_file_view::known_entries::KEYS_DESCRIPTIONS;
+use crate::desktop_file_view::known_entries::{custom_key_description, GroupKind, KeyInfo};
 
 pub fn make_additional_option_button(
     icon_name: &str,
     tooltip_text: &str,
     action_name: &str,
-    action_target: &str,
+    action_target: Option<&str>,
     extra_css_class: &str,
 ) -> gtk::Widget {
-    gtk::Button::builder()
+    let button = gtk::Button::builder()
         .icon_name(icon_name)
         .halign(gtk::Align::Center)
         .valign(gtk::Align::Center)
         .tooltip_text(tooltip_text)
         .action_name(action_name)
-        .action_target(&action_target.to_variant())
         .css_classes(["circular", extra_css_class])
-        .build()
-        .into()
-}
+        .build();
 
-pub fn make_additional_options_menu(key: &str) -> gtk::Widget {
-    let remove_only = !KEYS_DESCRIPTIONS.contains_key(key);
-
-    if remove_only {
-        return make_additional_option_button(
-            "list-remove-symbolic",
-            "Remove",
-            "desktop_file_group.remove_entry",
-            key,
-            "destructive-action",
-        );
+    if let Some(action_target) = action_target {
+        button.set_action_target(Some(action_target.to_variant()));
     }
 
+    button.into()
+}
+
+pub fn make_additional_options_menu(key: &str) -> gtk::Widget {
     let menu = Menu::new();
     menu.append(
         Some("Remove"),
         Some(&format!("desktop_file_group.remove_entry('{key}')")),
     );
-
-    if !remove_only {
-        menu.append(
-            Some("Description"),
-            Some(&format!("desktop_file_group.show_entry_info('{key}')")),
-        );
-    }
+    menu.append(
+        Some("Move to Group…"),
+        Some(&format!("desktop_file_group.move_entry('{key}')")),
+    );
+    menu.append(
+        Some("Description"),
+        Some(&format!("desktop_file_group.show_entry_info('{key}')")),
+    );
 
     gtk::MenuButton::builder()
         .icon_name("view-more-symbolic")
@@ -75,23 +69,78 @@ pub fn add_additional_options_buttons<F>(key: &str, add_fn: F)
 where
     F: Fn(&gtk::Widget),
 {
-    let remove_only = !KEYS_DESCRIPTIONS.contains_key(key);
-
     add_fn(&make_additional_option_button(
         "list-remove-symbolic",
         "Remove",
         "desktop_file_group.remove_entry",
-        key,
+        Some(key),
         "destructive-action",
     ));
 
-    if !remove_only {
-        add_fn(&make_additional_option_button(
-            "help-about-symbolic",
-            "Description",
-            "desktop_file_group.show_entry_info",
-            key,
-            "",
-        ));
+    add_fn(&make_additional_option_button(
+        "folder-move-symbolic",
+        "Move to Group…",
+        "desktop_file_group.move_entry",
+        Some(key),
+        "",
+    ));
+
+    add_fn(&make_additional_option_button(
+        "help-about-symbolic",
+        "Description",
+        "desktop_file_group.show_entry_info",
+        Some(key),
+        "",
+    ));
+}
+
+/// Dialog describing a key as used in a group of the kind `group`, with its value type, default
+/// value and a link to the specification
+pub fn make_key_info_dialog(info: &KeyInfo, group: GroupKind) -> adw::AlertDialog {
+    let mut details = format!("Type: {}", info.value_type.name());
+    if !info.default.is_empty() {
+        details.push_str(&format!("\nDefault: {}", info.default));
     }
+    if !info.allowed_in(group) {
+        details.push_str("\nNot used in this group");
+    }
+
+    let details_label = gtk::Label::builder()
+        .label(details)
+        .halign(gtk::Align::Center)
+        .justify(gtk::Justification::Center)
+        .selectable(true)
+        .css_classes(["dim-label"])
+        .build();
+
+    let spec_button = gtk::LinkButton::builder()
+        .uri(info.spec_link_in(group))
+        .label("Read the Specification")
+        .halign(gtk::Align::Center)
+        .build();
+
+    let container = gtk::Box::builder()
+        .orientation(gtk::Orientation::Vertical)
+        .spacing(6)
+        .build();
+    container.append(&details_label);
+    container.append(&spec_button);
+
+    let dialog = adw::AlertDialog::builder()
+        .heading(info.key)
+        .body(info.description_in(group))
+        .extra_child(&container)
+        .build();
+    dialog.add_response("close", "Close");
+    dialog
+}
+
+/// Dialog explaining a key that the specification does not define
+pub fn make_custom_key_dialog(key: &str) -> adw::AlertDialog {
+    let dialog = adw::AlertDialog::builder()
+        .heading(key)
+        .body(custom_key_description(key))
+        .build();
+    dialog.add_response("close", "Close");
+    dialog
 }
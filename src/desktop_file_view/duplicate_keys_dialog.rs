@@ -0,0 +1,132 @@
+/*
+* Copyright © 2025 Alessandro Balducci
+*
+* This file is part of Desktop File Editor.
+* Desktop File Editor is free software: you can redistribute it and/or modify it under the terms of the
+* GNU General Public License as published by the Free Software Foundation,
+* either version 3 of the License, or (at your option) any later version.
+* Desktop File Editor is distributed in the hope that it will be useful, but WITHOUT ANY WARRANTY;
+* without even the implied warranty of MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.
+* See the GNU General Public License for more details.
+* You should have received a copy of the GNU General Public License along with Desktop File Editor. If not, see <https://www.gnu.org/licenses/>.
+*/
+
+use adw::prelude::*;
+use gtk::glib::{self, clone, subclass::types::ObjectSubclassIsExt};
+
+use super::desktop_entry_ext::DuplicateKey;
+use super::DesktopFileView;
+
+/// Lets the user choose which definition of each duplicated key to keep. Decoding the file
+/// already kept the last one, choosing another replaces it in the entry, while the other lines
+/// are dropped the next time the file is saved
+pub fn show_duplicate_keys_dialog(view: &DesktopFileView, duplicates: Vec<DuplicateKey>) {
+    let dialog = adw::Dialog::builder()
+        .title("Duplicate Keys")
+        .content_width(480)
+        .content_height(520)
+        .build();
+
+    let page = adw::PreferencesPage::new();
+    page.set_description(
+        "Some keys are defined more than once, only the last definition is used. \
+         Choose the value to keep, the other definitions are removed when the file is saved.",
+    );
+
+    let mut choices = Vec::new();
+    for duplicate in duplicates {
+        let key = match &duplicate.locale {
+            Some(locale) => format!("{}[{locale}]", duplicate.key),
+            None => duplicate.key.clone(),
+        };
+        let group = adw::PreferencesGroup::builder()
+            .title(glib::markup_escape_text(&key))
+            .description(glib::markup_escape_text(&duplicate.group))
+            .build();
+
+        let last = duplicate.values.len() - 1;
+        let mut checks: Vec<gtk::CheckButton> = Vec::new();
+        for (i, (line, value)) in duplicate.values.iter().enumerate() {
+            let subtitle = if i == last {
+                format!("Line {line}, currently used")
+            } else {
+                format!("Line {line}")
+            };
+            let row = adw::ActionRow::builder()
+                .title(if value.is_empty() { "Empty" } else { value })
+                .subtitle(subtitle)
+                .use_markup(false)
+                .build();
+
+            let check = gtk::CheckButton::builder()
+                .valign(gtk::Align::Center)
+                .active(i == last)
+                .build();
+            if let Some(first) = checks.first() {
+                check.set_group(Some(first));
+            }
+            row.add_prefix(&check);
+            row.set_activatable_widget(Some(&check));
+            group.add(&row);
+            checks.push(check);
+        }
+
+        page.add(&group);
+        choices.push((duplicate, checks));
+    }
+
+    let header_bar = adw::HeaderBar::builder()
+        .show_start_title_buttons(false)
+        .show_end_title_buttons(false)
+        .build();
+
+    let cancel_button = gtk::Button::with_label("Cancel");
+    cancel_button.set_tooltip_text(Some("Keep using the last definitions"));
+    cancel_button.connect_clicked(clone!(
+        #[weak]
+        dialog,
+        move |_| {
+            dialog.close();
+        }
+    ));
+    header_bar.pack_start(&cancel_button);
+
+    let keep_button = gtk::Button::builder()
+        .label("Keep Selected")
+        .css_classes(["suggested-action"])
+        .build();
+    keep_button.connect_clicked(clone!(
+        #[weak]
+        dialog,
+        #[weak]
+        view,
+        move |_| {
+            view.imp().edit_entry(|editor| {
+                for (duplicate, checks) in choices.iter() {
+                    let chosen = checks.iter().position(|check| check.is_active());
+                    if let Some((_, value)) = chosen.and_then(|i| duplicate.values.get(i)) {
+                        editor.set_value(
+                            &duplicate.group,
+                            &duplicate.key,
+                            duplicate.locale.as_deref(),
+                            value.clone(),
+                        );
+                    }
+                }
+            });
+
+            // Even when the last values are kept, saving is what removes the other lines
+            view.refresh_groups();
+            view.set_content_changed(true);
+            dialog.close();
+        }
+    ));
+    header_bar.pack_end(&keep_button);
+
+    let toolbar_view = adw::ToolbarView::new();
+    toolbar_view.add_top_bar(&header_bar);
+    toolbar_view.set_content(Some(&page));
+    dialog.set_child(Some(&toolbar_view));
+
+    dialog.present(Some(view));
+}
@@ -0,0 +1,645 @@
+/*
+* Copyright © 2025 Alessandro Balducci
+*
+* This file is part of Desktop File Editor.
+* Desktop File Editor is free software: you can redistribute it and/or modify it under the terms of the
+* GNU General Public License as published by the Free Software Foundation,
+* either version 3 of the License, or (at your option) any later version.
+* Desktop File Editor is distributed in the hope that it will be useful, but WITHOUT ANY WARRANTY;
+* without even the implied warranty of MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.
+* See the GNU General Public License for more details.
+* You should have received a copy of the GNU General Public License along with Desktop File Editor. If not, see <https://www.gnu.org/licenses/>.
+*/
+
+use std::collections::BTreeMap;
+use std::time::{Duration, Instant};
+
+use freedesktop_desktop_entry::{DecodeError, DesktopEntry, GroupName, Key, LocaleMap, Value};
+
+use super::desktop_entry_ext::DesktopEntryExt;
+
+/// Edits of the same value closer than this are undone together, like the letters of a word
+const COALESCE_INTERVAL: Duration = Duration::from_secs(1);
+
+/// The most edits that can be undone
+const MAX_EDITS: usize = 200;
+
+/// The value of a key along with its translations
+type KeyValues = (Value, LocaleMap);
+
+/// The keys of a group along with their values
+type KeyMap = BTreeMap<Key, KeyValues>;
+
+/// One change to the entry, or to the locale it is shown in, holding what is needed to undo it
+#[derive(Debug, Clone, PartialEq)]
+pub enum EntryCommand {
+    /// A key was added, changed or removed, `None` when the key does not exist
+    SetKey {
+        group: GroupName,
+        key: Key,
+        before: Option<KeyValues>,
+        after: Option<KeyValues>,
+    },
+    /// A group was added, changed as a whole or removed, `None` when the group does not exist
+    SetGroup {
+        name: GroupName,
+        before: Option<KeyMap>,
+        after: Option<KeyMap>,
+    },
+    RenameGroup {
+        from: GroupName,
+        to: GroupName,
+    },
+    /// A key was moved with its translations, replacing what the target group had for it
+    MoveKey {
+        from: GroupName,
+        to: GroupName,
+        key: Key,
+        replaced: Option<KeyValues>,
+        created_group: bool,
+    },
+    /// `X-Ubuntu-Gettext-Domain` is kept outside of the groups
+    SetGettextDomain {
+        before: Option<String>,
+        after: Option<String>,
+    },
+    /// Another locale was shown, `None` is the default locale
+    SwitchLocale {
+        before: Option<String>,
+        after: Option<String>,
+    },
+}
+
+impl EntryCommand {
+    pub fn apply(&self, entry: &mut DesktopEntry) {
+        match self {
+            Self::SetKey {
+                group, key, after, ..
+            } => set_key(entry, group, key, after.clone()),
+            Self::SetGroup { name, after, .. } => set_group(entry, name, after.clone()),
+            Self::RenameGroup { from, to } => entry.rename_group(from, to.clone()),
+            Self::MoveKey { from, to, key, .. } => {
+                entry.move_entry(from, to, key);
+            }
+            Self::SetGettextDomain { after, .. } => entry.ubuntu_gettext_domain = after.clone(),
+            Self::SwitchLocale { .. } => {}
+        }
+    }
+
+    pub fn revert(&self, entry: &mut DesktopEntry) {
+        match self {
+            Self::SetKey {
+                group, key, before, ..
+            } => set_key(entry, group, key, before.clone()),
+            Self::SetGroup { name, before, .. } => set_group(entry, name, before.clone()),
+            Self::RenameGroup { from, to } => entry.rename_group(to, from.clone()),
+            Self::MoveKey {
+                from,
+                to,
+                key,
+                replaced,
+                created_group,
+            } => {
+                entry.move_entry(to, from, key);
+                if *created_group {
+                    entry.remove_group(to.clone());
+                } else if let Some(replaced) = replaced {
+                    entry.set_entry_with_localizations(to.clone(), key.clone(), replaced.clone());
+                }
+            }
+            Self::SetGettextDomain { before, .. } => entry.ubuntu_gettext_domain = before.clone(),
+            Self::SwitchLocale { .. } => {}
+        }
+    }
+
+    /// Takes the result of `later` if it keeps changing the same value, returns whether it did
+    fn absorb(&mut self, later: &EntryCommand) -> bool {
+        match (self, later) {
+            (
+                Self::SetKey {
+                    group, key, after, ..
+                },
+                Self::SetKey {
+                    group: later_group,
+                    key: later_key,
+                    after: later_after,
+                    ..
+                },
+            ) if group == later_group && key == later_key => {
+                after.clone_from(later_after);
+                true
+            }
+            (
+                Self::SetGroup { name, after, .. },
+                Self::SetGroup {
+                    name: later_name,
+                    after: later_after,
+                    ..
+                },
+            ) if name == later_name => {
+                after.clone_from(later_after);
+                true
+            }
+            (
+                Self::SetGettextDomain { after, .. },
+                Self::SetGettextDomain {
+                    after: later_after, ..
+                },
+            ) => {
+                after.clone_from(later_after);
+                true
+            }
+            _ => false,
+        }
+    }
+}
+
+fn set_key(entry: &mut DesktopEntry, group: &str, key: &str, values: Option<KeyValues>) {
+    match values {
+        Some(values) => {
+            entry.set_entry_with_localizations(group.to_string(), key.to_string(), values)
+        }
+        None => entry.remove_entry(group.to_string(), key.to_string()),
+    }
+}
+
+fn set_group(entry: &mut DesktopEntry, name: &str, keys: Option<KeyMap>) {
+    match keys {
+        Some(keys) => {
+            entry.add_group(name.to_string());
+            if let Some(group) = entry.groups.0.get_mut(name) {
+                group.0 = keys;
+            }
+        }
+        None => entry.remove_group(name.to_string()),
+    }
+}
+
+/// Changes the entry through the `DesktopEntryExt` methods, recording a command for each change
+/// so that it can be undone
+pub struct EntryEditor<'a> {
+    entry: &'a mut DesktopEntry,
+    commands: Vec<EntryCommand>,
+}
+
+impl<'a> EntryEditor<'a> {
+    pub fn new(entry: &'a mut DesktopEntry) -> Self {
+        Self {
+            entry,
+            commands: Vec::new(),
+        }
+    }
+
+    pub fn entry(&self) -> &DesktopEntry {
+        self.entry
+    }
+
+    pub fn into_commands(self) -> Vec<EntryCommand> {
+        self.commands
+    }
+
+    fn key_values(&self, group: &str, key: &str) -> Option<KeyValues> {
+        self.entry.groups.0.get(group)?.0.get(key).cloned()
+    }
+
+    fn group_keys(&self, name: &str) -> Option<KeyMap> {
+        self.entry.groups.0.get(name).map(|group| group.0.clone())
+    }
+
+    /// Runs `change`, which only touches `key` of `group`, and records what it did. When the group
+    /// is created along the way its creation is recorded instead
+    fn change_key<R>(
+        &mut self,
+        group: &str,
+        key: &str,
+        change: impl FnOnce(&mut DesktopEntry) -> R,
+    ) -> R {
+        if !self.entry.groups.0.contains_key(group) {
+            return self.change_group(group, change);
+        }
+        let before = self.key_values(group, key);
+        let result = change(self.entry);
+        let after = self.key_values(group, key);
+        if before != after {
+            self.commands.push(EntryCommand::SetKey {
+                group: group.to_string(),
+                key: key.to_string(),
+                before,
+                after,
+            });
+        }
+        result
+    }
+
+    /// Runs `change`, which only touches the group `name`, and records what it did
+    fn change_group<R>(&mut self, name: &str, change: impl FnOnce(&mut DesktopEntry) -> R) -> R {
+        let before = self.group_keys(name);
+        let result = change(self.entry);
+        let after = self.group_keys(name);
+        if before != after {
+            self.commands.push(EntryCommand::SetGroup {
+                name: name.to_string(),
+                before,
+                after,
+            });
+        }
+        result
+    }
+
+    pub fn set_value(&mut self, group_name: &str, key: &str, locale: Option<&str>, value: String) {
+        self.change_key(group_name, key, |entry| {
+            entry.set_value(group_name, key, locale, value)
+        });
+    }
+
+    /// Remove the translation of `key` for `locale`, keeping the key itself
+    pub fn remove_translation(&mut self, group_name: &str, key: &str, locale: &str) {
+        self.change_key(group_name, key, |entry| {
+            let localized = entry
+                .groups
+                .0
+                .get_mut(group_name)
+                .and_then(|group| group.0.get_mut(key))
+                .map(|(_, localized)| localized);
+            if let Some(localized) = localized {
+                localized.remove(locale);
+            }
+        });
+    }
+
+    pub fn set_gettext_domain(&mut self, domain: Option<String>) {
+        if self.entry.ubuntu_gettext_domain == domain {
+            return;
+        }
+        let before = std::mem::replace(&mut self.entry.ubuntu_gettext_domain, domain.clone());
+        self.commands.push(EntryCommand::SetGettextDomain {
+            before,
+            after: domain,
+        });
+    }
+
+    pub fn add_entry(&mut self, group_name: String, key: String) -> bool {
+        let group = group_name.clone();
+        let key_name = key.clone();
+        self.change_key(&group, &key_name, |entry| entry.add_entry(group_name, key))
+    }
+
+    pub fn remove_entry(&mut self, group_name: String, key: String) {
+        let group = group_name.clone();
+        let key_name = key.clone();
+        self.change_key(&group, &key_name, |entry| {
+            entry.remove_entry(group_name, key)
+        });
+    }
+
+    pub fn set_entry_with_localizations(
+        &mut self,
+        group_name: String,
+        key: String,
+        value: (Value, LocaleMap),
+    ) {
+        let group = group_name.clone();
+        let key_name = key.clone();
+        self.change_key(&group, &key_name, |entry| {
+            entry.set_entry_with_localizations(group_name, key, value)
+        });
+    }
+
+    pub fn add_group(&mut self, name: String) {
+        let group = name.clone();
+        self.change_group(&group, |entry| entry.add_group(name));
+    }
+
+    pub fn add_action(&mut self, name: &str) {
+        self.add_group(format!("Desktop Action {name}"));
+    }
+
+    pub fn remove_group(&mut self, name: String) {
+        let group = name.clone();
+        self.change_group(&group, |entry| entry.remove_group(name));
+    }
+
+    pub fn rename_group(&mut self, from: &str, to: String) {
+        if from == to || !self.entry.groups.0.contains_key(from) {
+            return;
+        }
+        // Renaming over another group replaces it, which a rename alone could not bring back
+        if self.entry.groups.0.contains_key(&to) {
+            self.remove_group(to.clone());
+        }
+        self.entry.rename_group(from, to.clone());
+        self.commands.push(EntryCommand::RenameGroup {
+            from: from.to_string(),
+            to,
+        });
+    }
+
+    pub fn move_entry(&mut self, from: &str, to: &str, key: &str) -> bool {
+        let replaced = self.key_values(to, key);
+        let created_group = !self.entry.groups.0.contains_key(to);
+        let moved = self.entry.move_entry(from, to, key);
+        if moved {
+            self.commands.push(EntryCommand::MoveKey {
+                from: from.to_string(),
+                to: to.to_string(),
+                key: key.to_string(),
+                replaced,
+                created_group,
+            });
+        }
+        moved
+    }
+
+    pub fn strip_translations(&mut self, keep: &[String]) -> usize {
+        // Only the groups with translations can change
+        let translated: Vec<(GroupName, KeyMap)> = self
+            .entry
+            .groups
+            .0
+            .iter()
+            .filter(|(_, group)| group.0.values().any(|(_, localized)| !localized.is_empty()))
+            .map(|(name, group)| (name.clone(), group.0.clone()))
+            .collect();
+
+        let removed = self.entry.strip_translations(keep);
+
+        for (name, before) in translated {
+            let after = self.group_keys(&name);
+            if after.as_ref() != Some(&before) {
+                self.commands.push(EntryCommand::SetGroup {
+                    name,
+                    before: Some(before),
+                    after,
+                });
+            }
+        }
+        removed
+    }
+
+    pub fn paste_groups(&mut self, text: &str) -> Result<Vec<GroupName>, DecodeError> {
+        // Pasting changes the main group too, for its keys and the Actions list
+        let main_group = "Desktop Entry";
+        let main_before = self.group_keys(main_group);
+
+        let added = self.entry.paste_groups(text)?;
+
+        let main_after = self.group_keys(main_group);
+        if main_after != main_before {
+            self.commands.push(EntryCommand::SetGroup {
+                name: main_group.to_string(),
+                before: main_before,
+                after: main_after,
+            });
+        }
+        for name in added.iter().filter(|name| *name != main_group) {
+            self.commands.push(EntryCommand::SetGroup {
+                name: name.clone(),
+                before: None,
+                after: self.group_keys(name),
+            });
+        }
+        Ok(added)
+    }
+
+    /// Replace the whole entry with `other`, recording only the groups that differ
+    pub fn replace_with(&mut self, other: DesktopEntry) {
+        let mut names: Vec<&GroupName> = self.entry.groups.0.keys().collect();
+        names.extend(
+            other
+                .groups
+                .0
+                .keys()
+                .filter(|name| !self.entry.groups.0.contains_key(*name)),
+        );
+        for name in names {
+            let before = self.entry.groups.0.get(name).map(|group| &group.0);
+            let after = other.groups.0.get(name).map(|group| &group.0);
+            if before != after {
+                self.commands.push(EntryCommand::SetGroup {
+                    name: name.clone(),
+                    before: before.cloned(),
+                    after: after.cloned(),
+                });
+            }
+        }
+        if self.entry.ubuntu_gettext_domain != other.ubuntu_gettext_domain {
+            self.commands.push(EntryCommand::SetGettextDomain {
+                before: self.entry.ubuntu_gettext_domain.clone(),
+                after: other.ubuntu_gettext_domain.clone(),
+            });
+        }
+        *self.entry = other;
+    }
+}
+
+/// The commands of one edit, undone and redone together
+#[derive(Debug, Clone)]
+struct Step {
+    commands: Vec<EntryCommand>,
+    time: Instant,
+}
+
+impl Step {
+    /// Takes in `later` if it keeps editing the same value shortly after this step
+    fn absorb(&mut self, later: &Step) -> bool {
+        if later.time.duration_since(self.time) >= COALESCE_INTERVAL {
+            return false;
+        }
+        let absorbed = match (&mut self.commands[..], &later.commands[..]) {
+            ([command], [later_command]) => command.absorb(later_command),
+            _ => false,
+        };
+        if absorbed {
+            self.time = later.time;
+        }
+        absorbed
+    }
+
+    /// The locale that is shown after this step is undone or redone, if it switched one
+    fn locale(&self, redo: bool) -> Option<Option<String>> {
+        self.commands
+            .iter()
+            .rev()
+            .find_map(|command| match command {
+                EntryCommand::SwitchLocale { before, after } => {
+                    Some(if redo { after.clone() } else { before.clone() })
+                }
+                _ => None,
+            })
+    }
+}
+
+/// What undoing or redoing a step means for the editor, besides the changed entry
+#[derive(Debug, Default, PartialEq)]
+pub struct Stepped {
+    /// The locale to show again, when the step switched it
+    pub locale: Option<Option<String>>,
+}
+
+/// Edits that can be undone and redone, recorded as commands by an `EntryEditor` or by the
+/// locale selection
+#[derive(Debug, Default)]
+pub struct EditHistory {
+    undo: Vec<Step>,
+    redo: Vec<Step>,
+}
+
+impl EditHistory {
+    /// Forget every edit
+    pub fn clear(&mut self) {
+        self.undo.clear();
+        self.redo.clear();
+    }
+
+    /// Record the commands of one edit, returns whether there were any
+    pub fn record(&mut self, commands: Vec<EntryCommand>) -> bool {
+        if commands.is_empty() {
+            return false;
+        }
+        let step = Step {
+            commands,
+            time: Instant::now(),
+        };
+
+        match self.undo.last_mut() {
+            Some(previous) if previous.absorb(&step) => {}
+            _ => self.undo.push(step),
+        }
+        if self.undo.len() > MAX_EDITS {
+            self.undo.remove(0);
+        }
+        self.redo.clear();
+        true
+    }
+
+    pub fn undo(&mut self, entry: &mut DesktopEntry) -> Option<Stepped> {
+        let step = self.undo.pop()?;
+        for command in step.commands.iter().rev() {
+            command.revert(entry);
+        }
+        let stepped = Stepped {
+            locale: step.locale(false),
+        };
+        self.redo.push(step);
+        Some(stepped)
+    }
+
+    pub fn redo(&mut self, entry: &mut DesktopEntry) -> Option<Stepped> {
+        let mut step = self.redo.pop()?;
+        for command in &step.commands {
+            command.apply(entry);
+        }
+        let stepped = Stepped {
+            locale: step.locale(true),
+        };
+        // A redone step is never continued by the next edit
+        if let Some(time) = step.time.checked_sub(COALESCE_INTERVAL) {
+            step.time = time;
+        }
+        self.undo.push(step);
+        Some(stepped)
+    }
+
+    pub fn can_undo(&self) -> bool {
+        !self.undo.is_empty()
+    }
+
+    pub fn can_redo(&self) -> bool {
+        !self.redo.is_empty()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn parse(input: &str) -> DesktopEntry {
+        DesktopEntry::from_str("/tmp/app.desktop", input, None::<&[&str]>).unwrap()
+    }
+
+    #[test]
+    fn undo_and_redo() {
+        let mut entry = parse("[Desktop Entry]\nName=App\nName[de]=Anw\n\n[X-Tool]\nA=1\n");
+        let mut history = EditHistory::default();
+
+        let mut editor = EntryEditor::new(&mut entry);
+        editor.remove_entry("Desktop Entry".to_string(), "Name".to_string());
+        editor.remove_group("X-Tool".to_string());
+        editor.set_value("Desktop Action new", "Exec", None, "app --new".to_string());
+        assert!(history.record(editor.into_commands()));
+
+        assert_eq!(history.undo(&mut entry), Some(Stepped::default()));
+        assert_eq!(
+            entry.entry("Desktop Entry", "Name", Some("de")),
+            Some("Anw")
+        );
+        assert_eq!(entry.entry("X-Tool", "A", None), Some("1"));
+        assert!(!entry.groups.0.contains_key("Desktop Action new"));
+
+        assert!(history.redo(&mut entry).is_some());
+        assert_eq!(entry.entry("Desktop Entry", "Name", None), None);
+        assert!(!entry.groups.0.contains_key("X-Tool"));
+        assert!(!history.can_redo());
+    }
+
+    #[test]
+    fn typing_is_undone_at_once() {
+        let mut entry = parse("[Desktop Entry]\nName=App\n");
+        let mut history = EditHistory::default();
+
+        for name in ["Ap", "A", "Ab"] {
+            let mut editor = EntryEditor::new(&mut entry);
+            editor.set_value("Desktop Entry", "Name", None, name.to_string());
+            history.record(editor.into_commands());
+        }
+        assert!(history.undo(&mut entry).is_some());
+        assert_eq!(entry.entry("Desktop Entry", "Name", None), Some("App"));
+        assert!(!history.can_undo());
+    }
+
+    #[test]
+    fn moves_and_renames_are_undone() {
+        let mut entry = parse("[Desktop Entry]\nName=App\nX-A=1\nX-A[de]=2\n\n[X-Old]\nB=1\n");
+        let mut history = EditHistory::default();
+
+        let mut editor = EntryEditor::new(&mut entry);
+        assert!(editor.move_entry("Desktop Entry", "X-New", "X-A"));
+        editor.rename_group("X-Old", "X-Renamed".to_string());
+        history.record(editor.into_commands());
+        assert_eq!(entry.entry("X-New", "X-A", Some("de")), Some("2"));
+        assert_eq!(entry.entry("X-Renamed", "B", None), Some("1"));
+
+        history.undo(&mut entry);
+        assert_eq!(entry.entry("Desktop Entry", "X-A", Some("de")), Some("2"));
+        assert!(!entry.groups.0.contains_key("X-New"));
+        assert_eq!(entry.entry("X-Old", "B", None), Some("1"));
+        assert!(!entry.groups.0.contains_key("X-Renamed"));
+    }
+
+    #[test]
+    fn locale_switches_are_steps() {
+        let mut entry = parse("[Desktop Entry]\nName=App\n");
+        let mut history = EditHistory::default();
+
+        history.record(vec![EntryCommand::SwitchLocale {
+            before: None,
+            after: Some("de".to_string()),
+        }]);
+        let mut editor = EntryEditor::new(&mut entry);
+        editor.set_value("Desktop Entry", "Name", Some("de"), "Anw".to_string());
+        history.record(editor.into_commands());
+
+        assert_eq!(history.undo(&mut entry), Some(Stepped::default()));
+        assert_eq!(entry.entry("Desktop Entry", "Name", Some("de")), None);
+        assert_eq!(
+            history.undo(&mut entry),
+            Some(Stepped { locale: Some(None) })
+        );
+        assert_eq!(
+            history.redo(&mut entry),
+            Some(Stepped {
+                locale: Some(Some("de".to_string()))
+            })
+        );
+    }
+}
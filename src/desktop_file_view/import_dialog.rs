@@ -0,0 +1,217 @@
+/*
+* Copyright © 2025 Alessandro Balducci
+*
+* This file is part of Desktop File Editor.
+* Desktop File Editor is free software: you can redistribute it and/or modify it under the terms of the
+* GNU General Public License as published by the Free Software Foundation,
+* either version 3 of the License, or (at your option) any later version.
+* Desktop File Editor is distributed in the hope that it will be useful, but WITHOUT ANY WARRANTY;
+* without even the implied warranty of MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.
+* See the GNU General Public License for more details.
+* You should have received a copy of the GNU General Public License along with Desktop File Editor. If not, see <https://www.gnu.org/licenses/>.
+*/
+
+use freedesktop_desktop_entry::DesktopEntry;
+use gtk::glib::{self, subclass::types::ObjectSubclassIsExt};
+
+use super::DesktopFileView;
+
+mod imp {
+    use std::borrow::Borrow;
+    use std::cell::RefCell;
+
+    use adw::{prelude::*, subclass::prelude::*};
+    use freedesktop_desktop_entry::{DesktopEntry, GroupName, Key};
+    use gtk::glib::{self, clone};
+
+    use crate::desktop_file_view::desktop_entry_ext::{extend_list, DesktopEntryExt};
+    use crate::desktop_file_view::imp::DesktopEntryCell;
+    use crate::desktop_file_view::DesktopFileView;
+    use crate::settings::settings;
+
+    #[derive(Default)]
+    pub struct ImportDialog {
+        pub source: RefCell<Option<DesktopEntry>>,
+        pub target: RefCell<Option<DesktopFileView>>,
+        pub checks: RefCell<Vec<(GroupName, Key, gtk::CheckButton)>>,
+        pub import_button: gtk::Button,
+    }
+
+    #[glib::object_subclass]
+    impl ObjectSubclass for ImportDialog {
+        const NAME: &'static str = "ImportDialog";
+        type Type = super::ImportDialog;
+        type ParentType = adw::Dialog;
+    }
+
+    impl ObjectImpl for ImportDialog {
+        fn constructed(&self) {
+            self.parent_constructed();
+            let obj = self.obj();
+
+            obj.set_title("Import Keys");
+            obj.set_content_width(480);
+            obj.set_content_height(560);
+
+            self.import_button.set_label("Import");
+            self.import_button.add_css_class("suggested-action");
+            self.import_button.set_sensitive(false);
+            self.import_button.connect_clicked(clone!(
+                #[weak(rename_to = dialog)]
+                obj,
+                move |_| dialog.imp().import()
+            ));
+        }
+    }
+
+    impl WidgetImpl for ImportDialog {}
+    impl AdwDialogImpl for ImportDialog {}
+
+    impl ImportDialog {
+        /// Build a group of check rows for every key of the source file
+        pub fn populate(&self) {
+            let preferences_page = adw::PreferencesPage::new();
+            preferences_page.set_description(
+                "Choose the keys to copy into the current file. \
+                 Keys marked with a warning replace an existing value.",
+            );
+
+            let target = self.target.borrow().clone().expect("Target view not set");
+            let desktop_entry_rc = target.desktop_entry();
+            let desktop_entry_cell: &DesktopEntryCell = desktop_entry_rc.borrow();
+            let target_entry = desktop_entry_cell.borrow();
+
+            let source = self.source.borrow();
+            let source = source.as_ref().expect("Source entry not set");
+
+            let mut checks = Vec::new();
+            for (group_name, keymap) in source.sorted_groups(&settings().key_order_list()) {
+                let group = adw::PreferencesGroup::new();
+                group.set_title(&glib::markup_escape_text(&group_name));
+
+                for (key, (value, _)) in keymap {
+                    let row = adw::ActionRow::new();
+                    row.set_title(&glib::markup_escape_text(&key));
+                    row.set_subtitle(&glib::markup_escape_text(&value));
+                    row.set_subtitle_lines(1);
+
+                    let check = gtk::CheckButton::new();
+                    check.set_valign(gtk::Align::Center);
+                    check.connect_toggled(clone!(
+                        #[weak(rename_to = dialog)]
+                        self.obj(),
+                        move |_| dialog.imp().update_import_sensitivity()
+                    ));
+                    row.add_prefix(&check);
+                    row.set_activatable_widget(Some(&check));
+
+                    if let Some(current) = target_entry.entry(&group_name, &key, None) {
+                        let conflict = gtk::Image::from_icon_name("dialog-warning-symbolic");
+                        conflict.add_css_class("warning");
+                        conflict.set_tooltip_text(Some(&format!(
+                            "Replaces the current value “{current}”"
+                        )));
+                        row.add_suffix(&conflict);
+                    }
+
+                    group.add(&row);
+                    checks.push((group_name.clone(), key, check));
+                }
+
+                preferences_page.add(&group);
+            }
+            self.checks.replace(checks);
+
+            let header_bar = adw::HeaderBar::new();
+            header_bar.set_show_end_title_buttons(false);
+            header_bar.set_show_start_title_buttons(false);
+            let cancel_button = gtk::Button::with_label("Cancel");
+            cancel_button.connect_clicked(clone!(
+                #[weak(rename_to = dialog)]
+                self.obj(),
+                move |_| {
+                    dialog.close();
+                }
+            ));
+            header_bar.pack_start(&cancel_button);
+            header_bar.pack_end(&self.import_button);
+
+            let toolbar_view = adw::ToolbarView::new();
+            toolbar_view.add_top_bar(&header_bar);
+            toolbar_view.set_content(Some(&preferences_page));
+            self.obj().set_child(Some(&toolbar_view));
+        }
+
+        fn update_import_sensitivity(&self) {
+            let any_selected = self
+                .checks
+                .borrow()
+                .iter()
+                .any(|(_, _, check)| check.is_active());
+            self.import_button.set_sensitive(any_selected);
+        }
+
+        fn import(&self) {
+            let target = self.target.borrow().clone().expect("Target view not set");
+
+            target.imp().edit_entry(|editor| {
+                let source = self.source.borrow();
+                let source = source.as_ref().expect("Source entry not set");
+
+                for (group_name, key, check) in self.checks.borrow().iter() {
+                    if !check.is_active() {
+                        continue;
+                    }
+
+                    let value = source
+                        .groups
+                        .group(group_name)
+                        .and_then(|group| group.0.get(key));
+                    if let Some(value) = value {
+                        editor.set_entry_with_localizations(
+                            group_name.clone(),
+                            key.clone(),
+                            value.clone(),
+                        );
+                    }
+                }
+
+                // Imported actions are only shown by launchers once they are listed
+                let checks = self.checks.borrow();
+                let actions = checks
+                    .iter()
+                    .filter(|(_, _, check)| check.is_active())
+                    .filter_map(|(group_name, _, _)| group_name.strip_prefix("Desktop Action "));
+                let listed = editor
+                    .entry()
+                    .entry("Desktop Entry", "Actions", None)
+                    .unwrap_or_default();
+                if let Some(listed) = extend_list(listed, actions) {
+                    editor.set_value("Desktop Entry", "Actions", None, listed);
+                }
+            });
+
+            target.refresh_groups();
+            target.set_content_changed(true);
+            self.obj().close();
+        }
+    }
+}
+
+glib::wrapper! {
+    /// Lets the user pick keys of another desktop file to merge into the current one
+    pub struct ImportDialog(ObjectSubclass<imp::ImportDialog>)
+        @extends adw::Dialog, gtk::Widget,
+        @implements gtk::Accessible, gtk::Buildable, gtk::ConstraintTarget, gtk::ShortcutManager;
+}
+
+impl ImportDialog {
+    pub fn new(source: DesktopEntry, target: &DesktopFileView) -> Self {
+        let dialog: Self = glib::Object::builder().build();
+        let imp = dialog.imp();
+        imp.source.replace(Some(source));
+        imp.target.replace(Some(target.clone()));
+        imp.populate();
+        dialog
+    }
+}
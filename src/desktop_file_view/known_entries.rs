@@ -11,64 +11,470 @@
 * You should have received a copy of the GNU General Public License along with Desktop File Editor. If not, see <https://www.gnu.org/licenses/>.
 */
 
-use std::collections::HashMap;
-
-use once_cell::sync::Lazy;
-
-pub const KNOWN_KEYS: [&str; 25] = [
-    "Type",
-    "Version",
-    "Name",
-    "GenericName",
-    "NoDisplay",
-    "Comment",
-    "Icon",
-    "Hidden",
-    "OnlyShowIn",
-    "NotShowIn",
-    "DBusActivatable",
-    "TryExec",
-    "Exec",
-    "Path",
-    "Terminal",
-    "Actions",
-    "MimeType",
-    "Categories",
-    "Implements",
-    "Keywords",
-    "StartupNotify",
-    "StartupWMClass",
-    "URL",
-    "PrefersNonDefaultGPU",
-    "SingleMainWindow",
+/// How the value of a key is written, as defined by the specification
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ValueType {
+    String,
+    LocaleString,
+    IconString,
+    Boolean,
+    Strings,
+    LocaleStrings,
+}
+
+impl ValueType {
+    /// Name of the type in the specification
+    pub fn name(&self) -> &'static str {
+        match self {
+            Self::String => "string",
+            Self::LocaleString => "localestring",
+            Self::IconString => "iconstring",
+            Self::Boolean => "boolean",
+            Self::Strings => "string list",
+            Self::LocaleStrings => "localestring list",
+        }
+    }
+}
+
+/// The kinds of groups keys are defined for
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum GroupKind {
+    /// The `Desktop Entry` group
+    Main,
+    /// `Desktop Action` groups
+    Action,
+    /// Groups added by applications, their keys are not part of the specification
+    Other,
+}
+
+impl GroupKind {
+    pub fn of(group_name: &str) -> Self {
+        if group_name == "Desktop Entry" {
+            Self::Main
+        } else if group_name.starts_with("Desktop Action ") {
+            Self::Action
+        } else {
+            Self::Other
+        }
+    }
+}
+
+/// Check the value of a key has to pass
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ValueRule {
+    Any,
+    NotEmpty,
+    OneOf(&'static [&'static str]),
+}
+
+/// Everything known about a key of the specification
+#[derive(Debug)]
+pub struct KeyInfo {
+    pub key: &'static str,
+    pub value_type: ValueType,
+    pub groups: &'static [GroupKind],
+    /// Value of new entries with this key, when there isn't a better one in context
+    pub default: &'static str,
+    pub rule: ValueRule,
+    pub description: &'static str,
+    /// Section of the specification the key is defined in
+    pub spec_link: &'static str,
+}
+
+impl KeyInfo {
+    pub fn allowed_in(&self, group: GroupKind) -> bool {
+        self.groups.contains(&group)
+    }
+
+    /// First sentence of the description, short enough to be shown next to the key in a list
+    pub fn short_description(&self) -> &'static str {
+        match self.description.find(". ") {
+            Some(end) => &self.description[..=end],
+            None => self.description,
+        }
+    }
+
+    /// Description of the key in a group of the kind `group`, inside actions some keys describe
+    /// the action rather than the application
+    pub fn description_in(&self, group: GroupKind) -> &'static str {
+        self.action_description(group).unwrap_or(self.description)
+    }
+
+    /// Section of the specification explaining the key in a group of the kind `group`
+    pub fn spec_link_in(&self, group: GroupKind) -> &'static str {
+        match self.action_description(group) {
+            Some(_) => EXTRA_ACTIONS,
+            None => self.spec_link,
+        }
+    }
+
+    fn action_description(&self, group: GroupKind) -> Option<&'static str> {
+        if group != GroupKind::Action {
+            return None;
+        }
+        ACTION_DESCRIPTIONS
+            .iter()
+            .find(|(key, _)| *key == self.key)
+            .map(|(_, description)| *description)
+    }
+
+    /// Reason `value` is not a valid value for the key, if it isn't
+    pub fn check(&self, value: &str) -> Option<String> {
+        match self.rule {
+            ValueRule::Any => None,
+            ValueRule::NotEmpty => value
+                .trim()
+                .is_empty()
+                .then(|| format!("{} is empty", self.key)),
+            ValueRule::OneOf(values) => (!values.contains(&value))
+                .then(|| format!("{} must be one of {}", self.key, values.join(", "))),
+        }
+    }
+}
+
+const RECOGNIZED_KEYS: &str =
+    "https://specifications.freedesktop.org/desktop-entry-spec/latest/recognized-keys.html";
+const EXEC_VARIABLES: &str =
+    "https://specifications.freedesktop.org/desktop-entry-spec/latest/exec-variables.html";
+const DBUS_ACTIVATION: &str =
+    "https://specifications.freedesktop.org/desktop-entry-spec/latest/dbus.html";
+const EXTRA_ACTIONS: &str =
+    "https://specifications.freedesktop.org/desktop-entry-spec/latest/extra-actions.html";
+const INTERFACES: &str =
+    "https://specifications.freedesktop.org/desktop-entry-spec/latest/interfaces.html";
+const MIME_TYPES: &str =
+    "https://specifications.freedesktop.org/desktop-entry-spec/latest/mime-types.html";
+const CATEGORY_REGISTRY: &str =
+    "https://specifications.freedesktop.org/menu-spec/latest/category-registry.html";
+const ICON_THEME_SPEC: &str = "https://specifications.freedesktop.org/icon-theme-spec/latest/";
+
+/// What the keys shared with the main group mean inside a `Desktop Action` group
+const ACTION_DESCRIPTIONS: [(&str, &str); 3] = [
+    ("Name", "Label of the action, shown among the other actions of the application, for example in the menu of its launcher."),
+    ("Icon", "Icon of the action, shown next to its name where implementations support it. If the name is an absolute path, the given file will be used. If the name is not an absolute path, the algorithm described in the Icon Theme Specification will be used to locate the icon."),
+    ("Exec", "Program to execute when the action is chosen, possibly with arguments, following the same rules as the Exec key of the application. If the application is D-Bus activatable, the action is activated through D-Bus and this key is only used by implementations that do not understand DBusActivatable."),
 ];
 
-pub static KEYS_DESCRIPTIONS: Lazy<HashMap<&str, &str>> = Lazy::new(|| {
-    HashMap::from([
-        ("Type", "This specification defines 3 types of desktop entries: Application (type 1), Link (type 2) and Directory (type 3). To allow the addition of new types in the future, implementations should ignore desktop entries with an unknown type."),
-        ("Version", "Version of the Desktop Entry Specification that the desktop entry conforms with. Entries that confirm with this version of the specification should use 1.5. Note that the version field is not required to be present."),
-        ("Name", "Specific name of the application, for example \"Mozilla\"."),
-        ("GenericName", "Generic name of the application, for example \"Web Browser\"."),
-        ("NoDisplay", "NoDisplay means \"this application exists, but don't display it in the menus\". This can be useful to e.g. associate this application with MIME types, so that it gets launched from a file manager (or other apps), without having a menu entry for it (there are tons of good reasons for this, including e.g. the netscape -remote, or kfmclient openURL kind of stuff)."),
-        ("Comment", "Tooltip for the entry, for example \"View sites on the Internet\". The value should not be redundant with the values of Name and GenericName."),
-        ("Icon", "Icon to display in file manager, menus, etc. If the name is an absolute path, the given file will be used. If the name is not an absolute path, the algorithm described in the Icon Theme Specification will be used to locate the icon."),
-        ("Hidden", "Hidden should have been called Deleted. It means the user deleted (at their level) something that was present (at an upper level, e.g. in the system dirs). It's strictly equivalent to the .desktop file not existing at all, as far as that user is concerned. This can also be used to \"uninstall\" existing files (e.g. due to a renaming) - by letting make install install a file with Hidden=true in it."),
-        ("OnlyShowIn", "A list of strings identifying the desktop environments that should display/not display a given desktop entry.\nBy default, a desktop file should be shown, unless an OnlyShowIn key is present, in which case, the default is for the file not to be shown. If $XDG_CURRENT_DESKTOP is set then it contains a colon-separated list of strings. In order, each string is considered. If a matching entry is found in OnlyShowIn then the desktop file is shown. If an entry is found in NotShowIn then the desktop file is not shown. If none of the strings match then the default action is taken (as above).\n$XDG_CURRENT_DESKTOP should have been set by the login manager, according to the value of the DesktopNames found in the session file. The entry in the session file has multiple values separated in the usual way: with a semicolon.\nThe same desktop name may not appear in both OnlyShowIn and NotShowIn of a group."),
-        ("NotShowIn", "A list of strings identifying the desktop environments that should display/not display a given desktop entry.\nBy default, a desktop file should be shown, unless an OnlyShowIn key is present, in which case, the default is for the file not to be shown. If $XDG_CURRENT_DESKTOP is set then it contains a colon-separated list of strings. In order, each string is considered. If a matching entry is found in OnlyShowIn then the desktop file is shown. If an entry is found in NotShowIn then the desktop file is not shown. If none of the strings match then the default action is taken (as above).\n$XDG_CURRENT_DESKTOP should have been set by the login manager, according to the value of the DesktopNames found in the session file. The entry in the session file has multiple values separated in the usual way: with a semicolon.\nThe same desktop name may not appear in both OnlyShowIn and NotShowIn of a group."),
-        ("DBusActivatable", "A boolean value specifying if D-Bus activation is supported for this application. If this key is missing, the default value is false. If the value is true then implementations should ignore the Exec key and send a D-Bus message to launch the application. See D-Bus Activation for more information on how this works. Applications should still include Exec= lines in their desktop files for compatibility with implementations that do not understand the DBusActivatable key."),
-        ("TryExec", "Path to an executable file on disk used to determine if the program is actually installed. If the path is not an absolute path, the file is looked up in the $PATH environment variable. If the file is not present or if it is not executable, the entry may be ignored (not be used in menus, for example)."),
-        ("Exec", "Program to execute, possibly with arguments. See the Exec key for details on how this key works. The Exec key is required if DBusActivatable is not set to true. Even if DBusActivatable is true, Exec should be specified for compatibility with implementations that do not understand DBusActivatable."),
-        ("Path", "If entry is of type Application, the working directory to run the program in."),
-        ("Terminal", "Whether the program runs in a terminal window."),
-        ("Actions", "Identifiers for application actions. This can be used to tell the application to make a specific action, different from the default behavior. The Application actions section describes how actions work."),
-        ("MimeType", "The MIME type(s) supported by this application."),
-        ("Categories", "Categories in which the entry should be shown in a menu (for possible values see the Desktop Menu Specification)."),
-        ("Implements", "A list of interfaces that this application implements. By default, a desktop file implements no interfaces. See Interfaces for more information on how this works."),
-        ("Keywords", "A list of strings which may be used in addition to other metadata to describe this entry. This can be useful e.g. to facilitate searching through entries. The values are not meant for display, and should not be redundant with the values of Name or GenericName."),
-        ("StartupNotify", "If true, it is KNOWN that the application will send a \"remove\" message when started with the DESKTOP_STARTUP_ID environment variable set. If false, it is KNOWN that the application does not work with startup notification at all (does not shown any window, breaks even when using StartupWMClass, etc.). If absent, a reasonable handling is up to implementations (assuming false, using StartupWMClass, etc.). (See the Startup Notification Protocol Specification for more details)."),
-        ("StartupWMClass", "If specified, it is known that the application will map at least one window with the given string as its WM class or WM name hint (see the Startup Notification Protocol Specification for more details)."),
-        ("URL", "If entry is Link type, the URL to access."),
-        ("PrefersNonDefaultGPU", "If true, the application prefers to be run on a more powerful discrete GPU if available, which we describe as “a GPU other than the default one” in this spec to avoid the need to define what a discrete GPU is and in which cases it might be considered more powerful than the default GPU. This key is only a hint and support might not be present depending on the implementation."),
-        ("SingleMainWindow", "If true, the application has a single main window, and does not support having an additional one opened. This key is used to signal to the implementation to avoid offering a UI to launch another window of the app. This key is only a hint and support might not be present depending on the implementation. "),
-    ])
-});
+const MAIN: &[GroupKind] = &[GroupKind::Main];
+const MAIN_AND_ACTIONS: &[GroupKind] = &[GroupKind::Main, GroupKind::Action];
+const BOOLEAN: ValueRule = ValueRule::OneOf(&["true", "false"]);
+
+/// The keys defined by the specification, in the order they are listed there
+pub static KEYS: [KeyInfo; 25] = [
+    KeyInfo {
+        key: "Type",
+        value_type: ValueType::String,
+        groups: MAIN,
+        default: "Application",
+        rule: ValueRule::OneOf(&["Application", "Link", "Directory"]),
+        description: "This specification defines 3 types of desktop entries: Application (type 1), Link (type 2) and Directory (type 3). To allow the addition of new types in the future, implementations should ignore desktop entries with an unknown type.",
+        spec_link: RECOGNIZED_KEYS,
+    },
+    KeyInfo {
+        key: "Version",
+        value_type: ValueType::String,
+        groups: MAIN,
+        default: "1.5",
+        rule: ValueRule::Any,
+        description: "Version of the Desktop Entry Specification that the desktop entry conforms with. Entries that confirm with this version of the specification should use 1.5. Note that the version field is not required to be present.",
+        spec_link: RECOGNIZED_KEYS,
+    },
+    KeyInfo {
+        key: "Name",
+        value_type: ValueType::LocaleString,
+        groups: MAIN_AND_ACTIONS,
+        default: "",
+        rule: ValueRule::NotEmpty,
+        description: "Specific name of the application, for example \"Mozilla\".",
+        spec_link: RECOGNIZED_KEYS,
+    },
+    KeyInfo {
+        key: "GenericName",
+        value_type: ValueType::LocaleString,
+        groups: MAIN,
+        default: "",
+        rule: ValueRule::Any,
+        description: "Generic name of the application, for example \"Web Browser\".",
+        spec_link: RECOGNIZED_KEYS,
+    },
+    KeyInfo {
+        key: "NoDisplay",
+        value_type: ValueType::Boolean,
+        groups: MAIN,
+        default: "false",
+        rule: BOOLEAN,
+        description: "NoDisplay means \"this application exists, but don't display it in the menus\". This can be useful to e.g. associate this application with MIME types, so that it gets launched from a file manager (or other apps), without having a menu entry for it (there are tons of good reasons for this, including e.g. the netscape -remote, or kfmclient openURL kind of stuff).",
+        spec_link: RECOGNIZED_KEYS,
+    },
+    KeyInfo {
+        key: "Comment",
+        value_type: ValueType::LocaleString,
+        groups: MAIN,
+        default: "",
+        rule: ValueRule::Any,
+        description: "Tooltip for the entry, for example \"View sites on the Internet\". The value should not be redundant with the values of Name and GenericName.",
+        spec_link: RECOGNIZED_KEYS,
+    },
+    KeyInfo {
+        key: "Icon",
+        value_type: ValueType::IconString,
+        groups: MAIN_AND_ACTIONS,
+        default: "",
+        rule: ValueRule::Any,
+        description: "Icon to display in file manager, menus, etc. If the name is an absolute path, the given file will be used. If the name is not an absolute path, the algorithm described in the Icon Theme Specification will be used to locate the icon.",
+        spec_link: ICON_THEME_SPEC,
+    },
+    KeyInfo {
+        key: "Hidden",
+        value_type: ValueType::Boolean,
+        groups: MAIN,
+        default: "false",
+        rule: BOOLEAN,
+        description: "Hidden should have been called Deleted. It means the user deleted (at their level) something that was present (at an upper level, e.g. in the system dirs). It's strictly equivalent to the .desktop file not existing at all, as far as that user is concerned. This can also be used to \"uninstall\" existing files (e.g. due to a renaming) - by letting make install install a file with Hidden=true in it.",
+        spec_link: RECOGNIZED_KEYS,
+    },
+    KeyInfo {
+        key: "OnlyShowIn",
+        value_type: ValueType::Strings,
+        groups: MAIN,
+        default: "",
+        rule: ValueRule::Any,
+        description: "A list of strings identifying the desktop environments that should display/not display a given desktop entry.\nBy default, a desktop file should be shown, unless an OnlyShowIn key is present, in which case, the default is for the file not to be shown. If $XDG_CURRENT_DESKTOP is set then it contains a colon-separated list of strings. In order, each string is considered. If a matching entry is found in OnlyShowIn then the desktop file is shown. If an entry is found in NotShowIn then the desktop file is not shown. If none of the strings match then the default action is taken (as above).\n$XDG_CURRENT_DESKTOP should have been set by the login manager, according to the value of the DesktopNames found in the session file. The entry in the session file has multiple values separated in the usual way: with a semicolon.\nThe same desktop name may not appear in both OnlyShowIn and NotShowIn of a group.",
+        spec_link: RECOGNIZED_KEYS,
+    },
+    KeyInfo {
+        key: "NotShowIn",
+        value_type: ValueType::Strings,
+        groups: MAIN,
+        default: "",
+        rule: ValueRule::Any,
+        description: "A list of strings identifying the desktop environments that should display/not display a given desktop entry.\nBy default, a desktop file should be shown, unless an OnlyShowIn key is present, in which case, the default is for the file not to be shown. If $XDG_CURRENT_DESKTOP is set then it contains a colon-separated list of strings. In order, each string is considered. If a matching entry is found in OnlyShowIn then the desktop file is shown. If an entry is found in NotShowIn then the desktop file is not shown. If none of the strings match then the default action is taken (as above).\n$XDG_CURRENT_DESKTOP should have been set by the login manager, according to the value of the DesktopNames found in the session file. The entry in the session file has multiple values separated in the usual way: with a semicolon.\nThe same desktop name may not appear in both OnlyShowIn and NotShowIn of a group.",
+        spec_link: RECOGNIZED_KEYS,
+    },
+    KeyInfo {
+        key: "DBusActivatable",
+        value_type: ValueType::Boolean,
+        groups: MAIN,
+        default: "false",
+        rule: BOOLEAN,
+        description: "A boolean value specifying if D-Bus activation is supported for this application. If this key is missing, the default value is false. If the value is true then implementations should ignore the Exec key and send a D-Bus message to launch the application. See D-Bus Activation for more information on how this works. Applications should still include Exec= lines in their desktop files for compatibility with implementations that do not understand the DBusActivatable key.",
+        spec_link: DBUS_ACTIVATION,
+    },
+    KeyInfo {
+        key: "TryExec",
+        value_type: ValueType::String,
+        groups: MAIN,
+        default: "",
+        rule: ValueRule::NotEmpty,
+        description: "Path to an executable file on disk used to determine if the program is actually installed. If the path is not an absolute path, the file is looked up in the $PATH environment variable. If the file is not present or if it is not executable, the entry may be ignored (not be used in menus, for example).",
+        spec_link: RECOGNIZED_KEYS,
+    },
+    KeyInfo {
+        key: "Exec",
+        value_type: ValueType::String,
+        groups: MAIN_AND_ACTIONS,
+        default: "",
+        rule: ValueRule::NotEmpty,
+        description: "Program to execute, possibly with arguments. See the Exec key for details on how this key works. The Exec key is required if DBusActivatable is not set to true. Even if DBusActivatable is true, Exec should be specified for compatibility with implementations that do not understand DBusActivatable.",
+        spec_link: EXEC_VARIABLES,
+    },
+    KeyInfo {
+        key: "Path",
+        value_type: ValueType::String,
+        groups: MAIN,
+        default: "",
+        rule: ValueRule::Any,
+        description: "If entry is of type Application, the working directory to run the program in.",
+        spec_link: RECOGNIZED_KEYS,
+    },
+    KeyInfo {
+        key: "Terminal",
+        value_type: ValueType::Boolean,
+        groups: MAIN,
+        default: "false",
+        rule: BOOLEAN,
+        description: "Whether the program runs in a terminal window.",
+        spec_link: RECOGNIZED_KEYS,
+    },
+    KeyInfo {
+        key: "Actions",
+        value_type: ValueType::Strings,
+        groups: MAIN,
+        default: "",
+        rule: ValueRule::Any,
+        description: "Identifiers for application actions. This can be used to tell the application to make a specific action, different from the default behavior. The Application actions section describes how actions work.",
+        spec_link: EXTRA_ACTIONS,
+    },
+    KeyInfo {
+        key: "MimeType",
+        value_type: ValueType::Strings,
+        groups: MAIN,
+        default: "",
+        rule: ValueRule::Any,
+        description: "The MIME type(s) supported by this application.",
+        spec_link: MIME_TYPES,
+    },
+    KeyInfo {
+        key: "Categories",
+        value_type: ValueType::Strings,
+        groups: MAIN,
+        default: "",
+        rule: ValueRule::Any,
+        description: "Categories in which the entry should be shown in a menu (for possible values see the Desktop Menu Specification).",
+        spec_link: CATEGORY_REGISTRY,
+    },
+    KeyInfo {
+        key: "Implements",
+        value_type: ValueType::Strings,
+        groups: MAIN,
+        default: "",
+        rule: ValueRule::Any,
+        description: "A list of interfaces that this application implements. By default, a desktop file implements no interfaces. See Interfaces for more information on how this works.",
+        spec_link: INTERFACES,
+    },
+    KeyInfo {
+        key: "Keywords",
+        value_type: ValueType::LocaleStrings,
+        groups: MAIN,
+        default: "",
+        rule: ValueRule::Any,
+        description: "A list of strings which may be used in addition to other metadata to describe this entry. This can be useful e.g. to facilitate searching through entries. The values are not meant for display, and should not be redundant with the values of Name or GenericName.",
+        spec_link: RECOGNIZED_KEYS,
+    },
+    KeyInfo {
+        key: "StartupNotify",
+        value_type: ValueType::Boolean,
+        groups: MAIN,
+        default: "false",
+        rule: BOOLEAN,
+        description: "If true, it is KNOWN that the application will send a \"remove\" message when started with the DESKTOP_STARTUP_ID environment variable set. If false, it is KNOWN that the application does not work with startup notification at all (does not shown any window, breaks even when using StartupWMClass, etc.). If absent, a reasonable handling is up to implementations (assuming false, using StartupWMClass, etc.). (See the Startup Notification Protocol Specification for more details).",
+        spec_link: RECOGNIZED_KEYS,
+    },
+    KeyInfo {
+        key: "StartupWMClass",
+        value_type: ValueType::String,
+        groups: MAIN,
+        default: "",
+        rule: ValueRule::Any,
+        description: "If specified, it is known that the application will map at least one window with the given string as its WM class or WM name hint (see the Startup Notification Protocol Specification for more details).",
+        spec_link: RECOGNIZED_KEYS,
+    },
+    KeyInfo {
+        key: "URL",
+        value_type: ValueType::String,
+        groups: MAIN,
+        default: "",
+        rule: ValueRule::NotEmpty,
+        description: "If entry is Link type, the URL to access.",
+        spec_link: RECOGNIZED_KEYS,
+    },
+    KeyInfo {
+        key: "PrefersNonDefaultGPU",
+        value_type: ValueType::Boolean,
+        groups: MAIN,
+        default: "false",
+        rule: BOOLEAN,
+        description: "If true, the application prefers to be run on a more powerful discrete GPU if available, which we describe as “a GPU other than the default one” in this spec to avoid the need to define what a discrete GPU is and in which cases it might be considered more powerful than the default GPU. This key is only a hint and support might not be present depending on the implementation.",
+        spec_link: RECOGNIZED_KEYS,
+    },
+    KeyInfo {
+        key: "SingleMainWindow",
+        value_type: ValueType::Boolean,
+        groups: MAIN,
+        default: "false",
+        rule: BOOLEAN,
+        description: "If true, the application has a single main window, and does not support having an additional one opened. This key is used to signal to the implementation to avoid offering a UI to launch another window of the app. This key is only a hint and support might not be present depending on the implementation. ",
+        spec_link: RECOGNIZED_KEYS,
+    },
+];
+
+pub fn key_info(key: &str) -> Option<&'static KeyInfo> {
+    KEYS.iter().find(|info| info.key == key)
+}
+
+/// Private note about the file, e.g. why it was customized. Keys starting with `X-` are ignored
+/// by launchers, so the note can be kept in the file itself
+pub const NOTE_KEY: &str = "X-DesktopFileEditor-Note";
+
+/// Explanation of a key the specification does not define
+pub fn custom_key_description(key: &str) -> String {
+    if key.starts_with("X-") {
+        format!(
+            "{key} is an extension, read by the application or the desktop that added it. \
+             Implementations that do not know it ignore it."
+        )
+    } else if DEPRECATED_KEYS.contains(&key) {
+        format!(
+            "{key} was part of older versions of the specification and should no longer be used."
+        )
+    } else {
+        format!(
+            "{key} is not defined by the specification. Keys added by applications should start \
+             with X-, implementations might ignore the file otherwise."
+        )
+    }
+}
+
+/// Keys that were part of older versions of the specification and should no longer be used
+pub const DEPRECATED_KEYS: [&str; 10] = [
+    "Encoding",
+    "MiniIcon",
+    "TerminalOptions",
+    "Protocols",
+    "Extensions",
+    "BinaryPattern",
+    "MapNotify",
+    "SwallowTitle",
+    "SwallowExec",
+    "SortOrder",
+];
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn group_kinds() {
+        assert_eq!(GroupKind::of("Desktop Entry"), GroupKind::Main);
+        assert_eq!(
+            GroupKind::of("Desktop Action new-window"),
+            GroupKind::Action
+        );
+        assert_eq!(GroupKind::of("X-Custom"), GroupKind::Other);
+    }
+
+    #[test]
+    fn value_rules() {
+        let type_info = key_info("Type").unwrap();
+        assert_eq!(type_info.check("Application"), None);
+        assert!(type_info.check("App").is_some());
+        assert!(key_info("Terminal").unwrap().check("True").is_some());
+        assert!(key_info("Exec").unwrap().check("  ").is_some());
+        assert_eq!(key_info("Comment").unwrap().check(""), None);
+    }
+
+    #[test]
+    fn group_descriptions() {
+        let info = key_info("Name").unwrap();
+        assert_eq!(info.description_in(GroupKind::Main), info.description);
+        assert_ne!(info.description_in(GroupKind::Action), info.description);
+        assert_eq!(info.spec_link_in(GroupKind::Action), EXTRA_ACTIONS);
+
+        let terminal_info = key_info("Terminal").unwrap();
+        assert_eq!(
+            terminal_info.description_in(GroupKind::Action),
+            terminal_info.description
+        );
+
+        assert!(custom_key_description("X-GNOME-UsesNotifications").contains("extension"));
+        assert!(custom_key_description("Encoding").contains("older versions"));
+    }
+
+    #[test]
+    fn short_description() {
+        assert_eq!(
+            key_info("Comment").unwrap().short_description(),
+            "Tooltip for the entry, for example \"View sites on the Internet\"."
+        );
+    }
+}
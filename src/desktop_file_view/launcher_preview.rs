@@ -0,0 +1,248 @@
+/*
+* Copyright © 2025 Alessandro Balducci
+*
+* This file is part of Desktop File Editor.
+* Desktop File Editor is free software: you can redistribute it and/or modify it under the terms of the
+* GNU General Public License as published by the Free Software Foundation,
+* either version 3 of the License, or (at your option) any later version.
+* Desktop File Editor is distributed in the hope that it will be useful, but WITHOUT ANY WARRANTY;
+* without even the implied warranty of MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.
+* See the GNU General Public License for more details.
+* You should have received a copy of the GNU General Public License along with Desktop File Editor. If not, see <https://www.gnu.org/licenses/>.
+*/
+
+use adw::subclass::prelude::*;
+use freedesktop_desktop_entry::DesktopEntry;
+use gtk::{
+    glib::{self, Object},
+    pango::{EllipsizeMode, WrapMode},
+    prelude::*,
+    Align, Label, Orientation,
+};
+
+use crate::window::file_entry::{current_desktop, shown_in_desktop, ToGIcon};
+
+use super::desktop_entry_ext::DesktopEntryExt;
+
+mod imp {
+    use adw::subclass::prelude::*;
+    use gtk::glib::{self, object_subclass};
+
+    #[derive(Debug, Default)]
+    pub struct LauncherPreview {
+        pub tile_icon: gtk::Image,
+        pub tile_name: gtk::Label,
+        /// Tells that the desktop being simulated would not list the launcher
+        pub desktop_note: gtk::Label,
+        pub search_icon: gtk::Image,
+        pub search_name: gtk::Label,
+        pub search_comment: gtk::Label,
+        pub menu_items: gtk::Box,
+    }
+
+    #[object_subclass]
+    impl ObjectSubclass for LauncherPreview {
+        const NAME: &'static str = "DMLauncherPreview";
+        type Type = super::LauncherPreview;
+        type ParentType = gtk::Box;
+    }
+
+    impl ObjectImpl for LauncherPreview {
+        fn constructed(&self) {
+            self.parent_constructed();
+            self.obj().build();
+        }
+    }
+
+    impl WidgetImpl for LauncherPreview {}
+    impl BoxImpl for LauncherPreview {}
+}
+
+glib::wrapper! {
+    /// Mock-up of how the launcher shows up in the shell's app grid, search results and context
+    /// menu
+    pub struct LauncherPreview(ObjectSubclass<imp::LauncherPreview>)
+    @extends gtk::Widget, gtk::Box,
+    @implements gtk::Accessible, gtk::Buildable, gtk::ConstraintTarget, gtk::Orientable;
+}
+
+impl Default for LauncherPreview {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl LauncherPreview {
+    /// Width of an app grid tile, in characters
+    const TILE_WIDTH_CHARS: i32 = 12;
+
+    pub fn new() -> Self {
+        Object::builder()
+            .property("orientation", Orientation::Horizontal)
+            .property("spacing", 24)
+            .property("homogeneous", true)
+            .build()
+    }
+
+    fn build(&self) {
+        let imp = self.imp();
+
+        let tile = gtk::Box::new(Orientation::Vertical, 6);
+        tile.set_halign(Align::Center);
+        tile.set_valign(Align::Start);
+        imp.tile_icon.set_pixel_size(64);
+        imp.tile_icon.add_css_class("icon-dropshadow");
+        tile.append(&imp.tile_icon);
+        imp.tile_name.set_justify(gtk::Justification::Center);
+        imp.tile_name.set_wrap(true);
+        imp.tile_name.set_wrap_mode(WrapMode::WordChar);
+        imp.tile_name.set_lines(2);
+        imp.tile_name.set_ellipsize(EllipsizeMode::End);
+        imp.tile_name.set_width_chars(Self::TILE_WIDTH_CHARS);
+        imp.tile_name.set_max_width_chars(Self::TILE_WIDTH_CHARS);
+        tile.append(&imp.tile_name);
+        imp.desktop_note.set_justify(gtk::Justification::Center);
+        imp.desktop_note.set_wrap(true);
+        imp.desktop_note.set_max_width_chars(Self::TILE_WIDTH_CHARS);
+        imp.desktop_note.add_css_class("caption");
+        imp.desktop_note.add_css_class("warning");
+        tile.append(&imp.desktop_note);
+
+        let search_result = gtk::Box::new(Orientation::Horizontal, 12);
+        search_result.set_valign(Align::Start);
+        imp.search_icon.set_pixel_size(32);
+        search_result.append(&imp.search_icon);
+        let search_titles = gtk::Box::new(Orientation::Vertical, 3);
+        search_titles.set_valign(Align::Center);
+        for label in [&imp.search_name, &imp.search_comment] {
+            label.set_halign(Align::Start);
+            label.set_xalign(0.0);
+            label.set_ellipsize(EllipsizeMode::End);
+            search_titles.append(label);
+        }
+        imp.search_name.add_css_class("heading");
+        imp.search_comment.add_css_class("dim-label");
+        search_result.append(&search_titles);
+
+        imp.menu_items.set_orientation(Orientation::Vertical);
+        imp.menu_items.set_valign(Align::Start);
+        imp.menu_items.add_css_class("card");
+
+        self.append(&Self::section("App Grid", &tile));
+        self.append(&Self::section("Search", &search_result));
+        self.append(&Self::section("Context Menu", &imp.menu_items));
+    }
+
+    fn section(title: &str, child: &impl IsA<gtk::Widget>) -> gtk::Box {
+        let section = gtk::Box::new(Orientation::Vertical, 12);
+
+        let title = Label::new(Some(title));
+        title.set_halign(Align::Start);
+        title.add_css_class("caption-heading");
+        title.add_css_class("dim-label");
+        section.append(&title);
+        section.append(child);
+
+        section
+    }
+
+    /// Show the values of `desktop_entry` as they resolve for `locale`
+    pub fn update(&self, desktop_entry: &DesktopEntry, locale: Option<&str>) {
+        let imp = self.imp();
+        let value = |group: &str, key: &str| localized_value(desktop_entry, group, key, locale);
+
+        let gicon = desktop_entry.gicon();
+        imp.tile_icon.set_from_gicon(&gicon);
+        imp.search_icon.set_from_gicon(&gicon);
+
+        let name = value("Desktop Entry", "Name").unwrap_or_default();
+        imp.tile_name.set_label(&name);
+        imp.search_name.set_label(&name);
+
+        let desktop = current_desktop();
+        let shown = shown_in_desktop(
+            desktop_entry.entry("Desktop Entry", "OnlyShowIn", None),
+            desktop_entry.entry("Desktop Entry", "NotShowIn", None),
+            &desktop,
+        );
+        let desktop_name = desktop.split(':').next().unwrap_or_default();
+        imp.desktop_note.set_visible(!shown);
+        imp.desktop_note.set_label(&match desktop_name {
+            "" => "Not shown in this desktop".to_string(),
+            name => format!("Not shown in {name}"),
+        });
+        imp.tile_icon.set_opacity(if shown { 1.0 } else { 0.5 });
+
+        // The shell shows the generic name when there is no comment
+        let comment = value("Desktop Entry", "Comment")
+            .or_else(|| value("Desktop Entry", "GenericName"))
+            .unwrap_or_default();
+        imp.search_comment.set_visible(!comment.is_empty());
+        imp.search_comment.set_label(&comment);
+
+        while let Some(child) = imp.menu_items.first_child() {
+            imp.menu_items.remove(&child);
+        }
+
+        let actions = desktop_entry
+            .entry("Desktop Entry", "Actions", None)
+            .unwrap_or_default()
+            .split(';')
+            .filter(|action| !action.is_empty())
+            .map(|action| {
+                value(&format!("Desktop Action {action}"), "Name")
+                    .unwrap_or_else(|| action.to_string())
+            })
+            .collect::<Vec<_>>();
+        for action in actions.iter() {
+            imp.menu_items.append(&Self::menu_item(action, false));
+        }
+
+        if !actions.is_empty() {
+            imp.menu_items
+                .append(&gtk::Separator::new(Orientation::Horizontal));
+        }
+
+        // Items added by the shell itself
+        imp.menu_items.append(&Self::menu_item("Pin to Dash", true));
+        imp.menu_items.append(&Self::menu_item("App Details", true));
+    }
+
+    fn menu_item(label: &str, from_shell: bool) -> Label {
+        let item = Label::new(Some(label));
+        item.set_halign(Align::Start);
+        item.set_xalign(0.0);
+        item.set_ellipsize(EllipsizeMode::End);
+        item.set_margin_top(6);
+        item.set_margin_bottom(6);
+        item.set_margin_start(12);
+        item.set_margin_end(12);
+        if from_shell {
+            item.add_css_class("dim-label");
+        }
+        item
+    }
+}
+
+/// Value of `key` the way a desktop would resolve it for `locale`, falling back to less specific
+/// locales and then to the unlocalized value
+fn localized_value(
+    desktop_entry: &DesktopEntry,
+    group: &str,
+    key: &str,
+    locale: Option<&str>,
+) -> Option<String> {
+    let value = match locale {
+        Some(locale) => desktop_entry
+            .entry(group, key, Some(locale))
+            .filter(|value| !value.is_empty())
+            .or_else(|| {
+                desktop_entry
+                    .inherited_entry(group, key, locale)
+                    .map(|(value, _)| value)
+            }),
+        None => desktop_entry.entry(group, key, None),
+    };
+    value.filter(|value| !value.is_empty()).map(String::from)
+}
+
@@ -12,26 +12,56 @@
 */
 
 mod add_action_dialog;
+mod add_group_dialog;
 mod add_locale_dialog;
+mod categories;
 mod close_confirm_dialog;
 pub mod desktop_entry_ext;
 mod desktop_file_group;
-mod known_entries;
+mod duplicate_keys_dialog;
+mod edit_history;
+mod import_dialog;
+pub mod known_entries;
 mod languages;
+mod launcher_preview;
+mod quality_hints;
 mod string_entry_row;
+mod suggestion_entry;
+mod test_launch;
 mod util;
-
-use std::{borrow::Borrow, path::Path, rc::Rc};
+mod validation;
+
+pub use close_confirm_dialog::show_unsaved_changes_dialog;
+
+use std::{
+    borrow::Borrow,
+    cell::RefCell,
+    ffi::OsStr,
+    fs,
+    path::{Path, PathBuf},
+    rc::Rc,
+    time::SystemTime,
+};
 
 use adw::{prelude::*, NavigationPage};
 use gtk::{
     gio,
-    glib::{self, subclass::types::ObjectSubclassIsExt},
+    glib::{self, clone, subclass::types::ObjectSubclassIsExt},
     Widget,
 };
 
+use freedesktop_desktop_entry::DesktopEntry;
+
+use crate::window::{file_entry::ToGIcon, DMWindow};
+
 use self::{
-    desktop_entry_ext::DesktopEntryExt, desktop_file_group::DesktopFileGroup, imp::DesktopEntryCell,
+    desktop_entry_ext::{DesktopEntryExt, KEYMAP_ORDER},
+    desktop_file_group::DesktopFileGroup,
+    imp::DesktopEntryCell,
+    import_dialog::ImportDialog,
+    languages::LANGUAGES_LOCALE_MAP,
+    quality_hints::{quality_hints, EntryTexts},
+    test_launch::{launch_argv, with_environment},
 };
 
 mod imp {
@@ -41,14 +71,17 @@ mod imp {
         self, Cancellable, FileCreateFlags, IOErrorEnum, MountMountFlags, MountOperation,
     };
     use gtk::glib::property::PropertySet;
-    use gtk::glib::{clone, closure, closure_local, Object, Propagation, SignalHandlerId};
+    use gtk::glib::{clone, closure, closure_local, Object, SignalHandlerId};
+    use gtk::gdk;
     use gtk::PropertyExpression;
     use notify::{INotifyWatcher, RecursiveMode, Watcher};
     use std::borrow::Borrow;
     use std::cell::Cell;
+    use std::collections::HashSet;
 
     use std::path::Path;
     use std::rc::Rc;
+    use std::time::SystemTime;
     use std::{cell::RefCell, path::PathBuf};
 
     use adw::{prelude::*, NavigationPage, NavigationView};
@@ -60,18 +93,44 @@ mod imp {
         CompositeTemplate,
     };
 
-    use crate::desktop_file_view::desktop_entry_ext::{DesktopEntryExt, DEFAULT_LOCALE, NO_LOCALE};
+    use crate::desktop_file_view::desktop_entry_ext::{
+        find_duplicate_keys, matching_locale, merge_entries, split_unrecognized, DesktopEntryExt,
+        RawBlock, RawPlacement, DEFAULT_LOCALE, NO_LOCALE,
+    };
+    use crate::desktop_file_view::known_entries::NOTE_KEY;
 
+    use crate::backups;
+    use crate::change_journal;
+    use crate::edited_files;
+    use crate::settings::{settings, LintProfile};
+    use crate::util;
     use crate::window::file_entry::ToGIcon;
+    use crate::window::new_launcher::{
+        launcher_file_stem, new_files_dir, renamed_file_stem, save_template,
+    };
+    use crate::window::overrides::user_override_target;
+    use crate::window::unsaved_indicator::unsaved_indicator;
+    use crate::window::DMWindow;
 
     use super::add_action_dialog::AddActionDialog;
+    use super::add_group_dialog::AddGroupDialog;
     use super::add_locale_dialog::AddLocaleDialog;
     use super::close_confirm_dialog::show_close_confirm_dialog;
     use super::desktop_file_group::DesktopFileGroup;
+    use super::duplicate_keys_dialog::show_duplicate_keys_dialog;
+    use super::edit_history::{EditHistory, EntryCommand, EntryEditor};
+    use super::fill_issue_list;
     use super::languages::LANGUAGES_LOCALE_MAP;
+    use super::launcher_preview::LauncherPreview;
+    use super::quality_hints::QualityHint;
+    use super::user_messages_locale;
+    use super::validation::{validate, Issue, Severity};
 
     pub type DesktopEntryCell = RefCell<DesktopEntry>;
 
+    /// What a scratch entry starts from
+    const SCRATCH_CONTENTS: &str = "[Desktop Entry]\nType=Application\nName=New Launcher\nExec=\n";
+
     #[derive(CompositeTemplate, Default, Properties)]
     #[template(resource = "/com/argoware/desktop-file-editor/desktop_file_view.ui")]
     #[properties(wrapper_type = super::DesktopFileView)]
@@ -89,11 +148,67 @@ mod imp {
         pub locale_dropdown: TemplateChild<gtk::DropDown>,
 
         #[template_child]
-        reload_bar: TemplateChild<gtk::Revealer>,
+        pub reload_bar: TemplateChild<gtk::Revealer>,
+
+        #[template_child]
+        pub override_banner: TemplateChild<adw::Banner>,
+
+        #[template_child]
+        pub scrolled_window: TemplateChild<gtk::ScrolledWindow>,
+
+        #[template_child]
+        pub add_locale_button: TemplateChild<gtk::Button>,
+
+        #[template_child]
+        pub save_button: TemplateChild<gtk::Button>,
+
+        #[template_child]
+        unsaved_indicator_bin: TemplateChild<adw::Bin>,
+
+        #[template_child]
+        add_action_button: TemplateChild<gtk::Button>,
+
+        #[template_child]
+        add_group_button: TemplateChild<gtk::Button>,
+
+        #[template_child]
+        pub launcher_preview: TemplateChild<LauncherPreview>,
+
+        #[template_child]
+        pub output_bar: TemplateChild<gtk::Revealer>,
+
+        #[template_child]
+        pub output_status: TemplateChild<gtk::Label>,
+
+        #[template_child]
+        pub output_view: TemplateChild<gtk::TextView>,
+
+        #[template_child]
+        unrecognized_group: TemplateChild<adw::PreferencesGroup>,
+
+        #[template_child]
+        content_stack: TemplateChild<gtk::Stack>,
+
+        #[template_child]
+        raw_view: TemplateChild<gtk::TextView>,
+
+        #[template_child]
+        raw_error: TemplateChild<gtk::Label>,
+
+        #[template_child]
+        quality_hints_group: TemplateChild<adw::PreferencesGroup>,
 
         #[property(get, set, construct)]
         path: RefCell<PathBuf>,
 
+        /// The entry only exists in memory, its file is chosen when it is first saved
+        #[property(get, construct_only)]
+        scratch: Cell<bool>,
+
+        /// Text a scratch entry starts from, instead of an empty launcher
+        #[property(get, construct_only, nullable)]
+        scratch_contents: RefCell<Option<String>>,
+
         #[property(get, set, construct)]
         parent_navigation_view: RefCell<adw::NavigationView>,
 
@@ -103,13 +218,44 @@ mod imp {
         #[property(get, set, nullable)]
         locale: RefCell<Option<String>>,
 
+        #[property(get, set)]
+        show_preview: Cell<bool>,
+
+        /// The file is edited as text instead of with the groups, both edit the same entry
+        #[property(get, set)]
+        raw_mode: Cell<bool>,
+        /// Set while the text is being replaced from the entry, so it is not decoded back
+        raw_syncing: Cell<bool>,
+        pub preview_update_queued: Cell<bool>,
+
+        /// Process started by the last test launch, its output is the one shown
+        pub launched_process: RefCell<Option<gio::Subprocess>>,
+
         pub desktop_entry: RefCell<Option<Rc<DesktopEntryCell>>>,
 
+        /// Text of the file when it was loaded, the base for saving with preserved formatting
+        original_contents: RefCell<Option<String>>,
+
+        /// Edits of the entry since it was loaded, to undo and redo them
+        history: RefCell<EditHistory>,
+        /// Set while the editor selects a locale by itself, which is not an edit to record
+        selecting_locale: Cell<bool>,
+
+        /// Parts of the file the desktop entry could not decode, written back when saving
+        unrecognized: RefCell<Vec<RawBlock>>,
+        unrecognized_rows: RefCell<Vec<adw::ExpanderRow>>,
+
+        quality_hint_rows: RefCell<Vec<adw::ActionRow>>,
+        /// Hints the user dismissed, they stay hidden as long as the file is open
+        dismissed_hints: RefCell<HashSet<&'static str>>,
+
+        /// Icon shown at the top, kept to render it again when the scale factor changes
+        header_icon: RefCell<Option<gio::Icon>>,
+
         back_confirm_handler: Cell<Option<SignalHandlerId>>,
-        close_confirm_handler: RefCell<Option<SignalHandlerId>>,
-        window: RefCell<Option<adw::ApplicationWindow>>,
 
         file_watcher: RefCell<Option<INotifyWatcher>>,
+        pub suspended_modified_time: Cell<Option<SystemTime>>,
     }
 
     #[glib::object_subclass]
@@ -120,8 +266,100 @@ mod imp {
 
         fn class_init(klass: &mut Self::Class) {
             DesktopFileGroup::ensure_type();
+            LauncherPreview::ensure_type();
             klass.bind_template();
             klass.bind_template_callbacks();
+
+            klass.install_action_async("win.save", None, |view, _, _| async move {
+                view.imp().save().await;
+            });
+            klass.install_action_async("win.reset", None, |view, _, _| async move {
+                view.imp().discard_changes().await;
+            });
+            klass.install_action_async("win.reload", None, |view, _, _| async move {
+                view.imp().reload().await;
+            });
+            klass.install_action("win.undo", None, |view, _, _| {
+                view.imp().step_history(false)
+            });
+            klass.install_action("win.redo", None, |view, _, _| view.imp().step_history(true));
+            klass.install_action("win.add-locale", None, |view, _, _| {
+                view.imp().show_add_locale_dialog()
+            });
+            klass.install_action("win.add-action", None, |view, _, _| {
+                view.imp().show_add_action_dialog()
+            });
+            klass.install_action("win.add-group", None, |view, _, _| {
+                view.imp().show_add_group_dialog()
+            });
+            klass.install_action_async("win.paste-group", None, |view, _, _| async move {
+                view.paste_groups_from_clipboard().await;
+            });
+            klass.install_action_async("win.import-from-file", None, |view, _, _| async move {
+                view.import_from_file().await;
+            });
+            klass.install_action("win.strip-translations", None, |view, _, _| {
+                view.show_strip_translations_dialog();
+            });
+            klass.install_property_action("win.show-preview", "show-preview");
+            klass.install_property_action("win.edit-as-text", "raw-mode");
+            klass.install_action_async("win.test-launch", None, |view, _, _| async move {
+                view.test_launch().await;
+            });
+            klass.install_action("win.launch", None, |view, _, _| {
+                view.launch();
+            });
+            klass.install_action("win.edit-note", None, |view, _, _| {
+                view.focus_entry(NOTE_KEY);
+            });
+            klass.install_action("win.show-file-changes", None, |view, _, _| {
+                change_journal::show_change_journal_dialog(view, Some(&view.path()));
+            });
+            klass.install_action("win.save-as-template", None, |view, _, _| {
+                view.imp().show_save_template_dialog();
+            });
+            klass.install_action("win.restore-backup", None, |view, _, _| {
+                view.imp().show_backups_dialog();
+            });
+            klass.install_action("win.override-in-user-dir", None, |view, _, _| {
+                view.imp().override_in_user_dir();
+            });
+            klass.install_action("win.open-in-text-editor", None, |view, _, _| {
+                if let Some(window) = view.window() {
+                    window.open_in_text_editor(&view.path());
+                }
+            });
+            klass.install_action_async("win.rename-file", None, |view, _, _| async move {
+                view.imp().rename_file().await;
+            });
+            klass.install_action("win.check-entry", None, |view, _, _| {
+                view.imp().show_lint_dialog();
+            });
+
+            klass.add_binding_action(gdk::Key::s, gdk::ModifierType::CONTROL_MASK, "win.save");
+            klass.add_binding_action(gdk::Key::r, gdk::ModifierType::CONTROL_MASK, "win.reload");
+            klass.add_binding_action(gdk::Key::F5, gdk::ModifierType::empty(), "win.reload");
+            klass.add_binding_action(gdk::Key::z, gdk::ModifierType::CONTROL_MASK, "win.undo");
+            klass.add_binding_action(
+                gdk::Key::z,
+                gdk::ModifierType::CONTROL_MASK | gdk::ModifierType::SHIFT_MASK,
+                "win.redo",
+            );
+            klass.add_binding_action(
+                gdk::Key::r,
+                gdk::ModifierType::CONTROL_MASK | gdk::ModifierType::SHIFT_MASK,
+                "win.reset",
+            );
+            klass.add_binding_action(
+                gdk::Key::Return,
+                gdk::ModifierType::CONTROL_MASK,
+                "win.test-launch",
+            );
+            klass.add_binding_action(
+                gdk::Key::Return,
+                gdk::ModifierType::CONTROL_MASK | gdk::ModifierType::SHIFT_MASK,
+                "win.launch",
+            );
         }
 
         fn instance_init(obj: &InitializingObject<Self>) {
@@ -132,6 +370,7 @@ mod imp {
     #[glib::derived_properties]
     impl ObjectImpl for DesktopFileView {
         fn dispose(&self) {
+            self.obj().shutdown();
             self.dispose_template();
         }
 
@@ -157,76 +396,828 @@ mod imp {
 
             self.back_confirm_handler.set(Some(back_confirm_handler));
 
+            self.obj()
+                .connect_scale_factor_notify(|view| view.imp().render_header_icon());
+
             self.obj().connect_locale_notify(|desktop_file_view| {
                 desktop_file_view.update_locale();
+                desktop_file_view.queue_preview_update();
+            });
+
+            // Saving without changes would needlessly rewrite the file, unless there is no file
+            let obj = self.obj();
+            self.update_file_actions();
+            obj.connect_content_changed_notify(|desktop_file_view| {
+                desktop_file_view.imp().update_file_actions();
+                desktop_file_view.queue_preview_update();
+                if let Some(window) = desktop_file_view.window() {
+                    window.update_unsaved_views();
+                }
+            });
+            if let Some(window) = obj.window() {
+                self.unsaved_indicator_bin
+                    .set_child(Some(&unsaved_indicator(&window, &obj)));
+            }
+            obj.connect_scratch_notify(|desktop_file_view| {
+                desktop_file_view.imp().update_file_actions();
+            });
+            obj.connect_path_notify(|desktop_file_view| {
+                desktop_file_view.imp().update_file_actions();
+            });
+            obj.connect_show_preview_notify(|desktop_file_view| {
+                desktop_file_view.queue_preview_update();
             });
+            settings().connect_simulated_desktop_notify(clone!(
+                #[weak]
+                obj,
+                move |_| obj.queue_preview_update()
+            ));
+            obj.connect_raw_mode_notify(|desktop_file_view| {
+                desktop_file_view.imp().update_raw_mode();
+            });
+            self.raw_view.buffer().connect_changed(clone!(
+                #[weak(rename_to=this)]
+                self,
+                move |_| this.apply_raw_text()
+            ));
+
+            if settings().preselect_user_locale() {
+                self.preselect_user_locale();
+            }
         }
     }
 
-    impl WidgetImpl for DesktopFileView {
-        fn realize(&self) {
-            self.parent_realize();
+    impl WidgetImpl for DesktopFileView {}
+    impl NavigationPageImpl for DesktopFileView {}
+
+    #[gtk::template_callbacks]
+    impl DesktopFileView {
+        #[template_callback]
+        fn on_output_close_clicked(&self) {
+            self.output_bar.set_reveal_child(false);
+        }
 
-            if self.close_confirm_handler.borrow().is_none() {
-                let desktop_file_view = ObjectExt::downgrade(&self.obj().clone());
+        #[template_callback]
+        fn desktop_file_id(&self, path: PathBuf) -> String {
+            // Scratch entries have no file yet
+            if path.as_os_str().is_empty() {
+                return "Not Saved".to_string();
+            }
+            util::desktop_file_id(&path)
+        }
 
-                let close_confirm_handler = self.window().connect_close_request(move |win| {
-                    let desktop_file_view = if let Some(upgraded) = desktop_file_view.upgrade() {
-                        upgraded
-                    } else {
-                        eprintln!("DesktopFileView ceased to exist but the relative window's close-request signal is still connected, this is a bug, please report it.");
-                        return Propagation::Proceed;
+        fn update_file_actions(&self) {
+            let obj = self.obj();
+            let scratch = self.scratch.get();
+            // Text that can't be decoded is neither written nor brought back to the groups
+            let undecodable = self.raw_error.is_visible();
+            obj.action_set_enabled(
+                "win.save",
+                (obj.content_changed() || scratch) && !undecodable,
+            );
+            obj.action_set_enabled("win.edit-as-text", !undecodable);
+            obj.action_set_enabled("win.reload", !scratch);
+            obj.action_set_enabled("win.show-file-changes", !scratch);
+            obj.action_set_enabled("win.restore-backup", !scratch);
+            obj.action_set_enabled("win.open-in-text-editor", !scratch);
+            obj.action_set_enabled("win.rename-file", !scratch);
+            let can_override = !scratch && user_override_target(&self.path.borrow()).is_some();
+            obj.action_set_enabled("win.override-in-user-dir", can_override);
+
+            // Saving a system file needs administrator rights, overriding it does not
+            let can_write = || {
+                gio::File::for_path(&*self.path.borrow())
+                    .query_info(
+                        gio::FILE_ATTRIBUTE_ACCESS_CAN_WRITE,
+                        gio::FileQueryInfoFlags::NONE,
+                        gio::Cancellable::NONE,
+                    )
+                    .map(|info| info.boolean(gio::FILE_ATTRIBUTE_ACCESS_CAN_WRITE))
+                    .unwrap_or(true)
+            };
+            self.override_banner
+                .set_revealed(can_override && !can_write());
+        }
+
+        /// Ask where to write a scratch entry, in the applications directory of the user unless
+        /// told otherwise
+        async fn choose_scratch_path(&self) -> Option<PathBuf> {
+            let name = {
+                let borrow = self.desktop_entry.borrow();
+                let content: &RefCell<DesktopEntry> = borrow.as_ref().unwrap().borrow();
+                let content_borrow = content.borrow();
+                content_borrow
+                    .name(&NO_LOCALE)
+                    .map(|name| name.into_owned())
+                    .unwrap_or_default()
+            };
+
+            let dir = new_files_dir();
+            if let Err(e) = std::fs::create_dir_all(&dir) {
+                eprintln!("Failed to create the folder for new launchers: {e}");
+            }
+
+            let dialog = gtk::FileDialog::builder()
+                .title("Save Desktop File")
+                .accept_label("Save")
+                .initial_folder(&gio::File::for_path(&dir))
+                .initial_name(format!("{}.desktop", launcher_file_stem(&name)))
+                .build();
+            let root = self.obj().root().and_downcast::<gtk::Window>();
+            dialog
+                .save_future(root.as_ref())
+                .await
+                .ok()
+                .and_then(|file| file.path())
+        }
+
+        /// The text the entry is written as, following the original formatting if it is preserved
+        fn serialized_contents(&self) -> String {
+            let borrow = self.desktop_entry.borrow();
+            let content: &RefCell<DesktopEntry> = borrow.as_ref().unwrap().borrow();
+            let content_borrow = content.borrow();
+            let unrecognized = self.unrecognized.borrow();
+            let key_order = settings().key_order_list();
+            match self.original_contents.borrow().as_deref() {
+                Some(original) if settings().preserve_formatting() => {
+                    content_borrow.to_minimal_diff_string(original, &unrecognized, &key_order)
+                }
+                _ => content_borrow.to_sorted_entry_string_with_raw(&unrecognized, &key_order),
+            }
+        }
+
+        fn raw_text(&self) -> String {
+            let buffer = self.raw_view.buffer();
+            buffer
+                .text(&buffer.start_iter(), &buffer.end_iter(), false)
+                .into()
+        }
+
+        /// Replace the text with the entry as it is now
+        fn load_raw_text(&self) {
+            let contents = self.serialized_contents();
+            self.raw_syncing.set(true);
+            self.raw_view.buffer().set_text(&contents);
+            self.raw_syncing.set(false);
+            self.raw_error.set_visible(false);
+            self.update_file_actions();
+        }
+
+        /// Decode the edited text into the entry, the last text that could be decoded is kept
+        fn apply_raw_text(&self) {
+            if self.raw_syncing.get() || !self.raw_mode.get() {
+                return;
+            }
+
+            let text = self.raw_text();
+            let (decodable, unrecognized) = split_unrecognized(&text);
+            let path = self.path.borrow().clone();
+            match DesktopEntry::from_str(path, &decodable, None::<&[&str]>) {
+                Ok(desktop_entry) => {
+                    self.edit_entry(|editor| editor.replace_with(desktop_entry));
+                    self.unrecognized.replace(unrecognized);
+                    self.raw_error.set_visible(false);
+                }
+                Err(e) => {
+                    let message = format!("The text cannot be decoded: {e}");
+                    self.raw_error.set_label(&message);
+                    self.raw_error.set_visible(true);
+                }
+            }
+            self.obj().set_content_changed(true);
+            self.update_file_actions();
+        }
+
+        /// Switch between the groups and the text, bringing the changes of one to the other
+        fn update_raw_mode(&self) {
+            let raw_mode = self.raw_mode.get();
+            if raw_mode {
+                self.load_raw_text();
+                self.content_stack.set_visible_child_name("raw");
+            } else {
+                self.populate();
+                self.content_stack.set_visible_child_name("structured");
+            }
+
+            // These edit the groups, which are hidden while editing the text
+            let obj = self.obj();
+            for action in [
+                "win.add-locale",
+                "win.add-action",
+                "win.add-group",
+                "win.paste-group",
+                "win.import-from-file",
+                "win.strip-translations",
+                "win.edit-note",
+            ] {
+                obj.action_set_enabled(action, !raw_mode);
+            }
+            self.locale_dropdown.set_sensitive(!raw_mode);
+        }
+
+        fn spec_issues(&self, profile: LintProfile) -> Vec<Issue> {
+            let contents = if self.raw_mode.get() {
+                self.raw_text()
+            } else {
+                self.serialized_contents()
+            };
+            let desktop_entry_rc = self.obj().desktop_entry();
+            let desktop_entry_cell: &DesktopEntryCell = desktop_entry_rc.borrow();
+            validate(&desktop_entry_cell.borrow(), &contents, profile)
+        }
+
+        /// Checks the entry against the specification. Errors have to be confirmed before saving,
+        /// warnings alone don't stop it. Returns whether saving can go on
+        async fn confirm_spec_issues(&self) -> bool {
+            let issues = self.spec_issues(settings().lint_profile());
+            if !issues.iter().any(|issue| issue.severity == Severity::Error) {
+                return true;
+            }
+
+            let list = gtk::ListBox::new();
+            list.set_selection_mode(gtk::SelectionMode::None);
+            list.add_css_class("boxed-list");
+            fill_issue_list(&list, &issues, &self.obj());
+            let scrolled_window = gtk::ScrolledWindow::builder()
+                .hscrollbar_policy(gtk::PolicyType::Never)
+                .propagate_natural_height(true)
+                .max_content_height(320)
+                .child(&list)
+                .build();
+
+            let dialog = adw::AlertDialog::builder()
+                .heading("Save Invalid Entry?")
+                .body(
+                    "The entry does not follow the Desktop Entry Specification, desktops might \
+                     not show or launch it",
+                )
+                .extra_child(&scrolled_window)
+                .build();
+            dialog.add_responses(&[("cancel", "Cancel"), ("save", "Save Anyway")]);
+            dialog.set_response_appearance("save", adw::ResponseAppearance::Destructive);
+            dialog.set_default_response(Some("cancel"));
+            dialog.set_close_response("cancel");
+
+            dialog.choose_future(&*self.obj()).await.as_str() == "save"
+        }
+
+        /// Ask for a name, then save the entry as a template new desktop files can start from
+        fn show_save_template_dialog(&self) {
+            let obj = self.obj();
+            let name_row = adw::EntryRow::builder()
+                .title("Template Name")
+                .text(obj.title())
+                .build();
+            let list = gtk::ListBox::builder()
+                .selection_mode(gtk::SelectionMode::None)
+                .css_classes(["boxed-list"])
+                .build();
+            list.append(&name_row);
+
+            let dialog = adw::AlertDialog::builder()
+                .heading("Save as Template")
+                .body(
+                    "New desktop files can start from the template, with all the keys of this \
+                     entry",
+                )
+                .extra_child(&list)
+                .build();
+            dialog.add_responses(&[("cancel", "Cancel"), ("save", "Save")]);
+            dialog.set_response_appearance("save", adw::ResponseAppearance::Suggested);
+            dialog.set_default_response(Some("save"));
+            dialog.set_close_response("cancel");
+
+            name_row.connect_changed(clone!(
+                #[weak]
+                dialog,
+                move |row| dialog.set_response_enabled("save", !row.text().trim().is_empty())
+            ));
+            dialog.connect_response(
+                Some("save"),
+                clone!(
+                    #[weak(rename_to = this)]
+                    self,
+                    #[weak]
+                    name_row,
+                    move |_, _| {
+                        let contents = if this.raw_mode.get() {
+                            this.raw_text()
+                        } else {
+                            this.serialized_contents()
+                        };
+                        if let Err(e) = save_template(name_row.text().trim(), &contents) {
+                            eprintln!("Failed to save template: {e}");
+                            let dialog = adw::AlertDialog::builder()
+                                .heading("Failed to Save Template")
+                                .body(e.to_string())
+                                .build();
+                            dialog.add_response("close", "Close");
+                            dialog.present(Some(&*this.obj()));
+                        }
+                    }
+                ),
+            );
+
+            dialog.present(Some(&*obj));
+        }
+
+        /// Lists the previous versions of the file, the chosen one replaces the entry as unsaved
+        /// changes
+        fn show_backups_dialog(&self) {
+            let obj = self.obj();
+            let current = if self.raw_mode.get() {
+                self.raw_text()
+            } else {
+                self.serialized_contents()
+            };
+            backups::show_backups_dialog(
+                &*obj,
+                &self.path.borrow(),
+                &current,
+                clone!(
+                    #[weak(rename_to = this)]
+                    self,
+                    move |contents| this.restore_contents(&contents)
+                ),
+            );
+        }
+
+        /// Replace the entry with `contents`, as an edit that can be undone
+        pub fn restore_contents(&self, contents: &str) {
+            let (decodable, unrecognized) = split_unrecognized(contents);
+            let path = self.path.borrow().clone();
+            match DesktopEntry::from_str(path, &decodable, None::<&[&str]>) {
+                Ok(desktop_entry) => {
+                    self.edit_entry(|editor| editor.replace_with(desktop_entry));
+                    self.unrecognized.replace(unrecognized);
+                }
+                Err(e) => {
+                    let dialog = adw::AlertDialog::builder()
+                        .heading("Failed to Restore")
+                        .body(format!("The contents cannot be decoded: {e}"))
+                        .build();
+                    dialog.add_response("close", "Close");
+                    dialog.present(Some(&*self.obj()));
+                    return;
+                }
+            }
+
+            if self.raw_mode.get() {
+                self.load_raw_text();
+            } else {
+                self.populate();
+            }
+            self.obj().set_content_changed(true);
+        }
+
+        /// Writes the value that corrects `issue`
+        pub fn apply_fix(&self, issue: &Issue) {
+            let (Some(key), Some(fix)) = (&issue.key, &issue.fix) else {
+                return;
+            };
+            self.edit_entry(|editor| {
+                editor.set_value(&issue.group, key, fix.locale.as_deref(), fix.value.clone())
+            });
+
+            if self.raw_mode.get() {
+                self.load_raw_text();
+            } else {
+                self.obj().refresh_groups();
+            }
+            self.obj().set_content_changed(true);
+        }
+
+        /// Lists the issues of the entry, checked with a profile that can be changed for this
+        /// check only
+        fn show_lint_dialog(&self) {
+            let profiles: Vec<&str> = LintProfile::ALL.iter().map(|p| p.title()).collect();
+            let profile_row = adw::ComboRow::builder()
+                .title("Profile")
+                .model(&gtk::StringList::new(&profiles))
+                .selected(settings().lint_profile().position())
+                .build();
+            let profile_list = gtk::ListBox::builder()
+                .selection_mode(gtk::SelectionMode::None)
+                .css_classes(["boxed-list"])
+                .build();
+            profile_list.append(&profile_row);
+
+            let list = gtk::ListBox::builder()
+                .selection_mode(gtk::SelectionMode::None)
+                .css_classes(["boxed-list"])
+                .build();
+            let update = clone!(
+                #[weak(rename_to = this)]
+                self,
+                #[weak]
+                list,
+                move |profile_row: &adw::ComboRow| {
+                    let profile = LintProfile::from_position(profile_row.selected());
+                    list.remove_all();
+                    let issues = this.spec_issues(profile);
+                    if issues.is_empty() {
+                        list.append(&adw::ActionRow::builder().title("No issues found").build());
+                    }
+                    fill_issue_list(&list, &issues, &this.obj());
+                }
+            );
+            update(&profile_row);
+            profile_row.connect_selected_notify(update);
+
+            let scrolled_window = gtk::ScrolledWindow::builder()
+                .hscrollbar_policy(gtk::PolicyType::Never)
+                .propagate_natural_height(true)
+                .max_content_height(320)
+                .child(&list)
+                .build();
+            let content = gtk::Box::builder()
+                .orientation(gtk::Orientation::Vertical)
+                .spacing(12)
+                .build();
+            content.append(&profile_list);
+            content.append(&scrolled_window);
+
+            let dialog = adw::AlertDialog::builder()
+                .heading("Check Entry")
+                .body(
+                    "Issues with the entry according to the specification and the guidelines of \
+                     the chosen desktop",
+                )
+                .extra_child(&content)
+                .build();
+            dialog.add_response("close", "Close");
+            dialog.present(Some(&*self.obj()));
+        }
+
+        /// Checks that the file was not changed by another program since it was loaded, the
+        /// reload bar might have been missed. If it was, asks whether to reload, merge or
+        /// overwrite. Returns whether saving can go on
+        async fn resolve_external_change(&self) -> bool {
+            let path = self.path.borrow().to_path_buf();
+            let Some(base_contents) = self.original_contents.borrow().clone() else {
+                return true;
+            };
+            let Ok(disk_contents) = std::fs::read_to_string(&path) else {
+                return true;
+            };
+            if disk_contents == base_contents {
+                return true;
+            }
+
+            let dialog = adw::AlertDialog::builder()
+                .heading("File Changed on Disk")
+                .body(
+                    "Another program, like a package manager, changed this file since it was \
+                     opened. Merging keeps the changes of both, yours win where they conflict.",
+                )
+                .build();
+            dialog.add_responses(&[
+                ("cancel", "Cancel"),
+                ("reload", "Discard Mine & Reload"),
+                ("overwrite", "Overwrite"),
+                ("merge", "Merge"),
+            ]);
+            dialog.set_response_appearance("reload", adw::ResponseAppearance::Destructive);
+            dialog.set_response_appearance("overwrite", adw::ResponseAppearance::Destructive);
+            dialog.set_response_appearance("merge", adw::ResponseAppearance::Suggested);
+            dialog.set_default_response(Some("merge"));
+            dialog.set_close_response("cancel");
+
+            match dialog.choose_future(&*self.obj()).await.as_str() {
+                "reload" => {
+                    self.reset();
+                    false
+                }
+                "overwrite" => true,
+                "merge" => {
+                    let (their_decodable, their_unrecognized) = split_unrecognized(&disk_contents);
+                    let (base_decodable, _) = split_unrecognized(&base_contents);
+                    let parse = |contents: &str| {
+                        DesktopEntry::from_str(path.clone(), contents, None::<&[&str]>)
+                    };
+                    let (Ok(theirs), Ok(base)) = (parse(&their_decodable), parse(&base_decodable))
+                    else {
+                        eprintln!("Failed to decode the desktop file to merge");
+                        let dialog = adw::AlertDialog::builder()
+                            .heading("Failed to Merge")
+                            .body(
+                                "The file on disk could not be read as a desktop entry, nothing \
+                                 was saved. Reload it or overwrite it instead",
+                            )
+                            .build();
+                        dialog.add_response("close", "Close");
+                        dialog.present(Some(&*self.obj()));
+                        return false;
                     };
 
-                    let imp = desktop_file_view.imp();
-                    if !imp.content_changed.get() {
-                        // If nothing changed there is no need for confirmation
-                        imp.disconnect_close_confirm_handlers();
-                        return Propagation::Proceed;
+                    let desktop_entry_rc = self.obj().desktop_entry();
+                    let desktop_entry_cell: &DesktopEntryCell = desktop_entry_rc.borrow();
+                    let merged = merge_entries(&base, &desktop_entry_cell.borrow(), &theirs);
+                    desktop_entry_cell.replace(merged);
+                    self.unrecognized.replace(their_unrecognized);
+                    self.original_contents.replace(Some(disk_contents));
+                    self.reset_history();
+                    if self.raw_mode.get() {
+                        self.load_raw_text();
                     }
+                    true
+                }
+                _ => false,
+            }
+        }
 
-                    show_close_confirm_dialog(
-                        win,
-                        || {},
-                        clone!(
-                            #[weak]
-                            win,
+        /// Continue editing a copy of the file in the user applications directory, which takes
+        /// the place of the original without needing the permissions to change it
+        fn override_in_user_dir(&self) {
+            let obj = self.obj();
+            let Some(window) = obj.root().and_downcast::<DMWindow>() else {
+                return;
+            };
+
+            let contents = obj.content_changed().then(|| {
+                if self.raw_mode.get() {
+                    self.raw_text()
+                } else {
+                    self.serialized_contents()
+                }
+            });
+
+            // The changes move to the copy, so leaving the original needs no confirmation
+            obj.set_content_changed(false);
+            self.parent_navigation_view.borrow().pop();
+            window.override_in_user_dir(&self.path.borrow(), contents.as_deref());
+        }
+
+        /// Let the user pick a new name or folder for the file, then move it there and keep
+        /// editing it at its new path
+        async fn rename_file(&self) {
+            let obj = self.obj();
+            let path = obj.path();
+            let mut dialog = gtk::FileDialog::builder()
+                .title("Rename Desktop File")
+                .accept_label("Rename");
+            if let Some(parent) = path.parent() {
+                dialog = dialog.initial_folder(&gio::File::for_path(parent));
+            }
+            if let Some(file_name) = path.file_name() {
+                dialog = dialog.initial_name(file_name.to_string_lossy().into_owned());
+            }
+            let root = obj.root().and_downcast::<gtk::Window>();
+            let Some(mut new_path) = dialog
+                .build()
+                .save_future(root.as_ref())
+                .await
+                .ok()
+                .and_then(|file| file.path())
+            else {
+                return;
+            };
+            // The dialog only asked about replacing the file with the name that was chosen
+            let mut replace_confirmed = true;
+            if new_path.extension().is_none_or(|ext| ext != "desktop") {
+                new_path.as_mut_os_string().push(".desktop");
+                replace_confirmed = false;
+            }
+            if new_path != path {
+                self.move_file_to(&new_path, replace_confirmed).await;
+            }
+        }
+
+        /// Moves the file to `new_path` and keeps editing it there. A file already at `new_path`
+        /// is only replaced once the user agrees, unless `replace_confirmed`
+        async fn move_file_to(&self, new_path: &Path, replace_confirmed: bool) {
+            let obj = self.obj();
+            let path = obj.path();
+
+            if new_path.exists() && !replace_confirmed {
+                let file_name = new_path
+                    .file_name()
+                    .map(|name| name.to_string_lossy().into_owned())
+                    .unwrap_or_default();
+                let dialog = adw::AlertDialog::builder()
+                    .heading(format!("Replace {file_name}?"))
+                    .body(format!(
+                        "A file with the same name already exists in {}, renaming replaces it",
+                        new_path
+                            .parent()
+                            .map(|parent| util::display_path(parent).to_string_lossy().into_owned())
+                            .unwrap_or_default()
+                    ))
+                    .close_response("cancel")
+                    .default_response("cancel")
+                    .build();
+                dialog.add_response("cancel", "Cancel");
+                dialog.add_response("replace", "Replace");
+                dialog.set_response_appearance("replace", adw::ResponseAppearance::Destructive);
+                if dialog.choose_future(&*obj).await.as_str() != "replace" {
+                    return;
+                }
+            }
+
+            // The move would otherwise show up as a change made by another program
+            if let Err(e) = self.stop_file_watcher() {
+                eprintln!("Failed to stop file watcher before renaming: {e}");
+            }
+            let result = super::move_file(&path, new_path);
+            if let Err(e) = self.start_file_watcher() {
+                eprintln!("Failed to restart file watcher: {e}");
+            }
+
+            if let Err(e) = result {
+                eprintln!("Failed to rename {}: {e}", path.to_string_lossy());
+                let dialog = adw::AlertDialog::builder()
+                    .heading("Failed to Rename")
+                    .body(e.to_string())
+                    .build();
+                dialog.add_response("close", "Close");
+                dialog.present(Some(&*obj));
+                return;
+            }
+
+            obj.follow_rename(new_path);
+            if let Some(window) = obj.window() {
+                window.refresh_entries(&[&path, new_path]);
+            }
+        }
+
+        /// Offers to rename a file named after the Name of its entry when the Name changed, the
+        /// desktop file ID changes with it
+        fn offer_rename_to_name(&self, previous_contents: &str, contents: &str) {
+            let obj = self.obj();
+            // The editor is being closed after saving
+            if obj.root().is_none() {
+                return;
+            }
+
+            let parse = |contents: &str| {
+                DesktopEntry::from_str(PathBuf::new(), contents, None::<&[&str]>).ok()
+            };
+            let (Some(previous), Some(entry)) = (parse(previous_contents), parse(contents)) else {
+                return;
+            };
+            let (Some(old_name), Some(new_name)) =
+                (previous.name(&NO_LOCALE), entry.name(&NO_LOCALE))
+            else {
+                return;
+            };
+            let path = obj.path();
+            let Some(file_stem) = path.file_stem().map(|stem| stem.to_string_lossy()) else {
+                return;
+            };
+            let Some(new_stem) = renamed_file_stem(&file_stem, &old_name, &new_name) else {
+                return;
+            };
+            let new_path = path.with_file_name(format!("{new_stem}.desktop"));
+            if new_path.exists() {
+                return;
+            }
+
+            let mut body = format!(
+                "The file could be renamed to {new_stem}.desktop to match the new name. Its \
+                 desktop file ID changes with it, so favorites, default applications and \
+                 autostart entries referring to the old ID lose track of the launcher"
+            );
+            let dbus_activatable = entry.desktop_entry("DBusActivatable") == Some("true");
+            if dbus_activatable {
+                body.push_str(
+                    "\n\nThe launcher is D-Bus activatable, its file name has to match the D-Bus \
+                     name of the application, otherwise it can no longer be started",
+                );
+            }
+
+            let dialog = adw::AlertDialog::builder()
+                .heading("Rename File to Match?")
+                .body(body)
+                .close_response("keep")
+                .default_response(if dbus_activatable { "keep" } else { "rename" })
+                .build();
+            dialog.add_response("keep", "Keep File Name");
+            dialog.add_response("rename", "Rename");
+            dialog.set_response_appearance(
+                "rename",
+                if dbus_activatable {
+                    adw::ResponseAppearance::Destructive
+                } else {
+                    adw::ResponseAppearance::Suggested
+                },
+            );
+            dialog.connect_response(
+                Some("rename"),
+                clone!(
+                    #[weak]
+                    obj,
+                    move |_, _| {
+                        // The file may have appeared while the dialog was open
+                        let new_path = new_path.clone();
+                        glib::spawn_future_local(clone!(
                             #[weak]
-                            desktop_file_view,
-                            move || {
-                                // On discard
-                                desktop_file_view.imp().disconnect_close_confirm_handlers();
-                                win.close();
+                            obj,
+                            async move {
+                                obj.imp().move_file_to(&new_path, false).await;
                             }
-                        ),
-                    );
+                        ));
+                    }
+                ),
+            );
+            dialog.present(Some(&*obj));
+        }
 
-                    Propagation::Stop
-                });
-                self.close_confirm_handler.set(Some(close_confirm_handler));
+        /// Asks whether the unsaved changes can be lost, there is nothing to ask without them
+        async fn confirm_discard(&self, body: &str) -> bool {
+            let obj = self.obj();
+            if !obj.content_changed() {
+                return true;
             }
+
+            let dialog = adw::AlertDialog::builder()
+                .heading("Discard Changes?")
+                .body(body)
+                .close_response("cancel")
+                .default_response("cancel")
+                .build();
+            dialog.add_response("cancel", "Cancel");
+            dialog.add_response("discard", "Discard");
+            dialog.set_response_appearance("discard", adw::ResponseAppearance::Destructive);
+            dialog.choose_future(&*obj).await.as_str() == "discard"
         }
-    }
-    impl NavigationPageImpl for DesktopFileView {}
 
-    #[gtk::template_callbacks]
-    impl DesktopFileView {
-        #[template_callback]
-        async fn on_save_button_clicked(&self, button: &gtk::Button) {
-            let write_success = || {
-                button.set_sensitive(false);
+        /// Brings the entry back to how it was last loaded or saved, without reading the file
+        async fn discard_changes(&self) {
+            let body = "The entry goes back to how it was when it was last loaded or saved";
+            if !self.confirm_discard(body).await {
+                return;
+            }
+
+            let contents = self.original_contents.borrow().clone();
+            self.obj().set_content_changed(false);
+            self.load_contents(contents);
+            self.show_loaded_entry();
+        }
+
+        /// Reloads the file from disk, asking first if that discards unsaved changes
+        async fn reload(&self) {
+            let body = "The unsaved changes will be lost and the file read again from disk";
+            if self.confirm_discard(body).await {
+                self.reset();
+            }
+        }
+
+        /// Writes the desktop entry to its file, returns whether it succeeded
+        pub async fn save(&self) -> bool {
+            // The entry is behind the text, saving would write something that was not validated
+            if self.raw_error.is_visible() {
+                let dialog = adw::AlertDialog::builder()
+                    .heading("Failed to Save")
+                    .body("The text cannot be decoded, correct it before saving")
+                    .build();
+                dialog.add_response("close", "Close");
+                dialog.present(Some(&*self.obj()));
+                return false;
+            }
+            if !self.confirm_spec_issues().await {
+                return false;
+            }
+
+            let was_scratch = self.scratch.get();
+            if was_scratch {
+                let Some(path) = self.choose_scratch_path().await else {
+                    return false;
+                };
+                self.obj().follow_rename(&path);
+            } else if !self.resolve_external_change().await {
+                return false;
+            }
+
+            let mut success = false;
+            let mut write_success = || {
+                success = true;
+                if self.scratch.replace(false) {
+                    self.obj().notify_scratch();
+                }
                 self.reset();
             };
 
-            let contents = {
-                let borrow = self.desktop_entry.borrow();
-                let content: &RefCell<DesktopEntry> = borrow.as_ref().unwrap().borrow();
-                let content_borrow = content.borrow();
-                content_borrow.to_sorted_entry_string()
+            // The text is written as it is, with the comments and formatting it was given
+            let contents = if self.raw_mode.get() {
+                self.raw_text()
+            } else {
+                self.serialized_contents()
             };
             let contents = glib::GString::from(contents);
+            let previous_contents = if was_scratch {
+                None
+            } else {
+                self.original_contents.borrow().clone()
+            };
 
             let path = self.path.borrow().to_path_buf();
+            // The backup is of the file being replaced, which has the changes of other programs
+            // when the user chose to overwrite them
+            let replaced_contents = if was_scratch {
+                None
+            } else {
+                std::fs::read_to_string(&path).ok()
+            };
 
             if let Err(e) = self.stop_file_watcher() {
                 eprintln!("Failed to stop file watcher before saving: {e}");
@@ -258,7 +1249,12 @@ mod imp {
                             println!("Failed to mount admin volume: {e}");
                         }
                         let res = file
-                            .replace_contents_future(contents, None, false, FileCreateFlags::NONE)
+                            .replace_contents_future(
+                                contents.clone(),
+                                None,
+                                false,
+                                FileCreateFlags::NONE,
+                            )
                             .await;
                         match res {
                             Ok(_) => write_success(),
@@ -272,20 +1268,32 @@ mod imp {
             if let Err(e) = self.start_file_watcher() {
                 eprintln!("Failed to restart file watcher: {e}");
             }
-        }
 
-        #[template_callback]
-        fn on_reset_button_clicked(&self, _: &gtk::Button) {
-            self.reset();
-        }
+            // The entry stays in memory if its new file could not be written
+            if was_scratch && !success {
+                self.obj().follow_rename(Path::new(""));
+            }
 
-        #[template_callback]
-        fn on_reload_button_clicked(&self, _: &gtk::Button) {
-            self.reset();
+            if success {
+                change_journal::record(
+                    &self.path.borrow(),
+                    previous_contents.as_deref(),
+                    &contents,
+                );
+                if let Some(replaced_contents) = &replaced_contents {
+                    backups::back_up(&self.path.borrow(), replaced_contents);
+                }
+                edited_files::mark_edited(&self.path.borrow());
+                if let Some(previous_contents) = &previous_contents {
+                    self.offer_rename_to_name(previous_contents, &contents);
+                }
+            }
+
+            success
         }
 
-        #[template_callback]
-        fn on_add_locale_button_clicked(&self, button: &gtk::Button) {
+        fn show_add_locale_dialog(&self) {
+            let button = &*self.add_locale_button;
             let dialog = AddLocaleDialog::new();
             dialog
                 .property_expression_weak("locale")
@@ -314,8 +1322,8 @@ mod imp {
             );
         }
 
-        #[template_callback]
-        fn on_add_action_button_clicked(&self, button: &gtk::Button) {
+        fn show_add_action_dialog(&self) {
+            let button = &*self.add_action_button;
             let dialog = AddActionDialog::new();
             dialog.clone().choose(
                 button,
@@ -335,17 +1343,129 @@ mod imp {
             );
         }
 
+        fn show_add_group_dialog(&self) {
+            let existing_groups = {
+                let borrowed_entry = self.desktop_entry.borrow();
+                let desktop_entry_cell: &DesktopEntryCell =
+                    borrowed_entry.as_ref().unwrap().borrow();
+                let desktop_entry = desktop_entry_cell.borrow();
+                desktop_entry.groups.0.keys().cloned().collect()
+            };
+
+            let button = &*self.add_group_button;
+            let dialog = AddGroupDialog::new(existing_groups);
+            dialog.clone().choose(
+                button,
+                Cancellable::NONE,
+                clone!(
+                    #[weak(rename_to=this)]
+                    self,
+                    #[weak]
+                    dialog,
+                    move |response| {
+                        if response == "add" {
+                            this.obj().add_group(&dialog.group());
+                        }
+                    }
+                ),
+            );
+        }
+
         fn load_desktop_entry_file(&self) {
-            let path = self.path.clone().into_inner();
-            let desktop_entry = DesktopEntry::from_path(path.clone(), None::<&[&str]>)
-                .unwrap_or_else(|_| {
-                    panic!(
-                        "Failed to create desktop entry from path: {}",
-                        path.to_string_lossy()
-                    )
-                });
+            let path = self.path.borrow().clone();
+            let contents = if self.scratch.get() {
+                let scratch_contents = self.scratch_contents.borrow().clone();
+                Some(scratch_contents.unwrap_or_else(|| SCRATCH_CONTENTS.to_string()))
+            } else {
+                match std::fs::read_to_string(&path) {
+                    Ok(contents) => Some(contents),
+                    Err(e) => {
+                        eprintln!("Failed to read original desktop file contents: {e}");
+                        None
+                    }
+                }
+            };
+            self.load_contents(contents);
+        }
+
+        /// Decodes `contents` into the entry, they become what the file is compared to. Without
+        /// contents the file is decoded directly
+        fn load_contents(&self, contents: Option<String>) {
+            let path = self.path.borrow().clone();
+
+            // What can't be decoded is kept aside, instead of failing or losing it on save
+            let (decodable, unrecognized) = contents
+                .as_deref()
+                .map(split_unrecognized)
+                .unwrap_or_default();
+            let desktop_entry = match contents {
+                Some(_) => DesktopEntry::from_str(path.clone(), &decodable, None::<&[&str]>),
+                None => DesktopEntry::from_path(path.clone(), None::<&[&str]>),
+            }
+            .unwrap_or_else(|_| {
+                panic!(
+                    "Failed to create desktop entry from path: {}",
+                    path.to_string_lossy()
+                )
+            });
             self.desktop_entry
                 .set(Some(Rc::new(RefCell::new(desktop_entry))));
+            self.unrecognized.replace(unrecognized);
+            self.original_contents.replace(contents);
+        }
+
+        /// Show the blocks the desktop entry could not decode, they can only be edited as text
+        fn populate_unrecognized(&self) {
+            let group = &self.unrecognized_group;
+            while let Some(row) = self.unrecognized_rows.borrow_mut().pop() {
+                group.remove(&row);
+            }
+
+            let unrecognized = self.unrecognized.borrow();
+            group.set_visible(!unrecognized.is_empty());
+
+            for (i, block) in unrecognized.iter().enumerate() {
+                let location = match &block.placement {
+                    RawPlacement::Start => "Before the first group".to_string(),
+                    RawPlacement::Group(group_name) => format!("In group {group_name}"),
+                    RawPlacement::End => "Written after the other groups".to_string(),
+                };
+                let row = adw::ExpanderRow::builder()
+                    .title(block.reason)
+                    .subtitle(format!("Line {}, {location}", block.first_line + 1))
+                    .use_markup(false)
+                    .build();
+
+                let text_view = gtk::TextView::builder()
+                    .monospace(true)
+                    .wrap_mode(gtk::WrapMode::WordChar)
+                    .top_margin(6)
+                    .bottom_margin(6)
+                    .left_margin(6)
+                    .right_margin(6)
+                    .build();
+                text_view.buffer().set_text(&block.text);
+                text_view.buffer().connect_changed(clone!(
+                    #[weak(rename_to = this)]
+                    self,
+                    move |buffer| {
+                        if let Some(block) = this.unrecognized.borrow_mut().get_mut(i) {
+                            block.text = buffer
+                                .text(&buffer.start_iter(), &buffer.end_iter(), false)
+                                .into();
+                        }
+                        this.obj().set_content_changed(true);
+                    }
+                ));
+
+                let text_row = gtk::ListBoxRow::builder()
+                    .activatable(false)
+                    .child(&text_view)
+                    .build();
+                row.add_row(&text_row);
+                group.add(&row);
+                self.unrecognized_rows.borrow_mut().push(row);
+            }
         }
 
         fn reset(&self) {
@@ -357,6 +1477,115 @@ mod imp {
 
             // Reload file
             self.load_desktop_entry_file();
+            self.show_loaded_entry();
+        }
+
+        /// Shows the entry just loaded, with a fresh history
+        fn show_loaded_entry(&self) {
+            let obj = self.obj();
+            self.reset_history();
+            self.populate();
+            if self.raw_mode.get() {
+                self.load_raw_text();
+            }
+
+            let duplicates = self
+                .original_contents
+                .borrow()
+                .as_deref()
+                .map(find_duplicate_keys)
+                .unwrap_or_default();
+            if !duplicates.is_empty() {
+                obj.once_shown(move |view| show_duplicate_keys_dialog(view, duplicates.clone()));
+            }
+        }
+
+        fn reset_history(&self) {
+            self.history.borrow_mut().clear();
+            self.update_history_actions();
+        }
+
+        /// Change the entry through an editor that records every change, so that it can be
+        /// undone. The entry is borrowed while `edit` runs
+        pub fn edit_entry<R>(&self, edit: impl FnOnce(&mut EntryEditor) -> R) -> R {
+            let (result, commands) = {
+                let desktop_entry_rc = self.obj().desktop_entry();
+                let desktop_entry_cell: &DesktopEntryCell = desktop_entry_rc.borrow();
+                let mut desktop_entry = desktop_entry_cell.borrow_mut();
+                let mut editor = EntryEditor::new(&mut desktop_entry);
+                let result = edit(&mut editor);
+                (result, editor.into_commands())
+            };
+            self.record_commands(commands);
+            result
+        }
+
+        fn record_commands(&self, commands: Vec<EntryCommand>) {
+            if self.history.borrow_mut().record(commands) {
+                self.update_history_actions();
+            }
+        }
+
+        fn update_history_actions(&self) {
+            let obj = self.obj();
+            let history = self.history.borrow();
+            obj.action_set_enabled("win.undo", history.can_undo());
+            obj.action_set_enabled("win.redo", history.can_redo());
+        }
+
+        /// Undo the last edit, or redo the last undone one
+        fn step_history(&self, redo: bool) {
+            let stepped = {
+                let desktop_entry_rc = self.obj().desktop_entry();
+                let desktop_entry_cell: &DesktopEntryCell = desktop_entry_rc.borrow();
+                let mut desktop_entry = desktop_entry_cell.borrow_mut();
+                let mut history = self.history.borrow_mut();
+                if redo {
+                    history.redo(&mut desktop_entry)
+                } else {
+                    history.undo(&mut desktop_entry)
+                }
+            };
+            let Some(stepped) = stepped else {
+                return;
+            };
+
+            // Showing the entry again selects the default locale, the one shown before is kept
+            // unless the step switched it
+            let locale = stepped.locale.unwrap_or_else(|| self.obj().locale());
+            if self.raw_mode.get() {
+                self.load_raw_text();
+            } else {
+                self.populate();
+            }
+            self.select_locale(locale.as_deref());
+            self.obj().set_content_changed(true);
+            self.update_history_actions();
+        }
+
+        /// Show `locale` in the dropdown, adding it if the entry has no values for it. Selecting
+        /// it this way is not recorded as an edit
+        fn select_locale(&self, locale: Option<&str>) {
+            let item_name = locale.unwrap_or(DEFAULT_LOCALE);
+            let position = self.locale_dropdown.model().and_then(|model| {
+                model
+                    .iter::<gtk::StringObject>()
+                    .filter_map(Result::ok)
+                    .position(|item| item.string() == item_name)
+            });
+
+            self.selecting_locale.set(true);
+            match (position, locale) {
+                (Some(position), _) => self.locale_dropdown.set_selected(position as u32),
+                (None, Some(locale)) => self.obj().add_locale(locale),
+                (None, None) => (),
+            }
+            self.selecting_locale.set(false);
+        }
+
+        /// Show the entry as it is now in the header and the groups
+        fn populate(&self) {
+            let obj = self.obj();
 
             // The scope is necessary to avoid BorrowMutError, to make sure the borrowed desktop
             // entry is dropped.
@@ -372,12 +1601,93 @@ mod imp {
                 let name = desktop_entry.name(&NO_LOCALE);
                 obj.set_title(&name.unwrap_or_else(|| "No Name".into()));
 
-                self.image.set_from_gicon(&desktop_entry.gicon());
+                obj.set_image_icon(&desktop_entry.gicon());
             }
 
             self.desktop_entry_group.populate();
             self.reset_additional_groups();
             self.populate_dropdown();
+            self.populate_unrecognized();
+
+            obj.queue_preview_update();
+        }
+
+        /// Show the hints that were not dismissed
+        pub fn show_quality_hints(&self, hints: Vec<QualityHint>) {
+            let group = &self.quality_hints_group;
+            while let Some(row) = self.quality_hint_rows.borrow_mut().pop() {
+                group.remove(&row);
+            }
+
+            let dismissed = self.dismissed_hints.borrow();
+            let hints: Vec<_> = hints
+                .into_iter()
+                .filter(|hint| !dismissed.contains(hint.id))
+                .collect();
+            group.set_visible(!hints.is_empty());
+
+            for hint in hints {
+                let row = adw::ActionRow::builder()
+                    .title(hint.key)
+                    .subtitle(&hint.message)
+                    .use_markup(false)
+                    .build();
+
+                let dismiss_button = gtk::Button::builder()
+                    .icon_name("window-close-symbolic")
+                    .tooltip_text("Dismiss")
+                    .valign(gtk::Align::Center)
+                    .css_classes(["flat", "circular"])
+                    .build();
+                dismiss_button.connect_clicked(clone!(
+                    #[weak(rename_to = this)]
+                    self,
+                    #[weak]
+                    row,
+                    move |_| {
+                        this.dismissed_hints.borrow_mut().insert(hint.id);
+                        this.quality_hint_rows.borrow_mut().retain(|r| *r != row);
+                        this.quality_hints_group.remove(&row);
+                        this.quality_hints_group
+                            .set_visible(!this.quality_hint_rows.borrow().is_empty());
+                    }
+                ));
+                row.add_suffix(&dismiss_button);
+
+                group.add(&row);
+                self.quality_hint_rows.borrow_mut().push(row);
+            }
+        }
+
+        /// Render the header icon at the pixel size of the image for the current scale factor, so
+        /// that it stays sharp on high density displays. Icons that can't be found fall back to
+        /// the default one rather than the missing image icon
+        fn render_header_icon(&self) {
+            let Some(icon) = self.header_icon.borrow().clone() else {
+                return;
+            };
+
+            let obj = self.obj();
+            let icon_theme = gtk::IconTheme::for_display(&obj.display());
+            let exists = match icon.downcast_ref::<gio::FileIcon>() {
+                Some(file_icon) => file_icon.file().query_exists(Cancellable::NONE),
+                None => icon_theme.has_gicon(&icon),
+            };
+            let icon = if exists {
+                icon
+            } else {
+                DesktopEntry::default_exec_gicon()
+            };
+
+            // Icons loaded from files are scaled to fit, keeping their aspect ratio
+            let paintable = icon_theme.lookup_by_gicon(
+                &icon,
+                self.image.pixel_size(),
+                obj.scale_factor(),
+                obj.direction(),
+                gtk::IconLookupFlags::empty(),
+            );
+            self.image.set_paintable(Some(&paintable));
         }
 
         pub fn reset_additional_groups(&self) {
@@ -446,12 +1756,42 @@ mod imp {
                         Some(locale.to_string())
                     };
 
-                    this.obj().set_locale(locale);
+                    let before = this.obj().locale();
+                    this.obj().set_locale(locale.clone());
+                    if before != locale && !this.selecting_locale.get() {
+                        this.record_commands(vec![EntryCommand::SwitchLocale {
+                            before,
+                            after: locale,
+                        }]);
+                    }
                 }
             ));
         }
 
-        fn populate_dropdown(&self) {
+        /// Select the locale the user reads values in, if the entry is translated to it
+        fn preselect_user_locale(&self) {
+            let Some(user_locale) = user_messages_locale() else {
+                return;
+            };
+            let Some(model) = self.locale_dropdown.model() else {
+                return;
+            };
+
+            let locales: Vec<String> = model
+                .iter::<gtk::StringObject>()
+                .filter_map(Result::ok)
+                .map(|item| item.string().into())
+                .collect();
+            let position = matching_locale(&user_locale, &locales)
+                .and_then(|locale| locales.iter().position(|item| item == locale));
+            if let Some(position) = position {
+                self.selecting_locale.set(true);
+                self.locale_dropdown.set_selected(position as u32);
+                self.selecting_locale.set(false);
+            }
+        }
+
+        pub fn populate_dropdown(&self) {
             // For the same reason as above, we drop the borrow before setting the model
             let string_list = {
                 let borrowed_entry = self.desktop_entry.borrow();
@@ -466,10 +1806,12 @@ mod imp {
                 gtk::StringList::new(&locales[..])
             };
 
+            self.selecting_locale.set(true);
             self.locale_dropdown.set_model(Some(&string_list));
+            self.selecting_locale.set(false);
         }
 
-        fn init_file_watcher(&self) -> Result<(), notify::Error> {
+        pub fn init_file_watcher(&self) -> Result<(), notify::Error> {
             let path_ref = self.path.borrow();
 
             let (sender, receiver) = async_channel::bounded(1);
@@ -514,7 +1856,10 @@ mod imp {
             // watch the parent directory and only act on the file we want
             let path_borrow = self.path.borrow();
             let path = path_borrow.as_path();
-            let parent_path = path.parent().expect("Failed to get file's parent path");
+            let Some(parent_path) = path.parent() else {
+                // Scratch entries have no file to watch yet
+                return Ok(());
+            };
 
             let res = self
                 .file_watcher
@@ -525,45 +1870,35 @@ mod imp {
             res.unwrap_or(Ok(()))
         }
 
-        fn start_file_watcher(&self) -> Result<(), notify::Error> {
+        pub fn start_file_watcher(&self) -> Result<(), notify::Error> {
             self.perform_watcher_action(|watcher, parent_path| {
                 watcher.watch(parent_path, RecursiveMode::NonRecursive)
             })
         }
 
-        fn stop_file_watcher(&self) -> Result<(), notify::Error> {
+        pub fn stop_file_watcher(&self) -> Result<(), notify::Error> {
             self.perform_watcher_action(|watcher, parent_path| watcher.unwatch(parent_path))
         }
 
-        fn window(&self) -> adw::ApplicationWindow {
-            let win = self.window.borrow().clone();
-            match win {
-                Some(win) => win,
-                None => {
-                    let win = self
-                        .obj()
-                        .root()
-                        .expect("No Root")
-                        .downcast::<adw::ApplicationWindow>()
-                        .expect("Root is not a window");
-                    self.window.set(Some(win.clone()));
-                    win
-                }
-            }
+        /// Drops the file watcher, which closes its channel and ends the task revealing the
+        /// reload bar
+        pub fn drop_file_watcher(&self) {
+            self.file_watcher.take();
         }
 
-        fn disconnect_close_confirm_handlers(&self) {
+        fn disconnect_back_confirm_handler(&self) {
             let back_confirm_handler = self.back_confirm_handler.replace(None);
             if let Some(back_confirm_handler) = back_confirm_handler {
                 self.parent_navigation_view
                     .borrow()
                     .disconnect(back_confirm_handler);
             }
+        }
 
-            let close_confirm_handler = self.close_confirm_handler.replace(None);
-            if let Some(close_confirm_handler) = close_confirm_handler {
-                self.window().disconnect(close_confirm_handler);
-            }
+        /// The editor was popped for good, nothing needs to watch its file anymore
+        fn finish_closing(&self) {
+            self.disconnect_back_confirm_handler();
+            self.obj().shutdown();
         }
 
         fn back_confirm_callback(view: &NavigationView, page: &NavigationPage) {
@@ -572,7 +1907,7 @@ mod imp {
             let imp = desktop_file_view.imp();
             if !imp.content_changed.get() {
                 // If nothing changed there is no need for confirmation
-                imp.disconnect_close_confirm_handlers();
+                imp.finish_closing();
                 return;
             }
 
@@ -594,7 +1929,28 @@ mod imp {
                     move || {
                         // On discard
                         let imp = desktop_file_view.imp();
-                        imp.disconnect_close_confirm_handlers();
+                        imp.finish_closing();
+                    }
+                ),
+                clone!(
+                    #[weak]
+                    view,
+                    #[strong]
+                    desktop_file_view,
+                    move || {
+                        // On save, go back to the editor if the changes could not be written
+                        glib::spawn_future_local(clone!(
+                            #[strong]
+                            desktop_file_view,
+                            async move {
+                                let imp = desktop_file_view.imp();
+                                if imp.save().await {
+                                    imp.finish_closing();
+                                } else {
+                                    view.push(&desktop_file_view);
+                                }
+                            }
+                        ));
                     }
                 ),
             );
@@ -616,20 +1972,345 @@ impl DesktopFileView {
             .build()
     }
 
+    /// Editor for a new entry that is kept in memory, the user chooses its file when saving it
+    pub fn new_scratch(parent_navigation_view: adw::NavigationView) -> DesktopFileView {
+        glib::Object::builder()
+            .property("path", PathBuf::new())
+            .property("scratch", true)
+            .property("parent_navigation_view", parent_navigation_view)
+            .build()
+    }
+
+    /// Editor for a new entry that starts from `contents`, like a scratch entry it is kept in
+    /// memory until the user chooses its file
+    pub fn new_scratch_with_contents(
+        parent_navigation_view: adw::NavigationView,
+        contents: &str,
+    ) -> DesktopFileView {
+        glib::Object::builder()
+            .property("path", PathBuf::new())
+            .property("scratch", true)
+            .property("scratch-contents", contents)
+            .property("parent_navigation_view", parent_navigation_view)
+            .build()
+    }
+
+    /// Writes the desktop entry to its file, returns whether it succeeded
+    pub async fn save(&self) -> bool {
+        self.imp().save().await
+    }
+
+    /// The window the editor belongs to, also while another editor is shown on top of it
+    fn window(&self) -> Option<DMWindow> {
+        self.parent_navigation_view().root().and_downcast()
+    }
+
+    /// Replace the entry with `contents` as unsaved changes, the file is left as it is
+    pub fn apply_unsaved_contents(&self, contents: &str) {
+        self.imp().restore_contents(contents);
+    }
+
+    /// Moves the focus to the entry `key` of the main group once the view is shown, adding the
+    /// entry if it is missing
+    pub fn focus_entry(&self, key: &str) {
+        let key = key.to_string();
+        self.once_shown(move |view| view.imp().desktop_entry_group.focus_entry(&key));
+    }
+
+    /// Runs `f` when the view is shown, right away if it already is
+    fn once_shown<F: Fn(&Self) + 'static>(&self, f: F) {
+        if self.is_mapped() {
+            f(self);
+            return;
+        }
+
+        let handler_id = Rc::new(RefCell::new(None));
+        let id = self.connect_shown(clone!(
+            #[strong]
+            handler_id,
+            move |view| {
+                f(view);
+                if let Some(id) = handler_id.take() {
+                    view.disconnect(id);
+                }
+            }
+        ));
+        handler_id.replace(Some(id));
+    }
+
     pub fn desktop_entry(&self) -> Rc<DesktopEntryCell> {
         self.imp().desktop_entry.borrow().as_ref().unwrap().clone()
     }
 
+    /// Keeps editing the same file after it has been renamed or moved to `path`
+    pub fn follow_rename(&self, path: &Path) {
+        let imp = self.imp();
+        if let Err(e) = imp.stop_file_watcher() {
+            eprintln!("Failed to stop watching renamed desktop file: {e}");
+        }
+
+        self.set_path(path.to_path_buf());
+        {
+            // Desktop Entry borrow should not escape this block
+            let desktop_entry_rc = self.desktop_entry();
+            let desktop_entry_cell: &DesktopEntryCell = desktop_entry_rc.borrow();
+            desktop_entry_cell.borrow_mut().path = path.to_path_buf();
+        }
+
+        // The watcher only reports events for the path it was created with
+        if let Err(e) = imp.init_file_watcher() {
+            eprintln!("Failed to initialize file watcher: {e}");
+        };
+
+        if let Err(e) = imp.start_file_watcher() {
+            eprintln!("Failed to watch desktop file for changes: {e}");
+        };
+    }
+
+    /// Stops watching the file for good, unlike [`Self::suspend_file_watcher`]. Called when the
+    /// editor is closed, so that long sessions don't pile up watchers and their tasks
+    pub fn shutdown(&self) {
+        self.imp().drop_file_watcher();
+    }
+
+    /// Stops watching the file until [`Self::resume_file_watcher`] is called
+    pub fn suspend_file_watcher(&self) {
+        let imp = self.imp();
+        imp.suspended_modified_time.set(modified_time(&self.path()));
+        if let Err(e) = imp.stop_file_watcher() {
+            eprintln!("Failed to suspend desktop file watcher: {e}");
+        }
+    }
+
+    /// Restarts watching the file, offering a reload if it changed while suspended
+    pub fn resume_file_watcher(&self) {
+        let imp = self.imp();
+        if let Err(e) = imp.start_file_watcher() {
+            eprintln!("Failed to watch desktop file for changes: {e}");
+        }
+
+        if modified_time(&self.path()) != imp.suspended_modified_time.take() {
+            imp.reload_bar.set_reveal_child(true);
+        }
+    }
+
+    /// Refresh the launcher preview and the suggestions once the current change is done, entries
+    /// are often still borrowed by whoever is editing them when this is called
+    pub fn queue_preview_update(&self) {
+        let imp = self.imp();
+        if imp.preview_update_queued.replace(true) {
+            return;
+        }
+
+        glib::idle_add_local_once(clone!(
+            #[weak(rename_to = view)]
+            self,
+            move || {
+                let imp = view.imp();
+                imp.preview_update_queued.set(false);
+
+                let locale = view.locale();
+                let hints = {
+                    let desktop_entry_rc = view.desktop_entry();
+                    let desktop_entry_cell: &DesktopEntryCell = desktop_entry_rc.borrow();
+                    let desktop_entry = desktop_entry_cell.borrow();
+                    if view.show_preview() {
+                        imp.launcher_preview
+                            .update(&desktop_entry, locale.as_deref());
+                    }
+
+                    // Hints are about the texts shown for the locale, which may be inherited
+                    let value = |key: &str| match locale.as_deref() {
+                        Some(locale) => desktop_entry
+                            .entry("Desktop Entry", key, Some(locale))
+                            .or_else(|| {
+                                desktop_entry
+                                    .inherited_entry("Desktop Entry", key, locale)
+                                    .map(|(value, _)| value)
+                            }),
+                        None => desktop_entry.entry("Desktop Entry", key, None),
+                    };
+                    quality_hints(&EntryTexts {
+                        name: value("Name"),
+                        generic_name: value("GenericName"),
+                        comment: value("Comment"),
+                        keywords: value("Keywords"),
+                    })
+                };
+                imp.show_quality_hints(hints);
+            }
+        ));
+    }
+
+    /// Start the application the way launchers do, with the entry as it currently is. Unlike
+    /// [`Self::test_launch`] field codes and `Terminal` are handled by GIO and the output is not
+    /// shown
+    pub fn launch(&self) {
+        let app_info = {
+            let desktop_entry_rc = self.desktop_entry();
+            let desktop_entry_cell: &DesktopEntryCell = desktop_entry_rc.borrow();
+            let desktop_entry = desktop_entry_cell.borrow();
+            desktop_app_info(&desktop_entry)
+        };
+
+        if let Some(window) = self.root().and_downcast::<DMWindow>() {
+            window.launch_app_info(app_info);
+        }
+    }
+
+    /// Run the command of the entry as it currently is, showing what it prints in the output pane.
+    /// Startup notification is set up the same way a launcher would, following the
+    /// `StartupNotify` and `StartupWMClass` keys of the entry
+    pub async fn test_launch(&self) {
+        let (argv, working_dir, startup_notify, wm_class, app_info) = {
+            let desktop_entry_rc = self.desktop_entry();
+            let desktop_entry_cell: &DesktopEntryCell = desktop_entry_rc.borrow();
+            let desktop_entry = desktop_entry_cell.borrow();
+
+            let value = |key: &str| desktop_entry.entry("Desktop Entry", key, None);
+            let argv = value("Exec")
+                .and_then(|exec| launch_argv(exec, value("Name"), value("Icon"), &self.path()));
+            let working_dir = value("Path")
+                .filter(|path| !path.is_empty())
+                .map(PathBuf::from);
+            let startup_notify = value("StartupNotify") == Some("true");
+            let wm_class = value("StartupWMClass")
+                .filter(|wm_class| !wm_class.is_empty())
+                .map(String::from);
+
+            // The launch context reads the startup notification details from the app info
+            let app_info = desktop_app_info(&desktop_entry);
+            (argv, working_dir, startup_notify, wm_class, app_info)
+        };
+
+        let imp = self.imp();
+        imp.output_view.buffer().set_text("");
+        imp.output_bar.set_reveal_child(true);
+        imp.launched_process.replace(None);
+
+        let Some(argv) = argv else {
+            imp.output_status.set_label("There is no command to launch");
+            return;
+        };
+        let command_line = argv.join(" ");
+
+        let launch_context = self.display().app_launch_context();
+        let startup_id = match (startup_notify, &app_info) {
+            (true, Some(app_info)) => launch_context.startup_notify_id(Some(app_info), &[]),
+            _ => None,
+        };
+        match (&startup_id, &wm_class) {
+            // The shell falls back to the class to match the window, if the program never
+            // completes the startup notification itself
+            (Some(startup_id), Some(wm_class)) => self.append_output(&format!(
+                "Startup notification {startup_id}, window class {wm_class}"
+            )),
+            (Some(startup_id), None) => {
+                self.append_output(&format!("Startup notification {startup_id}"))
+            }
+            (None, _) if startup_notify => {
+                self.append_output("Startup notification is not available")
+            }
+            (None, _) => {
+                self.append_output("StartupNotify is not enabled, the launch is not tracked")
+            }
+        }
+
+        // Passed on the command line instead of the launcher environment, so that it also
+        // reaches the host when sandboxed
+        let argv = match &startup_id {
+            Some(startup_id) => with_environment(
+                argv,
+                &[
+                    ("DESKTOP_STARTUP_ID", startup_id),
+                    ("XDG_ACTIVATION_TOKEN", startup_id),
+                ],
+            ),
+            None => argv,
+        };
+
+        let launcher = gio::SubprocessLauncher::new(
+            gio::SubprocessFlags::STDOUT_PIPE | gio::SubprocessFlags::STDERR_MERGE,
+        );
+        #[cfg(not(feature = "flatpak"))]
+        if let Some(working_dir) = &working_dir {
+            launcher.set_cwd(working_dir);
+        }
+
+        let argv = crate::util::host_command(argv, working_dir.as_deref());
+        let argv: Vec<&OsStr> = argv.iter().map(OsStr::new).collect();
+        let process = match launcher.spawn(&argv) {
+            Ok(process) => process,
+            Err(e) => {
+                if let Some(startup_id) = &startup_id {
+                    launch_context.launch_failed(startup_id);
+                }
+                imp.output_status
+                    .set_label(&format!("Failed to launch {command_line}: {e}"));
+                return;
+            }
+        };
+        imp.output_status
+            .set_label(&format!("Running {command_line}"));
+        imp.launched_process.replace(Some(process.clone()));
+
+        let is_current = || imp.launched_process.borrow().as_ref() == Some(&process);
+
+        if let Some(stdout) = process.stdout_pipe() {
+            let stream = gio::DataInputStream::new(&stdout);
+            while let Ok(Some(line)) = stream.read_line_future(glib::Priority::DEFAULT).await {
+                if !is_current() {
+                    return;
+                }
+                self.append_output(&String::from_utf8_lossy(&line[..]));
+            }
+        }
+
+        if let Err(e) = process.wait_future().await {
+            eprintln!("Failed to wait for test launch of {command_line}: {e}");
+        }
+        if !is_current() {
+            return;
+        }
+
+        let status = if process.has_exited() {
+            format!("exited with status {}", process.exit_status())
+        } else if process.has_signaled() {
+            format!("was terminated by signal {}", process.term_sig())
+        } else {
+            "stopped".to_string()
+        };
+        imp.output_status
+            .set_label(&format!("{command_line} {status}"));
+    }
+
+    fn append_output(&self, line: &str) {
+        let output_view = &self.imp().output_view;
+        let buffer = output_view.buffer();
+        buffer.insert(&mut buffer.end_iter(), &format!("{line}\n"));
+
+        let end = buffer.create_mark(None, &buffer.end_iter(), false);
+        output_view.scroll_mark_onscreen(&end);
+        buffer.delete_mark(&end);
+    }
+
     pub fn set_image_icon(&self, icon: &impl IsA<gio::Icon>) {
-        self.imp().image.set_from_gicon(icon);
+        let imp = self.imp();
+        imp.header_icon.replace(Some(icon.as_ref().clone()));
+        imp.render_header_icon();
     }
 
-    pub fn remove_group(&self, group: &DesktopFileGroup) {
+    /// Look the icon of the entry up again, after the icon theme changed
+    pub fn refresh_icon(&self) {
         let desktop_entry_rc = self.desktop_entry();
         let desktop_entry_cell: &DesktopEntryCell = desktop_entry_rc.borrow();
-        let mut desktop_entry = desktop_entry_cell.borrow_mut();
+        let gicon = desktop_entry_cell.borrow().gicon();
+        self.set_image_icon(&gicon);
+    }
 
-        desktop_entry.remove_group(group.name());
+    pub fn remove_group(&self, group: &DesktopFileGroup) {
+        self.imp()
+            .edit_entry(|editor| editor.remove_group(group.name()));
         self.imp().additional_groups.remove(group);
         self.set_content_changed(true);
     }
@@ -679,22 +2360,174 @@ impl DesktopFileView {
         self.set_locale(Some(locale));
     }
 
-    pub fn add_action(&self, action_name: &str) {
-        if action_name.is_empty() {
+    /// Rebuild the group widgets after the entry was changed outside of them
+    pub fn refresh_groups(&self) {
+        let imp = self.imp();
+        imp.desktop_entry_group.populate();
+        imp.reset_additional_groups();
+    }
+
+    /// Let the user choose another desktop file and import some of its keys
+    pub async fn import_from_file(&self) {
+        let filter = gtk::FileFilter::new();
+        filter.set_name(Some("Desktop Files"));
+        filter.add_suffix("desktop");
+
+        let dialog = gtk::FileDialog::builder()
+            .title("Import From File")
+            .default_filter(&filter)
+            .build();
+
+        let root = self.root().and_downcast::<gtk::Window>();
+        let file = match dialog.open_future(root.as_ref()).await {
+            Ok(file) => file,
+            Err(_) => return,
+        };
+
+        let Some(path) = file.path() else {
             return;
+        };
+
+        match DesktopEntry::from_path(path, None::<&[&str]>) {
+            Ok(source) => ImportDialog::new(source, self).present(Some(self)),
+            Err(e) => {
+                let dialog = adw::AlertDialog::builder()
+                    .heading("Failed to Import")
+                    .body(format!("The file could not be read: {e}"))
+                    .build();
+                dialog.add_response("close", "Close");
+                dialog.present(Some(self));
+            }
         }
+    }
 
-        {
-            // Desktop Entry borrow should not escape this block
+    /// Ask which locales to keep, then remove all the other translations
+    pub fn show_strip_translations_dialog(&self) {
+        let locales = {
             let desktop_entry_rc = self.desktop_entry();
             let desktop_entry_cell: &DesktopEntryCell = desktop_entry_rc.borrow();
-            let mut desktop_entry = desktop_entry_cell.borrow_mut();
-            desktop_entry.add_action(action_name);
-            let group_name = format!("Desktop Action {action_name}");
-            desktop_entry.add_entry(group_name.clone(), "Name".to_string());
-            desktop_entry.add_entry(group_name, "Exec".to_string());
+            let desktop_entry = desktop_entry_cell.borrow();
+            desktop_entry.locales()
+        };
+
+        let dialog = adw::AlertDialog::builder()
+            .heading("Remove Translations?")
+            .body("All localized values will be removed, except for the locales chosen below")
+            .build();
+        dialog.add_responses(&[("cancel", "Cancel"), ("remove", "Remove")]);
+        dialog.set_response_appearance("remove", adw::ResponseAppearance::Destructive);
+        dialog.set_default_response(Some("cancel"));
+        dialog.set_close_response("cancel");
+
+        if locales.is_empty() {
+            dialog.set_body("This file has no translations");
+            dialog.set_response_enabled("remove", false);
+        }
+
+        let list = gtk::ListBox::new();
+        list.set_selection_mode(gtk::SelectionMode::None);
+        list.add_css_class("boxed-list");
+        let keep_rows: Vec<_> = locales
+            .into_iter()
+            .map(|locale| {
+                let row = adw::SwitchRow::new();
+                row.set_title(&format!("Keep {locale}"));
+                if let Some(language) = LANGUAGES_LOCALE_MAP.get(&locale[..]) {
+                    row.set_subtitle(language);
+                }
+                list.append(&row);
+                (locale, row)
+            })
+            .collect();
+
+        if !keep_rows.is_empty() {
+            let scrolled_window = gtk::ScrolledWindow::builder()
+                .hscrollbar_policy(gtk::PolicyType::Never)
+                .propagate_natural_height(true)
+                .max_content_height(300)
+                .child(&list)
+                .build();
+            dialog.set_extra_child(Some(&scrolled_window));
+        }
+
+        dialog.connect_response(
+            Some("remove"),
+            clone!(
+                #[weak(rename_to = view)]
+                self,
+                move |_, _| {
+                    let keep: Vec<String> = keep_rows
+                        .iter()
+                        .filter(|(_, row)| row.is_active())
+                        .map(|(locale, _)| locale.clone())
+                        .collect();
+
+                    let removed = view
+                        .imp()
+                        .edit_entry(|editor| editor.strip_translations(&keep));
+
+                    if removed > 0 {
+                        view.imp().populate_dropdown();
+                        view.refresh_groups();
+                        view.set_content_changed(true);
+                    }
+                }
+            ),
+        );
+
+        dialog.present(Some(self));
+    }
+
+    /// Add the groups found in the clipboard text to the entry
+    pub async fn paste_groups_from_clipboard(&self) {
+        let text = match self.clipboard().read_text_future().await {
+            Ok(Some(text)) => text,
+            Ok(None) => return,
+            Err(e) => {
+                eprintln!("Failed to read clipboard: {e}");
+                return;
+            }
+        };
+
+        let result = self.imp().edit_entry(|editor| editor.paste_groups(&text));
+
+        match result {
+            Ok(added) if added.is_empty() => (),
+            Ok(_) => {
+                self.refresh_groups();
+                self.set_content_changed(true);
+            }
+            Err(e) => {
+                let dialog = adw::AlertDialog::builder()
+                    .heading("Failed to Paste Group")
+                    .body(format!("The clipboard does not contain a valid group: {e}"))
+                    .build();
+                dialog.add_response("close", "Close");
+                dialog.present(Some(self));
+            }
+        }
+    }
+
+    pub fn add_action(&self, action_name: &str) {
+        if action_name.is_empty() {
+            return;
         }
 
+        self.imp().edit_entry(|editor| {
+            editor.add_action(action_name);
+            let group_name = format!("Desktop Action {action_name}");
+            editor.add_entry(group_name.clone(), "Name".to_string());
+            editor.add_entry(group_name, "Exec".to_string());
+        });
+
+        self.imp().reset_additional_groups();
+        self.set_content_changed(true);
+    }
+
+    pub fn add_group(&self, group_name: &str) {
+        self.imp()
+            .edit_entry(|editor| editor.add_group(group_name.to_string()));
+
         self.imp().reset_additional_groups();
         self.set_content_changed(true);
     }
@@ -704,6 +2537,11 @@ impl DesktopFileView {
         let locale_ref = locale.as_deref();
         let imp = self.imp();
 
+        // Rows that change size would make the view jump, and rebuilt rows lose focus
+        let vadjustment = imp.scrolled_window.vadjustment();
+        let scroll_position = vadjustment.value();
+        let focus = self.root().and_then(|root| root.focus());
+
         imp.desktop_entry_group.change_locale(locale_ref);
 
         let mut child = imp.additional_groups.first_child();
@@ -717,6 +2555,15 @@ impl DesktopFileView {
             child = group.next_sibling();
         }
 
+        glib::idle_add_local_once(move || {
+            vadjustment.set_value(scroll_position);
+            if let Some(focus) = focus {
+                if focus.root().is_some() && !focus.has_focus() {
+                    focus.grab_focus();
+                }
+            }
+        });
+
         // Also select the appropriate item in the dropdown if not already selected, this is
         // necessary when programmatically changing the selected locale
         let dropdown = &self.imp().locale_dropdown;
@@ -737,3 +2584,87 @@ impl DesktopFileView {
         }
     }
 }
+
+/// App info for the entry as it currently is, `None` if GIO does not consider it an application
+/// that can be launched
+fn desktop_app_info(desktop_entry: &DesktopEntry) -> Option<gio::DesktopAppInfo> {
+    let key_file = glib::KeyFile::new();
+    match key_file.load_from_data(
+        &desktop_entry.to_sorted_entry_string(&KEYMAP_ORDER),
+        glib::KeyFileFlags::NONE,
+    ) {
+        Ok(()) => gio::DesktopAppInfo::from_keyfile(&key_file),
+        Err(e) => {
+            eprintln!("Failed to load the entry as a key file: {e}");
+            None
+        }
+    }
+}
+
+/// Locale of the messages shown to the user, as set in the environment
+fn user_messages_locale() -> Option<String> {
+    ["LC_ALL", "LC_MESSAGES", "LANG"]
+        .into_iter()
+        .filter_map(|var| std::env::var(var).ok())
+        .find(|locale| !locale.is_empty())
+        .filter(|locale| locale != "C" && locale != "POSIX")
+}
+
+fn modified_time(path: &Path) -> Option<SystemTime> {
+    fs::metadata(path).and_then(|metadata| metadata.modified()).ok()
+}
+
+/// Renames `from` to `to`, copying it when they are on different file systems
+fn move_file(from: &Path, to: &Path) -> std::io::Result<()> {
+    match fs::rename(from, to) {
+        Err(e) if e.kind() == std::io::ErrorKind::CrossesDevices => {
+            fs::copy(from, to)?;
+            fs::remove_file(from)
+        }
+        result => result,
+    }
+}
+
+/// Adds a row for each issue to the list, issues that can be corrected automatically get a button
+/// applying the fix to `view`
+fn fill_issue_list(list: &gtk::ListBox, issues: &[validation::Issue], view: &DesktopFileView) {
+    for issue in issues {
+        let (icon_name, css_class) = match issue.severity {
+            validation::Severity::Error => ("dialog-error-symbolic", "error"),
+            validation::Severity::Warning => ("dialog-warning-symbolic", "warning"),
+        };
+        let subtitle = match &issue.key {
+            Some(key) => format!("{key} in {}", issue.group),
+            None => issue.group.clone(),
+        };
+        let row = adw::ActionRow::builder()
+            .title(&issue.message)
+            .subtitle(subtitle)
+            .use_markup(false)
+            .build();
+        let icon = gtk::Image::from_icon_name(icon_name);
+        icon.add_css_class(css_class);
+        row.add_prefix(&icon);
+        if issue.fix.is_some() {
+            let fix_button = gtk::Button::builder()
+                .label("Fix")
+                .valign(gtk::Align::Center)
+                .build();
+            let issue = issue.clone();
+            fix_button.connect_clicked(clone!(
+                #[weak]
+                view,
+                #[weak]
+                list,
+                #[weak]
+                row,
+                move |_| {
+                    view.imp().apply_fix(&issue);
+                    list.remove(&row);
+                }
+            ));
+            row.add_suffix(&fix_button);
+        }
+        list.append(&row);
+    }
+}
@@ -0,0 +1,180 @@
+/*
+* Copyright © 2025 Alessandro Balducci
+*
+* This file is part of Desktop File Editor.
+* Desktop File Editor is free software: you can redistribute it and/or modify it under the terms of the
+* GNU General Public License as published by the Free Software Foundation,
+* either version 3 of the License, or (at your option) any later version.
+* Desktop File Editor is distributed in the hope that it will be useful, but WITHOUT ANY WARRANTY;
+* without even the implied warranty of MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.
+* See the GNU General Public License for more details.
+* You should have received a copy of the GNU General Public License along with Desktop File Editor. If not, see <https://www.gnu.org/licenses/>.
+*/
+
+//! Soft suggestions on how the texts of an entry read, following the advice of the GNOME Human
+//! Interface Guidelines. Unlike the validity warnings they are only hints, and can be dismissed
+
+use std::collections::HashSet;
+
+/// A suggestion about one of the texts of the main group
+#[derive(Debug, Clone, PartialEq)]
+pub struct QualityHint {
+    /// Identifies the kind of hint, to remember that it was dismissed
+    pub id: &'static str,
+    /// Key the hint is about
+    pub key: &'static str,
+    pub message: String,
+}
+
+/// Texts of the main group the hints are about, in the locale being edited
+#[derive(Debug, Default)]
+pub struct EntryTexts<'a> {
+    pub name: Option<&'a str>,
+    pub generic_name: Option<&'a str>,
+    pub comment: Option<&'a str>,
+    pub keywords: Option<&'a str>,
+}
+
+/// Find the hints that apply to `texts`
+pub fn quality_hints(texts: &EntryTexts) -> Vec<QualityHint> {
+    let name = non_empty(texts.name);
+    let generic_name = non_empty(texts.generic_name);
+    let comment = non_empty(texts.comment);
+    let keywords = non_empty(texts.keywords);
+
+    let mut hints = Vec::new();
+
+    if let (Some(name), Some(generic_name)) = (name, generic_name) {
+        if name.to_lowercase() == generic_name.to_lowercase() {
+            hints.push(QualityHint {
+                id: "generic-name-repeats-name",
+                key: "GenericName",
+                message: "GenericName is the same as Name, it should say what kind of application \
+                          this is, e.g. “Web Browser”"
+                    .to_string(),
+            });
+        }
+    }
+
+    if let Some(keywords) = keywords {
+        let known_words: HashSet<String> =
+            name.into_iter().chain(comment).flat_map(words).collect();
+
+        let mut repeated: Vec<&str> = Vec::new();
+        for keyword in keywords.split(';').map(str::trim) {
+            let mut keyword_words = words(keyword).peekable();
+            if keyword_words.peek().is_none() || repeated.contains(&keyword) {
+                continue;
+            }
+            if keyword_words.all(|word| known_words.contains(&word)) {
+                repeated.push(keyword);
+            }
+        }
+
+        if !repeated.is_empty() {
+            hints.push(QualityHint {
+                id: "keywords-repeat-texts",
+                key: "Keywords",
+                message: format!(
+                    "Searches already match the name and comment, these keywords can be removed: \
+                     {}",
+                    repeated.join(", ")
+                ),
+            });
+        }
+    }
+
+    if let Some(comment) = comment {
+        if comment.ends_with('.') && !comment.ends_with("..") {
+            hints.push(QualityHint {
+                id: "comment-ends-with-period",
+                key: "Comment",
+                message: "Comment ends with a period, short descriptions read better without"
+                    .to_string(),
+            });
+        }
+
+        if name.is_some_and(|name| starts_with_word(comment, name)) {
+            hints.push(QualityHint {
+                id: "comment-starts-with-name",
+                key: "Comment",
+                message: "Comment starts with the name of the application, which is already shown \
+                          next to it"
+                    .to_string(),
+            });
+        }
+    }
+
+    hints
+}
+
+fn non_empty(text: Option<&str>) -> Option<&str> {
+    text.map(str::trim).filter(|text| !text.is_empty())
+}
+
+/// Lowercase words of `text`, without punctuation
+fn words(text: &str) -> impl Iterator<Item = String> + '_ {
+    text.split(|c: char| !c.is_alphanumeric())
+        .filter(|word| !word.is_empty())
+        .map(str::to_lowercase)
+}
+
+/// Whether `text` starts with all of `prefix`, ignoring case, and not just part of a word
+fn starts_with_word(text: &str, prefix: &str) -> bool {
+    let text = text.to_lowercase();
+    let prefix = prefix.to_lowercase();
+    text.strip_prefix(&prefix)
+        .is_some_and(|rest| !rest.starts_with(char::is_alphanumeric))
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn hint_ids(texts: EntryTexts) -> Vec<&'static str> {
+        quality_hints(&texts).iter().map(|hint| hint.id).collect()
+    }
+
+    #[test]
+    fn generic_name_repeats_name() {
+        let texts = EntryTexts {
+            name: Some("Files"),
+            generic_name: Some("files "),
+            ..Default::default()
+        };
+        assert_eq!(hint_ids(texts), ["generic-name-repeats-name"]);
+    }
+
+    #[test]
+    fn keywords_repeat_texts() {
+        let texts = EntryTexts {
+            name: Some("Text Editor"),
+            comment: Some("Edit text files"),
+            keywords: Some("text;Files;Plain Text;notepad;"),
+            ..Default::default()
+        };
+        let hints = quality_hints(&texts);
+        assert_eq!(hints.len(), 1);
+        assert!(hints[0].message.ends_with(": text, Files"));
+    }
+
+    #[test]
+    fn comment_punctuation() {
+        let texts = EntryTexts {
+            name: Some("Maps"),
+            comment: Some("Maps of the world."),
+            ..Default::default()
+        };
+        assert_eq!(
+            hint_ids(texts),
+            ["comment-ends-with-period", "comment-starts-with-name"]
+        );
+
+        let texts = EntryTexts {
+            name: Some("Map"),
+            comment: Some("Mapping tools..."),
+            ..Default::default()
+        };
+        assert!(hint_ids(texts).is_empty());
+    }
+}
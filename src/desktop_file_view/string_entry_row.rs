@@ -123,7 +123,41 @@ impl StringEntryRow {
         imp.locale.set(locale);
         self.notify_locale();
 
-        self.set_text(localized_text);
+        // Setting the same text would still emit a change and move the cursor
+        if self.text().as_str() != localized_text {
+            let position = self.position();
+            self.set_text(localized_text);
+            if self.has_focus() {
+                self.set_position(position.min(localized_text.chars().count() as i32));
+            }
+        }
         drop(freeze_guard);
     }
+
+    /// Show the value the entry falls back to when it has no value for the current locale. The
+    /// value is displayed greyed out as the placeholder of the row text.
+    pub fn set_inherited_value(&self, inherited: Option<(&str, Option<&str>)>) {
+        let text = match self.delegate().and_downcast::<gtk::Text>() {
+            Some(text) => text,
+            None => return,
+        };
+
+        let (placeholder, tooltip) = match inherited {
+            Some((value, from_locale)) => (Some(value), Some(inherited_from_tooltip(from_locale))),
+            None => (None, None),
+        };
+        if text.placeholder_text().as_deref() != placeholder {
+            text.set_placeholder_text(placeholder);
+        }
+        if text.tooltip_text().as_deref() != tooltip.as_deref() {
+            text.set_tooltip_text(tooltip.as_deref());
+        }
+    }
+}
+
+pub fn inherited_from_tooltip(from_locale: Option<&str>) -> String {
+    match from_locale {
+        Some(locale) => format!("Inherited from [{locale}]"),
+        None => "Inherited from the default value".to_string(),
+    }
 }
@@ -0,0 +1,381 @@
+/*
+* Copyright © 2025 Alessandro Balducci
+*
+* This file is part of Desktop File Editor.
+* Desktop File Editor is free software: you can redistribute it and/or modify it under the terms of the 
+* GNU General Public License as published by the Free Software Foundation, 
+* either version 3 of the License, or (at your option) any later version.
+* Desktop File Editor is distributed in the hope that it will be useful, but WITHOUT ANY WARRANTY;
+* without even the implied warranty of MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.
+* See the GNU General Public License for more details.
+* You should have received a copy of the GNU General Public License along with Desktop File Editor. If not, see <https://www.gnu.org/licenses/>.
+*/
+
+use adw::subclass::prelude::ObjectSubclassIsExt;
+use gtk::{gio, glib};
+
+mod imp {
+    use std::cell::{Cell, RefCell};
+
+    use adw::{prelude::*, subclass::prelude::*};
+    use gtk::{
+        gdk::{Key, ModifierType},
+        gio,
+        glib::{self, clone, Propagation, Properties},
+        EventControllerFocus, EventControllerKey, Label, ListItem, PropagationPhase,
+        SignalListItemFactory,
+    };
+
+    #[derive(Default, Properties)]
+    #[properties(wrapper_type = super::Suggestion)]
+    pub struct Suggestion {
+        /// Text put in the entry when the suggestion is picked
+        #[property(get, set, construct_only)]
+        value: RefCell<String>,
+
+        #[property(get, set, construct_only)]
+        title: RefCell<String>,
+
+        #[property(get, set, construct_only, nullable)]
+        description: RefCell<Option<String>>,
+    }
+
+    #[glib::object_subclass]
+    impl ObjectSubclass for Suggestion {
+        const NAME: &'static str = "DMSuggestion";
+        type Type = super::Suggestion;
+    }
+
+    #[glib::derived_properties]
+    impl ObjectImpl for Suggestion {}
+
+    #[derive(Default, Properties)]
+    #[properties(wrapper_type = super::SuggestionEntry)]
+    pub struct SuggestionEntry {
+        pub entry: gtk::Entry,
+        popover: gtk::Popover,
+        list_view: gtk::ListView,
+        selection: gtk::SingleSelection,
+        pub filter_model: gtk::FilterListModel,
+        filter: gtk::CustomFilter,
+
+        /// Number of characters to type before suggestions are shown
+        #[property(get, set)]
+        minimum_key_length: Cell<u32>,
+
+        /// The text is being set from a suggestion, which must not show the suggestions again
+        picking: Cell<bool>,
+    }
+
+    #[glib::object_subclass]
+    impl ObjectSubclass for SuggestionEntry {
+        const NAME: &'static str = "DMSuggestionEntry";
+        type Type = super::SuggestionEntry;
+        type ParentType = gtk::Widget;
+    }
+
+    #[glib::derived_properties]
+    impl ObjectImpl for SuggestionEntry {
+        fn constructed(&self) {
+            self.parent_constructed();
+            let obj = self.obj();
+
+            self.filter.set_filter_func(clone!(
+                #[weak(rename_to = entry)]
+                self.entry,
+                #[upgrade_or]
+                false,
+                move |item| {
+                    let suggestion = item
+                        .downcast_ref::<super::Suggestion>()
+                        .expect("The item is not a suggestion");
+                    suggestion.matches(&entry.text())
+                }
+            ));
+            self.filter_model
+                .set_model(Some(&gio::ListStore::new::<super::Suggestion>()));
+            self.filter_model.set_filter(Some(&self.filter));
+
+            self.selection.set_model(Some(&self.filter_model));
+            self.selection.set_autoselect(false);
+            self.selection.set_can_unselect(true);
+            self.selection.set_selected(gtk::INVALID_LIST_POSITION);
+
+            let factory = SignalListItemFactory::new();
+            factory.connect_setup(|_, list_item| {
+                let list_item = list_item
+                    .downcast_ref::<ListItem>()
+                    .expect("Should be ListItem");
+
+                let title = Label::builder()
+                    .halign(gtk::Align::Start)
+                    .ellipsize(gtk::pango::EllipsizeMode::End)
+                    .build();
+                let description = Label::builder()
+                    .halign(gtk::Align::Start)
+                    .ellipsize(gtk::pango::EllipsizeMode::End)
+                    .css_classes(["caption", "dim-label"])
+                    .build();
+
+                let row = gtk::Box::builder()
+                    .orientation(gtk::Orientation::Vertical)
+                    .margin_top(3)
+                    .margin_bottom(3)
+                    .margin_start(6)
+                    .margin_end(6)
+                    .build();
+                row.append(&title);
+                row.append(&description);
+                list_item.set_child(Some(&row));
+            });
+            factory.connect_bind(|_, list_item| {
+                let list_item = list_item
+                    .downcast_ref::<ListItem>()
+                    .expect("Should be ListItem");
+                let suggestion = list_item
+                    .item()
+                    .and_downcast::<super::Suggestion>()
+                    .expect("The item is not a suggestion");
+                let row = list_item.child().expect("The row has not been set up");
+                let title = row
+                    .first_child()
+                    .and_downcast::<Label>()
+                    .expect("The title is not a label");
+                let description = row
+                    .last_child()
+                    .and_downcast::<Label>()
+                    .expect("The description is not a label");
+
+                title.set_label(&suggestion.title());
+                description.set_label(&suggestion.description().unwrap_or_default());
+                description.set_visible(suggestion.description().is_some());
+            });
+
+            self.list_view.set_model(Some(&self.selection));
+            self.list_view.set_factory(Some(&factory));
+            self.list_view.set_single_click_activate(true);
+            self.list_view.add_css_class("navigation-sidebar");
+            self.list_view.connect_activate(clone!(
+                #[weak(rename_to = this)]
+                self,
+                move |_, position| this.pick(position)
+            ));
+
+            let scrolled_window = gtk::ScrolledWindow::builder()
+                .hscrollbar_policy(gtk::PolicyType::Never)
+                .max_content_height(240)
+                .propagate_natural_height(true)
+                .child(&self.list_view)
+                .build();
+
+            // The focus stays in the entry, suggestions are navigated with the arrow keys
+            self.popover.set_child(Some(&scrolled_window));
+            self.popover.set_autohide(false);
+            self.popover.set_has_arrow(false);
+            self.popover.set_can_focus(false);
+            self.popover.set_position(gtk::PositionType::Bottom);
+            self.popover.set_halign(gtk::Align::Start);
+
+            self.entry.connect_changed(clone!(
+                #[weak(rename_to = this)]
+                self,
+                move |_| this.on_entry_changed()
+            ));
+
+            let key_controller = EventControllerKey::new();
+            key_controller.set_propagation_phase(PropagationPhase::Capture);
+            key_controller.connect_key_pressed(clone!(
+                #[weak(rename_to = this)]
+                self,
+                #[upgrade_or]
+                Propagation::Proceed,
+                move |_, key, _, modifier| this.on_key_pressed(key, modifier)
+            ));
+            self.entry.add_controller(key_controller);
+
+            let focus_controller = EventControllerFocus::new();
+            focus_controller.connect_leave(clone!(
+                #[weak(rename_to = popover)]
+                self.popover,
+                move |_| popover.popdown()
+            ));
+            self.entry.add_controller(focus_controller);
+
+            self.entry.set_parent(&*obj);
+            self.popover.set_parent(&*obj);
+        }
+
+        fn dispose(&self) {
+            self.entry.unparent();
+            self.popover.unparent();
+        }
+    }
+
+    impl WidgetImpl for SuggestionEntry {
+        fn measure(&self, orientation: gtk::Orientation, for_size: i32) -> (i32, i32, i32, i32) {
+            self.entry.measure(orientation, for_size)
+        }
+
+        fn size_allocate(&self, width: i32, height: i32, baseline: i32) {
+            self.entry.allocate(width, height, baseline, None);
+
+            // Suggestions are as wide as the entry they complete
+            if self.popover.width_request() != width {
+                self.popover.set_width_request(width);
+            }
+            self.popover.present();
+        }
+
+        fn grab_focus(&self) -> bool {
+            self.entry.grab_focus()
+        }
+
+        fn unmap(&self) {
+            self.popover.popdown();
+            self.parent_unmap();
+        }
+    }
+
+    impl SuggestionEntry {
+        fn on_entry_changed(&self) {
+            if self.picking.get() {
+                return;
+            }
+
+            self.filter.changed(gtk::FilterChange::Different);
+            self.selection.set_selected(gtk::INVALID_LIST_POSITION);
+            self.update_popover();
+        }
+
+        fn update_popover(&self) {
+            let long_enough =
+                self.entry.text().chars().count() >= self.minimum_key_length.get() as usize;
+            // The focus is actually in the text widget inside the entry
+            let focused = self
+                .entry
+                .state_flags()
+                .contains(gtk::StateFlags::FOCUS_WITHIN);
+            if long_enough && focused && self.filter_model.n_items() > 0 {
+                self.popover.popup();
+            } else {
+                self.popover.popdown();
+            }
+        }
+
+        fn on_key_pressed(&self, key: Key, modifier: ModifierType) -> Propagation {
+            if !modifier.is_empty() {
+                return Propagation::Proceed;
+            }
+
+            let n_items = self.filter_model.n_items();
+            let selected = self.selection.selected();
+            let shown = self.popover.is_visible();
+
+            match key {
+                Key::Down | Key::Up if !shown => {
+                    self.update_popover();
+                    Propagation::Stop
+                }
+                Key::Down | Key::Up if n_items > 0 => {
+                    let next = match (key, selected) {
+                        (Key::Down, gtk::INVALID_LIST_POSITION) => 0,
+                        (Key::Down, _) => (selected + 1) % n_items,
+                        (_, gtk::INVALID_LIST_POSITION) | (_, 0) => n_items - 1,
+                        _ => selected - 1,
+                    };
+                    self.selection.set_selected(next);
+                    self.list_view
+                        .scroll_to(next, gtk::ListScrollFlags::NONE, None);
+                    Propagation::Stop
+                }
+                Key::Return | Key::KP_Enter | Key::ISO_Enter
+                    if shown && selected != gtk::INVALID_LIST_POSITION =>
+                {
+                    self.pick(selected);
+                    Propagation::Stop
+                }
+                // Closes the suggestions rather than the dialog the entry is in
+                Key::Escape if shown => {
+                    self.popover.popdown();
+                    Propagation::Stop
+                }
+                _ => Propagation::Proceed,
+            }
+        }
+
+        fn pick(&self, position: u32) {
+            let Some(suggestion) = self
+                .filter_model
+                .item(position)
+                .and_downcast::<super::Suggestion>()
+            else {
+                return;
+            };
+
+            self.picking.set(true);
+            self.entry.set_text(&suggestion.value());
+            self.entry.set_position(-1);
+            self.picking.set(false);
+            self.popover.popdown();
+        }
+    }
+}
+
+glib::wrapper! {
+    pub struct Suggestion(ObjectSubclass<imp::Suggestion>);
+}
+
+impl Suggestion {
+    pub fn new(value: &str, title: &str, description: Option<&str>) -> Self {
+        glib::Object::builder()
+            .property("value", value)
+            .property("title", title)
+            .property("description", description)
+            .build()
+    }
+
+    /// Whether every word of `query` appears in the suggestion, ignoring case
+    fn matches(&self, query: &str) -> bool {
+        let haystack = format!(
+            "{} {} {}",
+            self.value(),
+            self.title(),
+            self.description().unwrap_or_default()
+        )
+        .to_lowercase();
+        query
+            .to_lowercase()
+            .split_whitespace()
+            .all(|word| haystack.contains(word))
+    }
+}
+
+glib::wrapper! {
+    /// Entry showing suggestions for its text in a popover below it, picked with the arrow keys
+    /// and Enter or by clicking them
+    pub struct SuggestionEntry(ObjectSubclass<imp::SuggestionEntry>)
+        @extends gtk::Widget,
+        @implements gtk::Accessible, gtk::Buildable, gtk::ConstraintTarget;
+}
+
+impl SuggestionEntry {
+    pub fn new() -> Self {
+        glib::Object::builder().build()
+    }
+
+    pub fn entry(&self) -> gtk::Entry {
+        self.imp().entry.clone()
+    }
+
+    pub fn set_suggestions(&self, suggestions: &[Suggestion]) {
+        let store = gio::ListStore::new::<Suggestion>();
+        store.extend_from_slice(suggestions);
+        self.imp().filter_model.set_model(Some(&store));
+    }
+}
+
+impl Default for SuggestionEntry {
+    fn default() -> Self {
+        Self::new()
+    }
+}
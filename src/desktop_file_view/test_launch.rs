@@ -0,0 +1,160 @@
+/*
+* Copyright © 2025 Alessandro Balducci
+*
+* This file is part of Desktop File Editor.
+* Desktop File Editor is free software: you can redistribute it and/or modify it under the terms of the
+* GNU General Public License as published by the Free Software Foundation,
+* either version 3 of the License, or (at your option) any later version.
+* Desktop File Editor is distributed in the hope that it will be useful, but WITHOUT ANY WARRANTY;
+* without even the implied warranty of MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.
+* See the GNU General Public License for more details.
+* You should have received a copy of the GNU General Public License along with Desktop File Editor. If not, see <https://www.gnu.org/licenses/>.
+*/
+
+//! Turning the Exec key into a command line that can be run to try the entry out
+
+use std::path::Path;
+
+use crate::shellparse;
+
+/// Field codes that expand to files or URLs, test launches don't pass any
+const FILE_FIELD_CODES: [&str; 4] = ["%f", "%F", "%u", "%U"];
+
+/// Field codes that were removed from the specification, they expand to nothing
+const DEPRECATED_FIELD_CODES: [&str; 6] = ["%d", "%D", "%n", "%N", "%v", "%m"];
+
+/// Arguments to run the `exec` command line of an entry with no files, expanding the field codes
+/// as a launcher would. Returns `None` if there is no command to run
+pub fn launch_argv(
+    exec: &str,
+    name: Option<&str>,
+    icon: Option<&str>,
+    desktop_file: &Path,
+) -> Option<Vec<String>> {
+    let command = shellparse::parse(exec)?;
+    if command.command.is_empty() {
+        return None;
+    }
+
+    let mut argv = Vec::new();
+    if !command.variables.is_empty() {
+        argv.push("env".to_string());
+        argv.extend(
+            command
+                .variables
+                .iter()
+                .map(|(var, value)| format!("{var}={value}")),
+        );
+    }
+
+    let desktop_file = desktop_file.to_string_lossy();
+    for arg in std::iter::once(&command.command).chain(command.args.iter()) {
+        if FILE_FIELD_CODES.contains(&arg.as_str()) || DEPRECATED_FIELD_CODES.contains(&arg.as_str())
+        {
+            continue;
+        }
+
+        if arg == "%i" {
+            if let Some(icon) = icon.filter(|icon| !icon.is_empty()) {
+                argv.push("--icon".to_string());
+                argv.push(icon.to_string());
+            }
+            continue;
+        }
+
+        argv.push(expand_field_codes(
+            arg,
+            name.unwrap_or_default(),
+            &desktop_file,
+        ));
+    }
+
+    Some(argv)
+}
+
+/// Adds environment variables to a command line built by [`launch_argv`], reusing its `env`
+/// prefix if there is one
+pub fn with_environment(mut argv: Vec<String>, variables: &[(&str, &str)]) -> Vec<String> {
+    if variables.is_empty() {
+        return argv;
+    }
+
+    if argv.first().map(String::as_str) != Some("env") {
+        argv.insert(0, "env".to_string());
+    }
+    argv.splice(
+        1..1,
+        variables.iter().map(|(var, value)| format!("{var}={value}")),
+    );
+    argv
+}
+
+/// Expands the field codes that may appear inside an argument
+fn expand_field_codes(arg: &str, name: &str, desktop_file: &str) -> String {
+    let mut expanded = String::with_capacity(arg.len());
+    let mut chars = arg.chars();
+    while let Some(c) = chars.next() {
+        if c != '%' {
+            expanded.push(c);
+            continue;
+        }
+
+        match chars.next() {
+            Some('%') => expanded.push('%'),
+            Some('c') => expanded.push_str(name),
+            Some('k') => expanded.push_str(desktop_file),
+            // Other codes are either invalid here or expand to nothing
+            Some(_) => (),
+            None => expanded.push('%'),
+        }
+    }
+    expanded
+}
+
+#[cfg(test)]
+mod test {
+    use std::path::Path;
+
+    use super::{launch_argv, with_environment};
+
+    fn argv(exec: &str) -> Vec<String> {
+        launch_argv(exec, Some("Editor"), Some("editor"), Path::new("/a/editor.desktop")).unwrap()
+    }
+
+    #[test]
+    fn drops_file_codes() {
+        assert_eq!(argv("gedit %U"), ["gedit"]);
+        assert_eq!(argv("gedit --new-window %f"), ["gedit", "--new-window"]);
+    }
+
+    #[test]
+    fn expands_codes() {
+        assert_eq!(
+            argv("run %i --title=%c %k 100%%"),
+            ["run", "--icon", "editor", "--title=Editor", "/a/editor.desktop", "100%"]
+        );
+    }
+
+    #[test]
+    fn variables_use_env() {
+        assert_eq!(argv("FOO=1 gedit"), ["env", "FOO=1", "gedit"]);
+    }
+
+    #[test]
+    fn adds_environment() {
+        let startup_id = [("DESKTOP_STARTUP_ID", "id")];
+        assert_eq!(
+            with_environment(argv("gedit"), &startup_id),
+            ["env", "DESKTOP_STARTUP_ID=id", "gedit"]
+        );
+        assert_eq!(
+            with_environment(argv("FOO=1 gedit"), &startup_id),
+            ["env", "DESKTOP_STARTUP_ID=id", "FOO=1", "gedit"]
+        );
+    }
+
+    #[test]
+    fn empty_exec() {
+        assert!(launch_argv("", None, None, Path::new("a.desktop")).is_none());
+    }
+}
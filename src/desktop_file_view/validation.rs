@@ -0,0 +1,524 @@
+/*
+* Copyright © 2025 Alessandro Balducci
+*
+* This file is part of Desktop File Editor.
+* Desktop File Editor is free software: you can redistribute it and/or modify it under the terms of the
+* GNU General Public License as published by the Free Software Foundation,
+* either version 3 of the License, or (at your option) any later version.
+* Desktop File Editor is distributed in the hope that it will be useful, but WITHOUT ANY WARRANTY;
+* without even the implied warranty of MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.
+* See the GNU General Public License for more details.
+* You should have received a copy of the GNU General Public License along with Desktop File Editor. If not, see <https://www.gnu.org/licenses/>.
+*/
+
+use freedesktop_desktop_entry::DesktopEntry;
+
+use super::categories::category_issue;
+use super::desktop_entry_ext::split_locale;
+use super::known_entries::{key_info, GroupKind, ValueType};
+use crate::settings::LintProfile;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Severity {
+    /// Desktops might refuse the entry, or not launch it
+    Error,
+    /// Allowed, but discouraged by the specification
+    Warning,
+}
+
+/// A way the entry does not follow the Desktop Entry Specification
+#[derive(Debug, Clone, PartialEq)]
+pub struct Issue {
+    pub severity: Severity,
+    pub group: String,
+    pub key: Option<String>,
+    pub message: String,
+    /// How to correct the issue automatically, when there is an obvious way
+    pub fix: Option<Fix>,
+}
+
+/// Value to write for the key of an issue to correct it
+#[derive(Debug, Clone, PartialEq)]
+pub struct Fix {
+    pub locale: Option<String>,
+    pub value: String,
+}
+
+impl Issue {
+    fn new(severity: Severity, group: &str, key: Option<&str>, message: String) -> Self {
+        Self {
+            severity,
+            group: group.to_string(),
+            key: key.map(String::from),
+            message,
+            fix: None,
+        }
+    }
+
+    fn with_fix(mut self, locale: Option<&str>, value: String) -> Self {
+        self.fix = Some(Fix {
+            locale: locale.map(String::from),
+            value,
+        });
+        self
+    }
+}
+
+/// Check the entry against the Desktop Entry Specification: the keys each Type requires, boolean
+/// values, the field codes of Exec and the registered Categories. The guidelines of a desktop
+/// are checked as well, depending on the profile. `contents` is the text the entry is written as,
+/// for what decoding the entry loses
+pub fn validate(entry: &DesktopEntry, contents: &str, profile: LintProfile) -> Vec<Issue> {
+    let mut issues = spec_issues(entry);
+    issues.extend(whitespace_issues(contents));
+    match profile {
+        LintProfile::Spec => {}
+        LintProfile::Gnome => issues.extend(gnome_issues(entry)),
+        LintProfile::Kde => issues.extend(kde_issues(entry)),
+    }
+    issues
+}
+
+fn spec_issues(entry: &DesktopEntry) -> Vec<Issue> {
+    let mut issues = Vec::new();
+    let main_group = "Desktop Entry";
+    let Some(main) = entry.groups.0.get(main_group) else {
+        let message = "The Desktop Entry group is missing".to_string();
+        issues.push(Issue::new(Severity::Error, main_group, None, message));
+        return issues;
+    };
+    let value = |key: &str| {
+        main.0
+            .get(key)
+            .map(|(value, _)| value.trim())
+            .filter(|value| !value.is_empty())
+    };
+    let mut error = |key: &str, message: String| {
+        issues.push(Issue::new(Severity::Error, main_group, Some(key), message));
+    };
+
+    match value("Type") {
+        None => error("Type", "Type is required".to_string()),
+        Some(kind) => {
+            if let Some(reason) = key_info("Type").and_then(|info| info.check(kind)) {
+                error("Type", reason);
+            }
+        }
+    }
+    if value("Name").is_none() {
+        error("Name", "Name is required".to_string());
+    }
+    match value("Type") {
+        Some("Application")
+            if value("Exec").is_none() && value("DBusActivatable") != Some("true") =>
+        {
+            let message = "Applications need an Exec command, unless they are D-Bus activatable";
+            error("Exec", message.to_string());
+        }
+        Some("Link") if value("URL").is_none() => {
+            error("URL", "Links need a URL".to_string());
+        }
+        _ => {}
+    }
+
+    for (group_name, group) in &entry.groups.0 {
+        if GroupKind::of(group_name) == GroupKind::Other {
+            continue;
+        }
+        for (key, (value, _)) in &group.0 {
+            let is_boolean =
+                key_info(key).is_some_and(|info| info.value_type == ValueType::Boolean);
+            if is_boolean && value != "true" && value != "false" {
+                let message = format!("{key} must be true or false, not {value}");
+                let issue = Issue::new(Severity::Error, group_name, Some(key), message);
+                // Only the lowercase spelling is valid
+                let lowercase = value.to_ascii_lowercase();
+                issues.push(if lowercase == "true" || lowercase == "false" {
+                    issue.with_fix(None, lowercase)
+                } else {
+                    issue
+                });
+            }
+        }
+        if let Some((exec, _)) = group.0.get("Exec") {
+            issues.extend(
+                field_code_issues(exec)
+                    .into_iter()
+                    .map(|(severity, message)| {
+                        Issue::new(severity, group_name, Some("Exec"), message)
+                    }),
+            );
+        }
+    }
+
+    let categories = value("Categories").unwrap_or_default();
+    for category in categories.split(';').map(str::trim) {
+        if category.is_empty() {
+            continue;
+        }
+        let Some(message) = category_issue(category) else {
+            continue;
+        };
+        issues.push(Issue::new(
+            Severity::Warning,
+            main_group,
+            Some("Categories"),
+            message,
+        ));
+    }
+
+    let actions = value("Actions").unwrap_or_default();
+    for action in actions.split(';').filter(|action| !action.is_empty()) {
+        let group_name = format!("Desktop Action {action}");
+        let name = entry.entry(&group_name, "Name", None);
+        if !entry.groups.0.contains_key(&group_name) {
+            let message = format!("The {action} action has no {group_name} group");
+            issues.push(Issue::new(
+                Severity::Warning,
+                main_group,
+                Some("Actions"),
+                message,
+            ));
+        } else if name.filter(|name| !name.is_empty()).is_none() {
+            let message = format!("The {action} action needs a Name");
+            issues.push(Issue::new(
+                Severity::Error,
+                &group_name,
+                Some("Name"),
+                message,
+            ));
+        }
+    }
+
+    issues.extend(value_issues(entry));
+    issues
+}
+
+/// Backslashes used as path separators in the keys that hold paths
+fn value_issues(entry: &DesktopEntry) -> Vec<Issue> {
+    let mut issues = Vec::new();
+    for (group_name, group) in &entry.groups.0 {
+        let known_group = GroupKind::of(group_name) != GroupKind::Other;
+        for (key, (value, localized)) in &group.0 {
+            let localized = localized
+                .iter()
+                .map(|(locale, value)| (Some(&locale[..]), value));
+            for (locale, value) in std::iter::once((None, value)).chain(localized) {
+                let shown_key = match locale {
+                    Some(locale) => format!("{key}[{locale}]"),
+                    None => key.to_string(),
+                };
+                if !known_group || !matches!(&key[..], "Exec" | "Path" | "Icon") {
+                    continue;
+                }
+                if let Some(fixed) = forward_slashes(value) {
+                    let message =
+                        format!("{shown_key} uses backslashes as path separators, write / instead");
+                    issues.push(
+                        Issue::new(Severity::Warning, group_name, Some(key), message)
+                            .with_fix(locale, fixed),
+                    );
+                }
+            }
+        }
+    }
+    issues
+}
+
+/// Values ending with whitespace in the desktop file `contents`. Decoding trims the values, so
+/// the whitespace only shows in the text
+fn whitespace_issues(contents: &str) -> Vec<Issue> {
+    let mut issues = Vec::new();
+    let mut group: Option<&str> = None;
+
+    for line in contents.lines() {
+        let trimmed = line.trim();
+        if trimmed.is_empty() || trimmed.starts_with('#') {
+            continue;
+        }
+
+        if trimmed.starts_with('[') && trimmed.ends_with(']') {
+            group = Some(&trimmed[1..trimmed.len() - 1]);
+            continue;
+        }
+
+        let (Some(group), Some((key_text, value))) = (group, line.split_once('=')) else {
+            continue;
+        };
+        let value = value.trim_start();
+        if value.trim_end().len() == value.len() {
+            continue;
+        }
+
+        let key_text = key_text.trim();
+        let (key, locale) = split_locale(key_text);
+        let message = format!("{key_text} ends with whitespace");
+        issues.push(
+            Issue::new(Severity::Warning, group, Some(key), message)
+                .with_fix(locale, value.trim_end().to_string()),
+        );
+    }
+    issues
+}
+
+/// `value` with the backslashes that separate the parts of a path replaced by slashes, `None`
+/// when there are none. Backslashes escaping a character, as in `\\` or `\"`, are kept
+fn forward_slashes(value: &str) -> Option<String> {
+    let mut result = String::with_capacity(value.len());
+    let mut replaced = false;
+    let mut chars = value.chars().peekable();
+    while let Some(c) = chars.next() {
+        if c != '\\' {
+            result.push(c);
+            continue;
+        }
+        match chars.peek() {
+            Some(next) if next.is_alphanumeric() => {
+                result.push('/');
+                replaced = true;
+            }
+            Some(_) => {
+                result.push(c);
+                result.extend(chars.next());
+            }
+            None => result.push(c),
+        }
+    }
+    replaced.then_some(result)
+}
+
+/// Value of a key of the main group, `None` when missing or empty
+fn main_value<'a>(entry: &'a DesktopEntry, key: &str) -> Option<&'a str> {
+    entry
+        .entry("Desktop Entry", key, None)
+        .map(str::trim)
+        .filter(|value| !value.is_empty())
+}
+
+/// The GNOME Human Interface Guidelines want applications to describe themselves and to be named
+/// after their application ID, icon included
+fn gnome_issues(entry: &DesktopEntry) -> Vec<Issue> {
+    let mut issues = Vec::new();
+    if main_value(entry, "Type") != Some("Application") {
+        return issues;
+    }
+    let mut warning = |key: &str, message: String| {
+        issues.push(Issue::new(
+            Severity::Warning,
+            "Desktop Entry",
+            Some(key),
+            message,
+        ));
+    };
+
+    if main_value(entry, "Comment").is_none() {
+        warning(
+            "Comment",
+            "Applications should have a Comment describing what they do".to_string(),
+        );
+    }
+
+    // Only reverse domain names are application IDs, other file names can't be expected to match
+    let app_id = entry.appid.as_str();
+    let is_app_id = app_id.split('.').count() >= 3;
+    match main_value(entry, "Icon") {
+        None => warning("Icon", "Applications should have an Icon".to_string()),
+        Some(icon) if is_app_id && icon != app_id => warning(
+            "Icon",
+            format!("The icon should be named after the application ID, {app_id}"),
+        ),
+        Some(_) => {}
+    }
+    issues
+}
+
+/// Keys KDE reads as booleans
+const KDE_BOOLEAN_KEYS: [&str; 4] = [
+    "X-KDE-RunOnDiscreteGpu",
+    "X-KDE-SubstituteUID",
+    "X-KDE-StartupNotify",
+    "X-KDE-Wayland-VirtualKeyboard",
+];
+
+/// Devices listed in X-KDE-FormFactors
+const KDE_FORM_FACTORS: [&str; 4] = ["desktop", "tablet", "handset", "mediacenter"];
+
+/// KDE asks for a GenericName and reads a few keys of its own, which are checked here
+fn kde_issues(entry: &DesktopEntry) -> Vec<Issue> {
+    let mut issues = Vec::new();
+    let mut push = |severity: Severity, key: &str, message: String| {
+        issues.push(Issue::new(severity, "Desktop Entry", Some(key), message));
+    };
+
+    if main_value(entry, "Type") == Some("Application")
+        && main_value(entry, "GenericName").is_none()
+    {
+        push(
+            Severity::Warning,
+            "GenericName",
+            "Applications should have a GenericName, KDE menus show it next to the Name"
+                .to_string(),
+        );
+    }
+
+    for key in KDE_BOOLEAN_KEYS {
+        if let Some(value) = main_value(entry, key).filter(|v| *v != "true" && *v != "false") {
+            push(
+                Severity::Error,
+                key,
+                format!("{key} must be true or false, not {value}"),
+            );
+        }
+    }
+
+    if main_value(entry, "X-KDE-Username").is_some()
+        && main_value(entry, "X-KDE-SubstituteUID") != Some("true")
+    {
+        push(
+            Severity::Warning,
+            "X-KDE-Username",
+            "X-KDE-Username is ignored unless X-KDE-SubstituteUID is true".to_string(),
+        );
+    }
+
+    let form_factors = main_value(entry, "X-KDE-FormFactors").unwrap_or_default();
+    for form_factor in form_factors.split(';').map(str::trim) {
+        if !form_factor.is_empty() && !KDE_FORM_FACTORS.contains(&form_factor) {
+            push(
+                Severity::Warning,
+                "X-KDE-FormFactors",
+                format!("{form_factor} is not a form factor known to KDE"),
+            );
+        }
+    }
+    issues
+}
+
+/// Problems with the field codes of an Exec command line
+fn field_code_issues(exec: &str) -> Vec<(Severity, String)> {
+    let mut issues = Vec::new();
+    let mut file_codes = 0;
+
+    let mut chars = exec.chars();
+    while let Some(c) = chars.next() {
+        if c != '%' {
+            continue;
+        }
+        match chars.next() {
+            Some('%' | 'i' | 'c' | 'k') => {}
+            Some('f' | 'F' | 'u' | 'U') => file_codes += 1,
+            Some(code @ ('d' | 'D' | 'n' | 'N' | 'v' | 'm')) => issues.push((
+                Severity::Warning,
+                format!("The %{code} field code is deprecated"),
+            )),
+            Some(code) => issues.push((
+                Severity::Error,
+                format!("%{code} is not a field code, write %% for a percent sign"),
+            )),
+            None => issues.push((
+                Severity::Error,
+                "Exec ends with a lone %, write %% for a percent sign".to_string(),
+            )),
+        }
+    }
+
+    if file_codes > 1 {
+        issues.push((
+            Severity::Error,
+            "Exec can only take one of the %f, %F, %u and %U field codes".to_string(),
+        ));
+    }
+    issues
+}
+
+#[cfg(test)]
+mod test {
+    use super::super::desktop_entry_ext::{DesktopEntryExt, KEYMAP_ORDER};
+    use super::*;
+
+    fn issues(input: &str) -> Vec<String> {
+        profile_issues(input, LintProfile::Spec)
+    }
+
+    fn profile_issues(input: &str, profile: LintProfile) -> Vec<String> {
+        let path = "/tmp/org.example.App.desktop";
+        let entry = DesktopEntry::from_str(path, input, None::<&[&str]>).unwrap();
+        validate(&entry, input, profile)
+            .into_iter()
+            .map(|issue| issue.message)
+            .collect()
+    }
+
+    #[test]
+    fn required_keys() {
+        assert_eq!(
+            issues("[Desktop Entry]\nType=Application\nName=App\n"),
+            ["Applications need an Exec command, unless they are D-Bus activatable"]
+        );
+        assert_eq!(
+            issues("[Desktop Entry]\nType=Link\n"),
+            ["Name is required", "Links need a URL"]
+        );
+    }
+
+    #[test]
+    fn profiles() {
+        let input = "[Desktop Entry]\nType=Application\nName=App\nExec=app\nIcon=app\n";
+        assert!(issues(input).is_empty());
+        assert_eq!(
+            profile_issues(input, LintProfile::Gnome),
+            [
+                "Applications should have a Comment describing what they do",
+                "The icon should be named after the application ID, org.example.App",
+            ]
+        );
+        assert_eq!(profile_issues(input, LintProfile::Kde).len(), 1);
+
+        let input = format!("{input}X-KDE-SubstituteUID=yes\nX-KDE-FormFactors=desktop;watch\n");
+        assert_eq!(profile_issues(&input, LintProfile::Kde).len(), 3);
+    }
+
+    #[test]
+    fn field_codes() {
+        assert!(field_code_issues("app %U --name=100%%").is_empty());
+        assert_eq!(field_code_issues("app %f %u").len(), 1);
+        assert_eq!(field_code_issues("app %x %").len(), 2);
+        assert_eq!(field_code_issues("app %m")[0].0, Severity::Warning);
+    }
+
+    #[test]
+    fn value_slips() {
+        let path = "/tmp/org.example.App.desktop";
+        let input = "[Desktop Entry]\nType=Application\nName=App \nExec=C:\\Apps\\app.exe\n\
+                     Terminal=False\n";
+        let entry = DesktopEntry::from_str(path, input, None::<&[&str]>).unwrap();
+        let issues = validate(&entry, input, LintProfile::Spec);
+        let fixes: Vec<(String, String)> = issues
+            .iter()
+            .filter_map(|issue| Some((issue.key.clone()?, issue.fix.clone()?.value)))
+            .collect();
+        assert!(fixes.contains(&("Name".to_string(), "App".to_string())));
+        assert!(fixes.contains(&("Terminal".to_string(), "false".to_string())));
+        assert!(fixes.contains(&("Exec".to_string(), "C:/Apps/app.exe".to_string())));
+
+        // The fix has to reach the file when the formatting is preserved
+        let whitespace = issues
+            .iter()
+            .find(|issue| issue.message == "Name ends with whitespace")
+            .unwrap();
+        let mut entry = entry;
+        let fix = whitespace.fix.clone().unwrap();
+        entry.set_value("Desktop Entry", "Name", None, fix.value);
+        let output = entry.to_minimal_diff_string(input, &[], &KEYMAP_ORDER);
+        assert!(output.contains("\nName=App\n"));
+        assert!(!validate(&entry, &output, LintProfile::Spec)
+            .iter()
+            .any(|issue| issue.message.ends_with("whitespace")));
+
+        assert_eq!(forward_slashes(r#"sh -c "echo \"\$HOME\"""#), None);
+        assert_eq!(
+            forward_slashes(r"icons\app.png").as_deref(),
+            Some("icons/app.png")
+        );
+    }
+}
@@ -0,0 +1,184 @@
+/*
+* Copyright © 2025 Alessandro Balducci
+*
+* This file is part of Desktop File Editor.
+* Desktop File Editor is free software: you can redistribute it and/or modify it under the terms of the
+* GNU General Public License as published by the Free Software Foundation,
+* either version 3 of the License, or (at your option) any later version.
+* Desktop File Editor is distributed in the hope that it will be useful, but WITHOUT ANY WARRANTY;
+* without even the implied warranty of MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.
+* See the GNU General Public License for more details.
+* You should have received a copy of the GNU General Public License along with Desktop File Editor. If not, see <https://www.gnu.org/licenses/>.
+*/
+
+//! Installation of launchers through the DynamicLauncher portal, the sandbox-friendly way of
+//! creating desktop files on the host from inside a Flatpak.
+
+use std::collections::HashMap;
+
+use gtk::glib;
+use zbus::{
+    export::futures_util::StreamExt,
+    proxy,
+    zvariant::{OwnedObjectPath, OwnedValue, Structure, Value},
+    Connection,
+};
+
+use crate::APP_ID;
+
+/// `launcher_type` option value for launchers of regular applications
+const LAUNCHER_TYPE_APPLICATION: u32 = 1;
+
+#[proxy(
+    interface = "org.freedesktop.portal.DynamicLauncher",
+    default_service = "org.freedesktop.portal.Desktop",
+    default_path = "/org/freedesktop/portal/desktop"
+)]
+trait DynamicLauncher {
+    #[zbus(property)]
+    fn version(&self) -> zbus::Result<u32>;
+
+    fn prepare_install(
+        &self,
+        parent_window: &str,
+        name: &str,
+        icon: Value<'_>,
+        options: HashMap<&str, Value<'_>>,
+    ) -> zbus::Result<OwnedObjectPath>;
+
+    fn request_install_token(
+        &self,
+        name: &str,
+        icon: Value<'_>,
+        options: HashMap<&str, Value<'_>>,
+    ) -> zbus::Result<String>;
+
+    fn install(
+        &self,
+        token: &str,
+        desktop_file_id: &str,
+        desktop_entry: &str,
+        options: HashMap<&str, Value<'_>>,
+    ) -> zbus::Result<()>;
+}
+
+#[proxy(
+    interface = "org.freedesktop.portal.Request",
+    default_service = "org.freedesktop.portal.Desktop"
+)]
+trait Request {
+    #[zbus(signal)]
+    fn response(&self, response: u32, results: HashMap<String, OwnedValue>) -> zbus::Result<()>;
+}
+
+/// A launcher confirmed by the user through the portal
+pub struct Installed {
+    /// Name picked by the user, it may differ from the requested one
+    pub name: String,
+    pub desktop_file_id: String,
+}
+
+/// Whether the portal implementation on the host provides the DynamicLauncher interface
+pub async fn is_available() -> bool {
+    let Ok(connection) = Connection::session().await else {
+        return false;
+    };
+    match DynamicLauncherProxy::new(&connection).await {
+        Ok(proxy) => proxy.version().await.is_ok(),
+        Err(_) => false,
+    }
+}
+
+/// Desktop file id for a launcher, the portal only accepts ids prefixed by the app id
+pub fn desktop_file_id(stem: &str) -> String {
+    format!("{APP_ID}.{stem}.desktop")
+}
+
+/// Installs a launcher with the given icon, which must be a PNG, JPEG or SVG image.
+///
+/// `desktop_entry` is called with the name confirmed by the user and must return the contents of
+/// the desktop file, the portal fills in the `Icon` key by itself.
+/// Returns `None` if the user cancelled the installation.
+pub async fn install(
+    name: &str,
+    icon: Vec<u8>,
+    desktop_file_id: &str,
+    desktop_entry: impl FnOnce(&str) -> String,
+) -> zbus::Result<Option<Installed>> {
+    let connection = Connection::session().await?;
+    let proxy = DynamicLauncherProxy::new(&connection).await?;
+
+    // A serialized GBytesIcon
+    let icon_value = || Value::from(Structure::from(("bytes", Value::from(icon.clone()))));
+
+    // Trusted apps may get a token without asking, everyone else goes through the dialog
+    let (name, token) = match proxy.request_install_token(name, icon_value(), HashMap::new()).await {
+        Ok(token) => (name.to_string(), token),
+        Err(_) => match prepare_install(&connection, &proxy, name, icon_value()).await? {
+            Some(confirmed) => confirmed,
+            None => return Ok(None),
+        },
+    };
+
+    proxy
+        .install(&token, desktop_file_id, &desktop_entry(&name), HashMap::new())
+        .await?;
+
+    Ok(Some(Installed {
+        name,
+        desktop_file_id: desktop_file_id.to_string(),
+    }))
+}
+
+/// Shows the portal's confirmation dialog, returns the chosen name and the install token
+async fn prepare_install(
+    connection: &Connection,
+    proxy: &DynamicLauncherProxy<'_>,
+    name: &str,
+    icon: Value<'_>,
+) -> zbus::Result<Option<(String, String)>> {
+    // Subscribe to the response before making the call so it can't be missed, the request path is
+    // predictable from the handle token
+    let handle_token = format!("desktop_file_editor_{}", glib::random_int());
+    let sender = connection
+        .unique_name()
+        .map(|name| name.as_str().trim_start_matches(':').replace('.', "_"))
+        .unwrap_or_default();
+    let request = RequestProxy::builder(connection)
+        .path(format!(
+            "/org/freedesktop/portal/desktop/request/{sender}/{handle_token}"
+        ))?
+        .build()
+        .await?;
+    let mut responses = request.receive_response().await?;
+
+    let options = HashMap::from([
+        ("handle_token", Value::from(handle_token.as_str())),
+        ("launcher_type", Value::from(LAUNCHER_TYPE_APPLICATION)),
+        ("editable_name", Value::from(true)),
+        ("editable_icon", Value::from(false)),
+    ]);
+    proxy.prepare_install("", name, icon, options).await?;
+
+    let Some(response) = responses.next().await else {
+        return Ok(None);
+    };
+    let args = response.args()?;
+    if args.response != 0 {
+        // Cancelled by the user or otherwise ended
+        return Ok(None);
+    }
+
+    let string = |key: &str| {
+        args.results
+            .get(key)
+            .and_then(|value| <&str>::try_from(value).ok())
+            .map(String::from)
+    };
+    let Some(token) = string("token") else {
+        return Err(zbus::Error::Failure(
+            "The portal did not return an install token".to_string(),
+        ));
+    };
+    Ok(Some((string("name").unwrap_or_else(|| name.to_string()), token)))
+}
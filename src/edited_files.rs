@@ -0,0 +1,101 @@
+/*
+* Copyright © 2025 Alessandro Balducci
+*
+* This file is part of Desktop File Editor.
+* Desktop File Editor is free software: you can redistribute it and/or modify it under the terms of the
+* GNU General Public License as published by the Free Software Foundation,
+* either version 3 of the License, or (at your option) any later version.
+* Desktop File Editor is distributed in the hope that it will be useful, but WITHOUT ANY WARRANTY;
+* without even the implied warranty of MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.
+* See the GNU General Public License for more details.
+* You should have received a copy of the GNU General Public License along with Desktop File Editor. If not, see <https://www.gnu.org/licenses/>.
+*/
+
+//! The files saved with the editor, so that the launchers customized through it can be found
+//! again. Only the paths are kept, one per line, under the user data directory
+
+use std::{
+    cell::RefCell,
+    collections::HashSet,
+    fs,
+    io::{self, Write},
+    path::{Path, PathBuf},
+};
+
+use gtk::glib;
+
+thread_local! {
+    /// Read from the file the first time it is needed
+    static EDITED_FILES: RefCell<Option<HashSet<PathBuf>>> = const { RefCell::new(None) };
+}
+
+fn file_path() -> PathBuf {
+    glib::user_data_dir()
+        .join("desktop-file-editor")
+        .join("edited.txt")
+}
+
+fn with_edited_files<R>(f: impl FnOnce(&mut HashSet<PathBuf>) -> R) -> R {
+    EDITED_FILES
+        .with_borrow_mut(|edited_files| f(edited_files.get_or_insert_with(read_edited_files)))
+}
+
+fn read_edited_files() -> HashSet<PathBuf> {
+    match fs::read_to_string(file_path()) {
+        Ok(contents) => parse_edited_files(&contents),
+        Err(e) => {
+            if e.kind() != io::ErrorKind::NotFound {
+                eprintln!("Failed to read the list of edited files: {e}");
+            }
+            HashSet::new()
+        }
+    }
+}
+
+fn parse_edited_files(contents: &str) -> HashSet<PathBuf> {
+    contents
+        .lines()
+        .filter(|line| !line.is_empty())
+        .map(PathBuf::from)
+        .collect()
+}
+
+/// Remember that the file at `path` was written by the editor
+pub fn mark_edited(path: &Path) {
+    if !with_edited_files(|edited_files| edited_files.insert(path.to_path_buf())) {
+        return;
+    }
+
+    let list_path = file_path();
+    let result = list_path
+        .parent()
+        .map_or(Ok(()), fs::create_dir_all)
+        .and_then(|_| {
+            fs::OpenOptions::new()
+                .create(true)
+                .append(true)
+                .open(&list_path)
+        })
+        .and_then(|mut file| writeln!(file, "{}", path.to_string_lossy()));
+
+    if let Err(e) = result {
+        eprintln!("Failed to add {} to the edited files: {e}", path.display());
+    }
+}
+
+/// Whether the file at `path` was ever saved with the editor
+pub fn is_edited(path: &Path) -> bool {
+    with_edited_files(|edited_files| edited_files.contains(path))
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn parse_list() {
+        let edited_files = parse_edited_files("/a b/one.desktop\n\n/two.desktop\n/two.desktop\n");
+        assert_eq!(edited_files.len(), 2);
+        assert!(edited_files.contains(Path::new("/a b/one.desktop")));
+    }
+}
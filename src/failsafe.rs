@@ -0,0 +1,87 @@
+/*
+* Copyright © 2025 Alessandro Balducci
+*
+* This file is part of Desktop File Editor.
+* Desktop File Editor is free software: you can redistribute it and/or modify it under the terms of the
+* GNU General Public License as published by the Free Software Foundation,
+* either version 3 of the License, or (at your option) any later version.
+* Desktop File Editor is distributed in the hope that it will be useful, but WITHOUT ANY WARRANTY;
+* without even the implied warranty of MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.
+* See the GNU General Public License for more details.
+* You should have received a copy of the GNU General Public License along with Desktop File Editor. If not, see <https://www.gnu.org/licenses/>.
+*/
+
+//! Fallback for broken installations. When the resources of the application are missing or stale
+//! the widgets built from templates can't be created, so a plain window explains the problem
+//! instead of the application aborting
+
+use adw::prelude::*;
+use gtk::{gio, glib};
+
+use crate::APP_ID;
+
+const RESOURCE_PREFIX: &str = "/com/argoware/desktop-file-editor/";
+
+/// The templates of the widgets, with the class each of them declares
+const TEMPLATES: [(&str, &str); 6] = [
+    ("window.ui", "DMWindow"),
+    ("list_entry.ui", "DMListEntry"),
+    ("sliding_search_entry.ui", "SlidingSearchEntry"),
+    ("desktop_file_view.ui", "DesktopFileView"),
+    ("desktop_file_group.ui", "DesktopFileGroup"),
+    ("preferences_dialog.ui", "DMPreferencesDialog"),
+];
+
+/// Checks that every template is registered and declares the class that is going to use it
+pub fn check_templates() -> Result<(), String> {
+    for (name, class) in TEMPLATES {
+        let path = format!("{RESOURCE_PREFIX}{name}");
+        let data = gio::resources_lookup_data(&path, gio::ResourceLookupFlags::NONE)
+            .map_err(|e| format!("The template {path} is missing: {e}"))?;
+
+        let declaration = format!("<template class=\"{class}\"");
+        let contents = String::from_utf8_lossy(&data);
+        if !contents.contains(&declaration) {
+            return Err(format!(
+                "The template {path} does not define the {class} widget, it may be left over from \
+                 another version"
+            ));
+        }
+    }
+    Ok(())
+}
+
+/// Runs an application that only shows what is wrong with the installation
+pub fn run(problem: &str) -> glib::ExitCode {
+    let app = adw::Application::builder().application_id(APP_ID).build();
+
+    let problem = problem.to_string();
+    app.connect_activate(move |app| {
+        let description = format!(
+            "{}\n\nReinstalling the application should fix this.",
+            glib::markup_escape_text(&problem)
+        );
+        let status_page = adw::StatusPage::builder()
+            .icon_name("dialog-error-symbolic")
+            .title("Broken Installation")
+            .description(description)
+            .build();
+
+        let toolbar_view = adw::ToolbarView::new();
+        toolbar_view.add_top_bar(&adw::HeaderBar::new());
+        toolbar_view.set_content(Some(&status_page));
+
+        let window = adw::ApplicationWindow::builder()
+            .application(app)
+            .title("Desktop File Editor")
+            .default_width(480)
+            .default_height(360)
+            .content(&toolbar_view)
+            .build();
+        window.present();
+    });
+
+    // The options of the full application are not known here, so they are not passed on
+    let program = std::env::args().take(1).collect::<Vec<_>>();
+    app.run_with_args(&program)
+}
@@ -19,12 +19,21 @@ use gtk::gio;
 use gtk::glib;
 
 mod application;
+mod backups;
+mod change_journal;
+mod dbus;
 mod desktop_file_view;
+mod edited_files;
+mod failsafe;
+mod preferences_dialog;
+mod settings;
 mod window;
 mod shellparse;
 mod util;
 #[cfg(feature = "flatpak")]
 mod flatpak;
+#[cfg(feature = "flatpak")]
+mod dynamic_launcher;
 
 const APP_ID: &str = "com.argoware.desktop-file-editor";
 
@@ -32,8 +41,16 @@ fn main() -> glib::ExitCode {
     #[cfg(feature = "flatpak")]
     flatpak::init();
 
-    gio::resources_register_include!("desktop_file_editor.gresource")
-        .expect("Failed to register resources");
+    let problem = match gio::resources_register_include!("desktop_file_editor.gresource") {
+        Ok(()) => failsafe::check_templates().err(),
+        Err(e) => Some(format!(
+            "The resources of the application could not be registered: {e}"
+        )),
+    };
+    if let Some(problem) = problem {
+        eprintln!("{problem}");
+        return failsafe::run(&problem);
+    }
 
     let app = DMApplication::default();
 
@@ -0,0 +1,335 @@
+/*
+* Copyright © 2025 Alessandro Balducci
+*
+* This file is part of Desktop File Editor.
+* Desktop File Editor is free software: you can redistribute it and/or modify it under the terms of the
+* GNU General Public License as published by the Free Software Foundation,
+* either version 3 of the License, or (at your option) any later version.
+* Desktop File Editor is distributed in the hope that it will be useful, but WITHOUT ANY WARRANTY;
+* without even the implied warranty of MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.
+* See the GNU General Public License for more details.
+* You should have received a copy of the GNU General Public License along with Desktop File Editor. If not, see <https://www.gnu.org/licenses/>.
+*/
+
+use adw::subclass::prelude::*;
+use gtk::glib::{self, Object};
+
+mod imp {
+    use std::cell::{Cell, RefCell};
+    use std::path::Path;
+
+    use adw::prelude::*;
+    use adw::subclass::prelude::*;
+    use gtk::gio;
+    use gtk::glib::{self, clone, object_subclass, subclass::InitializingObject};
+    use gtk::CompositeTemplate;
+
+    use crate::settings::{settings, LintProfile, ListSubtitle};
+    use crate::util::display_path;
+    use crate::window::new_launcher::user_applications_dir;
+
+    #[derive(Debug, Default, CompositeTemplate)]
+    #[template(resource = "/com/argoware/desktop-file-editor/preferences_dialog.ui")]
+    pub struct PreferencesDialog {
+        #[template_child]
+        pub list_subtitle_row: TemplateChild<adw::ComboRow>,
+
+        #[template_child]
+        pub lint_profile_row: TemplateChild<adw::ComboRow>,
+
+        #[template_child]
+        pub translated_names_row: TemplateChild<adw::SwitchRow>,
+
+        #[template_child]
+        pub preselect_user_locale_row: TemplateChild<adw::SwitchRow>,
+
+        #[template_child]
+        pub preserve_formatting_row: TemplateChild<adw::SwitchRow>,
+
+        #[template_child]
+        pub record_changes_row: TemplateChild<adw::SwitchRow>,
+
+        #[template_child]
+        pub keep_backups_row: TemplateChild<adw::SwitchRow>,
+
+        #[template_child]
+        pub key_order_list: TemplateChild<gtk::ListBox>,
+
+        #[template_child]
+        pub add_key_row: TemplateChild<adw::EntryRow>,
+
+        #[template_child]
+        pub new_files_dir_row: TemplateChild<adw::ComboRow>,
+
+        /// Folders offered for new launchers, in the order of the row. Empty for the applications
+        /// directory of the user
+        new_files_dirs: RefCell<Vec<String>>,
+        populating_new_files_dirs: Cell<bool>,
+    }
+
+    #[object_subclass]
+    impl ObjectSubclass for PreferencesDialog {
+        const NAME: &'static str = "DMPreferencesDialog";
+        type Type = super::PreferencesDialog;
+        type ParentType = adw::PreferencesDialog;
+
+        fn class_init(klass: &mut Self::Class) {
+            klass.bind_template();
+            klass.bind_template_callbacks();
+        }
+
+        fn instance_init(obj: &InitializingObject<Self>) {
+            obj.init_template();
+        }
+    }
+
+    impl ObjectImpl for PreferencesDialog {
+        fn constructed(&self) {
+            self.parent_constructed();
+
+            settings()
+                .bind_property("list-subtitle", &*self.list_subtitle_row, "selected")
+                .transform_to(|_, subtitle: ListSubtitle| Some(subtitle.position()))
+                .transform_from(|_, position: u32| Some(ListSubtitle::from_position(position)))
+                .bidirectional()
+                .sync_create()
+                .build();
+
+            settings()
+                .bind_property("translated-names", &*self.translated_names_row, "active")
+                .bidirectional()
+                .sync_create()
+                .build();
+
+            settings()
+                .bind_property(
+                    "preselect-user-locale",
+                    &*self.preselect_user_locale_row,
+                    "active",
+                )
+                .bidirectional()
+                .sync_create()
+                .build();
+
+            settings()
+                .bind_property("preserve-formatting", &*self.preserve_formatting_row, "active")
+                .bidirectional()
+                .sync_create()
+                .build();
+
+            settings()
+                .bind_property("record-changes", &*self.record_changes_row, "active")
+                .bidirectional()
+                .sync_create()
+                .build();
+
+            settings()
+                .bind_property("keep-backups", &*self.keep_backups_row, "active")
+                .bidirectional()
+                .sync_create()
+                .build();
+
+            settings()
+                .bind_property("lint-profile", &*self.lint_profile_row, "selected")
+                .transform_to(|_, profile: LintProfile| Some(profile.position()))
+                .transform_from(|_, position: u32| Some(LintProfile::from_position(position)))
+                .bidirectional()
+                .sync_create()
+                .build();
+
+            self.populate_key_order();
+
+            self.new_files_dir_row.connect_selected_notify(clone!(
+                #[weak(rename_to = dialog)]
+                self.obj(),
+                move |row| {
+                    let imp = dialog.imp();
+                    if imp.populating_new_files_dirs.get() {
+                        return;
+                    }
+                    let dir = imp
+                        .new_files_dirs
+                        .borrow()
+                        .get(row.selected() as usize)
+                        .cloned();
+                    if let Some(dir) = dir {
+                        settings().set_new_files_dir(dir);
+                    }
+                }
+            ));
+            self.populate_new_files_dirs(Vec::new());
+        }
+
+        fn dispose(&self) {
+            self.dispose_template();
+        }
+    }
+
+    #[gtk::template_callbacks]
+    impl PreferencesDialog {
+        #[template_callback]
+        fn on_reset_key_order_clicked(&self) {
+            settings().set_key_order("");
+            self.populate_key_order();
+        }
+
+        #[template_callback]
+        fn on_add_key_row_apply(&self) {
+            let key = self.add_key_row.text().trim().to_string();
+            let valid = !key.is_empty()
+                && key.chars().all(|c| c.is_ascii_alphanumeric() || c == '-');
+            if !valid {
+                self.add_key_row.add_css_class("error");
+                return;
+            }
+            self.add_key_row.remove_css_class("error");
+
+            let mut keys = settings().key_order_list();
+            if !keys.contains(&key) {
+                keys.push(key);
+                settings().set_key_order_list(&keys);
+                self.populate_key_order();
+            }
+            self.add_key_row.set_text("");
+        }
+
+        #[template_callback]
+        fn on_choose_new_files_dir_clicked(&self) {
+            let current = settings().new_files_dir();
+            let initial_folder = if current.is_empty() {
+                user_applications_dir()
+            } else {
+                current.into()
+            };
+            let file_dialog = gtk::FileDialog::builder()
+                .title("Choose Folder for New Launchers")
+                .initial_folder(&gio::File::for_path(initial_folder))
+                .build();
+
+            let parent = self.obj().root().and_downcast::<gtk::Window>();
+            file_dialog.select_folder(
+                parent.as_ref(),
+                gio::Cancellable::NONE,
+                clone!(
+                    #[weak(rename_to = dialog)]
+                    self.obj(),
+                    move |res: Result<gio::File, glib::Error>| {
+                        let Some(path) = res.ok().and_then(|file| file.path()) else {
+                            return;
+                        };
+                        let imp = dialog.imp();
+                        settings().set_new_files_dir(path.to_string_lossy().into_owned());
+                        let dirs = imp.new_files_dirs.borrow().clone();
+                        imp.populate_new_files_dirs(dirs);
+                    }
+                ),
+            );
+        }
+
+        /// Offers the applications directory of the user, `dirs` and the folder currently chosen
+        pub fn populate_new_files_dirs(&self, mut dirs: Vec<String>) {
+            let current = settings().new_files_dir();
+            dirs.retain(|dir| !dir.is_empty());
+            dirs.insert(0, String::new());
+            if !dirs.contains(&current) {
+                dirs.push(current.clone());
+            }
+
+            let labels: Vec<String> = dirs
+                .iter()
+                .map(|dir| match dir.as_str() {
+                    "" => "Personal Applications".to_string(),
+                    dir => display_path(Path::new(dir)).to_string_lossy().into_owned(),
+                })
+                .collect();
+            let labels: Vec<&str> = labels.iter().map(String::as_str).collect();
+            let selected = dirs.iter().position(|dir| *dir == current).unwrap_or(0);
+
+            self.populating_new_files_dirs.set(true);
+            self.new_files_dir_row
+                .set_model(Some(&gtk::StringList::new(&labels)));
+            self.new_files_dir_row.set_selected(selected as u32);
+            self.populating_new_files_dirs.set(false);
+            self.new_files_dirs.replace(dirs);
+        }
+
+        fn populate_key_order(&self) {
+            self.key_order_list.remove_all();
+
+            let keys = settings().key_order_list();
+            for (i, key) in keys.iter().enumerate() {
+                let row = adw::ActionRow::builder().title(key.as_str()).build();
+
+                let up_button = self.key_order_button("go-up-symbolic", "Move Up", i, |keys, i| {
+                    keys.swap(i - 1, i)
+                });
+                up_button.set_sensitive(i > 0);
+                row.add_suffix(&up_button);
+
+                let down_button =
+                    self.key_order_button("go-down-symbolic", "Move Down", i, |keys, i| {
+                        keys.swap(i, i + 1)
+                    });
+                down_button.set_sensitive(i + 1 < keys.len());
+                row.add_suffix(&down_button);
+
+                let remove_button =
+                    self.key_order_button("list-remove-symbolic", "Remove", i, |keys, i| {
+                        keys.remove(i);
+                    });
+                row.add_suffix(&remove_button);
+
+                self.key_order_list.append(&row);
+            }
+        }
+
+        /// Button that applies `change` to the key order, `i` is the position of the row's key
+        fn key_order_button(
+            &self,
+            icon_name: &str,
+            tooltip: &str,
+            i: usize,
+            change: impl Fn(&mut Vec<String>, usize) + 'static,
+        ) -> gtk::Button {
+            let button = gtk::Button::builder()
+                .icon_name(icon_name)
+                .tooltip_text(tooltip)
+                .valign(gtk::Align::Center)
+                .css_classes(["flat"])
+                .build();
+
+            button.connect_clicked(clone!(
+                #[weak(rename_to = dialog)]
+                self.obj(),
+                move |_| {
+                    let mut keys = settings().key_order_list();
+                    change(&mut keys, i);
+                    settings().set_key_order_list(&keys);
+                    dialog.imp().populate_key_order();
+                }
+            ));
+            button
+        }
+    }
+
+    impl WidgetImpl for PreferencesDialog {}
+    impl AdwDialogImpl for PreferencesDialog {}
+    impl PreferencesDialogImpl for PreferencesDialog {}
+}
+
+glib::wrapper! {
+    pub struct PreferencesDialog(ObjectSubclass<imp::PreferencesDialog>)
+    @extends adw::PreferencesDialog, adw::Dialog, gtk::Widget,
+    @implements gtk::Accessible, gtk::Buildable, gtk::ConstraintTarget, gtk::ShortcutManager;
+}
+
+impl PreferencesDialog {
+    pub fn new() -> Self {
+        Object::builder().build()
+    }
+
+    /// Offers `dirs` as folders for new launchers, next to the applications directory of the user
+    pub fn suggest_new_files_dirs(&self, dirs: Vec<String>) {
+        self.imp().populate_new_files_dirs(dirs);
+    }
+}
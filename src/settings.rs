@@ -0,0 +1,285 @@
+/*
+* Copyright © 2025 Alessandro Balducci
+*
+* This file is part of Desktop File Editor.
+* Desktop File Editor is free software: you can redistribute it and/or modify it under the terms of the
+* GNU General Public License as published by the Free Software Foundation,
+* either version 3 of the License, or (at your option) any later version.
+* Desktop File Editor is distributed in the hope that it will be useful, but WITHOUT ANY WARRANTY;
+* without even the implied warranty of MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.
+* See the GNU General Public License for more details.
+* You should have received a copy of the GNU General Public License along with Desktop File Editor. If not, see <https://www.gnu.org/licenses/>.
+*/
+
+use std::path::PathBuf;
+
+use gtk::glib::{self, prelude::*, subclass::types::ObjectSubclassIsExt, EnumClass, Object};
+
+use crate::desktop_file_view::desktop_entry_ext::KEYMAP_ORDER;
+
+const SETTINGS_GROUP: &str = "Settings";
+
+mod imp {
+    use std::cell::{Cell, RefCell};
+
+    use gtk::glib::{self, object_subclass, prelude::*, subclass::prelude::*, Properties};
+
+    use super::{LintProfile, ListSubtitle};
+
+    #[derive(Debug, Properties)]
+    #[properties(wrapper_type = super::Settings)]
+    pub struct Settings {
+        #[property(get, set, builder(ListSubtitle::default()))]
+        pub list_subtitle: Cell<ListSubtitle>,
+
+        #[property(get, set)]
+        pub translated_names: Cell<bool>,
+
+        /// Semicolon separated keys, in the order they are written to files. Empty for the default
+        /// order
+        #[property(get, set)]
+        pub key_order: RefCell<String>,
+
+        /// Only rewrite the lines that changed when saving, instead of sorting the whole file
+        #[property(get, set)]
+        pub preserve_formatting: Cell<bool>,
+
+        /// Keep a journal of the changes written by every save
+        #[property(get, set)]
+        pub record_changes: Cell<bool>,
+
+        /// Keep a copy of the previous version of a file each time it is saved
+        #[property(get, set)]
+        pub keep_backups: Cell<bool>,
+
+        /// The user went through the tour of the window, or chose to skip it
+        #[property(get, set)]
+        pub tour_completed: Cell<bool>,
+
+        /// Desktop whose OnlyShowIn and NotShowIn rules are applied, as it would be named in
+        /// XDG_CURRENT_DESKTOP. Empty for the desktop of the session
+        #[property(get, set)]
+        pub simulated_desktop: RefCell<String>,
+
+        /// Open entries in the locale of the user when they are translated to it
+        #[property(get, set)]
+        pub preselect_user_locale: Cell<bool>,
+
+        /// Guidelines the entries are checked against, on top of the specification
+        #[property(get, set, builder(LintProfile::default()))]
+        pub lint_profile: Cell<LintProfile>,
+
+        /// Folder new launchers are written to. Empty for the applications directory of the user
+        #[property(get, set)]
+        pub new_files_dir: RefCell<String>,
+
+        pub loading: Cell<bool>,
+    }
+
+    impl Default for Settings {
+        fn default() -> Self {
+            Self {
+                list_subtitle: Cell::default(),
+                translated_names: Cell::new(true),
+                key_order: RefCell::default(),
+                preserve_formatting: Cell::default(),
+                record_changes: Cell::default(),
+                keep_backups: Cell::new(true),
+                tour_completed: Cell::default(),
+                simulated_desktop: RefCell::default(),
+                preselect_user_locale: Cell::new(true),
+                lint_profile: Cell::default(),
+                new_files_dir: RefCell::default(),
+                loading: Cell::default(),
+            }
+        }
+    }
+
+    #[object_subclass]
+    impl ObjectSubclass for Settings {
+        const NAME: &'static str = "DMSettings";
+        type Type = super::Settings;
+    }
+
+    #[glib::derived_properties]
+    impl ObjectImpl for Settings {
+        fn constructed(&self) {
+            self.parent_constructed();
+
+            let obj = self.obj();
+            obj.load();
+            obj.connect_notify_local(None, |settings, _| {
+                if !settings.imp().loading.get() {
+                    settings.save();
+                }
+            });
+        }
+    }
+}
+
+glib::wrapper! {
+    /// User preferences, persisted in a key file inside the user configuration directory.
+    /// Every property of this object is saved automatically when it changes
+    pub struct Settings(ObjectSubclass<imp::Settings>);
+}
+
+thread_local! {
+    static SETTINGS: Settings = Object::builder().build();
+}
+
+/// The settings shared by the whole application
+pub fn settings() -> Settings {
+    SETTINGS.with(Settings::clone)
+}
+
+impl Settings {
+    /// Keys in the order they are written to files
+    pub fn key_order_list(&self) -> Vec<String> {
+        let key_order = self.key_order();
+        if key_order.is_empty() {
+            return KEYMAP_ORDER.iter().map(|key| key.to_string()).collect();
+        }
+
+        key_order
+            .split(';')
+            .filter(|key| !key.is_empty())
+            .map(String::from)
+            .collect()
+    }
+
+    pub fn set_key_order_list(&self, keys: &[String]) {
+        let key_order = keys.join(";");
+        if keys.iter().map(String::as_str).eq(KEYMAP_ORDER) {
+            self.set_key_order("");
+        } else {
+            self.set_key_order(key_order);
+        }
+    }
+
+    fn file_path() -> PathBuf {
+        glib::user_config_dir()
+            .join("desktop-file-editor")
+            .join("settings.ini")
+    }
+
+    fn load(&self) {
+        let key_file = glib::KeyFile::new();
+        if let Err(e) = key_file.load_from_file(Self::file_path(), glib::KeyFileFlags::NONE) {
+            if !e.matches(glib::FileError::Noent) {
+                eprintln!("Failed to load settings: {e}");
+            }
+            return;
+        }
+
+        self.imp().loading.set(true);
+        for pspec in self.list_properties().iter() {
+            let key = pspec.name();
+            let value_type = pspec.value_type();
+
+            let value = if value_type == bool::static_type() {
+                key_file.boolean(SETTINGS_GROUP, key).ok().map(|b| b.to_value())
+            } else if value_type == String::static_type() {
+                key_file.string(SETTINGS_GROUP, key).ok().map(|s| s.to_value())
+            } else if let Some(enum_class) = EnumClass::with_type(value_type) {
+                key_file
+                    .string(SETTINGS_GROUP, key)
+                    .ok()
+                    .and_then(|nick| enum_class.to_value_by_nick(&nick))
+            } else {
+                unreachable!("Unsupported settings type {value_type} for {key}")
+            };
+
+            if let Some(value) = value {
+                self.set_property_from_value(key, &value);
+            }
+        }
+        self.imp().loading.set(false);
+    }
+
+    fn save(&self) {
+        let key_file = glib::KeyFile::new();
+        for pspec in self.list_properties().iter() {
+            let key = pspec.name();
+            let value = self.property_value(key);
+
+            if let Ok(b) = value.get::<bool>() {
+                key_file.set_boolean(SETTINGS_GROUP, key, b);
+            } else if let Ok(s) = value.get::<String>() {
+                key_file.set_string(SETTINGS_GROUP, key, &s);
+            } else if let Some((_, enum_value)) = glib::EnumValue::from_value(&value) {
+                key_file.set_string(SETTINGS_GROUP, key, enum_value.nick());
+            }
+        }
+
+        let path = Self::file_path();
+        let result = path
+            .parent()
+            .map_or(Ok(()), std::fs::create_dir_all)
+            .map_err(|e| e.to_string())
+            .and_then(|_| key_file.save_to_file(&path).map_err(|e| e.to_string()));
+
+        if let Err(e) = result {
+            eprintln!("Failed to save settings: {e}");
+        }
+    }
+}
+
+/// What is shown below the name of each entry in the list
+#[derive(Debug, Default, Copy, Clone, PartialEq, Eq, glib::Enum)]
+#[enum_type(name = "ListSubtitle")]
+pub enum ListSubtitle {
+    #[default]
+    Path,
+    Comment,
+    Exec,
+    Categories,
+}
+
+impl ListSubtitle {
+    pub const ALL: [Self; 4] = [Self::Path, Self::Comment, Self::Exec, Self::Categories];
+
+    pub fn position(self) -> u32 {
+        Self::ALL.iter().position(|s| *s == self).unwrap() as u32
+    }
+
+    pub fn from_position(position: u32) -> Self {
+        Self::ALL
+            .get(position as usize)
+            .copied()
+            .unwrap_or_default()
+    }
+}
+
+/// Rules the entries are checked with before saving, the specification alone or together with
+/// the guidelines of a desktop
+#[derive(Debug, Default, Copy, Clone, PartialEq, Eq, glib::Enum)]
+#[enum_type(name = "LintProfile")]
+pub enum LintProfile {
+    #[default]
+    Spec,
+    Gnome,
+    Kde,
+}
+
+impl LintProfile {
+    pub const ALL: [Self; 3] = [Self::Spec, Self::Gnome, Self::Kde];
+
+    pub fn title(self) -> &'static str {
+        match self {
+            LintProfile::Spec => "Strict Specification",
+            LintProfile::Gnome => "GNOME Guidelines",
+            LintProfile::Kde => "KDE Guidelines",
+        }
+    }
+
+    pub fn position(self) -> u32 {
+        Self::ALL.iter().position(|p| *p == self).unwrap() as u32
+    }
+
+    pub fn from_position(position: u32) -> Self {
+        Self::ALL
+            .get(position as usize)
+            .copied()
+            .unwrap_or_default()
+    }
+}
@@ -12,9 +12,11 @@
 */
 
 use std::fmt::Display;
+use std::path::Path;
 
 #[cfg(feature = "steam")]
 mod steamutil;
+mod wrapper;
 
 #[derive(Debug, PartialEq, Clone)]
 pub struct Command {
@@ -90,6 +92,34 @@ impl Command {
         let binary = self.args.remove(0);
         self.command = binary;
     }
+
+    /// The script run by a shell invoked as `sh -c "script"`
+    pub fn shell_script(&self) -> Option<&str> {
+        let name = Path::new(&self.command).file_name()?.to_str()?;
+        if !SHELLS.contains(&name) {
+            return None;
+        }
+
+        shell_script_arg(&self.args).map(|(_, script)| script)
+    }
+
+    /// "Flatten" commands that run a shell script by replacing the shell with the main command
+    /// of the script, see [`main_command`]. The command is left as is if the script uses syntax
+    /// that can't be followed
+    pub fn flatten_shell(&mut self) {
+        let inner = self
+            .shell_script()
+            .and_then(script_commands)
+            .and_then(main_command);
+        let Some(inner) = inner else {
+            return;
+        };
+
+        let variables = std::mem::take(&mut self.variables);
+        *self = inner;
+        self.variables.splice(0..0, variables);
+        self.flatten_env();
+    }
 }
 
 impl Display for Command {
@@ -115,6 +145,56 @@ impl Display for Command {
     }
 }
 
+impl Command {
+    /// Serializes the command into a value for the Exec key, quoting arguments as needed.
+    /// Variables are passed through `env`, since Exec does not support assignments
+    pub fn to_exec_string(&self) -> String {
+        self.join_exec(quote_exec_arg)
+    }
+
+    /// Like [`Command::to_exec_string`], but the words are taken as written in the Exec key, so
+    /// field codes and escaped percent signs are kept as they are
+    pub fn to_exec_string_with_codes(&self) -> String {
+        self.join_exec(quote_exec_word)
+    }
+
+    fn join_exec(&self, quote: fn(&str) -> String) -> String {
+        let variables = self
+            .variables
+            .iter()
+            .map(|(var, value)| quote(&format!("{var}={value}")));
+
+        let env = (!self.variables.is_empty()).then(|| "env".to_string());
+
+        env.into_iter()
+            .chain(variables)
+            .chain(std::iter::once(quote(&self.command)))
+            .chain(self.args.iter().map(|arg| quote(arg)))
+            .collect::<Vec<_>>()
+            .join(" ")
+    }
+
+    /// Field codes used in the arguments, in order of appearance and without repetitions.
+    /// Escaped percent signs (`%%`) are not field codes
+    pub fn field_codes(&self) -> Vec<char> {
+        let mut codes = Vec::new();
+        for arg in self.args.iter() {
+            let mut chars = arg.chars();
+            while let Some(c) = chars.next() {
+                if c != '%' {
+                    continue;
+                }
+                match chars.next() {
+                    Some('%') | None => {}
+                    Some(code) if !codes.contains(&code) => codes.push(code),
+                    Some(_) => {}
+                }
+            }
+        }
+        codes
+    }
+}
+
 impl From<Command> for Vec<String> {
     fn from(value: Command) -> Self {
         value
@@ -127,6 +207,191 @@ impl From<Command> for Vec<String> {
     }
 }
 
+/// Characters that require an argument of the Exec key to be quoted
+const RESERVED_CHARS: &[char] = &[
+    ' ', '\t', '\n', '"', '\'', '\\', '>', '<', '~', '|', '&', ';', '$', '*', '?', '#', '(', ')',
+    '`',
+];
+
+/// Quotes a literal argument following the rules of the Exec key in the desktop entry
+/// specification. Percent signs are doubled, so the result never contains field codes
+pub fn quote_exec_arg(arg: &str) -> String {
+    quote_exec_word(&arg.replace('%', "%%"))
+}
+
+/// Quotes a word of the Exec key that may contain field codes, percent signs are left untouched
+pub fn quote_exec_word(arg: &str) -> String {
+    if !arg.is_empty() && !arg.contains(RESERVED_CHARS) {
+        return arg.to_string();
+    }
+
+    let mut quoted = String::with_capacity(arg.len() + 2);
+    quoted.push('"');
+    for c in arg.chars() {
+        if matches!(c, '"' | '`' | '$' | '\\') {
+            quoted.push('\\');
+        }
+        quoted.push(c);
+    }
+    quoted.push('"');
+    quoted
+}
+
+/// Shells that are commonly used to run a script from the Exec key
+const SHELLS: [&str; 4] = ["sh", "bash", "dash", "zsh"];
+
+/// Commands of a script that only prepare for running the main command
+const SCRIPT_SETUP_COMMANDS: [&str; 9] = [
+    ":", ".", "cd", "echo", "printf", "set", "sleep", "source", "unset",
+];
+
+/// Finds the script in the arguments of a shell, returning its index as well
+fn shell_script_arg(args: &[String]) -> Option<(usize, &str)> {
+    let c_index = args.iter().position(|arg| arg == "-c")?;
+    if args[..c_index].iter().any(|arg| !arg.starts_with('-')) {
+        return None;
+    }
+
+    args.get(c_index + 1)
+        .map(|script| (c_index + 1, script.as_str()))
+}
+
+/// Splits a shell script into the commands it runs. Only the first command of a pipeline is kept,
+/// the others just process its output, and the same goes for commands run after `||`.
+/// Returns `None` for scripts using syntax that can't be followed, like substitutions or
+/// subshells
+fn script_commands(script: &str) -> Option<Vec<Command>> {
+    if script.contains('`') || script.contains("$(") {
+        return None;
+    }
+
+    fn push_segment(commands: &mut Vec<Command>, segment: &mut String, skip: bool) {
+        let text = std::mem::take(segment);
+        if skip {
+            return;
+        }
+        if let Some(command) = parse(&text).and_then(strip_redirections) {
+            commands.push(command);
+        }
+    }
+
+    let mut commands = Vec::new();
+    let mut segment = String::new();
+    let mut skip = false;
+    let mut quote = None;
+    let mut escape = false;
+    let mut chars = script.chars().peekable();
+    while let Some(c) = chars.next() {
+        if escape {
+            escape = false;
+            segment.push(c);
+            continue;
+        }
+
+        match (c, quote) {
+            ('\\', quote) if quote != Some('\'') => {
+                escape = true;
+                segment.push(c);
+            }
+            ('"' | '\'', None) => {
+                quote = Some(c);
+                segment.push(c);
+            }
+            (c, Some(delim)) if c == delim => {
+                quote = None;
+                segment.push(c);
+            }
+            (_, Some(_)) => segment.push(c),
+            ('(' | ')' | '{' | '}', None) => return None,
+            (';' | '\n', None) => {
+                push_segment(&mut commands, &mut segment, skip);
+                skip = false;
+            }
+            ('&', None) => {
+                // Part of a redirection, like `2>&1` or `&>`
+                if segment.ends_with('>') || chars.peek() == Some(&'>') {
+                    segment.push(c);
+                    continue;
+                }
+
+                chars.next_if_eq(&'&');
+                push_segment(&mut commands, &mut segment, skip);
+                skip = false;
+            }
+            ('|', None) => {
+                chars.next_if_eq(&'|');
+                push_segment(&mut commands, &mut segment, skip);
+                skip = true;
+            }
+            _ => segment.push(c),
+        }
+    }
+
+    if quote.is_some() {
+        return None;
+    }
+    push_segment(&mut commands, &mut segment, skip);
+
+    Some(commands)
+}
+
+/// Removes the redirections of standard streams from a command
+fn strip_redirections(command: Command) -> Option<Command> {
+    let mut tokens = Vec::new();
+    let mut words = std::iter::once(command.command).chain(command.args);
+    while let Some(word) = words.next() {
+        let operator = word.trim_start_matches(|c: char| c.is_ascii_digit());
+        if !operator.starts_with(['>', '<']) && !operator.starts_with("&>") {
+            tokens.push(word);
+            continue;
+        }
+
+        // The target follows the operator when they are separated by whitespace
+        if operator.trim_start_matches(['>', '<', '&']).is_empty() {
+            words.next();
+        }
+    }
+
+    let mut tokens = tokens.into_iter();
+    Some(Command {
+        command: tokens.next()?,
+        args: tokens.collect(),
+        variables: command.variables,
+    })
+}
+
+/// The command a script is written for, that is the last one that isn't just setting things up
+/// for it. Variables exported along the way are kept
+fn main_command(commands: Vec<Command>) -> Option<Command> {
+    let mut exported = Vec::new();
+    let mut main = None;
+    for mut command in commands {
+        if command.command == "exec" {
+            if command.args.is_empty() {
+                continue;
+            }
+            command.command = command.args.remove(0);
+        }
+
+        if command.command == "export" {
+            exported.extend(command.args.iter().filter_map(|arg| {
+                let (var, value) = parse_variable(arg)?;
+                Some((var.to_string(), value.to_string()))
+            }));
+            continue;
+        }
+
+        if !SCRIPT_SETUP_COMMANDS.contains(&command.command.as_str()) {
+            main = Some(command);
+        }
+    }
+
+    let mut main = main?;
+    exported.append(&mut main.variables);
+    main.variables = exported;
+    Some(main)
+}
+
 fn parse_variable(token: &str) -> Option<(&str, &str)> {
     let parts: Vec<_> = token.splitn(2, '=').collect();
     if parts.len() != 2 {
@@ -216,7 +481,7 @@ pub fn parse(input: &str) -> Option<Command> {
 mod test {
     use crate::shellparse::Command;
 
-    use super::parse;
+    use super::{parse, quote_exec_arg, script_commands};
 
     fn cmd(command: &str, args: &[&str]) -> Option<Command> {
         cmd_vars(command, args, &[])
@@ -243,6 +508,58 @@ mod test {
         assert_eq!(command, None)
     }
 
+    #[test]
+    fn quote_plain_arg() {
+        assert_eq!(
+            quote_exec_arg("--app=https://example.org"),
+            "--app=https://example.org"
+        );
+        assert_eq!(quote_exec_arg(""), r#""""#);
+    }
+
+    #[test]
+    fn quote_reserved_chars() {
+        assert_eq!(quote_exec_arg("my file"), r#""my file""#);
+        assert_eq!(quote_exec_arg(r#"a"b$c"#), r#""a\"b\$c""#);
+        assert_eq!(quote_exec_arg(r"C:\dir"), r#""C:\\dir""#);
+    }
+
+    #[test]
+    fn exec_string() {
+        let command = cmd("python3", &["/home/user/my script.py", "--flag"]).unwrap();
+        assert_eq!(
+            command.to_exec_string(),
+            r#"python3 "/home/user/my script.py" --flag"#
+        );
+    }
+
+    #[test]
+    fn exec_string_variables() {
+        let command = cmd_vars("bin", &["a"], &[("LANG", "C"), ("X", "a b")]).unwrap();
+        assert_eq!(command.to_exec_string(), r#"env LANG=C "X=a b" bin a"#);
+    }
+
+    #[test]
+    fn exec_string_round_trip() {
+        let command = cmd("bin", &["a b", "c\"d"]).unwrap();
+        assert_eq!(parse(&command.to_exec_string()), Some(command));
+    }
+
+    #[test]
+    fn exec_string_keeps_field_codes() {
+        let command = parse(r#"app "%%d days" --file %f"#).unwrap();
+        assert_eq!(
+            command.to_exec_string_with_codes(),
+            r#"app "%%d days" --file %f"#
+        );
+    }
+
+    #[test]
+    fn quote_percent() {
+        assert_eq!(quote_exec_arg("100%"), "100%%");
+        assert_eq!(quote_exec_arg("a b%20"), r#""a b%%20""#);
+    }
+
     #[test]
     fn single() {
         let command = parse("binary_name");
@@ -330,6 +647,44 @@ mod test {
         )
     }
 
+    #[test]
+    fn script() {
+        let commands = script_commands("cd ~/game && ./run --fast 2>&1 | tee log; echo done");
+        assert_eq!(
+            commands,
+            Some(vec![
+                cmd("cd", &["~/game"]).unwrap(),
+                cmd("./run", &["--fast"]).unwrap(),
+                cmd("echo", &["done"]).unwrap(),
+            ])
+        );
+        assert_eq!(script_commands("(cd dir; ./run)"), None);
+        assert_eq!(script_commands("run $(cat args)"), None);
+    }
+
+    #[test]
+    fn flatten_shell() {
+        let mut command =
+            parse(r#"sh -c "export WINEPREFIX='/games/wine'; cd /games && exec wine game.exe""#)
+                .unwrap();
+        command.flatten_shell();
+        assert_eq!(
+            command,
+            cmd_vars("wine", &["game.exe"], &[("WINEPREFIX", "/games/wine")]).unwrap()
+        );
+
+        let mut command = parse(r#"bash -c "gamemoderun ./game > /tmp/log""#).unwrap();
+        command.flatten_shell();
+        assert_eq!(command, cmd("gamemoderun", &["./game"]).unwrap());
+    }
+
+    #[test]
+    fn field_codes() {
+        let command = parse(r#"app --icon %i --name=%c %U %%i %U"#).unwrap();
+        assert_eq!(command.field_codes(), vec!['i', 'c', 'U']);
+        assert!(cmd("app", &["100%"]).unwrap().field_codes().is_empty());
+    }
+
     // This currently fails, I don't know if I want to fix this
     // #[test]
     // fn quoted_var() {
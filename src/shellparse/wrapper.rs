@@ -0,0 +1,327 @@
+/*
+* Copyright © 2025 Alessandro Balducci
+*
+* This file is part of Desktop File Editor.
+* Desktop File Editor is free software: you can redistribute it and/or modify it under the terms of the
+* GNU General Public License as published by the Free Software Foundation,
+* either version 3 of the License, or (at your option) any later version.
+* Desktop File Editor is distributed in the hope that it will be useful, but WITHOUT ANY WARRANTY;
+* without even the implied warranty of MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.
+* See the GNU General Public License for more details.
+* You should have received a copy of the GNU General Public License along with Desktop File Editor. If not, see <https://www.gnu.org/licenses/>.
+*/
+
+//! Recognizing programs that only exist to run another command, to find out what an Exec line
+//! actually ends up running
+
+use std::path::Path;
+
+use super::{main_command, parse_variable, script_commands, shell_script_arg, Command, SHELLS};
+
+#[derive(Debug, PartialEq, Clone, Copy)]
+pub enum WrapperKind {
+    Env,
+    Flatpak,
+    Snap,
+    GameMode,
+    MangoHud,
+    Optirun,
+    PrimeRun,
+    Nohup,
+    Shell,
+}
+
+impl WrapperKind {
+    fn from_program(program: &str) -> Option<Self> {
+        let name = Path::new(program).file_name()?.to_str()?;
+        match name {
+            "env" => Some(Self::Env),
+            "flatpak" => Some(Self::Flatpak),
+            "snap" => Some(Self::Snap),
+            "gamemoderun" => Some(Self::GameMode),
+            "mangohud" => Some(Self::MangoHud),
+            "optirun" => Some(Self::Optirun),
+            "prime-run" => Some(Self::PrimeRun),
+            "nohup" => Some(Self::Nohup),
+            _ if SHELLS.contains(&name) => Some(Self::Shell),
+            _ => None,
+        }
+    }
+
+    pub fn description(&self) -> &'static str {
+        match self {
+            WrapperKind::Env => "Environment",
+            WrapperKind::Flatpak => "Flatpak",
+            WrapperKind::Snap => "Snap",
+            WrapperKind::GameMode => "GameMode",
+            WrapperKind::MangoHud => "MangoHud",
+            WrapperKind::Optirun => "Bumblebee",
+            WrapperKind::PrimeRun => "PRIME Render Offload",
+            WrapperKind::Nohup => "Ignore Hangups",
+            WrapperKind::Shell => "Shell",
+        }
+    }
+
+    /// Splits the arguments of the wrapper into its own options and the command it runs
+    fn split(&self, args: &[String]) -> Option<(Vec<String>, Command)> {
+        match self {
+            WrapperKind::Env => split_env(args),
+            WrapperKind::Flatpak | WrapperKind::Snap => split_run_subcommand(*self, args),
+            WrapperKind::GameMode
+            | WrapperKind::MangoHud
+            | WrapperKind::PrimeRun
+            | WrapperKind::Nohup => split_options(args, &[]),
+            WrapperKind::Optirun => split_options(
+                args,
+                &["-b", "--bridge", "-c", "--compress", "--vgl-options"],
+            ),
+            WrapperKind::Shell => split_shell(args),
+        }
+    }
+}
+
+#[derive(Debug, PartialEq, Clone)]
+pub struct Wrapper {
+    pub kind: WrapperKind,
+    /// The program as written in the command, e.g. `/usr/bin/flatpak`
+    pub program: String,
+    /// Arguments that are consumed by the wrapper itself
+    pub args: Vec<String>,
+}
+
+#[derive(Debug, PartialEq, Clone)]
+pub struct UnwrappedCommand {
+    /// Wrappers in the order they are run, the first one is the program of the Exec line
+    pub wrappers: Vec<Wrapper>,
+    /// The command that is eventually run, with the variables set along the way
+    pub command: Command,
+}
+
+impl Command {
+    /// Follows the chain of wrapper programs down to the command that is eventually run
+    pub fn unwrap_wrappers(&self) -> UnwrappedCommand {
+        let mut wrappers = Vec::new();
+        let mut command = self.clone();
+
+        while let Some(kind) = WrapperKind::from_program(&command.command) {
+            let Some((args, mut inner)) = kind.split(&command.args) else {
+                break;
+            };
+
+            let mut variables = std::mem::take(&mut command.variables);
+            variables.append(&mut inner.variables);
+            inner.variables = variables;
+
+            wrappers.push(Wrapper {
+                kind,
+                program: command.command,
+                args,
+            });
+            command = inner;
+        }
+
+        UnwrappedCommand { wrappers, command }
+    }
+}
+
+impl UnwrappedCommand {
+    /// A guess of the window class of the application: the app id of Chromium web apps, the id
+    /// of flatpak applications, or else the name of the binary
+    pub fn suggested_wm_class(&self) -> Option<String> {
+        let web_app_id = self
+            .command
+            .args
+            .iter()
+            .find_map(|arg| arg.strip_prefix("--app-id="));
+        if let Some(app_id) = web_app_id {
+            return Some(format!("crx_{app_id}"));
+        }
+
+        let flatpak_id = self
+            .wrappers
+            .iter()
+            .find(|wrapper| wrapper.kind == WrapperKind::Flatpak)
+            .and_then(|wrapper| wrapper.args.last());
+        if let Some(app_id) = flatpak_id {
+            return Some(app_id.clone());
+        }
+
+        let binary = Path::new(&self.command.command).file_name()?.to_str()?;
+        Some(binary.to_string())
+    }
+}
+
+/// Command starting at the given argument, if there is one
+fn command_at(args: &[String], index: usize) -> Option<Command> {
+    let (command, args) = args.get(index..)?.split_first()?;
+    Some(Command {
+        command: command.clone(),
+        args: args.to_vec(),
+        variables: Vec::new(),
+    })
+}
+
+/// Skips the options at the start of the arguments, `value_options` take the argument following
+/// them as their value
+fn split_options(args: &[String], value_options: &[&str]) -> Option<(Vec<String>, Command)> {
+    let mut i = 0;
+    while let Some(arg) = args.get(i) {
+        if !arg.starts_with('-') {
+            break;
+        }
+        i += if value_options.contains(&arg.as_str()) {
+            2
+        } else {
+            1
+        };
+    }
+
+    let command = command_at(args, i)?;
+    Some((args[..i].to_vec(), command))
+}
+
+fn split_env(args: &[String]) -> Option<(Vec<String>, Command)> {
+    let mut options = Vec::new();
+    let mut variables = Vec::new();
+    let mut i = 0;
+    while let Some(arg) = args.get(i) {
+        if arg.starts_with('-') {
+            options.push(arg.clone());
+            if matches!(arg.as_str(), "-u" | "--unset" | "-C" | "--chdir") {
+                options.extend(args.get(i + 1).cloned());
+                i += 1;
+            }
+        } else if let Some((var, value)) = parse_variable(arg) {
+            variables.push((var.to_string(), value.to_string()));
+        } else {
+            break;
+        }
+        i += 1;
+    }
+
+    let mut command = command_at(args, i)?;
+    command.variables = variables;
+    Some((options, command))
+}
+
+/// `flatpak run` and `snap run`, the command is the application unless overridden with
+/// `--command`
+fn split_run_subcommand(kind: WrapperKind, args: &[String]) -> Option<(Vec<String>, Command)> {
+    if args.first().map(String::as_str) != Some("run") {
+        return None;
+    }
+
+    let mut command_override = None;
+    let mut i = 1;
+    while let Some(arg) = args.get(i) {
+        if !arg.starts_with('-') {
+            break;
+        }
+        if let Some(command) = arg.strip_prefix("--command=") {
+            command_override = Some(command.to_string());
+        }
+        i += 1;
+    }
+
+    let app = args.get(i)?;
+    let command = Command {
+        command: command_override.unwrap_or_else(|| app.clone()),
+        args: args[i + 1..]
+            .iter()
+            // File forwarding markers of flatpak are not passed to the application
+            .filter(|arg| !(kind == WrapperKind::Flatpak && matches!(arg.as_str(), "@@" | "@@u")))
+            .cloned()
+            .collect(),
+        variables: Vec::new(),
+    };
+    Some((args[..=i].to_vec(), command))
+}
+
+/// `sh -c 'script'`, the command is the main command of the script
+fn split_shell(args: &[String]) -> Option<(Vec<String>, Command)> {
+    let (script_index, script) = shell_script_arg(args)?;
+    let command = script_commands(script).and_then(main_command)?;
+    Some((args[..=script_index].to_vec(), command))
+}
+
+#[cfg(test)]
+mod test {
+    use crate::shellparse::parse;
+
+    use super::WrapperKind;
+
+    fn unwrap(exec: &str) -> (Vec<WrapperKind>, Vec<String>) {
+        let unwrapped = parse(exec).unwrap().unwrap_wrappers();
+        (
+            unwrapped
+                .wrappers
+                .iter()
+                .map(|wrapper| wrapper.kind)
+                .collect(),
+            unwrapped.command.into(),
+        )
+    }
+
+    #[test]
+    fn not_wrapped() {
+        assert_eq!(
+            unwrap("gedit --new-window"),
+            (vec![], vec!["gedit".into(), "--new-window".into()])
+        );
+    }
+
+    #[test]
+    fn wrapper_chain() {
+        let (wrappers, command) = unwrap("env DXVK_HUD=1 gamemoderun mangohud --dlsym ./game -w");
+        assert_eq!(
+            wrappers,
+            [
+                WrapperKind::Env,
+                WrapperKind::GameMode,
+                WrapperKind::MangoHud
+            ]
+        );
+        assert_eq!(command, ["DXVK_HUD=1", "./game", "-w"]);
+    }
+
+    #[test]
+    fn flatpak() {
+        let (wrappers, command) = unwrap(
+            "/usr/bin/flatpak run --branch=stable --command=amberol --file-forwarding io.bassi.Amberol @@u %U @@",
+        );
+        assert_eq!(wrappers, [WrapperKind::Flatpak]);
+        assert_eq!(command, ["amberol", "%U"]);
+    }
+
+    #[test]
+    fn shell() {
+        let (wrappers, command) = unwrap("sh -c \"nohup optirun -b primus blender\"");
+        assert_eq!(
+            wrappers,
+            [WrapperKind::Shell, WrapperKind::Nohup, WrapperKind::Optirun]
+        );
+        assert_eq!(command, ["blender"]);
+
+        let (wrappers, command) = unwrap("bash -c \"cd ~/game && ./run\"");
+        assert_eq!(wrappers, [WrapperKind::Shell]);
+        assert_eq!(command, ["./run"]);
+
+        let (wrappers, command) = unwrap("bash -c \"(cd ~/game; ./run)\"");
+        assert_eq!(wrappers, []);
+        assert_eq!(command, ["bash", "-c", "(cd ~/game; ./run)"]);
+    }
+
+    #[test]
+    fn suggested_wm_class() {
+        let suggest = |exec: &str| parse(exec).unwrap().unwrap_wrappers().suggested_wm_class();
+        assert_eq!(suggest("/usr/bin/gedit %U").as_deref(), Some("gedit"));
+        assert_eq!(
+            suggest("/usr/bin/flatpak run --branch=stable io.bassi.Amberol @@u %U @@").as_deref(),
+            Some("io.bassi.Amberol")
+        );
+        assert_eq!(
+            suggest("chromium --profile-directory=Default --app-id=abcdef").as_deref(),
+            Some("crx_abcdef")
+        );
+    }
+}
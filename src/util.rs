@@ -1,8 +1,11 @@
 use std::{
     ffi::OsString,
+    fs,
     path::{Path, PathBuf},
 };
 
+use gtk::glib;
+
 #[cfg(feature = "flatpak")]
 use crate::flatpak;
 
@@ -36,3 +39,174 @@ pub fn application_paths() -> impl Iterator<Item = PathBuf> {
 pub fn application_paths() -> impl Iterator<Item = PathBuf> {
     flatpak::application_paths()
 }
+
+/// Application directories with symbolic links resolved, the way entry paths are stored
+pub fn canonical_application_paths() -> Vec<PathBuf> {
+    application_paths()
+        .map(|dir| fs::canonicalize(&dir).unwrap_or(dir))
+        .collect()
+}
+
+/// Desktop file ID of the file at `path` as defined by the specification: its path relative to
+/// the applications directory containing it, with directory separators replaced by dashes. The
+/// index of that directory in `application_dirs` is returned as well, lower indices take
+/// precedence
+pub fn desktop_file_id_in(path: &Path, application_dirs: &[PathBuf]) -> Option<(usize, String)> {
+    application_dirs.iter().enumerate().find_map(|(i, dir)| {
+        let relative = path.strip_prefix(dir).ok()?;
+        let id = relative
+            .iter()
+            .map(|component| component.to_string_lossy())
+            .collect::<Vec<_>>()
+            .join("-");
+        Some((i, id))
+    })
+}
+
+/// Desktop file ID of the file at `path`. Files outside of the applications directories don't
+/// have one, their file name is used instead
+pub fn desktop_file_id(path: &Path) -> String {
+    desktop_file_id_in(path, &canonical_application_paths())
+        .map(|(_, id)| id)
+        .unwrap_or_else(|| {
+            path.file_name()
+                .map(|name| name.to_string_lossy().into_owned())
+                .unwrap_or_default()
+        })
+}
+
+/// Outside of the sandbox the working directory is set on the launcher instead
+#[cfg(not(feature = "flatpak"))]
+pub fn host_command(argv: Vec<String>, _working_dir: Option<&Path>) -> Vec<String> {
+    argv
+}
+
+/// Commands have to be run on the host, the sandbox does not contain the launched programs.
+/// The working directory is a host path, so flatpak-spawn has to change to it there
+#[cfg(feature = "flatpak")]
+pub fn host_command(argv: Vec<String>, working_dir: Option<&Path>) -> Vec<String> {
+    ["flatpak-spawn", "--host"]
+        .into_iter()
+        .map(String::from)
+        .chain(working_dir.map(|dir| format!("--directory={}", dir.display())))
+        .chain(argv)
+        .collect()
+}
+
+/// Sizes of the directories of the hicolor theme, bitmaps go in the closest one
+const HICOLOR_SIZES: [i32; 10] = [16, 22, 24, 32, 48, 64, 96, 128, 256, 512];
+
+/// Formats of the icon files that may be found in the hicolor theme
+pub const HICOLOR_EXTENSIONS: [&str; 3] = ["png", "svg", "xpm"];
+
+/// The hicolor theme in the user data directory, icons installed there can be used by name
+pub fn hicolor_dir() -> PathBuf {
+    glib::user_data_dir().join("icons").join("hicolor")
+}
+
+/// Folder of the hicolor theme for an icon `width` pixels wide, `None` for vector icons
+pub fn hicolor_size_dir(width: Option<i32>) -> String {
+    let Some(width) = width else {
+        return "scalable".to_string();
+    };
+
+    let size = HICOLOR_SIZES
+        .iter()
+        .min_by_key(|size| (*size - width).abs())
+        .expect("There are hicolor sizes");
+    format!("{size}x{size}")
+}
+
+/// Whether the user hicolor theme has an icon called `name`, in any size
+pub fn hicolor_icon_exists(name: &str) -> bool {
+    let Ok(size_dirs) = fs::read_dir(hicolor_dir()) else {
+        return false;
+    };
+    size_dirs.flatten().any(|size_dir| {
+        HICOLOR_EXTENSIONS.iter().any(|extension| {
+            size_dir
+                .path()
+                .join("apps")
+                .join(format!("{name}.{extension}"))
+                .is_file()
+        })
+    })
+}
+
+/// Human readable description of how long ago something happened, given the elapsed seconds
+pub fn time_ago(seconds: i64) -> String {
+    const MINUTE: i64 = 60;
+    const HOUR: i64 = 60 * MINUTE;
+    const DAY: i64 = 24 * HOUR;
+    const MONTH: i64 = 30 * DAY;
+    const YEAR: i64 = 365 * DAY;
+
+    let (amount, unit) = match seconds {
+        s if s < MINUTE => return "just now".to_string(),
+        s if s < HOUR => (s / MINUTE, "minute"),
+        s if s < DAY => (s / HOUR, "hour"),
+        s if s < MONTH => (s / DAY, "day"),
+        s if s < YEAR => (s / MONTH, "month"),
+        s => (s / YEAR, "year"),
+    };
+
+    if amount == 1 {
+        format!("1 {unit} ago")
+    } else {
+        format!("{amount} {unit}s ago")
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::path::{Path, PathBuf};
+
+    use super::{desktop_file_id_in, hicolor_size_dir, time_ago};
+
+    #[test]
+    fn desktop_file_ids() {
+        let dirs = [
+            PathBuf::from("/home/user/.local/share/applications"),
+            PathBuf::from("/usr/share/applications"),
+        ];
+        assert_eq!(
+            desktop_file_id_in(
+                Path::new("/usr/share/applications/kde4/konsole.desktop"),
+                &dirs
+            ),
+            Some((1, "kde4-konsole.desktop".to_string()))
+        );
+        assert_eq!(
+            desktop_file_id_in(
+                Path::new("/home/user/.local/share/applications/firefox.desktop"),
+                &dirs
+            ),
+            Some((0, "firefox.desktop".to_string()))
+        );
+        assert_eq!(
+            desktop_file_id_in(Path::new("/tmp/applications/a.desktop"), &dirs),
+            None
+        );
+    }
+
+    #[test]
+    fn hicolor_sizes() {
+        assert_eq!(hicolor_size_dir(None), "scalable");
+        assert_eq!(hicolor_size_dir(Some(50)), "48x48");
+        assert_eq!(hicolor_size_dir(Some(1024)), "512x512");
+    }
+
+    #[test]
+    fn time_ago_recent() {
+        assert_eq!(time_ago(-5), "just now");
+        assert_eq!(time_ago(30), "just now");
+    }
+
+    #[test]
+    fn time_ago_units() {
+        assert_eq!(time_ago(60), "1 minute ago");
+        assert_eq!(time_ago(2 * 60 * 60), "2 hours ago");
+        assert_eq!(time_ago(2 * 24 * 60 * 60 + 100), "2 days ago");
+        assert_eq!(time_ago(400 * 24 * 60 * 60), "1 year ago");
+    }
+}
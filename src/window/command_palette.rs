@@ -0,0 +1,534 @@
+/*
+* Copyright © 2025 Alessandro Balducci
+*
+* This file is part of Desktop File Editor.
+* Desktop File Editor is free software: you can redistribute it and/or modify it under the terms of the
+* GNU General Public License as published by the Free Software Foundation,
+* either version 3 of the License, or (at your option) any later version.
+* Desktop File Editor is distributed in the hope that it will be useful, but WITHOUT ANY WARRANTY;
+* without even the implied warranty of MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.
+* See the GNU General Public License for more details.
+* You should have received a copy of the GNU General Public License along with Desktop File Editor. If not, see <https://www.gnu.org/licenses/>.
+*/
+
+use adw::prelude::*;
+use gtk::glib::{self, subclass::types::ObjectSubclassIsExt, Object};
+
+/// Where a command can be activated from
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CommandScope {
+    /// Available everywhere in the window
+    Window,
+    /// Only available while a desktop file is open
+    Editor,
+}
+
+#[derive(Debug)]
+pub struct PaletteCommand {
+    pub title: &'static str,
+    pub action: &'static str,
+    pub accel: Option<&'static str>,
+    pub scope: CommandScope,
+}
+
+/// Every command that can be searched in the palette
+pub const COMMANDS: &[PaletteCommand] = &[
+    PaletteCommand {
+        title: "New Desktop File",
+        action: "app.new-file",
+        accel: Some("<Control>n"),
+        scope: CommandScope::Window,
+    },
+    PaletteCommand {
+        title: "New Launcher for Installed Program",
+        action: "win.new-launcher-from-binary",
+        accel: None,
+        scope: CommandScope::Window,
+    },
+    PaletteCommand {
+        title: "New Script Launcher",
+        action: "win.new-script-launcher",
+        accel: None,
+        scope: CommandScope::Window,
+    },
+    PaletteCommand {
+        title: "New Web App",
+        action: "win.new-web-app",
+        accel: None,
+        scope: CommandScope::Window,
+    },
+    PaletteCommand {
+        title: "New Empty Launcher",
+        action: "win.new-empty-launcher",
+        accel: None,
+        scope: CommandScope::Window,
+    },
+    PaletteCommand {
+        title: "Paste as New File",
+        action: "win.paste-new-file",
+        accel: None,
+        scope: CommandScope::Window,
+    },
+    PaletteCommand {
+        title: "Toggle Only Show Selected",
+        action: "win.filter-only-show-selected",
+        accel: None,
+        scope: CommandScope::Window,
+    },
+    PaletteCommand {
+        title: "Toggle Hidden Entries Filter",
+        action: "win.filter-hidden",
+        accel: None,
+        scope: CommandScope::Window,
+    },
+    PaletteCommand {
+        title: "Toggle Errors Filter",
+        action: "win.filter-invalid",
+        accel: None,
+        scope: CommandScope::Window,
+    },
+    PaletteCommand {
+        title: "Toggle Warnings Filter",
+        action: "win.filter-warnings",
+        accel: None,
+        scope: CommandScope::Window,
+    },
+    PaletteCommand {
+        title: "Toggle Uninstalled Steam Games Filter",
+        action: "win.filter-uninstalled-steam",
+        accel: None,
+        scope: CommandScope::Window,
+    },
+    PaletteCommand {
+        title: "Remove Uninstalled Steam Games",
+        action: "win.remove-uninstalled-steam",
+        accel: None,
+        scope: CommandScope::Window,
+    },
+    PaletteCommand {
+        title: "Open Desktop File",
+        action: "win.open-file",
+        accel: Some("<Control>o"),
+        scope: CommandScope::Window,
+    },
+    PaletteCommand {
+        title: "Preferences",
+        action: "win.preferences",
+        accel: Some("<Control>comma"),
+        scope: CommandScope::Window,
+    },
+    PaletteCommand {
+        title: "Take the Tour",
+        action: "win.start-tour",
+        accel: None,
+        scope: CommandScope::Window,
+    },
+    PaletteCommand {
+        title: "Save",
+        action: "win.save",
+        accel: Some("<Control>s"),
+        scope: CommandScope::Editor,
+    },
+    PaletteCommand {
+        title: "Discard Changes",
+        action: "win.reset",
+        accel: Some("<Control><Shift>r"),
+        scope: CommandScope::Editor,
+    },
+    PaletteCommand {
+        title: "Reload From Disk",
+        action: "win.reload",
+        accel: Some("<Control>r"),
+        scope: CommandScope::Editor,
+    },
+    PaletteCommand {
+        title: "Add Locale",
+        action: "win.add-locale",
+        accel: None,
+        scope: CommandScope::Editor,
+    },
+    PaletteCommand {
+        title: "Add Action",
+        action: "win.add-action",
+        accel: None,
+        scope: CommandScope::Editor,
+    },
+    PaletteCommand {
+        title: "Add Group",
+        action: "win.add-group",
+        accel: None,
+        scope: CommandScope::Editor,
+    },
+    PaletteCommand {
+        title: "Import From File…",
+        action: "win.import-from-file",
+        accel: None,
+        scope: CommandScope::Editor,
+    },
+    PaletteCommand {
+        title: "Paste Group",
+        action: "win.paste-group",
+        accel: None,
+        scope: CommandScope::Editor,
+    },
+    PaletteCommand {
+        title: "Remove Translations…",
+        action: "win.strip-translations",
+        accel: None,
+        scope: CommandScope::Editor,
+    },
+    PaletteCommand {
+        title: "Test Launch",
+        action: "win.test-launch",
+        accel: Some("<Control>Return"),
+        scope: CommandScope::Editor,
+    },
+    PaletteCommand {
+        title: "Launch",
+        action: "win.launch",
+        accel: Some("<Control><Shift>Return"),
+        scope: CommandScope::Editor,
+    },
+    PaletteCommand {
+        title: "Toggle Launcher Preview",
+        action: "win.show-preview",
+        accel: None,
+        scope: CommandScope::Editor,
+    },
+    PaletteCommand {
+        title: "Edit as Text",
+        action: "win.edit-as-text",
+        accel: None,
+        scope: CommandScope::Editor,
+    },
+    PaletteCommand {
+        title: "Undo",
+        action: "win.undo",
+        accel: Some("<Control>z"),
+        scope: CommandScope::Editor,
+    },
+    PaletteCommand {
+        title: "Redo",
+        action: "win.redo",
+        accel: Some("<Control><Shift>z"),
+        scope: CommandScope::Editor,
+    },
+    PaletteCommand {
+        title: "Check Entry…",
+        action: "win.check-entry",
+        accel: None,
+        scope: CommandScope::Editor,
+    },
+    PaletteCommand {
+        title: "Override in User Directory",
+        action: "win.override-in-user-dir",
+        accel: None,
+        scope: CommandScope::Editor,
+    },
+    PaletteCommand {
+        title: "Save as Template",
+        action: "win.save-as-template",
+        accel: None,
+        scope: CommandScope::Editor,
+    },
+    PaletteCommand {
+        title: "Restore Previous Version",
+        action: "win.restore-backup",
+        accel: None,
+        scope: CommandScope::Editor,
+    },
+    PaletteCommand {
+        title: "Open in Text Editor",
+        action: "win.open-in-text-editor",
+        accel: None,
+        scope: CommandScope::Editor,
+    },
+    PaletteCommand {
+        title: "Rename File",
+        action: "win.rename-file",
+        accel: None,
+        scope: CommandScope::Editor,
+    },
+];
+
+mod imp {
+    use std::cell::RefCell;
+    use std::rc::Rc;
+
+    use adw::{prelude::*, subclass::prelude::*};
+    use gtk::{
+        gdk,
+        glib::{self, clone, Propagation},
+    };
+
+    use super::{fuzzy_score, CommandScope, PaletteCommand, COMMANDS};
+
+    #[derive(Default)]
+    pub struct CommandPalette {
+        pub search_entry: gtk::SearchEntry,
+        pub list_box: gtk::ListBox,
+        pub window_target: RefCell<Option<gtk::Widget>>,
+        pub editor_target: RefCell<Option<gtk::Widget>>,
+        pub commands: RefCell<Vec<(&'static PaletteCommand, gtk::ListBoxRow)>>,
+    }
+
+    #[glib::object_subclass]
+    impl ObjectSubclass for CommandPalette {
+        const NAME: &'static str = "CommandPalette";
+        type Type = super::CommandPalette;
+        type ParentType = adw::Dialog;
+    }
+
+    impl ObjectImpl for CommandPalette {
+        fn constructed(&self) {
+            self.parent_constructed();
+            let obj = self.obj();
+
+            obj.set_title("Commands");
+            obj.set_content_width(420);
+            obj.set_content_height(420);
+
+            self.search_entry.set_placeholder_text(Some("Search commands"));
+            self.search_entry.set_hexpand(true);
+            self.search_entry.connect_search_changed(clone!(
+                #[weak(rename_to = palette)]
+                obj,
+                move |_| palette.imp().refilter()
+            ));
+            self.search_entry.connect_activate(clone!(
+                #[weak(rename_to = palette)]
+                obj,
+                move |_| {
+                    let imp = palette.imp();
+                    if let Some(row) = imp.first_visible_row() {
+                        imp.activate_row(&row);
+                    }
+                }
+            ));
+
+            // Let the arrow keys move from the search entry to the commands
+            let key_controller = gtk::EventControllerKey::new();
+            key_controller.connect_key_pressed(clone!(
+                #[weak(rename_to = palette)]
+                obj,
+                #[upgrade_or]
+                Propagation::Proceed,
+                move |_, key, _, _| {
+                    if key != gdk::Key::Down {
+                        return Propagation::Proceed;
+                    }
+
+                    match palette.imp().first_visible_row() {
+                        Some(row) => {
+                            row.grab_focus();
+                            Propagation::Stop
+                        }
+                        None => Propagation::Proceed,
+                    }
+                }
+            ));
+            self.search_entry.add_controller(key_controller);
+
+            self.list_box.add_css_class("boxed-list");
+            self.list_box.set_selection_mode(gtk::SelectionMode::None);
+            self.list_box.set_valign(gtk::Align::Start);
+            self.list_box.connect_row_activated(clone!(
+                #[weak(rename_to = palette)]
+                obj,
+                move |_, row| palette.imp().activate_row(row)
+            ));
+
+            let header_bar = adw::HeaderBar::new();
+            header_bar.set_title_widget(Some(&self.search_entry));
+
+            let scrolled_window = gtk::ScrolledWindow::new();
+            scrolled_window.set_hscrollbar_policy(gtk::PolicyType::Never);
+            scrolled_window.set_vexpand(true);
+            let clamp = adw::Clamp::new();
+            clamp.set_margin_top(12);
+            clamp.set_margin_bottom(12);
+            clamp.set_margin_start(12);
+            clamp.set_margin_end(12);
+            clamp.set_child(Some(&self.list_box));
+            scrolled_window.set_child(Some(&clamp));
+
+            let toolbar_view = adw::ToolbarView::new();
+            toolbar_view.add_top_bar(&header_bar);
+            toolbar_view.set_content(Some(&scrolled_window));
+
+            obj.set_child(Some(&toolbar_view));
+            obj.set_focus(Some(&self.search_entry));
+        }
+    }
+
+    impl WidgetImpl for CommandPalette {}
+    impl AdwDialogImpl for CommandPalette {}
+
+    impl CommandPalette {
+        pub fn populate(&self) {
+            let has_editor = self.editor_target.borrow().is_some();
+            let commands: Vec<_> = COMMANDS
+                .iter()
+                .filter(|command| has_editor || command.scope == CommandScope::Window)
+                .map(|command| {
+                    let row = adw::ActionRow::new();
+                    row.set_title(command.title);
+                    row.set_activatable(true);
+                    if let Some(accel) = command.accel {
+                        let shortcut = gtk::ShortcutLabel::new(accel);
+                        shortcut.set_valign(gtk::Align::Center);
+                        row.add_suffix(&shortcut);
+                    }
+                    self.list_box.append(&row);
+                    (command, row.upcast::<gtk::ListBoxRow>())
+                })
+                .collect();
+
+            self.commands.replace(commands);
+            self.refilter();
+        }
+
+        fn refilter(&self) {
+            let query = self.search_entry.text().to_lowercase();
+            let scores: Rc<Vec<_>> = Rc::new(
+                self.commands
+                    .borrow()
+                    .iter()
+                    .map(|(command, row)| (row.clone(), fuzzy_score(&query, command.title)))
+                    .collect(),
+            );
+
+            // Rows are moved around by sorting, so they are looked up instead of using their index
+            let score_of = |scores: &[(gtk::ListBoxRow, Option<i32>)], row: &gtk::ListBoxRow| {
+                scores
+                    .iter()
+                    .position(|(r, _)| r == row)
+                    .map(|position| (scores[position].1, position))
+                    .unwrap_or_default()
+            };
+
+            self.list_box.set_filter_func(clone!(
+                #[strong]
+                scores,
+                move |row| score_of(&scores, row).0.is_some()
+            ));
+
+            // Best matches first, ties keep the declaration order
+            self.list_box.set_sort_func(move |row1, row2| {
+                let (score1, position1) = score_of(&scores, row1);
+                let (score2, position2) = score_of(&scores, row2);
+                score2
+                    .cmp(&score1)
+                    .then(position1.cmp(&position2))
+                    .into()
+            });
+        }
+
+        fn first_visible_row(&self) -> Option<gtk::ListBoxRow> {
+            (0..)
+                .map_while(|index| self.list_box.row_at_index(index))
+                .find(|row| row.is_child_visible())
+        }
+
+        fn activate_row(&self, row: &gtk::ListBoxRow) {
+            let Some(command) = self
+                .commands
+                .borrow()
+                .iter()
+                .find_map(|(command, r)| (r == row).then_some(*command))
+            else {
+                return;
+            };
+            let target = match command.scope {
+                CommandScope::Window => self.window_target.borrow().clone(),
+                CommandScope::Editor => self.editor_target.borrow().clone(),
+            };
+
+            self.obj().close();
+
+            if let Some(target) = target {
+                if let Err(e) = target.activate_action(command.action, None) {
+                    eprintln!("Failed to activate {}: {e}", command.action);
+                }
+            }
+        }
+    }
+}
+
+glib::wrapper! {
+    /// Searchable list of the actions available in the window
+    pub struct CommandPalette(ObjectSubclass<imp::CommandPalette>)
+        @extends adw::Dialog, gtk::Widget,
+        @implements gtk::Accessible, gtk::Buildable, gtk::ConstraintTarget, gtk::ShortcutManager;
+}
+
+impl CommandPalette {
+    /// Editor commands are activated on `editor` and only listed when it is set
+    pub fn new(window: &impl IsA<gtk::Widget>, editor: Option<&impl IsA<gtk::Widget>>) -> Self {
+        let palette: Self = Object::builder().build();
+        let imp = palette.imp();
+        imp.window_target.replace(Some(window.clone().upcast()));
+        imp.editor_target
+            .replace(editor.map(|editor| editor.clone().upcast()));
+        imp.populate();
+        palette
+    }
+}
+
+/// Score of how well `query` matches `text` as a subsequence, higher is better.
+/// Consecutive characters and characters at the start of words are worth more.
+/// `query` is expected to be lowercase
+fn fuzzy_score(query: &str, text: &str) -> Option<i32> {
+    let mut score = 0;
+    let mut query_chars = query.chars().filter(|c| !c.is_whitespace()).peekable();
+    let mut previous_matched = false;
+    let mut previous_char = ' ';
+
+    for c in text.chars() {
+        let Some(&q) = query_chars.peek() else {
+            break;
+        };
+
+        if c.to_lowercase().eq(q.to_lowercase()) {
+            score += 1;
+            if previous_matched {
+                score += 2;
+            }
+            if !previous_char.is_alphanumeric() {
+                score += 3;
+            }
+            query_chars.next();
+            previous_matched = true;
+        } else {
+            previous_matched = false;
+        }
+        previous_char = c;
+    }
+
+    query_chars.peek().is_none().then_some(score)
+}
+
+#[cfg(test)]
+mod test {
+    use super::fuzzy_score;
+
+    #[test]
+    fn fuzzy_empty_query() {
+        assert_eq!(fuzzy_score("", "Save"), Some(0));
+    }
+
+    #[test]
+    fn fuzzy_subsequence() {
+        assert!(fuzzy_score("sv", "Save").is_some());
+        assert!(fuzzy_score("al", "Add Locale").is_some());
+        assert!(fuzzy_score("xyz", "Save").is_none());
+        assert!(fuzzy_score("evas", "Save").is_none());
+    }
+
+    #[test]
+    fn fuzzy_prefers_word_starts() {
+        let word_starts = fuzzy_score("lo", "Add Locale").unwrap();
+        let inner = fuzzy_score("lo", "Reload").unwrap();
+        assert!(word_starts > inner);
+    }
+}
@@ -14,18 +14,21 @@
 use gtk::glib;
 
 mod imp {
-    use std::cell::Cell;
+    use std::cell::{Cell, RefCell};
+    use std::path::PathBuf;
 
     use adw::prelude::*;
     use adw::subclass::prelude::*;
-    use gtk::glib::{self, Properties};
+    use gtk::glib::{self, clone, Properties};
     use gtk::glib::{
         object_subclass,
         subclass::{object::ObjectImpl, types::ObjectSubclass},
     };
     use gtk::subclass::filter::FilterImpl;
 
-    use crate::window::file_entry::{FileEntry, ShouldShow};
+    use crate::edited_files::is_edited;
+    use crate::settings::settings;
+    use crate::window::file_entry::{current_desktop, FileEntry, ShouldShow};
 
     #[derive(Default, Properties)]
     #[properties(wrapper_type = super::EntryFilter)]
@@ -38,6 +41,28 @@ mod imp {
 
         #[property(get, set)]
         pub invalid: Cell<bool>,
+
+        #[property(get, set)]
+        pub warnings: Cell<bool>,
+
+        /// Only show the launchers of Steam games that are no longer installed
+        #[property(get, set)]
+        pub uninstalled_steam: Cell<bool>,
+
+        #[property(get, set, nullable)]
+        pub category: RefCell<Option<String>>,
+
+        /// `user`, `system` or a path prefix
+        #[property(get, set, nullable)]
+        pub directory: RefCell<Option<String>>,
+
+        /// Only show the entries listed in the simulated desktop, or in the one of the session
+        #[property(get, set)]
+        pub shown_in_desktop: Cell<bool>,
+
+        /// Only show the entries that were saved with the editor
+        #[property(get, set)]
+        pub edited: Cell<bool>,
     }
 
     #[object_subclass]
@@ -55,6 +80,21 @@ mod imp {
             obj.connect_only_show_selected_notify(filter_updated);
             obj.connect_hidden_notify(filter_updated);
             obj.connect_invalid_notify(filter_updated);
+            obj.connect_warnings_notify(filter_updated);
+            obj.connect_uninstalled_steam_notify(filter_updated);
+            obj.connect_category_notify(filter_updated);
+            obj.connect_directory_notify(filter_updated);
+            obj.connect_shown_in_desktop_notify(filter_updated);
+            obj.connect_edited_notify(filter_updated);
+            settings().connect_simulated_desktop_notify(clone!(
+                #[weak]
+                obj,
+                move |_| {
+                    if obj.shown_in_desktop() {
+                        filter_updated(&obj);
+                    }
+                }
+            ));
         }
     }
 
@@ -65,6 +105,21 @@ mod imp {
                 .downcast::<FileEntry>()
                 .expect("item should be `EntryObj`");
 
+            // Chip filters always restrict the results, whatever the mode
+            if !self.matches_chips(&file_entry) {
+                return false;
+            }
+
+            // These entries are invalid, they would be hidden by the other filters
+            if self.uninstalled_steam.get() {
+                return file_entry.validity_status().is_uninstalled_steam_app();
+            }
+
+            // Warnings are only what sets an entry apart when it has no errors, entries with
+            // errors are left to the errors toggle whatever their warnings
+            let status = file_entry.validity_status();
+            let only_warnings = status.is_valid() && status.has_warnings();
+
             if self.only_show_selected.get() {
                 let mut matches = false;
 
@@ -73,7 +128,11 @@ mod imp {
                 }
 
                 if self.invalid.get() {
-                    matches |= !file_entry.validity_status().is_valid();
+                    matches |= !status.is_valid();
+                }
+
+                if self.warnings.get() {
+                    matches |= only_warnings;
                 }
 
                 matches
@@ -85,7 +144,11 @@ mod imp {
                 }
 
                 if !self.invalid.get() {
-                    matches &= file_entry.validity_status().is_valid();
+                    matches &= status.is_valid();
+                }
+
+                if !self.warnings.get() {
+                    matches &= !only_warnings;
                 }
 
                 matches
@@ -93,6 +156,50 @@ mod imp {
         }
     }
 
+    impl EntryFilter {
+        fn matches_chips(&self, file_entry: &FileEntry) -> bool {
+            if let Some(category) = self.category.borrow().as_ref() {
+                let categories = file_entry.categories().unwrap_or_default();
+                if !categories
+                    .split(", ")
+                    .any(|c| c.eq_ignore_ascii_case(category))
+                {
+                    return false;
+                }
+            }
+
+            if let Some(directory) = self.directory.borrow().as_ref() {
+                let user_dir = glib::user_data_dir().join("applications");
+                let path = file_entry.path();
+                let matches = match &directory[..] {
+                    "user" => path.starts_with(&user_dir),
+                    "system" => !path.starts_with(&user_dir),
+                    prefix => path.starts_with(expand_home(prefix)),
+                };
+                if !matches {
+                    return false;
+                }
+            }
+
+            if self.shown_in_desktop.get() && !file_entry.shown_in(&current_desktop()) {
+                return false;
+            }
+
+            if self.edited.get() && !is_edited(&file_entry.path()) {
+                return false;
+            }
+
+            true
+        }
+    }
+
+    fn expand_home(path: &str) -> PathBuf {
+        match path.strip_prefix("~/") {
+            Some(relative) => glib::home_dir().join(relative),
+            None => PathBuf::from(path),
+        }
+    }
+
     fn filter_updated(filter: &super::EntryFilter) {
         filter.changed(gtk::FilterChange::Different);
     }
@@ -104,17 +211,138 @@ glib::wrapper! {
 }
 
 impl EntryFilter {
-    pub fn new(only_show_selected: bool, show_hidden: bool, show_invalid: bool) -> Self {
+    pub fn new(
+        only_show_selected: bool,
+        show_hidden: bool,
+        show_invalid: bool,
+        show_warnings: bool,
+    ) -> Self {
         glib::Object::builder()
             .property("only-show-selected", only_show_selected)
             .property("hidden", show_hidden)
             .property("invalid", show_invalid)
+            .property("warnings", show_warnings)
             .build()
     }
 }
 
+impl EntryFilter {
+    /// Apply a filter typed in the search entry
+    pub fn apply_token(&self, token: FilterToken) {
+        match token {
+            FilterToken::Category(category) => self.set_category(Some(category)),
+            FilterToken::Directory(directory) => self.set_directory(Some(directory)),
+            FilterToken::Hidden => self.set_hidden(true),
+            FilterToken::Invalid => self.set_invalid(true),
+            FilterToken::UninstalledSteam => self.set_uninstalled_steam(true),
+            FilterToken::Edited => self.set_edited(true),
+        }
+    }
+}
+
+/// A filter that can be typed in the search entry, such as `category:Game`
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum FilterToken {
+    Category(String),
+    Directory(String),
+    Hidden,
+    Invalid,
+    UninstalledSteam,
+    Edited,
+}
+
+impl FilterToken {
+    fn parse(word: &str) -> Option<Self> {
+        let (key, value) = word.split_once(':')?;
+        match (key, value) {
+            (_, "") => None,
+            ("category", category) => Some(Self::Category(category.to_string())),
+            ("dir", directory) => Some(Self::Directory(directory.to_string())),
+            ("is", "hidden") => Some(Self::Hidden),
+            ("is", "invalid") => Some(Self::Invalid),
+            ("is", "uninstalled-steam") => Some(Self::UninstalledSteam),
+            ("is", "edited") => Some(Self::Edited),
+            _ => None,
+        }
+    }
+}
+
+/// Extract the filter tokens from the search text, returning them along with the remaining text.
+/// The last word is only considered when `complete` is set, since it might still be being typed
+pub fn parse_filter_tokens(text: &str, complete: bool) -> (Vec<FilterToken>, String) {
+    let mut words: Vec<&str> = text.split(' ').collect();
+    let last = if complete { None } else { words.pop() };
+
+    let mut tokens = Vec::new();
+    let mut remaining = Vec::new();
+    for word in words {
+        match FilterToken::parse(word) {
+            Some(token) => tokens.push(token),
+            None => remaining.push(word),
+        }
+    }
+    remaining.extend(last);
+
+    (tokens, remaining.join(" "))
+}
+
 impl Default for EntryFilter {
     fn default() -> Self {
-        Self::new(false, false, false)
+        // Warnings are minor, so those entries are shown unless asked otherwise
+        Self::new(false, false, false, true)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::{parse_filter_tokens, FilterToken};
+
+    #[test]
+    fn tokens_need_a_space() {
+        let (tokens, text) = parse_filter_tokens("firefox category:Network", false);
+        assert!(tokens.is_empty());
+        assert_eq!(text, "firefox category:Network");
+    }
+
+    #[test]
+    fn tokens_are_extracted() {
+        let (tokens, text) = parse_filter_tokens("category:Game fire is:hidden dir:user ", false);
+        assert_eq!(
+            tokens,
+            vec![
+                FilterToken::Category("Game".to_string()),
+                FilterToken::Hidden,
+                FilterToken::Directory("user".to_string())
+            ]
+        );
+        assert_eq!(text, "fire ");
+    }
+
+    #[test]
+    fn complete_includes_last_word() {
+        let (tokens, text) = parse_filter_tokens("fire is:invalid", true);
+        assert_eq!(tokens, vec![FilterToken::Invalid]);
+        assert_eq!(text, "fire");
+    }
+
+    #[test]
+    fn uninstalled_steam_token() {
+        let (tokens, text) = parse_filter_tokens("is:uninstalled-steam", true);
+        assert_eq!(tokens, vec![FilterToken::UninstalledSteam]);
+        assert!(text.is_empty());
+    }
+
+    #[test]
+    fn edited_token() {
+        let (tokens, text) = parse_filter_tokens("is:edited fire", true);
+        assert_eq!(tokens, vec![FilterToken::Edited]);
+        assert_eq!(text, "fire");
+    }
+
+    #[test]
+    fn unknown_tokens_are_text() {
+        let (tokens, text) = parse_filter_tokens("is:nothing category: ", false);
+        assert!(tokens.is_empty());
+        assert_eq!(text, "is:nothing category: ");
     }
 }
@@ -0,0 +1,178 @@
+/*
+* Copyright © 2025 Alessandro Balducci
+*
+* This file is part of Desktop File Editor.
+* Desktop File Editor is free software: you can redistribute it and/or modify it under the terms of the
+* GNU General Public License as published by the Free Software Foundation,
+* either version 3 of the License, or (at your option) any later version.
+* Desktop File Editor is distributed in the hope that it will be useful, but WITHOUT ANY WARRANTY;
+* without even the implied warranty of MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.
+* See the GNU General Public License for more details.
+* You should have received a copy of the GNU General Public License along with Desktop File Editor. If not, see <https://www.gnu.org/licenses/>.
+*/
+
+use gtk::{
+    glib::{self, Object},
+    pango::EllipsizeMode,
+    prelude::*,
+    Align, Image, Label, Orientation,
+};
+
+use crate::util;
+
+use super::file_entry::{FileEntry, ToGIcon};
+
+mod imp {
+    use adw::subclass::prelude::*;
+    use gtk::glib::{self, object_subclass};
+
+    #[derive(Debug, Default)]
+    pub struct EntryPreview {}
+
+    #[object_subclass]
+    impl ObjectSubclass for EntryPreview {
+        const NAME: &'static str = "DMEntryPreview";
+        type Type = super::EntryPreview;
+        type ParentType = gtk::Box;
+    }
+
+    impl ObjectImpl for EntryPreview {}
+    impl WidgetImpl for EntryPreview {}
+    impl BoxImpl for EntryPreview {}
+}
+
+glib::wrapper! {
+    /// Summary card of a desktop entry, used to inspect entries without opening them
+    pub struct EntryPreview(ObjectSubclass<imp::EntryPreview>)
+    @extends gtk::Widget, gtk::Box,
+    @implements gtk::Accessible, gtk::Buildable, gtk::ConstraintTarget, gtk::Orientable;
+}
+
+impl EntryPreview {
+    const MAX_WIDTH_CHARS: i32 = 40;
+
+    pub fn new(entry: &FileEntry) -> Self {
+        let preview: Self = Object::builder()
+            .property("orientation", Orientation::Vertical)
+            .property("spacing", 12)
+            .property("margin-top", 6)
+            .property("margin-bottom", 6)
+            .property("margin-start", 6)
+            .property("margin-end", 6)
+            .build();
+
+        preview.append(&Self::header(entry));
+
+        if let Some(comment) = entry.comment() {
+            let label = Self::value_label(&comment);
+            label.set_wrap(true);
+            preview.append(&label);
+        }
+
+        if let Some(categories) = entry.categories() {
+            preview.append(&Self::field("Categories", &categories));
+        }
+
+        if let Some(exec) = entry.exec() {
+            preview.append(&Self::field("Command", &exec));
+        }
+
+        preview.append(&Self::field("Desktop File ID", &entry.desktop_file_id()));
+
+        if let Some(note) = entry.note() {
+            preview.append(&Self::status_row(
+                "document-edit-symbolic",
+                "dim-label",
+                &note,
+            ));
+        }
+
+        let status = entry.validity_status();
+        if let Some(errors) = status.error_string() {
+            preview.append(&Self::status_row("dialog-error-symbolic", "error", &errors));
+        }
+        if let Some(warnings) = status.warning_string() {
+            preview.append(&Self::status_row(
+                "dialog-warning-symbolic",
+                "warning",
+                &warnings,
+            ));
+        }
+        if let Some(shadowed_by) = entry.shadowed_by() {
+            let text = format!(
+                "Not used, shadowed by {}",
+                util::display_path(&shadowed_by).to_string_lossy()
+            );
+            preview.append(&Self::status_row(
+                "view-conceal-symbolic",
+                "dim-label",
+                &text,
+            ));
+        }
+
+        preview
+    }
+
+    fn header(entry: &FileEntry) -> gtk::Box {
+        let header = gtk::Box::new(Orientation::Horizontal, 12);
+
+        let icon = Image::from_gicon(&entry.gicon());
+        icon.set_pixel_size(48);
+        icon.add_css_class("icon-dropshadow");
+        header.append(&icon);
+
+        let titles = gtk::Box::new(Orientation::Vertical, 6);
+        titles.set_valign(Align::Center);
+
+        let name = Self::value_label(&entry.display_name().unwrap_or_default());
+        name.add_css_class("title-4");
+        titles.append(&name);
+
+        if let Some(generic_name) = entry.generic_name() {
+            let label = Self::value_label(&generic_name);
+            label.add_css_class("dim-label");
+            titles.append(&label);
+        }
+
+        header.append(&titles);
+        header
+    }
+
+    fn field(title: &str, value: &str) -> gtk::Box {
+        let field = gtk::Box::new(Orientation::Vertical, 3);
+
+        let title = Label::new(Some(title));
+        title.set_halign(Align::Start);
+        title.add_css_class("caption-heading");
+        field.append(&title);
+
+        let value = Self::value_label(value);
+        value.set_ellipsize(EllipsizeMode::End);
+        field.append(&value);
+
+        field
+    }
+
+    fn status_row(icon_name: &str, style: &str, text: &str) -> gtk::Box {
+        let row = gtk::Box::new(Orientation::Horizontal, 6);
+
+        let icon = Image::from_icon_name(icon_name);
+        icon.set_valign(Align::Start);
+        icon.add_css_class(style);
+        row.append(&icon);
+
+        let label = Self::value_label(text);
+        label.set_wrap(true);
+        row.append(&label);
+
+        row
+    }
+
+    fn value_label(text: &str) -> Label {
+        let label = Label::new(Some(text));
+        label.set_halign(Align::Start);
+        label.set_xalign(0.0);
+        label.set_max_width_chars(Self::MAX_WIDTH_CHARS);
+        label
+    }
+}
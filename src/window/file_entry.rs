@@ -14,15 +14,27 @@
 use std::{
     fs,
     path::{Path, PathBuf},
+    time::UNIX_EPOCH,
 };
 
-use freedesktop_desktop_entry::{DecodeError, DesktopEntry};
+use freedesktop_desktop_entry::{get_languages_from_env, DecodeError, DesktopEntry};
 use gtk::{
-    gio,
-    glib::{self, subclass::types::ObjectSubclassIsExt, Object},
+    gdk, gio,
+    glib::{self, closure, prelude::*, subclass::types::ObjectSubclassIsExt, Object},
+    ClosureExpression, Expression, GObjectPropertyExpressionExt, PropertyExpression,
 };
 
-use crate::{desktop_file_view::desktop_entry_ext::NO_LOCALE, shellparse, util};
+use crate::{
+    dbus,
+    desktop_file_view::{
+        desktop_entry_ext::{split_unrecognized, unescape_line_breaks, NO_LOCALE},
+        known_entries::{key_info, GroupKind, DEPRECATED_KEYS, NOTE_KEY},
+    },
+    settings::settings,
+    shellparse, util,
+};
+
+use super::scan_cache::CachedEntry;
 
 mod imp {
     use adw::prelude::ObjectExt;
@@ -55,6 +67,55 @@ mod imp {
 
         #[property(get, set)]
         pub validity_status: RefCell<ValidityStatus>,
+
+        #[property(get, set, nullable)]
+        pub localized_name: RefCell<Option<String>>,
+
+        #[property(get, set, nullable)]
+        pub comment: RefCell<Option<String>>,
+
+        #[property(get, set, nullable)]
+        pub generic_name: RefCell<Option<String>>,
+
+        #[property(get, set, nullable)]
+        pub categories: RefCell<Option<String>>,
+
+        /// Semicolon separated desktops, as written in the file
+        #[property(get, set, nullable)]
+        pub only_show_in: RefCell<Option<String>>,
+
+        #[property(get, set, nullable)]
+        pub not_show_in: RefCell<Option<String>>,
+
+        #[property(get, set, nullable)]
+        pub exec: RefCell<Option<String>>,
+
+        /// Private note of the user about the file, with line breaks unescaped
+        #[property(get, set, nullable)]
+        pub note: RefCell<Option<String>>,
+
+        /// Number of Desktop Actions, the items of the context menu of the launcher
+        #[property(get)]
+        pub action_count: Cell<u32>,
+
+        /// Names of the Desktop Actions, translated when possible
+        #[property(get)]
+        pub action_names: RefCell<Vec<String>>,
+
+        #[property(get, set, nullable)]
+        pub modified: RefCell<Option<glib::DateTime>>,
+
+        #[property(get, set)]
+        pub desktop_file_id: RefCell<String>,
+
+        /// Entry with the same desktop file ID that launchers use instead of this one
+        #[property(get, set, nullable)]
+        pub shadowed_by: RefCell<Option<PathBuf>>,
+
+        /// Entries with the same desktop file ID that launchers ignore in favor of this one, in
+        /// order of precedence
+        #[property(get, set)]
+        pub overrides: RefCell<Vec<String>>,
     }
 
     #[object_subclass]
@@ -96,7 +157,12 @@ impl FileEntry {
             )
         });
 
-        let entry = DesktopEntry::from_path(path.clone(), Some(&NO_LOCALE))?;
+        // Keep the user's translations around for the descriptive fields
+        let locales = get_languages_from_env();
+        // Lines that can't be decoded are left out, the rest of the file is still usable
+        let contents = fs::read_to_string(&path)?;
+        let (decodable, _) = split_unrecognized(&contents);
+        let entry = DesktopEntry::from_str(path.clone(), &decodable, Some(&locales))?;
 
         let name = entry.name(&NO_LOCALE).map(String::from);
 
@@ -111,13 +177,132 @@ impl FileEntry {
             .map(|x| x == "true")
             .unwrap_or(false);
 
-        Ok(FileEntry::new(
+        let modified = modified_time(&path);
+        let desktop_file_id = util::desktop_file_id(&path);
+        let file_entry = FileEntry::new(
             path,
             name,
             icon,
             ShouldShow::new(no_display, hidden),
             ValidityStatus::from_desktop_entry(&entry),
-        ))
+        );
+        file_entry.set_localized_name(entry.name(&locales).map(String::from));
+        file_entry.set_comment(entry.comment(&locales).map(String::from));
+        file_entry.set_generic_name(entry.generic_name(&locales).map(String::from));
+        file_entry.set_categories(entry.categories().map(|categories| categories.join(", ")));
+        file_entry.set_only_show_in(entry.desktop_entry("OnlyShowIn").map(String::from));
+        file_entry.set_not_show_in(entry.desktop_entry("NotShowIn").map(String::from));
+        file_entry.set_exec(entry.exec().map(String::from));
+        file_entry.set_note(
+            entry
+                .desktop_entry(NOTE_KEY)
+                .filter(|note| !note.is_empty())
+                .map(unescape_line_breaks),
+        );
+        file_entry.set_actions(action_names(&entry, &locales));
+        file_entry.set_modified(modified);
+        file_entry.set_desktop_file_id(desktop_file_id);
+        Ok(file_entry)
+    }
+
+    /// Decode the file at `path` into the metadata the list shows, `modified` being its
+    /// modification time in nanoseconds. No object is created, so this can run on another thread
+    pub fn read_cached(path: &Path, modified: u64) -> Result<CachedEntry, DecodeError> {
+        let target = fs::canonicalize(path)?;
+
+        let locales = get_languages_from_env();
+        let contents = fs::read_to_string(&target)?;
+        let (decodable, _) = split_unrecognized(&contents);
+        let entry = DesktopEntry::from_str(target.clone(), &decodable, Some(&locales))?;
+
+        Ok(CachedEntry {
+            modified,
+            target,
+            name: entry.name(&NO_LOCALE).map(String::from),
+            localized_name: entry.name(&locales).map(String::from),
+            icon: entry
+                .icon()
+                .map(|s| s.to_string())
+                .unwrap_or_else(|| String::from(Self::DEFAULT_ICON)),
+            no_display: entry.no_display(),
+            hidden: entry.desktop_entry("Hidden") == Some("true"),
+            comment: entry.comment(&locales).map(String::from),
+            generic_name: entry.generic_name(&locales).map(String::from),
+            categories: entry.categories().map(|categories| categories.join(", ")),
+            only_show_in: entry.desktop_entry("OnlyShowIn").map(String::from),
+            not_show_in: entry.desktop_entry("NotShowIn").map(String::from),
+            exec: entry.exec().map(String::from),
+            note: entry
+                .desktop_entry(NOTE_KEY)
+                .filter(|note| !note.is_empty())
+                .map(unescape_line_breaks),
+            actions: action_names(&entry, &locales),
+            warnings: find_warnings(&entry),
+        })
+    }
+
+    /// Rebuild an entry from the metadata saved by a previous scan, without reading the file
+    pub fn from_cached(cached: &CachedEntry) -> Self {
+        let seconds = (cached.modified / 1_000_000_000) as i64;
+        let file_entry = FileEntry::new(
+            cached.target.clone(),
+            cached.name.clone(),
+            cached.icon.clone(),
+            ShouldShow::new(cached.no_display, cached.hidden),
+            ValidityStatus::from_parts(
+                cached.name.is_none(),
+                cached.exec.as_deref(),
+                Some(&cached.icon),
+                cached.warnings.clone(),
+            ),
+        );
+        file_entry.set_localized_name(cached.localized_name.clone());
+        file_entry.set_comment(cached.comment.clone());
+        file_entry.set_generic_name(cached.generic_name.clone());
+        file_entry.set_categories(cached.categories.clone());
+        file_entry.set_only_show_in(cached.only_show_in.clone());
+        file_entry.set_not_show_in(cached.not_show_in.clone());
+        file_entry.set_exec(cached.exec.clone());
+        file_entry.set_note(cached.note.clone());
+        file_entry.set_actions(cached.actions.clone());
+        file_entry.set_modified(glib::DateTime::from_unix_local(seconds).ok());
+        file_entry.set_desktop_file_id(util::desktop_file_id(&cached.target));
+        file_entry
+    }
+
+    /// Metadata to save for the next scan, `modified` being the modification time of the file
+    /// in nanoseconds when it was read
+    pub fn to_cached(&self, modified: u64) -> CachedEntry {
+        let (no_display, hidden) = self.should_show().flags();
+        CachedEntry {
+            modified,
+            target: self.path(),
+            name: self.name(),
+            localized_name: self.localized_name(),
+            icon: self.icon(),
+            no_display,
+            hidden,
+            comment: self.comment(),
+            generic_name: self.generic_name(),
+            categories: self.categories(),
+            only_show_in: self.only_show_in(),
+            not_show_in: self.not_show_in(),
+            exec: self.exec(),
+            note: self.note(),
+            actions: self.action_names(),
+            warnings: self.validity_status().file_warnings().to_vec(),
+        }
+    }
+
+    /// Run the checks that depend on the rest of the system again, after it changed
+    pub fn recheck(&self) {
+        let status = self.validity_status();
+        self.set_validity_status(ValidityStatus::from_parts(
+            status.empty_name,
+            self.exec().as_deref(),
+            Some(&self.icon()),
+            status.file_warnings().to_vec(),
+        ));
     }
 
     pub fn update(&self) -> Result<(), DecodeError> {
@@ -128,17 +313,104 @@ impl FileEntry {
         imp.icon.swap(&updated_imp.icon);
         imp.should_show.swap(&updated_imp.should_show);
         imp.validity_status.swap(&updated_imp.validity_status);
+        imp.localized_name.swap(&updated_imp.localized_name);
+        imp.comment.swap(&updated_imp.comment);
+        imp.generic_name.swap(&updated_imp.generic_name);
+        imp.categories.swap(&updated_imp.categories);
+        imp.only_show_in.swap(&updated_imp.only_show_in);
+        imp.not_show_in.swap(&updated_imp.not_show_in);
+        imp.exec.swap(&updated_imp.exec);
+        imp.note.swap(&updated_imp.note);
+        imp.action_count.swap(&updated_imp.action_count);
+        imp.action_names.swap(&updated_imp.action_names);
+        imp.modified.swap(&updated_imp.modified);
+        imp.desktop_file_id.swap(&updated_imp.desktop_file_id);
         self.notify_name();
         self.notify_icon();
         self.notify_should_show();
         self.notify_validity_status();
+        self.notify_localized_name();
+        self.notify_comment();
+        self.notify_generic_name();
+        self.notify_categories();
+        self.notify_only_show_in();
+        self.notify_not_show_in();
+        self.notify_exec();
+        self.notify_note();
+        self.notify_action_count();
+        self.notify_action_names();
+        self.notify_modified();
+        self.notify_desktop_file_id();
         Ok(())
     }
 
+    /// Whether the launcher is listed in `current_desktop`, see [`shown_in_desktop`]
+    pub fn shown_in(&self, current_desktop: &str) -> bool {
+        shown_in_desktop(
+            self.only_show_in().as_deref(),
+            self.not_show_in().as_deref(),
+            current_desktop,
+        )
+    }
+
+    /// Set the names of the Desktop Actions, along with their count
+    pub fn set_actions(&self, names: Vec<String>) {
+        let imp = self.imp();
+        imp.action_count.set(names.len() as u32);
+        imp.action_names.replace(names);
+        self.notify_action_count();
+        self.notify_action_names();
+    }
+
+    /// The name shown to the user, translated if the user prefers so
+    pub fn display_name(&self) -> Option<String> {
+        Self::pick_display_name(
+            settings().translated_names(),
+            self.name(),
+            self.localized_name(),
+        )
+    }
+
+    /// Expression evaluating to the display name of the entry `this` evaluates to. The expression
+    /// is updated when either the names or the preference change
+    pub fn display_name_expression(this: Option<&Expression>) -> Expression {
+        ClosureExpression::new::<Option<String>>(
+            [
+                settings().property_expression("translated-names").upcast(),
+                PropertyExpression::new(Self::static_type(), this, "name").upcast(),
+                PropertyExpression::new(Self::static_type(), this, "localized-name").upcast(),
+            ],
+            closure!(|_: Option<Object>,
+                      prefer_translated: bool,
+                      name: Option<String>,
+                      localized_name: Option<String>| {
+                Self::pick_display_name(prefer_translated, name, localized_name)
+            }),
+        )
+        .upcast()
+    }
+
+    fn pick_display_name(
+        prefer_translated: bool,
+        name: Option<String>,
+        localized_name: Option<String>,
+    ) -> Option<String> {
+        if prefer_translated {
+            localized_name.or(name)
+        } else {
+            name
+        }
+    }
+
     pub fn search_key(&self) -> String {
+        // Both names are searchable, regardless of which one is shown, and so are the texts
+        // describing the application, to find it by what it does
         format!(
-            "{} {}",
+            "{} {} {} {} {}",
             self.name().unwrap_or_default(),
+            self.localized_name().unwrap_or_default(),
+            self.generic_name().unwrap_or_default(),
+            self.comment().unwrap_or_default(),
             self.path().to_str().unwrap_or_default()
         )
     }
@@ -151,11 +423,14 @@ pub trait ToGIcon {
         gio::Icon::for_string(Self::DEFAULT_ICON).unwrap()
     }
 
+    fn gicon_for_string(icon: &str) -> gio::Icon {
+        gio::Icon::for_string(icon).unwrap_or_else(|_| Self::default_exec_gicon())
+    }
+
     fn icon_string(&self) -> Option<String>;
 
     fn gicon(&self) -> gio::Icon {
-        gio::Icon::for_string(&self.icon_string().unwrap_or(Self::DEFAULT_ICON.to_string()))
-            .unwrap_or_else(|_| Self::default_exec_gicon())
+        Self::gicon_for_string(&self.icon_string().unwrap_or(Self::DEFAULT_ICON.to_string()))
     }
 }
 
@@ -183,6 +458,16 @@ impl ShouldShow {
             (false, false) => Self::Yes,
         }
     }
+
+    /// The NoDisplay and Hidden values this was built from
+    fn flags(self) -> (bool, bool) {
+        match self {
+            Self::NoDisplayAndHidden => (true, true),
+            Self::NoDisplay => (true, false),
+            Self::Hidden => (false, true),
+            Self::Yes => (false, false),
+        }
+    }
 }
 
 impl Default for ShouldShow {
@@ -191,23 +476,157 @@ impl Default for ShouldShow {
     }
 }
 
+/// The desktop being simulated, or the one of the session. Colon separated like
+/// XDG_CURRENT_DESKTOP, since a desktop can go by more than one name
+pub fn current_desktop() -> String {
+    let simulated = settings().simulated_desktop();
+    if !simulated.is_empty() {
+        return simulated;
+    }
+    std::env::var("XDG_CURRENT_DESKTOP").unwrap_or_default()
+}
+
+/// Whether a launcher with these OnlyShowIn and NotShowIn lists is shown in `current_desktop`.
+/// Its names are considered in order, the first one found in either list decides, otherwise
+/// the launcher is shown unless it has an OnlyShowIn list
+pub fn shown_in_desktop(
+    only_show_in: Option<&str>,
+    not_show_in: Option<&str>,
+    current_desktop: &str,
+) -> bool {
+    let names = |list: Option<&str>| {
+        list.unwrap_or_default()
+            .split(';')
+            .filter(|name| !name.is_empty())
+            .map(String::from)
+            .collect::<Vec<_>>()
+    };
+    let only_show_in = names(only_show_in);
+    let not_show_in = names(not_show_in);
+
+    for desktop in current_desktop.split(':').filter(|name| !name.is_empty()) {
+        if only_show_in.iter().any(|name| name == desktop) {
+            return true;
+        }
+        if not_show_in.iter().any(|name| name == desktop) {
+            return false;
+        }
+    }
+    only_show_in.is_empty()
+}
+
+/// A single failed check, along with the key it is about if there is one and a suggestion on
+/// how to fix it
+#[derive(Debug, Clone)]
+pub struct Problem {
+    pub key: Option<String>,
+    pub message: String,
+    pub suggestion: Option<String>,
+}
+
+impl Problem {
+    fn new(key: Option<&str>, message: impl Into<String>) -> Self {
+        Self {
+            key: key.map(String::from),
+            message: message.into(),
+            suggestion: None,
+        }
+    }
+
+    fn with_suggestion(mut self, suggestion: impl Into<String>) -> Self {
+        self.suggestion = Some(suggestion.into());
+        self
+    }
+}
+
+/// Problems found in a desktop entry. Errors make the entry unusable, while warnings are
+/// about things that should be improved
 #[derive(Debug, Default, Clone, glib::Boxed)]
 #[boxed_type(name = "ValidityStatus")]
 pub struct ValidityStatus {
     empty_name: bool,
     exec_ok: bool,
     exec_fail_reason: Option<String>,
+    /// The entry launches a Steam game that is no longer installed
+    steam_app_not_installed: bool,
+    warnings: Vec<Problem>,
+    /// The first warnings only depend on the file, the others on the rest of the system
+    file_warning_count: usize,
+    /// The command the Exec key runs, once `env` and shell scripts are looked through
+    command: Option<String>,
+    /// Where the binary of the command was found
+    binary_path: Option<PathBuf>,
+    /// Directories the binary was searched in
+    search_paths: Vec<PathBuf>,
+    /// The file the command points to, when it only lacks the executable bit to be run
+    non_executable_command: Option<PathBuf>,
 }
 
 impl ValidityStatus {
     pub fn from_desktop_entry(entry: &DesktopEntry) -> ValidityStatus {
-        let binary_search_paths = util::binary_search_paths();
+        Self::from_parts(
+            entry.name(&NO_LOCALE).is_none(),
+            entry.exec(),
+            entry.icon(),
+            find_warnings(entry),
+        )
+    }
 
-        let (exec_ok, exec_fail_reason) = match parse_exec(entry) {
-            Ok(binary) => match which::which_in_global(binary, binary_search_paths) {
-                Ok(_) => (true, None),
-                Err(e) => (false, Some(e.to_string())),
-            },
+    /// Run the checks that depend on the rest of the system, like whether the binary is
+    /// installed, on top of the results of the ones that only depend on the file
+    fn from_parts(
+        empty_name: bool,
+        exec: Option<&str>,
+        icon: Option<&str>,
+        mut warnings: Vec<Problem>,
+    ) -> ValidityStatus {
+        let file_warning_count = warnings.len();
+        warnings.extend(find_icon_warnings(icon));
+
+        let binary_search_paths = util::binary_search_paths();
+        let search_paths = binary_search_paths
+            .as_ref()
+            .map(|paths| std::env::split_paths(paths).collect())
+            .unwrap_or_default();
+
+        let mut command = None;
+        let mut binary_path = None;
+        let mut non_executable_command = None;
+        let mut steam_app_not_installed = false;
+        let (exec_ok, exec_fail_reason) = match parse_exec(exec) {
+            Ok(parsed) => {
+                let found = which::which_in_global(&parsed.command, binary_search_paths)
+                    .map(|mut found| found.next());
+                command = Some(parsed.to_exec_string());
+
+                // A path to a file that exists but can't be run has a more useful explanation
+                // than the binary not being found
+                let file = match &found {
+                    Ok(Some(path)) => Some(path.clone()),
+                    _ => parsed
+                        .command
+                        .contains('/')
+                        .then(|| PathBuf::from(&parsed.command)),
+                };
+                let file_problem = file.as_deref().and_then(command_file_problem);
+
+                match (found, file_problem) {
+                    (_, Some(problem)) => {
+                        if problem.fixable {
+                            non_executable_command = file;
+                        }
+                        (false, Some(problem.reason))
+                    }
+                    (Ok(Some(path)), None) => {
+                        binary_path = Some(path);
+                        (true, None)
+                    }
+                    (Ok(None), None) => {
+                        (false, Some(which::Error::CannotFindBinaryPath.to_string()))
+                    }
+                    (Err(e), None) => (false, Some(e.to_string())),
+                }
+            }
             Err(e) => match e {
                 // ExecError::WrongFormat(s) => (false, Some(format!("Wrong Exec Format: {s}"))),
                 // ExecError::ExecFieldIsEmpty => (false, Some("Exec field is empty".to_string())),
@@ -215,6 +634,7 @@ impl ValidityStatus {
 
                 #[cfg(feature = "steam")]
                 ExecError::SteamAppNotInstalled => {
+                    steam_app_not_installed = true;
                     (false, Some("Steam app not installed".to_string()))
                 }
 
@@ -227,9 +647,16 @@ impl ValidityStatus {
         );
 
         ValidityStatus {
-            empty_name: entry.name(&NO_LOCALE).is_none(),
+            empty_name,
             exec_ok,
             exec_fail_reason,
+            steam_app_not_installed,
+            warnings,
+            file_warning_count,
+            command,
+            binary_path,
+            search_paths,
+            non_executable_command,
         }
     }
 
@@ -237,6 +664,61 @@ impl ValidityStatus {
         !self.empty_name && self.exec_ok
     }
 
+    pub fn is_uninstalled_steam_app(&self) -> bool {
+        self.steam_app_not_installed
+    }
+
+    pub fn has_warnings(&self) -> bool {
+        !self.warnings.is_empty()
+    }
+
+    pub fn warning_string(&self) -> Option<String> {
+        self.has_warnings().then(|| {
+            self.warnings
+                .iter()
+                .map(|warning| warning.message.as_str())
+                .collect::<Vec<_>>()
+                .join("\n")
+        })
+    }
+
+    pub fn errors(&self) -> Vec<Problem> {
+        let mut errors = Vec::new();
+        if self.empty_name {
+            errors.push(Problem::new(Some("Name"), "Missing name field"));
+        }
+        if let Some(reason) = &self.exec_fail_reason {
+            errors.push(Problem::new(Some("Exec"), reason.clone()));
+        }
+        errors
+    }
+
+    pub fn warnings(&self) -> &[Problem] {
+        &self.warnings
+    }
+
+    /// The warnings that only depend on the file, which stay the same until it is modified
+    pub fn file_warnings(&self) -> &[Problem] {
+        &self.warnings[..self.file_warning_count]
+    }
+
+    pub fn command(&self) -> Option<&str> {
+        self.command.as_deref()
+    }
+
+    pub fn binary_path(&self) -> Option<&Path> {
+        self.binary_path.as_deref()
+    }
+
+    pub fn search_paths(&self) -> &[PathBuf] {
+        &self.search_paths
+    }
+
+    /// The file the command points to, if making it executable is enough to run the entry
+    pub fn non_executable_command(&self) -> Option<&Path> {
+        self.non_executable_command.as_deref()
+    }
+
     pub fn error_string(&self) -> Option<String> {
         if self.is_valid() {
             return None;
@@ -258,8 +740,243 @@ impl ValidityStatus {
     }
 }
 
-fn parse_exec(entry: &DesktopEntry) -> Result<String, ExecError> {
-    let exec = entry.exec().ok_or(ExecError::ExecFieldNotFound)?;
+/// Why the file a command points to can't be run by the user
+struct CommandFileProblem {
+    reason: String,
+    /// The user owns the file, which only needs the executable bit
+    fixable: bool,
+}
+
+/// Check that the file of a command can be executed and read, as scripts and AppImages need
+/// both. `None` when the file is fine or doesn't exist at all
+fn command_file_problem(path: &Path) -> Option<CommandFileProblem> {
+    let info = gio::File::for_path(path)
+        .query_info(
+            "standard::type,access::can-read,access::can-execute,owner::user",
+            gio::FileQueryInfoFlags::NONE,
+            gio::Cancellable::NONE,
+        )
+        .ok()?;
+
+    let owner = info.attribute_string("owner::user");
+    let owned = owner.as_deref() == glib::user_name().to_str();
+    let ownership = match &owner {
+        Some(owner) if !owned => format!(", it belongs to {owner}"),
+        _ => String::new(),
+    };
+
+    let path = path.display();
+    if info.file_type() == gio::FileType::Directory {
+        Some(CommandFileProblem {
+            reason: format!("{path} is a folder"),
+            fixable: false,
+        })
+    } else if !info.boolean("access::can-execute") {
+        Some(CommandFileProblem {
+            reason: format!("{path} is not executable{ownership}"),
+            fixable: owned,
+        })
+    } else if !info.boolean("access::can-read") {
+        Some(CommandFileProblem {
+            reason: format!("{path} cannot be read{ownership}"),
+            fixable: false,
+        })
+    } else {
+        None
+    }
+}
+
+/// Give permission to execute the file at `path` to everyone who can read it, like `chmod +x`
+pub fn make_executable(path: &Path) -> Result<(), glib::Error> {
+    let file = gio::File::for_path(path);
+    let info = file.query_info(
+        "unix::mode",
+        gio::FileQueryInfoFlags::NONE,
+        gio::Cancellable::NONE,
+    )?;
+    let mode = info.attribute_uint32("unix::mode");
+    file.set_attribute_uint32(
+        "unix::mode",
+        executable_mode(mode),
+        gio::FileQueryInfoFlags::NONE,
+        gio::Cancellable::NONE,
+    )
+}
+
+/// The permission bits with execution added for whoever can read the file
+fn executable_mode(mode: u32) -> u32 {
+    mode | ((mode & 0o444) >> 2)
+}
+
+/// Names of the actions listed by the entry, their identifiers when they have none
+fn action_names(entry: &DesktopEntry, locales: &[String]) -> Vec<String> {
+    entry
+        .actions()
+        .unwrap_or_default()
+        .into_iter()
+        .filter(|action| !action.is_empty())
+        .map(|action| {
+            entry
+                .action_name(action, locales)
+                .map(String::from)
+                .unwrap_or_else(|| action.to_string())
+        })
+        .collect()
+}
+
+fn modified_time(path: &Path) -> Option<glib::DateTime> {
+    let modified = fs::metadata(path).and_then(|m| m.modified()).ok()?;
+    let seconds = modified.duration_since(UNIX_EPOCH).ok()?.as_secs();
+    glib::DateTime::from_unix_local(seconds as i64).ok()
+}
+
+fn find_warnings(entry: &DesktopEntry) -> Vec<Problem> {
+    let mut warnings = Vec::new();
+
+    // Entries that are not displayed don't need to be recognizable in menus
+    let displayed = !entry.no_display() && entry.desktop_entry("Hidden") != Some("true");
+    if displayed {
+        if entry.icon().is_none() {
+            warnings.push(Problem::new(Some("Icon"), "Missing icon field"));
+        }
+
+        if entry.desktop_entry("Comment").is_none() {
+            warnings.push(Problem::new(Some("Comment"), "Missing comment field"));
+        }
+    }
+
+    if let Some(group) = entry.groups.0.get("Desktop Entry") {
+        for key in group.0.keys() {
+            if DEPRECATED_KEYS.contains(&key.as_str()) {
+                warnings.push(Problem::new(Some(key), format!("Deprecated {key} field")));
+            }
+        }
+    }
+
+    // Keys of the specification used where they don't belong, or with values it doesn't allow
+    for (group_name, group) in &entry.groups.0 {
+        let kind = GroupKind::of(group_name);
+        if kind == GroupKind::Other {
+            continue;
+        }
+
+        for (key, (value, _)) in &group.0 {
+            let Some(info) = key_info(key) else {
+                continue;
+            };
+
+            if !info.allowed_in(kind) {
+                let message = format!("{key} does not belong in the {group_name} group");
+                warnings.push(Problem::new(Some(key), message));
+            } else if let Some(reason) = info.check(value) {
+                warnings.push(Problem::new(Some(key), reason));
+            }
+        }
+    }
+
+    if entry.desktop_entry("DBusActivatable") == Some("true") {
+        warnings.extend(find_dbus_activation_warnings(entry));
+    }
+
+    warnings
+}
+
+/// Check that the icon can be found. Absolute paths are allowed, but they don't follow the icon
+/// theme and can't be scaled as well as theme icons
+fn find_icon_warnings(icon: Option<&str>) -> Vec<Problem> {
+    let Some(icon) = icon.filter(|icon| !icon.is_empty()) else {
+        return Vec::new();
+    };
+
+    if Path::new(icon).is_absolute() {
+        let problem = if Path::new(icon).exists() {
+            Problem::new(
+                Some("Icon"),
+                "Icon is an absolute path, it does not follow the icon theme",
+            )
+            .with_suggestion("Install the icon in the icon theme from the icon menu of the editor")
+        } else {
+            Problem::new(Some("Icon"), format!("Icon file {icon} does not exist"))
+        };
+        return vec![problem];
+    }
+
+    // Icon themes can only be looked up once there is a display
+    let Some(display) = gdk::Display::default() else {
+        return Vec::new();
+    };
+    if gtk::IconTheme::for_display(&display).has_icon(icon) {
+        return Vec::new();
+    }
+
+    let message = format!("No icon theme provides the icon {icon}");
+    vec![Problem::new(Some("Icon"), message)
+        .with_suggestion("Install the icon, or choose one of the icons of the theme")]
+}
+
+/// Field codes D-Bus activation has no way of passing, the application only receives the URIs
+/// to open
+const DBUS_UNSUPPORTED_FIELD_CODES: [char; 3] = ['i', 'c', 'k'];
+
+/// Check that a D-Bus activatable entry can actually be activated the same way it would be
+/// launched through Exec
+fn find_dbus_activation_warnings(entry: &DesktopEntry) -> Vec<Problem> {
+    let mut warnings = Vec::new();
+
+    let id = util::desktop_file_id(&entry.path);
+    let bus_name = id.trim_end_matches(".desktop");
+    if let Some(error) = dbus::bus_name_error(bus_name) {
+        let suggestion = match dbus::suggested_bus_name(bus_name) {
+            Some(name) => format!("Rename the file to {name}.desktop"),
+            None => "Rename the file after the bus name of the application, e.g. \
+                     org.example.App.desktop, or disable DBusActivatable"
+                .to_string(),
+        };
+        warnings.push(
+            Problem::new(
+                Some("DBusActivatable"),
+                format!("\"{bus_name}\" is not a valid bus name for D-Bus activation: {error}"),
+            )
+            .with_suggestion(suggestion),
+        );
+    }
+
+    if let Some(command) = entry.exec().and_then(shellparse::parse) {
+        let unsupported: Vec<String> = command
+            .field_codes()
+            .into_iter()
+            .filter(|code| DBUS_UNSUPPORTED_FIELD_CODES.contains(code))
+            .map(|code| format!("%{code}"))
+            .collect();
+        if !unsupported.is_empty() {
+            let codes = unsupported.join(", ");
+            warnings.push(
+                Problem::new(
+                    Some("Exec"),
+                    format!("{codes} can't be passed when the application is D-Bus activated"),
+                )
+                .with_suggestion(format!(
+                    "Remove {codes} from Exec, only the files and URIs to open are passed"
+                )),
+            );
+        }
+    }
+
+    if entry.desktop_entry("StartupNotify") == Some("false") {
+        warnings.push(
+            Problem::new(
+                Some("StartupNotify"),
+                "StartupNotify is false, but D-Bus activated applications always get a startup ID",
+            )
+            .with_suggestion("Remove StartupNotify or set it to true"),
+        );
+    }
+
+    warnings
+}
+
+fn parse_exec(exec: Option<&str>) -> Result<shellparse::Command, ExecError> {
+    let exec = exec.ok_or(ExecError::ExecFieldNotFound)?;
     let mut command = shellparse::parse(exec).ok_or(ExecError::ExecParseError)?;
 
     #[cfg(feature = "steam")]
@@ -268,7 +985,8 @@ fn parse_exec(entry: &DesktopEntry) -> Result<String, ExecError> {
     }
 
     command.flatten_env();
-    Ok(command.command)
+    command.flatten_shell();
+    Ok(command)
 }
 
 enum ExecError {
@@ -278,3 +996,21 @@ enum ExecError {
     #[cfg(feature = "steam")]
     SteamAppNotInstalled,
 }
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn shown_in_desktop_rules() {
+        assert!(shown_in_desktop(None, None, "GNOME"));
+        assert!(shown_in_desktop(None, Some("KDE;"), "GNOME"));
+        assert!(!shown_in_desktop(None, Some("KDE;"), "KDE"));
+        assert!(!shown_in_desktop(Some("KDE;"), None, "GNOME"));
+        assert!(!shown_in_desktop(Some("KDE;"), None, ""));
+        // The first name of the session found in a list decides
+        let (gnome, ubuntu) = (Some("GNOME;"), Some("ubuntu;"));
+        assert!(!shown_in_desktop(gnome, ubuntu, "ubuntu:GNOME"));
+        assert!(shown_in_desktop(gnome, ubuntu, "GNOME:ubuntu"));
+    }
+}
@@ -11,9 +11,13 @@
 * You should have received a copy of the GNU General Public License along with Desktop File Editor. If not, see <https://www.gnu.org/licenses/>.
 */
 
+use std::path::Path;
+
 use adw::glib;
 use gtk::{glib::Object, subclass::prelude::ObjectSubclassIsExt, Image, Label};
 
+use crate::util;
+
 mod imp {
 
     use std::cell::Cell;
@@ -21,11 +25,13 @@ mod imp {
     use std::path::PathBuf;
     use std::process::Command;
 
+    use adw::gio;
     use adw::glib;
     use adw::prelude::*;
     use adw::subclass::prelude::*;
     use gtk::gdk::Rectangle;
 
+    use gtk::glib::clone;
     use gtk::glib::closure;
     use gtk::glib::object_subclass;
     use gtk::glib::subclass::InitializingObject;
@@ -34,16 +40,25 @@ mod imp {
     use gtk::glib::Variant;
 
     use gtk::template_callbacks;
+    use gtk::ClosureExpression;
+    use gtk::Expression;
     use gtk::Image;
     use gtk::Label;
+    use gtk::Popover;
     use gtk::PopoverMenu;
+    use gtk::Tooltip;
     use gtk::Widget;
     use gtk::{subclass::prelude::ObjectSubclass, CompositeTemplate};
     use zbus::proxy;
     use zbus::Connection;
 
+    use crate::settings::{settings, ListSubtitle};
     use crate::util;
-    use crate::window::file_entry::ShouldShow;
+    use crate::window::entry_preview::EntryPreview;
+    use crate::window::file_entry::{FileEntry, ShouldShow};
+    use crate::window::overrides::user_override_target;
+    use crate::window::validity_dialog::show_validity_dialog;
+    use crate::window::DMWindow;
 
     #[derive(Debug, Default, CompositeTemplate, Properties)]
     #[properties(wrapper_type = super::ListEntry)]
@@ -53,7 +68,16 @@ mod imp {
         pub name_label: TemplateChild<Label>,
 
         #[template_child]
-        pub path_label: TemplateChild<Label>,
+        pub subtitle_label: TemplateChild<Label>,
+
+        #[template_child]
+        pub modified_label: TemplateChild<Label>,
+
+        #[template_child]
+        pub actions_label: TemplateChild<Label>,
+
+        #[template_child]
+        pub precedence_label: TemplateChild<Label>,
 
         #[template_child]
         pub icon_image: TemplateChild<Image>,
@@ -65,13 +89,29 @@ mod imp {
         pub hidden_marker: TemplateChild<Image>,
 
         #[template_child]
-        pub invalid_marker: TemplateChild<Image>,
+        pub invalid_marker: TemplateChild<gtk::Button>,
+
+        #[template_child]
+        pub warning_marker: TemplateChild<gtk::Button>,
 
         #[property(get, set, builder(ShouldShow::default()))]
         pub should_show: Cell<ShouldShow>,
 
         #[property(get, set)]
         pub path: RefCell<PathBuf>,
+
+        #[property(get, set, nullable)]
+        pub file_entry: RefCell<Option<FileEntry>>,
+
+        /// Unix time the edit time of the entry is shown relative to, kept current while the
+        /// row is mapped
+        #[property(get, set)]
+        pub now: Cell<i64>,
+
+        pub clock: RefCell<Option<glib::SourceId>>,
+
+        #[template_child]
+        pub preview_popover: TemplateChild<Popover>,
     }
 
     #[object_subclass]
@@ -93,6 +133,39 @@ mod imp {
                 let path = list_entry.path();
                 let _ = Command::new("xdg-open").arg(path).spawn().unwrap().wait();
             });
+            klass.install_action("list_entry.open_text", None, |list_entry, _, _| {
+                if let Some(window) = list_entry.root().and_downcast::<DMWindow>() {
+                    window.open_in_text_editor(&list_entry.path());
+                }
+            });
+            klass.install_action("list_entry.launch", None, |list_entry, _, _| {
+                if let Some(window) = list_entry.root().and_downcast::<DMWindow>() {
+                    window.launch_app_info(gio::DesktopAppInfo::from_filename(list_entry.path()));
+                }
+            });
+            klass.install_action("list_entry.override", None, |list_entry, _, _| {
+                if let Some(window) = list_entry.root().and_downcast::<DMWindow>() {
+                    window.override_in_user_dir(&list_entry.path(), None);
+                }
+            });
+            klass.install_action("list_entry.duplicate", None, |list_entry, _, _| {
+                if let Some(window) = list_entry.root().and_downcast::<DMWindow>() {
+                    let name = list_entry
+                        .file_entry()
+                        .and_then(|file_entry| file_entry.name())
+                        .unwrap_or_default();
+                    window.duplicate_entry(&list_entry.path(), &name);
+                }
+            });
+            klass.install_action_async("list_entry.trash", None, |list_entry, _, _| async move {
+                if let Some(window) = list_entry.root().and_downcast::<DMWindow>() {
+                    let name = list_entry
+                        .file_entry()
+                        .and_then(|file_entry| file_entry.display_name())
+                        .unwrap_or_else(|| "Launcher".to_string());
+                    window.trash_entry(&list_entry.path(), &name).await;
+                }
+            });
         }
 
         fn instance_init(obj: &InitializingObject<Self>) {
@@ -105,20 +178,162 @@ mod imp {
         fn constructed(&self) {
             let obj = self.obj();
 
-            obj.property_expression("path")
-                .chain_closure::<PathBuf>(closure!(|_: Option<Object>, path: PathBuf| {
-                    util::display_path(&path)
+            // Entries of the user have nothing to override, the others can't be removed by the user
+            obj.connect_path_notify(|list_entry| {
+                let can_override = user_override_target(&list_entry.path()).is_some();
+                list_entry.action_set_enabled("list_entry.override", can_override);
+                list_entry.action_set_enabled("list_entry.trash", !can_override);
+            });
+
+            let file_entry_expr = obj.property_expression("file-entry");
+            ClosureExpression::new::<String>(
+                [
+                    settings().property_expression("list-subtitle").upcast(),
+                    obj.property_expression("path").upcast(),
+                    file_entry_expr.chain_property::<FileEntry>("comment").upcast(),
+                    file_entry_expr.chain_property::<FileEntry>("exec").upcast(),
+                    file_entry_expr.chain_property::<FileEntry>("categories").upcast(),
+                ],
+                closure!(|_: Option<Object>,
+                          subtitle: ListSubtitle,
+                          path: PathBuf,
+                          comment: Option<String>,
+                          exec: Option<String>,
+                          categories: Option<String>| {
+                    match subtitle {
+                        ListSubtitle::Path => {
+                            util::display_path(&path).to_string_lossy().into_owned()
+                        }
+                        ListSubtitle::Comment => comment.unwrap_or("No comment".to_string()),
+                        ListSubtitle::Exec => exec.unwrap_or("No command".to_string()),
+                        ListSubtitle::Categories => {
+                            categories.unwrap_or("No categories".to_string())
+                        }
+                    }
+                }),
+            )
+            .bind(&obj.subtitle_label(), "label", Widget::NONE);
+
+            ClosureExpression::new::<String>(
+                [
+                    file_entry_expr
+                        .chain_property::<FileEntry>("modified")
+                        .upcast(),
+                    obj.property_expression("now").upcast(),
+                ],
+                closure!(
+                    |_: Option<Object>, modified: Option<glib::DateTime>, now: i64| {
+                        modified
+                            .map(|modified| {
+                                format!("edited {}", util::time_ago(now - modified.to_unix()))
+                            })
+                            .unwrap_or_default()
+                    }
+                ),
+            )
+            .bind(&*self.modified_label, "label", Widget::NONE);
+
+            // The actions are what the context menu of the launcher offers
+            let action_count = file_entry_expr.chain_property::<FileEntry>("action-count");
+            action_count
+                .chain_closure::<String>(closure!(|_: Option<Object>, count: u32| {
+                    match count {
+                        1 => "1 action".to_string(),
+                        count => format!("{count} actions"),
+                    }
+                }))
+                .bind(&*self.actions_label, "label", Widget::NONE);
+            action_count
+                .chain_closure::<bool>(closure!(|_: Option<Object>, count: u32| count > 0))
+                .bind(&*self.actions_label, "visible", Widget::NONE);
+            file_entry_expr
+                .chain_property::<FileEntry>("action-names")
+                .chain_closure::<String>(closure!(|_: Option<Object>, names: Vec<String>| {
+                    names.join("\n")
                 }))
-                .bind(&obj.path_label(), "label", Widget::NONE);
+                .bind(&*self.actions_label, "tooltip-text", Widget::NONE);
+
+            // Entries sharing the desktop file ID of another, only one of them is used
+            let precedence = || -> [Expression; 2] {
+                [
+                    file_entry_expr
+                        .chain_property::<FileEntry>("overrides")
+                        .upcast(),
+                    file_entry_expr
+                        .chain_property::<FileEntry>("shadowed-by")
+                        .upcast(),
+                ]
+            };
+            ClosureExpression::new::<String>(
+                precedence(),
+                closure!(|_: Option<Object>,
+                          overrides: Vec<String>,
+                          shadowed_by: Option<PathBuf>| {
+                    super::precedence_label(&overrides, shadowed_by.as_deref())
+                }),
+            )
+            .bind(&*self.precedence_label, "label", Widget::NONE);
+            ClosureExpression::new::<String>(
+                precedence(),
+                closure!(|_: Option<Object>,
+                          overrides: Vec<String>,
+                          shadowed_by: Option<PathBuf>| {
+                    super::precedence_tooltip(&overrides, shadowed_by.as_deref())
+                }),
+            )
+            .bind(&*self.precedence_label, "tooltip-text", Widget::NONE);
+            self.precedence_label
+                .property_expression("label")
+                .chain_closure::<bool>(closure!(|_: Option<Object>, label: String| {
+                    !label.is_empty()
+                }))
+                .bind(&*self.precedence_label, "visible", Widget::NONE);
         }
 
         fn dispose(&self) {
             self.dispose_template();
         }
     }
-    impl WidgetImpl for ListEntry {}
+
+    impl WidgetImpl for ListEntry {
+        fn map(&self) {
+            self.parent_map();
+
+            // The edit time is relative, advance it for as long as the row can be seen
+            self.tick();
+            let clock = glib::timeout_add_seconds_local(
+                60,
+                clone!(
+                    #[weak(rename_to = imp)]
+                    self,
+                    #[upgrade_or]
+                    glib::ControlFlow::Break,
+                    move || {
+                        imp.tick();
+                        glib::ControlFlow::Continue
+                    }
+                ),
+            );
+            if let Some(previous) = self.clock.replace(Some(clock)) {
+                previous.remove();
+            }
+        }
+
+        fn unmap(&self) {
+            if let Some(clock) = self.clock.take() {
+                clock.remove();
+            }
+            self.parent_unmap();
+        }
+    }
     impl BoxImpl for ListEntry {}
 
+    impl ListEntry {
+        fn tick(&self) {
+            self.obj().set_now(glib::real_time() / 1_000_000);
+        }
+    }
+
     #[template_callbacks]
     impl ListEntry {
         #[template_callback]
@@ -128,6 +343,55 @@ mod imp {
             self.context_menu.popup();
         }
 
+        #[template_callback]
+        fn on_query_tooltip(
+            &self,
+            _x: i32,
+            _y: i32,
+            _keyboard_mode: bool,
+            tooltip: &Tooltip,
+        ) -> bool {
+            let Some(file_entry) = self.file_entry.borrow().clone() else {
+                return false;
+            };
+
+            tooltip.set_custom(Some(&EntryPreview::new(&file_entry)));
+            true
+        }
+
+        #[template_callback]
+        fn on_long_press(&self, _x: f64, _y: f64) {
+            let Some(file_entry) = self.file_entry.borrow().clone() else {
+                return;
+            };
+
+            self.preview_popover
+                .set_child(Some(&EntryPreview::new(&file_entry)));
+            self.preview_popover.popup();
+        }
+
+        #[template_callback]
+        fn on_marker_clicked(&self) {
+            let Some(file_entry) = self.file_entry.borrow().clone() else {
+                return;
+            };
+            let obj = self.obj();
+            let Some(window) = obj.root().and_downcast::<DMWindow>() else {
+                return;
+            };
+
+            let path = file_entry.path();
+            show_validity_dialog(
+                &*obj,
+                &file_entry,
+                clone!(
+                    #[weak]
+                    window,
+                    move |key: &str| window.open_desktop_file_at(&path, key)
+                ),
+            );
+        }
+
         #[template_callback]
         fn is_hidden(&self, should_show: ShouldShow) -> bool {
             !matches!(should_show, ShouldShow::Yes)
@@ -181,8 +445,8 @@ impl ListEntry {
         self.imp().name_label.clone()
     }
 
-    fn path_label(&self) -> Label {
-        self.imp().path_label.clone()
+    fn subtitle_label(&self) -> Label {
+        self.imp().subtitle_label.clone()
     }
 
     pub fn icon_image(&self) -> Image {
@@ -193,9 +457,13 @@ impl ListEntry {
         self.imp().hidden_marker.clone()
     }
 
-    pub fn invalid_marker(&self) -> Image {
+    pub fn invalid_marker(&self) -> gtk::Button {
         self.imp().invalid_marker.clone()
     }
+
+    pub fn warning_marker(&self) -> gtk::Button {
+        self.imp().warning_marker.clone()
+    }
 }
 
 impl Default for ListEntry {
@@ -203,3 +471,31 @@ impl Default for ListEntry {
         Self::new()
     }
 }
+
+/// Short description of which of the entries sharing a desktop file ID launchers use
+fn precedence_label(overrides: &[String], shadowed_by: Option<&Path>) -> String {
+    match (overrides.len(), shadowed_by) {
+        (_, Some(_)) => "overridden".to_string(),
+        (0, None) => String::new(),
+        (1, None) => "overrides 1 file".to_string(),
+        (count, None) => format!("overrides {count} files"),
+    }
+}
+
+fn precedence_tooltip(overrides: &[String], shadowed_by: Option<&Path>) -> String {
+    if let Some(shadowed_by) = shadowed_by {
+        return format!(
+            "Launchers use {} instead",
+            util::display_path(shadowed_by).to_string_lossy()
+        );
+    }
+
+    overrides
+        .iter()
+        .map(|path| {
+            let path = util::display_path(Path::new(path));
+            format!("Used instead of {}", path.to_string_lossy())
+        })
+        .collect::<Vec<_>>()
+        .join("\n")
+}
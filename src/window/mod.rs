@@ -11,22 +11,37 @@
 * You should have received a copy of the GNU General Public License along with Desktop File Editor. If not, see <https://www.gnu.org/licenses/>.
 */
 
+mod command_palette;
 mod entry_filter;
+mod entry_preview;
 pub(crate) mod file_entry;
 mod list_entry;
+pub(crate) mod new_launcher;
+pub(crate) mod overrides;
+mod scan_cache;
 mod sliding_search_entry;
+mod tour;
+pub(crate) mod unsaved_indicator;
+mod validity_dialog;
 
+use adw::prelude::*;
 use gtk::{
     gio::{self, ListStore},
     glib,
     subclass::prelude::*,
 };
 
-use crate::application::DMApplication;
+use std::path::Path;
+
+use crate::{application::DMApplication, desktop_file_view::DesktopFileView, edited_files, util};
+
+use new_launcher::NewLauncher;
 
 mod imp {
     use std::cell::Cell;
+    use std::cell::OnceCell;
     use std::cell::RefCell;
+    use std::collections::{HashMap, HashSet};
 
     use std::fs;
     use std::io;
@@ -35,10 +50,12 @@ mod imp {
     use std::rc::Rc;
     use std::time::Duration;
 
+    use adw::gdk;
     use adw::gio;
     use adw::glib;
     use adw::prelude::*;
     use adw::subclass::prelude::*;
+    use adw::NavigationPage;
     use either::Either;
     use gtk::gio::ListStore;
     use gtk::gio::PropertyAction;
@@ -47,28 +64,43 @@ mod imp {
     use gtk::glib::Properties;
     use gtk::glib::{
         clone, closure, closure_local, object_subclass, subclass::InitializingObject, Object,
+        Propagation,
     };
     use gtk::EveryFilter;
     use gtk::{
-        template_callbacks, ClosureExpression, CompositeTemplate, CustomSorter, Expression,
-        FilterListModel, ListItem, ListView, NoSelection, SignalListItemFactory, SortListModel,
-        StringFilter, StringFilterMatchMode, Widget,
+        template_callbacks, ClosureExpression, Collation, CompositeTemplate, Expression,
+        FilterListModel, ListItem, ListView, MultiSorter, NoSelection, NumericSorter,
+        SignalListItemFactory, SortListModel, SorterChange, StringFilter, StringFilterMatchMode,
+        StringSorter, Widget,
     };
+    use notify::event::{ModifyKind, RenameMode};
+    use notify::EventKind;
     use notify::INotifyWatcher;
     use notify::Watcher;
     use notify_debouncer_full::DebounceEventResult;
     use notify_debouncer_full::Debouncer;
     use notify_debouncer_full::FileIdMap;
 
-    use crate::desktop_file_view::DesktopFileView;
+    use crate::change_journal::show_change_journal_dialog;
+    use crate::desktop_file_view::desktop_entry_ext::is_desktop_entry_text;
+    use crate::desktop_file_view::{show_unsaved_changes_dialog, DesktopFileView};
+    use crate::preferences_dialog::PreferencesDialog;
+    use crate::settings::settings;
     use crate::util;
     use crate::window::file_entry::ToGIcon;
     use crate::window::file_entry::ValidityStatus;
 
-    use super::entry_filter::EntryFilter;
-    use super::file_entry::FileEntry;
+    use super::command_palette::CommandPalette;
+    use super::entry_filter::{parse_filter_tokens, EntryFilter};
+    use super::file_entry::{current_desktop, FileEntry};
     use super::list_entry::ListEntry;
+    use super::new_launcher::{
+        user_applications_dir, BinaryLauncherDialog, ScriptLauncherDialog, WebAppDialog,
+    };
+    use super::overrides::{copy_files, relative_targets, user_overrides};
+    use super::scan_cache::{modified_nanos, ScanCache};
     use super::sliding_search_entry::SlidingSearchEntry;
+    use super::tour::{self, Tour};
 
     #[derive(Debug, Default, CompositeTemplate, Properties)]
     #[template(resource = "/com/argoware/desktop-file-editor/window.ui")]
@@ -83,6 +115,21 @@ mod imp {
         #[template_child]
         pub navigation_view: TemplateChild<adw::NavigationView>,
 
+        #[template_child]
+        pub toast_overlay: TemplateChild<adw::ToastOverlay>,
+
+        #[template_child]
+        pub filters_button: TemplateChild<gtk::MenuButton>,
+
+        #[template_child]
+        scan_progress_revealer: TemplateChild<gtk::Revealer>,
+
+        #[template_child]
+        scan_progress_label: TemplateChild<gtk::Label>,
+
+        #[template_child]
+        scan_progress_bar: TemplateChild<gtk::ProgressBar>,
+
         #[property(get, set, construct)]
         pub additional_search_paths: RefCell<Vec<String>>,
 
@@ -95,6 +142,14 @@ mod imp {
         entry_filter: Rc<RefCell<EntryFilter>>,
 
         pub app_paths_watcher: RefCell<Option<Debouncer<INotifyWatcher, FileIdMap>>>,
+        icon_dirs_watcher: RefCell<Option<Debouncer<INotifyWatcher, FileIdMap>>>,
+        binary_dirs_watcher: RefCell<Option<Debouncer<INotifyWatcher, FileIdMap>>>,
+        watchers_suspended: Cell<bool>,
+        shut_down: Cell<bool>,
+        close_confirmed: Cell<bool>,
+        pub unsaved_views: OnceCell<ListStore>,
+
+        pub tour: RefCell<Option<Tour>>,
     }
 
     #[object_subclass]
@@ -107,6 +162,102 @@ mod imp {
             SlidingSearchEntry::ensure_type();
             klass.bind_template();
             klass.bind_template_callbacks();
+
+            klass.install_action("win.preferences", None, |window, _, _| {
+                let dialog = PreferencesDialog::new();
+                dialog.suggest_new_files_dirs(window.additional_search_paths());
+                dialog.present(Some(window));
+            });
+            klass.add_binding_action(
+                gdk::Key::comma,
+                gdk::ModifierType::CONTROL_MASK,
+                "win.preferences",
+            );
+
+            klass.install_action_async(
+                "win.remove-uninstalled-steam",
+                None,
+                |window, _, _| async move {
+                    window.imp().remove_uninstalled_steam_launchers().await;
+                },
+            );
+
+            klass.install_action_async("win.open-file", None, |window, _, _| async move {
+                window.imp().open_file().await;
+            });
+            klass.add_binding_action(
+                gdk::Key::o,
+                gdk::ModifierType::CONTROL_MASK,
+                "win.open-file",
+            );
+
+            klass.install_action_async("win.export-overrides", None, |window, _, _| async move {
+                window.imp().export_overrides().await;
+            });
+
+            klass.install_action_async("win.import-overrides", None, |window, _, _| async move {
+                window.imp().import_overrides().await;
+            });
+
+            klass.install_action("win.start-tour", None, |window, _, _| {
+                tour::start(window);
+            });
+
+            klass.install_action("win.show-change-journal", None, |window, _, _| {
+                show_change_journal_dialog(window, None);
+            });
+
+            klass.install_action("win.command-palette", None, |window, _, _| {
+                let editor = window
+                    .imp()
+                    .navigation_view
+                    .visible_page()
+                    .and_downcast::<DesktopFileView>();
+                CommandPalette::new(window, editor.as_ref()).present(Some(window));
+            });
+            klass.add_binding_action(
+                gdk::Key::k,
+                gdk::ModifierType::CONTROL_MASK,
+                "win.command-palette",
+            );
+
+            klass.install_action("win.new-launcher-from-binary", None, |window, _, _| {
+                let dialog = BinaryLauncherDialog::new();
+                dialog.connect_launcher_created(clone!(
+                    #[weak]
+                    window,
+                    move |path| window.open_created_file(path)
+                ));
+                dialog.present(Some(window));
+            });
+
+            klass.install_action("win.new-script-launcher", None, |window, _, _| {
+                let dialog = ScriptLauncherDialog::new();
+                dialog.connect_launcher_created(clone!(
+                    #[weak]
+                    window,
+                    move |path| window.open_created_file(path)
+                ));
+                dialog.present(Some(window));
+            });
+
+            klass.install_action("win.new-empty-launcher", None, |window, _, _| {
+                window.open_scratch();
+            });
+
+            klass.install_action_async("win.paste-new-file", None, |window, _, _| async move {
+                window.imp().paste_new_file().await;
+            });
+
+            klass.install_action("win.new-web-app", None, |window, _, _| {
+                let dialog = WebAppDialog::new();
+                dialog.connect_launcher_created(clone!(
+                    #[weak]
+                    window,
+                    move |path| window.open_created_file(path)
+                ));
+                dialog.present(Some(window));
+            });
         }
 
         fn instance_init(obj: &InitializingObject<Self>) {
@@ -117,6 +268,8 @@ mod imp {
     #[glib::derived_properties]
     impl ObjectImpl for DMWindow {
         fn dispose(&self) {
+            tour::abandon(&self.obj());
+            self.shutdown();
             self.dispose_template();
         }
 
@@ -126,14 +279,60 @@ mod imp {
             self.search_entry
                 .search_entry()
                 .connect_search_changed(clone!(
-                    #[weak(rename_to = filter)]
-                    self.search_filter,
+                    #[weak(rename_to = window)]
+                    self.obj(),
                     move |search_entry| {
-                        filter.borrow().set_search(Some(&search_entry.text()));
-                        filter.borrow().search();
+                        let imp = window.imp();
+                        if imp.extract_filter_tokens(search_entry, false) {
+                            // Changing the text emits the signal again
+                            return;
+                        }
+
+                        let filter = imp.search_filter.borrow();
+                        filter.set_search(Some(&search_entry.text()));
+                        filter.search();
                     }
                 ));
 
+            self.search_entry
+                .search_entry()
+                .connect_activate(clone!(
+                    #[weak(rename_to = window)]
+                    self.obj(),
+                    move |search_entry| {
+                        let imp = window.imp();
+                        if !imp.extract_filter_tokens(search_entry, true)
+                            && !search_entry.text().is_empty()
+                        {
+                            imp.activate_first_result();
+                        }
+                    }
+                ));
+
+            // Down moves from the search to the results, so an entry can be found and opened
+            // without leaving the keyboard
+            let search_key_controller = gtk::EventControllerKey::new();
+            search_key_controller.set_propagation_phase(gtk::PropagationPhase::Capture);
+            search_key_controller.connect_key_pressed(clone!(
+                #[weak(rename_to = window)]
+                self.obj(),
+                #[upgrade_or]
+                Propagation::Proceed,
+                move |_, key, _, modifiers| {
+                    if key != gdk::Key::Down || !modifiers.is_empty() {
+                        return Propagation::Proceed;
+                    }
+                    if window.imp().focus_first_result() {
+                        Propagation::Stop
+                    } else {
+                        Propagation::Proceed
+                    }
+                }
+            ));
+            self.search_entry
+                .search_entry()
+                .add_controller(search_key_controller);
+
             self.search_entry
                 .set_key_capture_widget(Some(self.obj().clone().upcast::<Widget>()));
 
@@ -142,12 +341,97 @@ mod imp {
                 let filter_hidden_action = PropertyAction::new("filter-hidden", filter, "hidden");
                 let filter_invalid_action =
                     PropertyAction::new("filter-invalid", filter, "invalid");
+                let filter_warnings_action =
+                    PropertyAction::new("filter-warnings", filter, "warnings");
                 let filter_only_show_selected_action =
                     PropertyAction::new("filter-only-show-selected", filter, "only-show-selected");
+                let filter_uninstalled_steam_action =
+                    PropertyAction::new("filter-uninstalled-steam", filter, "uninstalled-steam");
+                let filter_shown_in_desktop_action =
+                    PropertyAction::new("filter-shown-in-desktop", filter, "shown-in-desktop");
+                let filter_edited_action = PropertyAction::new("filter-edited", filter, "edited");
                 obj.add_action(&filter_hidden_action);
                 obj.add_action(&filter_invalid_action);
+                obj.add_action(&filter_warnings_action);
                 obj.add_action(&filter_only_show_selected_action);
+                obj.add_action(&filter_uninstalled_steam_action);
+                obj.add_action(&filter_shown_in_desktop_action);
+                obj.add_action(&filter_edited_action);
+                obj.add_action(&PropertyAction::new(
+                    "simulate-desktop",
+                    &settings(),
+                    "simulated-desktop",
+                ));
+
+                settings().connect_simulated_desktop_notify(clone!(
+                    #[weak]
+                    obj,
+                    move |_| obj.imp().update_filter_chips()
+                ));
+
+                for property in [
+                    "hidden",
+                    "invalid",
+                    "uninstalled-steam",
+                    "category",
+                    "directory",
+                    "shown-in-desktop",
+                    "edited",
+                ] {
+                    filter.connect_notify_local(
+                        Some(property),
+                        clone!(
+                            #[weak]
+                            obj,
+                            move |_, _| obj.imp().update_filter_chips()
+                        ),
+                    );
+                }
             });
+
+            obj.connect_close_request(|window| window.imp().confirm_close());
+
+            // Editors with unsaved changes can end up behind others opened on top of them
+            self.navigation_view.connect_pushed(clone!(
+                #[weak]
+                obj,
+                move |_| obj.update_unsaved_views()
+            ));
+            self.navigation_view.connect_popped(clone!(
+                #[weak]
+                obj,
+                move |_, _| obj.update_unsaved_views()
+            ));
+
+            gtk::IconTheme::for_display(&obj.display()).connect_changed(clone!(
+                #[weak]
+                obj,
+                move |_| obj.imp().on_icon_theme_changed()
+            ));
+            if let Err(e) = self.watch_icon_dirs() {
+                eprintln!("Failed to watch icon directories: {e}");
+                eprintln!("Newly installed icons will not be shown until restart");
+            }
+            if let Err(e) = self.watch_binary_dirs() {
+                eprintln!("Failed to watch the directories of PATH: {e}");
+                eprintln!("Newly installed programs will not be found until restart");
+            }
+
+            // Avoid background wakeups while nobody is looking at the window
+            obj.connect_suspended_notify(|window| window.imp().update_watchers_suspension());
+            obj.connect_visible_notify(|window| window.imp().update_watchers_suspension());
+
+            self.navigation_view.connect_visible_page_notify(clone!(
+                #[weak]
+                obj,
+                move |_| tour::on_page_changed(&obj)
+            ));
+            // Wait for the window to be presented, the tour points at its widgets
+            glib::idle_add_local_once(clone!(
+                #[weak]
+                obj,
+                move || tour::start_if_new(&obj)
+            ));
         }
     }
 
@@ -167,18 +451,12 @@ mod imp {
                 .expect("The item is not an entry");
 
             if item.path().exists() {
-                let nav_view = self.navigation_view.clone();
-                let desktop_file_view = DesktopFileView::new(nav_view, &item.path());
-                self.navigation_view.push(&desktop_file_view);
+                self.obj().open_desktop_file(&item.path());
             }
         }
 
         fn init_list(&self) {
             self.load_entries();
-            if let Err(e) = self.watch_entries_dirs() {
-                eprintln!("Failed to watch application directories: {e}");
-                eprintln!("The list will not be updated on changes");
-            }
 
             let factory = SignalListItemFactory::new();
             factory.connect_setup(move |_, list_item| {
@@ -188,9 +466,8 @@ mod imp {
                     .expect("Should be ListItem");
                 list_item.set_child(Some(&entry));
 
-                list_item
-                    .property_expression("item")
-                    .chain_property::<FileEntry>("name")
+                let item_expr = list_item.property_expression("item");
+                FileEntry::display_name_expression(Some(item_expr.upcast_ref()))
                     .bind(&entry.name_label(), "label", Widget::NONE);
 
                 list_item
@@ -200,9 +477,17 @@ mod imp {
 
                 list_item
                     .property_expression("item")
+                    .bind(&entry, "file-entry", Widget::NONE);
+
+                // Bound to the icon rather than the item, so that notifying it looks the icon up again
+                list_item
+                    .property_expression("item")
+                    .chain_property::<FileEntry>("icon")
                     .chain_closure::<gio::Icon>(closure!(
-                        |_: Option<Object>, entry: Option<&FileEntry>| {
-                            entry.map_or_else(FileEntry::default_exec_gicon, |entry| entry.gicon())
+                        |_: Option<Object>, icon: Option<String>| {
+                            icon.map_or_else(FileEntry::default_exec_gicon, |icon| {
+                                FileEntry::gicon_for_string(&icon)
+                            })
                         }
                     ))
                     .bind(&entry.icon_image(), "gicon", Widget::NONE);
@@ -227,18 +512,57 @@ mod imp {
                         |_: Option<Object>, status: &ValidityStatus| { status.error_string() }
                     ))
                     .bind(&entry.invalid_marker(), "tooltip-text", Widget::NONE);
-            });
 
-            let sorter = CustomSorter::new(move |obj1, obj2| {
-                let obj1 = obj1
-                    .downcast_ref::<FileEntry>()
-                    .expect("Should be EntryObj");
-                let obj2 = obj2
-                    .downcast_ref::<FileEntry>()
-                    .expect("Should be EntryObj");
-                obj1.name().cmp(&obj2.name()).into()
+                // Warnings are only marked when there are no errors
+                list_item
+                    .property_expression("item")
+                    .chain_property::<FileEntry>("validity-status")
+                    .chain_closure::<bool>(closure!(
+                        |_: Option<Object>, status: &ValidityStatus| {
+                            status.is_valid() && status.has_warnings()
+                        }
+                    ))
+                    .bind(&entry.warning_marker(), "visible", Widget::NONE);
+
+                list_item
+                    .property_expression("item")
+                    .chain_property::<FileEntry>("validity-status")
+                    .chain_closure::<String>(closure!(
+                        |_: Option<Object>, status: &ValidityStatus| { status.warning_string() }
+                    ))
+                    .bind(&entry.warning_marker(), "tooltip-text", Widget::NONE);
             });
 
+            // Sort using collation keys so that names are ordered as expected for the user's locale
+            let sorter = StringSorter::builder()
+                .expression(FileEntry::display_name_expression(None))
+                .collation(Collation::Unicode)
+                .ignore_case(true)
+                .build();
+
+            // Entries with the same desktop file ID are kept together, the one launchers use
+            // first
+            let id_sorter = StringSorter::builder()
+                .expression(FileEntry::this_expression("desktop-file-id"))
+                .build();
+            let precedence_sorter = NumericSorter::builder()
+                .expression(
+                    FileEntry::this_expression("shadowed-by").chain_closure::<bool>(closure!(
+                        |_: Option<Object>, shadowed_by: Option<PathBuf>| { shadowed_by.is_some() }
+                    )),
+                )
+                .build();
+            let entries_sorter = MultiSorter::new();
+            entries_sorter.append(sorter.clone());
+            entries_sorter.append(id_sorter);
+            entries_sorter.append(precedence_sorter);
+
+            settings().connect_translated_names_notify(clone!(
+                #[weak]
+                sorter,
+                move |_| sorter.changed(SorterChange::Different)
+            ));
+
             // Setup search filter
             let empty_arr: &[Expression] = &[];
             let entry_key_expr = ClosureExpression::new::<String>(
@@ -261,42 +585,546 @@ mod imp {
             multi_filter.append(self.entry_filter.borrow().clone());
 
             let filter_model = FilterListModel::new(Some(self.obj().entries()), Some(multi_filter));
-            let sort_model = SortListModel::new(Some(filter_model), Some(sorter));
+            let sort_model = SortListModel::new(Some(filter_model), Some(entries_sorter));
             let selection_model = NoSelection::new(Some(sort_model));
 
             self.entries_list.set_factory(Some(&factory));
             self.entries_list.set_model(Some(&selection_model));
         }
 
+        /// Moves the focus to the first entry of the list, returns whether there is one
+        fn focus_first_result(&self) -> bool {
+            let has_results = self
+                .entries_list
+                .model()
+                .is_some_and(|model| model.n_items() > 0);
+            if has_results {
+                self.entries_list
+                    .scroll_to(0, gtk::ListScrollFlags::FOCUS, None);
+            }
+            has_results
+        }
+
+        /// Opens the first entry of the list, as if it was clicked
+        fn activate_first_result(&self) {
+            let has_results = self
+                .entries_list
+                .model()
+                .is_some_and(|model| model.n_items() > 0);
+            if has_results {
+                self.on_listview_activate(0, self.entries_list.get());
+            }
+        }
+
+        /// Turn filters typed in the search entry into chips. Returns whether any was found
+        fn extract_filter_tokens(&self, search_entry: &gtk::SearchEntry, complete: bool) -> bool {
+            let (tokens, text) = parse_filter_tokens(&search_entry.text(), complete);
+            if tokens.is_empty() {
+                return false;
+            }
+
+            let filter = self.entry_filter.borrow().clone();
+            for token in tokens {
+                filter.apply_token(token);
+            }
+
+            search_entry.set_text(&text);
+            search_entry.set_position(-1);
+            true
+        }
+
+        pub fn insert_entry(&self, path: &Path) {
+            // Entries are stored with canonical paths, use the same to avoid duplicates
+            let path = fs::canonicalize(path).unwrap_or_else(|_| path.to_path_buf());
+            let entries = self.obj().entries();
+            update_entry(&entries, &path);
+            update_shadowing(&entries);
+        }
+
+        pub fn refresh_entries(&self, paths: &[&Path]) {
+            let entries = self.obj().entries();
+            for path in paths {
+                // Entries are stored with canonical paths, removed files can't be canonicalized
+                let path = fs::canonicalize(path).unwrap_or_else(|_| path.to_path_buf());
+                update_entry(&entries, &path);
+            }
+            update_shadowing(&entries);
+        }
+
+        /// Move the launchers of Steam games that are no longer installed to the trash, after
+        /// asking for confirmation
+        async fn remove_uninstalled_steam_launchers(&self) {
+            let obj = self.obj();
+            let entries = obj.entries();
+            let orphaned: Vec<FileEntry> = entries
+                .iter::<FileEntry>()
+                .filter_map(Result::ok)
+                .filter(|entry| entry.validity_status().is_uninstalled_steam_app())
+                .collect();
+
+            if orphaned.is_empty() {
+                let dialog = adw::AlertDialog::builder()
+                    .heading("Nothing to Remove")
+                    .body("There are no launchers of uninstalled Steam games")
+                    .build();
+                dialog.add_response("close", "Close");
+                dialog.present(Some(&*obj));
+                return;
+            }
+
+            let names = orphaned
+                .iter()
+                .map(|entry| {
+                    entry
+                        .display_name()
+                        .unwrap_or_else(|| entry.path().to_string_lossy().into_owned())
+                })
+                .collect::<Vec<_>>()
+                .join("\n");
+            let dialog = adw::AlertDialog::builder()
+                .heading(format!("Remove {} Launchers?", orphaned.len()))
+                .body(format!(
+                    "The launchers of these games, which are no longer installed, will be moved \
+                     to the trash:\n\n{names}"
+                ))
+                .close_response("cancel")
+                .default_response("cancel")
+                .build();
+            dialog.add_response("cancel", "Cancel");
+            dialog.add_response("remove", "Move to Trash");
+            dialog.set_response_appearance("remove", adw::ResponseAppearance::Destructive);
+            if dialog.choose_future(&*obj).await != "remove" {
+                return;
+            }
+
+            let paths: Vec<PathBuf> = orphaned.iter().map(FileEntry::path).collect();
+            if let Err(e) = trash::delete_all(&paths) {
+                eprintln!("Failed to move launchers to the trash: {e}");
+                let dialog = adw::AlertDialog::builder()
+                    .heading("Failed to Move to Trash")
+                    .body(e.to_string())
+                    .build();
+                dialog.add_response("close", "Close");
+                dialog.present(Some(&*obj));
+            }
+
+            // Don't wait for the watcher, the removal was requested from the list itself. Some
+            // launchers may have been trashed even if it failed
+            for path in paths.iter() {
+                update_entry(&entries, path);
+            }
+            update_shadowing(&entries);
+        }
+
+        /// Let the user pick a desktop file anywhere on disk and open it in the editor, for files
+        /// that are not in the applications directories
+        async fn open_file(&self) {
+            let obj = self.obj();
+
+            let filter = gtk::FileFilter::new();
+            filter.set_name(Some("Desktop Files"));
+            filter.add_suffix("desktop");
+
+            let dialog = gtk::FileDialog::builder()
+                .title("Open Desktop File")
+                .default_filter(&filter)
+                .build();
+            let Some(path) = dialog
+                .open_future(Some(&*obj))
+                .await
+                .ok()
+                .and_then(|file| file.path())
+            else {
+                return;
+            };
+
+            // The editor expects a readable file
+            match fs::canonicalize(&path).and_then(|path| fs::read(&path).map(|_| path)) {
+                Ok(path) => obj.open_desktop_file(&path),
+                Err(e) => {
+                    let dialog = adw::AlertDialog::builder()
+                        .heading("Failed to Open")
+                        .body(format!("The file could not be read: {e}"))
+                        .build();
+                    dialog.add_response("close", "Close");
+                    dialog.present(Some(&*obj));
+                }
+            }
+        }
+
+        /// Start a new launcher from the desktop entry text in the clipboard, its file is chosen
+        /// when it is first saved
+        async fn paste_new_file(&self) {
+            let obj = self.obj();
+            let text = match obj.clipboard().read_text_future().await {
+                Ok(text) => text.filter(|text| is_desktop_entry_text(text)),
+                Err(e) => {
+                    eprintln!("Failed to read clipboard: {e}");
+                    None
+                }
+            };
+
+            let Some(text) = text else {
+                let dialog = adw::AlertDialog::builder()
+                    .heading("Nothing to Paste")
+                    .body("The clipboard does not contain a desktop entry")
+                    .build();
+                dialog.add_response("close", "Close");
+                dialog.present(Some(&*obj));
+                return;
+            };
+
+            obj.open_scratch_with_contents(&format!("{}\n", text.trim()));
+        }
+
+        /// Copy the entries of the user that override system entries to a chosen directory, to
+        /// bring them to another machine with [`Self::import_overrides`]
+        async fn export_overrides(&self) {
+            let obj = self.obj();
+            let overrides = user_overrides(&obj.entries());
+            if overrides.is_empty() {
+                let dialog = adw::AlertDialog::builder()
+                    .heading("Nothing to Export")
+                    .body("None of your launchers override a launcher installed on the system")
+                    .build();
+                dialog.add_response("close", "Close");
+                dialog.present(Some(&*obj));
+                return;
+            }
+
+            let dialog = gtk::FileDialog::builder()
+                .title("Export Customizations")
+                .accept_label("Export")
+                .build();
+            let Some(target_dir) = dialog
+                .select_folder_future(Some(&*obj))
+                .await
+                .ok()
+                .and_then(|folder| folder.path())
+            else {
+                return;
+            };
+
+            let user_dir = user_applications_dir();
+            let user_dir = fs::canonicalize(&user_dir).unwrap_or(user_dir);
+            let targets = relative_targets(&overrides, &user_dir, &target_dir);
+            let count = targets.len();
+            let result = gio::spawn_blocking(move || copy_files(&targets))
+                .await
+                .expect("Failed to join export thread");
+
+            let dialog = match result {
+                Ok(()) => adw::AlertDialog::builder()
+                    .heading("Customizations Exported")
+                    .body(format!(
+                        "{count} launchers were copied to {}",
+                        util::display_path(&target_dir).to_string_lossy()
+                    ))
+                    .build(),
+                Err(e) => adw::AlertDialog::builder()
+                    .heading("Failed to Export")
+                    .body(format!("The launchers could not be copied: {e}"))
+                    .build(),
+            };
+            dialog.add_response("close", "Close");
+            dialog.present(Some(&*obj));
+        }
+
+        /// Copy the desktop files of a chosen directory, such as one written by
+        /// [`Self::export_overrides`], to the user applications directory
+        async fn import_overrides(&self) {
+            let obj = self.obj();
+            let dialog = gtk::FileDialog::builder()
+                .title("Import Customizations")
+                .accept_label("Import")
+                .build();
+            let Some(source_dir) = dialog
+                .select_folder_future(Some(&*obj))
+                .await
+                .ok()
+                .and_then(|folder| folder.path())
+            else {
+                return;
+            };
+
+            let files = match find_all_desktop_files(&source_dir) {
+                Ok(files) => files,
+                Err(e) => {
+                    eprintln!("Failed to list desktop files to import: {e}");
+                    Vec::new()
+                }
+            };
+            let mut targets = relative_targets(&files, &source_dir, &user_applications_dir());
+            if targets.is_empty() {
+                let dialog = adw::AlertDialog::builder()
+                    .heading("Nothing to Import")
+                    .body("The folder does not contain any desktop file")
+                    .build();
+                dialog.add_response("close", "Close");
+                dialog.present(Some(&*obj));
+                return;
+            }
+
+            let existing: Vec<String> = targets
+                .iter()
+                .filter(|(_, target)| target.exists())
+                .map(|(_, target)| util::desktop_file_id(target))
+                .collect();
+            let dialog = adw::AlertDialog::builder()
+                .heading(format!("Import {} Launchers?", targets.len()))
+                .close_response("cancel")
+                .default_response("import")
+                .build();
+            dialog.add_response("cancel", "Cancel");
+            if existing.is_empty() {
+                dialog.set_body("They will be copied to your applications folder");
+                dialog.add_response("import", "Import");
+                dialog.set_response_appearance("import", adw::ResponseAppearance::Suggested);
+            } else {
+                dialog.set_body(&format!(
+                    "You already have some of these launchers:\n\n{}",
+                    existing.join("\n")
+                ));
+                dialog.add_response("skip", "Keep Mine");
+                dialog.add_response("import", "Replace");
+                dialog.set_response_appearance("import", adw::ResponseAppearance::Destructive);
+            }
+
+            match dialog.choose_future(&*obj).await.as_str() {
+                "import" => (),
+                "skip" => targets.retain(|(_, target)| !target.exists()),
+                _ => return,
+            }
+
+            // The directory watcher adds the copied files to the list
+            let result = gio::spawn_blocking(move || copy_files(&targets))
+                .await
+                .expect("Failed to join import thread");
+            if let Err(e) = result {
+                let dialog = adw::AlertDialog::builder()
+                    .heading("Failed to Import")
+                    .body(format!("The launchers could not be copied: {e}"))
+                    .build();
+                dialog.add_response("close", "Close");
+                dialog.present(Some(&*obj));
+            }
+        }
+
+        fn update_filter_chips(&self) {
+            let filter = self.entry_filter.borrow().clone();
+            let search_entry = &self.search_entry;
+            search_entry.clear_chips();
+
+            if filter.hidden() {
+                search_entry.add_chip(
+                    "Hidden",
+                    clone!(
+                        #[weak]
+                        filter,
+                        move || filter.set_hidden(false)
+                    ),
+                );
+            }
+
+            if filter.invalid() {
+                search_entry.add_chip(
+                    "Errors",
+                    clone!(
+                        #[weak]
+                        filter,
+                        move || filter.set_invalid(false)
+                    ),
+                );
+            }
+
+            if filter.uninstalled_steam() {
+                search_entry.add_chip(
+                    "Uninstalled Steam Games",
+                    clone!(
+                        #[weak]
+                        filter,
+                        move || filter.set_uninstalled_steam(false)
+                    ),
+                );
+            }
+
+            if filter.edited() {
+                search_entry.add_chip(
+                    "Edited",
+                    clone!(
+                        #[weak]
+                        filter,
+                        move || filter.set_edited(false)
+                    ),
+                );
+            }
+
+            if filter.shown_in_desktop() {
+                let desktop = current_desktop();
+                let label = match desktop.split(':').next() {
+                    Some(name) if !name.is_empty() => format!("Shown in {name}"),
+                    _ => "Shown in This Desktop".to_string(),
+                };
+                search_entry.add_chip(
+                    &label,
+                    clone!(
+                        #[weak]
+                        filter,
+                        move || filter.set_shown_in_desktop(false)
+                    ),
+                );
+            }
+
+            if let Some(category) = filter.category() {
+                search_entry.add_chip(
+                    &format!("category:{category}"),
+                    clone!(
+                        #[weak]
+                        filter,
+                        move || filter.set_category(None::<String>)
+                    ),
+                );
+            }
+
+            if let Some(directory) = filter.directory() {
+                search_entry.add_chip(
+                    &format!("dir:{directory}"),
+                    clone!(
+                        #[weak]
+                        filter,
+                        move || filter.set_directory(None::<String>)
+                    ),
+                );
+            }
+        }
+
+        /// Scan the application directories in the background. Entries are added to the list as
+        /// they are decoded, and the directories are watched from the start so that no change
+        /// made during the scan is missed
         fn load_entries(&self) {
-            let app_paths = self.application_paths();
+            let store = ListStore::new::<FileEntry>();
+            self.entries.set(Some(store.clone()));
 
-            let mut store = ListStore::new::<FileEntry>();
+            // Resuming starts watching on its own
+            if !self.watchers_suspended.get() {
+                if let Err(e) = self.watch_entries_dirs() {
+                    eprintln!("Failed to watch application directories: {e}");
+                    eprintln!("The list will not be updated on changes");
+                }
+            }
+
+            glib::spawn_future_local(clone!(
+                #[weak(rename_to = window)]
+                self.obj(),
+                async move { window.imp().scan_entries(&store).await }
+            ));
+        }
+
+        async fn scan_entries(&self, store: &ListStore) {
+            // Entries are decoded in small batches, giving the list a chance to show them
+            const BATCH_SIZE: usize = 32;
+
+            self.scan_progress_label
+                .set_label("Looking for desktop files\u{2026}");
+            self.scan_progress_bar.pulse();
+            self.scan_progress_revealer.set_reveal_child(true);
+
+            let app_paths: Vec<PathBuf> = self.application_paths().collect();
+            let listed = gio::spawn_blocking(move || {
+                app_paths
+                    .into_iter()
+                    .map(|dir| {
+                        let files = find_all_desktop_files(&dir);
+                        (dir, files)
+                    })
+                    .collect::<Vec<_>>()
+            })
+            .await
+            .unwrap_or_default();
 
-            for dir in app_paths {
+            let total: usize = listed
+                .iter()
+                .map(|(_, files)| files.as_ref().map_or(0, Vec::len))
+                .sum();
+            let mut decoded = 0;
+            let mut cache = ScanCache::load();
+            let previous = cache.previous();
+
+            // Paths of the listed entries. Files opened or changed while scanning are listed by
+            // others, the paths are then collected again
+            let mut listed_paths: HashSet<PathBuf> = HashSet::new();
+            let listed_elsewhere = Rc::new(Cell::new(true));
+            let items_changed_handler = store.connect_items_changed(clone!(
+                #[strong]
+                listed_elsewhere,
+                move |_, _, _, _| listed_elsewhere.set(true)
+            ));
+
+            for (dir, files) in listed {
                 println!("Scanning {dir:?}");
+                let files = match files {
+                    Ok(files) => files,
+                    Err(e) => {
+                        eprintln!("Failed to scan: {e}");
+                        continue;
+                    }
+                };
+
+                self.scan_progress_label
+                    .set_label(&format!("Scanning {}\u{2026}", dir.to_string_lossy()));
+
+                for batch in files.chunks(BATCH_SIZE) {
+                    let paths = batch.to_vec();
+                    let previous = previous.clone();
+                    let batch_entries = gio::spawn_blocking(move || {
+                        paths
+                            .into_iter()
+                            .map(|path| {
+                                let cached = previous.cached_entry(&path);
+                                (path, cached)
+                            })
+                            .collect::<Vec<_>>()
+                    })
+                    .await
+                    .unwrap_or_default();
 
-                let entries = match find_all_desktop_files(&dir) {
-                    Ok(files) => Either::Left(files.into_iter().filter_map(|path| {
-                        let file_entry = FileEntry::from_path(&path);
-                        if file_entry.is_err() {
-                            eprintln!(
+                    if listed_elsewhere.get() {
+                        listed_paths = store
+                            .iter::<FileEntry>()
+                            .filter_map(Result::ok)
+                            .map(|entry| entry.path())
+                            .collect();
+                    }
+
+                    let mut entries = Vec::new();
+                    for (path, cached) in batch_entries {
+                        match cached {
+                            Ok(cached) => {
+                                if listed_paths.insert(cached.target.clone()) {
+                                    entries.push(FileEntry::from_cached(&cached));
+                                }
+                                cache.insert(path, cached);
+                            }
+                            Err(e) => eprintln!(
                                 "Failed to create file entry for {}: {}",
                                 path.to_string_lossy(),
-                                file_entry.as_ref().unwrap_err()
-                            );
+                                e
+                            ),
                         }
-                        file_entry.ok()
-                    })),
-                    Err(e) => {
-                        eprintln!("Failed to scan: {e}");
-                        Either::Right(std::iter::empty())
                     }
-                };
-                store.extend(entries);
+                    store.extend_from_slice(&entries);
+                    listed_elsewhere.set(false);
+
+                    decoded += batch.len();
+                    self.scan_progress_bar
+                        .set_fraction(decoded as f64 / total as f64);
+                }
             }
 
-            self.entries.set(Some(store));
+            store.disconnect(items_changed_handler);
+            cache.save();
+            update_shadowing(store);
+            self.scan_progress_revealer.set_reveal_child(false);
         }
 
         fn watch_entries_dirs(&self) -> Result<(), notify::Error> {
@@ -306,12 +1134,44 @@ mod imp {
                 None,
                 move |result: DebounceEventResult| match result {
                     Ok(events) => events.into_iter().for_each(|event| {
+                        // Renames are stitched together by the debouncer using the file ids
+                        if let EventKind::Modify(ModifyKind::Name(RenameMode::Both)) = event.kind {
+                            if let [from, to] = &event.paths[..] {
+                                let mut updates =
+                                    vec![EntriesDirUpdate::Renamed(from.clone(), to.clone())];
+                                if to.is_dir() {
+                                    updates.push(EntriesDirUpdate::DirectoryCreated(to.clone()));
+                                }
+
+                                for update in updates {
+                                    if let Err(e) = sender.send_blocking(update) {
+                                        eprintln!(
+                                            "Error sending application list watch update: {e}"
+                                        );
+                                    }
+                                }
+                                return;
+                            }
+                        }
+
+                        if event.kind.is_create() {
+                            // New directories need to be watched and scanned on their own
+                            let directories = event.paths.iter().filter(|path| path.is_dir());
+                            for path in directories {
+                                let update = EntriesDirUpdate::DirectoryCreated(path.clone());
+                                if let Err(e) = sender.send_blocking(update) {
+                                    eprintln!("Error sending application list watch update: {e}");
+                                }
+                            }
+                        }
+
                         if event.kind.is_remove()
                             || event.kind.is_modify()
                             || event.kind.is_create()
                         {
                             for path in event.paths.iter() {
-                                if let Err(e) = sender.send_blocking(path.clone()) {
+                                let update = EntriesDirUpdate::Changed(path.clone());
+                                if let Err(e) = sender.send_blocking(update) {
                                     eprintln!("Error sending application list watch update: {e}");
                                 }
                             }
@@ -341,58 +1201,378 @@ mod imp {
             self.app_paths_watcher.set(Some(debouncer));
 
             let entries = self.obj().entries();
-            fn find_entry(entries: &ListStore, path: &Path) -> Option<(u32, FileEntry)> {
-                for (i, entry) in entries.iter::<FileEntry>().enumerate() {
-                    if let Ok(entry) = entry {
-                        if entry.path().as_path() == path {
-                            return Some((i as u32, entry));
+            let navigation_view = self.navigation_view.get();
+            glib::spawn_future_local(clone!(
+                #[weak(rename_to = window)]
+                self.obj(),
+                #[weak]
+                navigation_view,
+                async move {
+                    while let Ok(update) = receiver.recv().await {
+                        match update {
+                            EntriesDirUpdate::Changed(path) => update_entry(&entries, &path),
+                            EntriesDirUpdate::Renamed(from, to) => {
+                                rename_entries(&entries, &navigation_view, &from, &to)
+                            }
+                            EntriesDirUpdate::DirectoryCreated(path) => {
+                                window.imp().watch_new_directory(&path);
+                                match find_all_desktop_files(&path) {
+                                    Ok(files) => files
+                                        .iter()
+                                        .for_each(|file| update_entry(&entries, file)),
+                                    Err(e) => eprintln!("Failed to scan: {e}"),
+                                }
+                            }
                         }
+                        update_shadowing(&entries);
                     }
                 }
-                None
-            }
-
-            glib::spawn_future_local(clone!(async move {
-                while let Ok(path) = receiver.recv().await {
-                    if path.exists() {
-                        match find_entry(&entries, &path) {
-                            Some((i, entry)) => {
-                                // Update entry
-                                if let Err(e) = entry.update() {
-                                    eprintln!(
-                                        "Failed to decode entry on update {}: {}",
-                                        path.to_string_lossy(),
-                                        e
-                                    );
-                                    entries.remove(i);
-                                }
-                            }
-                            None => {
-                                // Create entry
-                                match FileEntry::from_path(&path) {
-                                    Ok(entry) => entries.append(&entry),
-                                    Err(e) => {
-                                        eprintln!(
-                                            "Entry creation failed {}: {}",
-                                            path.to_string_lossy(),
-                                            e
-                                        )
-                                    }
-                                }
+            ));
+
+            Ok(())
+        }
+
+        /// Open editors, from the bottom of the navigation stack to the top
+        fn desktop_file_views(&self) -> Vec<DesktopFileView> {
+            self.navigation_view
+                .navigation_stack()
+                .iter::<NavigationPage>()
+                .filter_map(Result::ok)
+                .filter_map(|page| page.downcast::<DesktopFileView>().ok())
+                .collect()
+        }
+
+        /// Asks once about every editor with unsaved changes before closing the window
+        fn confirm_close(&self) -> Propagation {
+            if self.close_confirmed.get() {
+                return Propagation::Proceed;
+            }
+
+            let unsaved: Vec<DesktopFileView> = self
+                .desktop_file_views()
+                .into_iter()
+                .filter(DesktopFileView::content_changed)
+                .collect();
+
+            if unsaved.is_empty() {
+                return Propagation::Proceed;
+            }
+
+            let obj = self.obj();
+            show_unsaved_changes_dialog(
+                &*obj,
+                unsaved,
+                clone!(
+                    #[weak]
+                    obj,
+                    move || {
+                        obj.imp().close_confirmed.set(true);
+                        obj.close();
+                    }
+                ),
+            );
+
+            Propagation::Stop
+        }
+
+        fn update_unsaved_views(&self) {
+            let unsaved: Vec<DesktopFileView> = self
+                .desktop_file_views()
+                .into_iter()
+                .filter(DesktopFileView::content_changed)
+                .collect();
+
+            let store = self.obj().unsaved_views();
+            let listed: Vec<DesktopFileView> = store
+                .iter::<DesktopFileView>()
+                .filter_map(Result::ok)
+                .collect();
+            if listed != unsaved {
+                store.splice(0, store.n_items(), &unsaved);
+            }
+        }
+
+        fn update_watchers_suspension(&self) {
+            let obj = self.obj();
+            let suspend = obj.is_suspended() || !obj.is_visible();
+            if self.shut_down.get() || suspend == self.watchers_suspended.get() {
+                return;
+            }
+            self.watchers_suspended.set(suspend);
+
+            let desktop_file_views = self.desktop_file_views();
+
+            if suspend {
+                self.drop_watchers();
+                desktop_file_views
+                    .iter()
+                    .for_each(DesktopFileView::suspend_file_watcher);
+            } else {
+                // Changes that happened in the meantime were missed, catch up with a single scan
+                self.rescan_entries();
+                if let Err(e) = self.watch_entries_dirs() {
+                    eprintln!("Failed to watch application directories: {e}");
+                    eprintln!("The list will not be updated on changes");
+                }
+                self.refresh_icon_theme();
+                if let Err(e) = self.watch_icon_dirs() {
+                    eprintln!("Failed to watch icon directories: {e}");
+                }
+                self.recheck_invalid_entries();
+                if let Err(e) = self.watch_binary_dirs() {
+                    eprintln!("Failed to watch the directories of PATH: {e}");
+                }
+                desktop_file_views
+                    .iter()
+                    .for_each(DesktopFileView::resume_file_watcher);
+            }
+        }
+
+        /// Dropping a debouncer stops both the watcher and the debouncer thread, the sender it
+        /// owns goes with it and the task receiving its updates ends
+        fn drop_watchers(&self) {
+            self.app_paths_watcher.set(None);
+            self.icon_dirs_watcher.set(None);
+            self.binary_dirs_watcher.set(None);
+        }
+
+        pub fn shutdown(&self) {
+            if self.shut_down.replace(true) {
+                return;
+            }
+
+            self.drop_watchers();
+            self.desktop_file_views()
+                .iter()
+                .for_each(DesktopFileView::shutdown);
+        }
+
+        /// Watch the directories icons are looked up in, so that icons installed while running
+        /// are shown instead of the fallback
+        fn watch_icon_dirs(&self) -> Result<(), notify::Error> {
+            let (sender, receiver) = async_channel::unbounded();
+            let mut debouncer = notify_debouncer_full::new_debouncer(
+                Duration::from_secs(1),
+                None,
+                move |result: DebounceEventResult| match result {
+                    Ok(events) => {
+                        if !events.is_empty() {
+                            if let Err(e) = sender.send_blocking(()) {
+                                eprintln!("Error sending icon directories watch update: {e}");
                             }
                         }
-                    } else {
-                        // Remove entry
-                        if let Some((i, _)) = find_entry(&entries, &path) {
-                            entries.remove(i);
+                    }
+                    Err(errors) => errors.iter().for_each(|error| println!("{error:?}")),
+                },
+            )?;
+
+            // Watching the themes recursively would take thousands of watches, but installing
+            // icons also updates the cache at the top of the theme
+            let icon_theme = gtk::IconTheme::for_display(&self.obj().display());
+            let dirs = icon_theme.search_path().into_iter().flat_map(|dir| {
+                let themes: Vec<PathBuf> = fs::read_dir(&dir)
+                    .into_iter()
+                    .flatten()
+                    .filter_map(|entry| Some(entry.ok()?.path()))
+                    .filter(|path| path.is_dir())
+                    .collect();
+                std::iter::once(dir).chain(themes)
+            });
+
+            for dir in dirs.filter(|dir| dir.is_dir()) {
+                let res = debouncer
+                    .watcher()
+                    .watch(&dir, notify::RecursiveMode::NonRecursive);
+                if let Err(e) = res {
+                    eprintln!("Failed to watch {}: {e}", dir.to_string_lossy());
+                }
+            }
+            self.icon_dirs_watcher.set(Some(debouncer));
+
+            glib::spawn_future_local(clone!(
+                #[weak(rename_to = window)]
+                self.obj(),
+                async move {
+                    while receiver.recv().await.is_ok() {
+                        window.imp().refresh_icon_theme();
+                    }
+                }
+            ));
+
+            Ok(())
+        }
+
+        /// Watch the directories of PATH, so that the launchers of programs installed while
+        /// running stop being reported as invalid
+        fn watch_binary_dirs(&self) -> Result<(), notify::Error> {
+            let (sender, receiver) = async_channel::unbounded();
+            let mut debouncer = notify_debouncer_full::new_debouncer(
+                Duration::from_secs(1),
+                None,
+                move |result: DebounceEventResult| match result {
+                    Ok(events) => {
+                        if !events.is_empty() {
+                            if let Err(e) = sender.send_blocking(()) {
+                                eprintln!("Error sending PATH watch update: {e}");
+                            }
                         }
                     }
+                    Err(errors) => errors.iter().for_each(|error| println!("{error:?}")),
+                },
+            )?;
+
+            let search_paths = util::binary_search_paths().unwrap_or_default();
+            for dir in std::env::split_paths(&search_paths).filter(|dir| dir.is_dir()) {
+                let res = debouncer
+                    .watcher()
+                    .watch(&dir, notify::RecursiveMode::NonRecursive);
+                if let Err(e) = res {
+                    eprintln!("Failed to watch {}: {e}", dir.to_string_lossy());
                 }
-            }));
+            }
+            self.binary_dirs_watcher.set(Some(debouncer));
+
+            glib::spawn_future_local(clone!(
+                #[weak(rename_to = window)]
+                self.obj(),
+                async move {
+                    while receiver.recv().await.is_ok() {
+                        window.imp().recheck_invalid_entries();
+                    }
+                }
+            ));
 
             Ok(())
         }
 
+        /// Look for the programs of the invalid entries again, they might have been installed
+        fn recheck_invalid_entries(&self) {
+            self.obj()
+                .entries()
+                .iter::<FileEntry>()
+                .filter_map(Result::ok)
+                .filter(|entry| !entry.validity_status().is_valid())
+                .for_each(|entry| entry.recheck());
+        }
+
+        /// Make the icon theme drop the icons it looked up so far, it emits `changed` once done
+        fn refresh_icon_theme(&self) {
+            let icon_theme = gtk::IconTheme::for_display(&self.obj().display());
+            let search_path = icon_theme.search_path();
+            let search_path: Vec<&Path> = search_path.iter().map(PathBuf::as_path).collect();
+            icon_theme.set_search_path(&search_path);
+        }
+
+        fn on_icon_theme_changed(&self) {
+            // Icons given as paths don't depend on the theme, but they might have been installed
+            // in the meantime as well. Missing icons may have been installed too
+            self.obj()
+                .entries()
+                .iter::<FileEntry>()
+                .filter_map(Result::ok)
+                .for_each(|entry| {
+                    entry.notify_icon();
+                    entry.recheck();
+                });
+            self.desktop_file_views()
+                .iter()
+                .for_each(DesktopFileView::refresh_icon);
+        }
+
+        /// Brings the list up to date with the desktop files currently on disk. Only the files
+        /// modified since the last scan are decoded again
+        fn rescan_entries(&self) {
+            let entries = self.obj().entries();
+
+            let mut paths = Vec::new();
+            for dir in self.application_paths() {
+                match find_all_desktop_files(&dir) {
+                    Ok(files) => paths.extend(files),
+                    Err(e) => eprintln!("Failed to scan: {e}"),
+                }
+            }
+
+            let mut cache = ScanCache::load();
+            let previous = cache.previous();
+            let mut listed: HashMap<PathBuf, FileEntry> = entries
+                .iter::<FileEntry>()
+                .filter_map(Result::ok)
+                .map(|entry| (entry.path(), entry))
+                .collect();
+            let mut found: HashSet<PathBuf> = HashSet::new();
+
+            for path in &paths {
+                let modified = modified_nanos(path);
+                let unchanged = modified.and_then(|modified| previous.unchanged(path, modified));
+                if let Some(cached) = unchanged {
+                    match listed.get(&cached.target) {
+                        // Only the checks depending on the rest of the system can give another
+                        // result
+                        Some(entry) => entry.recheck(),
+                        None => {
+                            let entry = FileEntry::from_cached(cached);
+                            entries.append(&entry);
+                            listed.insert(entry.path(), entry);
+                        }
+                    }
+                    found.insert(cached.target.clone());
+                    cache.insert(path.clone(), cached.clone());
+                    continue;
+                }
+
+                let target = fs::canonicalize(path).unwrap_or_else(|_| path.clone());
+                let entry = match listed.get(&target).cloned() {
+                    Some(entry) => entry.update().map(|_| entry),
+                    None => FileEntry::from_path(path).inspect(|entry| {
+                        entries.append(entry);
+                        listed.insert(entry.path(), entry.clone());
+                    }),
+                };
+                match entry {
+                    Ok(entry) => {
+                        found.insert(entry.path());
+                        if let Some(modified) = modified {
+                            cache.insert(path.clone(), entry.to_cached(modified));
+                        }
+                    }
+                    Err(e) => eprintln!(
+                        "Failed to decode entry on rescan {}: {}",
+                        path.to_string_lossy(),
+                        e
+                    ),
+                }
+            }
+
+            entries.retain(|object| {
+                object
+                    .downcast_ref::<FileEntry>()
+                    .is_some_and(|entry| found.contains(&entry.path()))
+            });
+            cache.save();
+            update_shadowing(&entries);
+        }
+
+        /// Adds a directory created after startup to the application directories watcher
+        fn watch_new_directory(&self, path: &Path) {
+            let mut watcher = self.app_paths_watcher.borrow_mut();
+            let Some(debouncer) = watcher.as_mut() else {
+                return;
+            };
+
+            let res = debouncer
+                .watcher()
+                .watch(path, notify::RecursiveMode::Recursive);
+
+            if let Err(e) = res {
+                eprintln!("Failed to watch: {e}");
+                return;
+            }
+
+            debouncer
+                .cache()
+                .add_root(path, notify::RecursiveMode::Recursive);
+        }
+
         fn application_paths(&self) -> impl Iterator<Item = PathBuf> {
             let application_paths = if self.ignore_default_paths.get() {
                 Either::Left(std::iter::empty())
@@ -410,6 +1590,184 @@ mod imp {
         }
     }
 
+    enum EntriesDirUpdate {
+        Changed(PathBuf),
+        Renamed(PathBuf, PathBuf),
+        DirectoryCreated(PathBuf),
+    }
+
+    fn find_entry(entries: &ListStore, path: &Path) -> Option<(u32, FileEntry)> {
+        for (i, entry) in entries.iter::<FileEntry>().enumerate() {
+            if let Ok(entry) = entry {
+                if entry.path().as_path() == path {
+                    return Some((i as u32, entry));
+                }
+            }
+        }
+        None
+    }
+
+    /// Marks the entries hidden by an entry with the same desktop file ID in a directory that
+    /// takes precedence, launchers only use the first one they find
+    fn update_shadowing(entries: &ListStore) {
+        let application_dirs = util::canonical_application_paths();
+        let entries: Vec<(FileEntry, Option<(usize, String)>)> = entries
+            .iter::<FileEntry>()
+            .filter_map(Result::ok)
+            .map(|entry| {
+                let id = util::desktop_file_id_in(&entry.path(), &application_dirs);
+                (entry, id)
+            })
+            .collect();
+
+        // The entries sharing each ID, in order of precedence
+        let mut by_id: HashMap<&str, Vec<(usize, PathBuf)>> = HashMap::new();
+        for (entry, id) in entries.iter() {
+            let Some((dir_index, id)) = id else {
+                continue;
+            };
+            by_id
+                .entry(id.as_str())
+                .or_default()
+                .push((*dir_index, entry.path()));
+        }
+        for paths in by_id.values_mut() {
+            paths.sort();
+        }
+
+        for (entry, id) in entries.iter() {
+            let paths = id.as_ref().and_then(|(_, id)| by_id.get(id.as_str()));
+            let effective = paths.and_then(|paths| paths.first()).map(|(_, path)| path);
+
+            let shadowed_by = effective.filter(|path| **path != entry.path()).cloned();
+            let overrides: Vec<String> = match paths {
+                Some(paths) if shadowed_by.is_none() => paths
+                    .iter()
+                    .skip(1)
+                    .map(|(_, path)| path.to_string_lossy().into_owned())
+                    .collect(),
+                _ => Vec::new(),
+            };
+
+            if entry.shadowed_by() != shadowed_by {
+                entry.set_shadowed_by(shadowed_by);
+            }
+            if entry.overrides() != overrides {
+                entry.set_overrides(overrides);
+            }
+        }
+    }
+
+    fn update_entry(entries: &ListStore, path: &Path) {
+        if path.exists() {
+            match find_entry(entries, path) {
+                Some((i, entry)) => {
+                    // Update entry
+                    if let Err(e) = entry.update() {
+                        eprintln!(
+                            "Failed to decode entry on update {}: {}",
+                            path.to_string_lossy(),
+                            e
+                        );
+                        entries.remove(i);
+                    }
+                }
+                None => {
+                    // Create entry
+                    if path.extension().is_some_and(|ext| ext == "desktop") {
+                        match FileEntry::from_path(path) {
+                            Ok(entry) => entries.append(&entry),
+                            Err(e) => {
+                                eprintln!(
+                                    "Entry creation failed {}: {}",
+                                    path.to_string_lossy(),
+                                    e
+                                )
+                            }
+                        }
+                    }
+                }
+            }
+        } else {
+            // Remove entry
+            if let Some((i, _)) = find_entry(entries, path) {
+                entries.remove(i);
+            }
+        }
+    }
+
+    /// Moves the entries at or below `from` to `to`, so that the same objects keep representing
+    /// the renamed files, editors open on them follow the rename as well
+    fn rename_entries(
+        entries: &ListStore,
+        navigation_view: &adw::NavigationView,
+        from: &Path,
+        to: &Path,
+    ) {
+        let renamed_path = |path: &Path| {
+            let relative_path = path.strip_prefix(from).ok()?;
+            if relative_path.as_os_str().is_empty() {
+                Some(to.to_path_buf())
+            } else {
+                Some(to.join(relative_path))
+            }
+        };
+
+        let renamed: Vec<(FileEntry, PathBuf)> = entries
+            .iter::<FileEntry>()
+            .filter_map(Result::ok)
+            .filter_map(|entry| {
+                let new_path = renamed_path(&entry.path())?;
+                Some((entry, new_path))
+            })
+            .collect();
+
+        if renamed.is_empty() {
+            // Nothing we know of was renamed, but the destination might be a desktop file now
+            update_entry(entries, to);
+            return;
+        }
+
+        for (entry, new_path) in renamed {
+            // The renamed file may have replaced an existing one
+            if let Some((i, _)) = find_entry(entries, &new_path) {
+                entries.remove(i);
+            }
+
+            let Some(i) = entries.find(&entry) else {
+                continue;
+            };
+
+            if new_path.extension().is_none_or(|ext| ext != "desktop") {
+                entries.remove(i);
+                continue;
+            }
+
+            entry.set_path(new_path.clone());
+            if let Err(e) = entry.update() {
+                eprintln!(
+                    "Failed to decode entry on rename {}: {}",
+                    new_path.to_string_lossy(),
+                    e
+                );
+                entries.remove(i);
+            }
+        }
+
+        let navigation_stack = navigation_view.navigation_stack();
+        for desktop_file_view in navigation_stack
+            .iter::<NavigationPage>()
+            .filter_map(Result::ok)
+            .filter_map(|page| page.downcast::<DesktopFileView>().ok())
+        {
+            if let Some(new_path) = renamed_path(&desktop_file_view.path()) {
+                if new_path.extension().is_some_and(|ext| ext == "desktop") {
+                    desktop_file_view.follow_rename(&new_path);
+                }
+            }
+        }
+    }
+
     /// Recursively find all desktop files in a given directory
     fn find_all_desktop_files(dir: &Path) -> io::Result<Vec<PathBuf>> {
         Ok(fs::read_dir(dir)?
@@ -448,6 +1806,281 @@ impl DMWindow {
             .build()
     }
 
+    /// Editors in the navigation stack with unsaved changes
+    pub fn unsaved_views(&self) -> ListStore {
+        self.imp()
+            .unsaved_views
+            .get_or_init(ListStore::new::<DesktopFileView>)
+            .clone()
+    }
+
+    /// Refresh [`Self::unsaved_views`] after an editor gained or lost unsaved changes
+    pub fn update_unsaved_views(&self) {
+        self.imp().update_unsaved_views();
+    }
+
+    /// Opens the editor for the desktop file at `path`
+    pub fn open_desktop_file(&self, path: &Path) {
+        let navigation_view = self.imp().navigation_view.get();
+        let desktop_file_view = DesktopFileView::new(navigation_view.clone(), path);
+        navigation_view.push(&desktop_file_view);
+    }
+
+    /// Opens the editor for the desktop file at `path`, with the focus on the entry `key`
+    pub fn open_desktop_file_at(&self, path: &Path, key: &str) {
+        let navigation_view = self.imp().navigation_view.get();
+        let desktop_file_view = DesktopFileView::new(navigation_view.clone(), path);
+        desktop_file_view.focus_entry(key);
+        navigation_view.push(&desktop_file_view);
+    }
+
+    /// Opens the editor for the desktop file at `path`, with `contents` replacing the entry as
+    /// unsaved changes
+    pub fn open_desktop_file_with_changes(&self, path: &Path, contents: &str) {
+        let navigation_view = self.imp().navigation_view.get();
+        let desktop_file_view = DesktopFileView::new(navigation_view.clone(), path);
+        navigation_view.push(&desktop_file_view);
+        desktop_file_view.apply_unsaved_contents(contents);
+    }
+
+    /// Opens the editor on a new entry that has no file until it is saved
+    pub fn open_scratch(&self) {
+        let navigation_view = self.imp().navigation_view.get();
+        let desktop_file_view = DesktopFileView::new_scratch(navigation_view.clone());
+        navigation_view.push(&desktop_file_view);
+    }
+
+    /// Open an editor for a new entry that starts from `contents`
+    pub fn open_scratch_with_contents(&self, contents: &str) {
+        let navigation_view = self.imp().navigation_view.get();
+        let desktop_file_view =
+            DesktopFileView::new_scratch_with_contents(navigation_view.clone(), contents);
+        navigation_view.push(&desktop_file_view);
+    }
+
+    /// Stops the watchers of the window and of its editors for good, ending the tasks waiting
+    /// on their updates. Suspending the window doesn't restart them afterwards
+    pub fn shutdown(&self) {
+        self.imp().shutdown();
+    }
+
+    /// Show a short message at the bottom of the window, for problems that don't need a dialog
+    pub fn add_toast(&self, toast: adw::Toast) {
+        self.imp().toast_overlay.add_toast(toast);
+    }
+
+    /// Add a file just created by the application to the list and open it. The list is updated
+    /// right away instead of waiting for the directory watcher to notice the new file
+    pub fn open_created_file(&self, path: &Path) {
+        self.imp().insert_entry(path);
+        self.open_desktop_file(path);
+    }
+
+    /// Update the entries of `paths` right away after the application moved or removed their
+    /// files, the directory watcher doesn't see files leaving the application directories
+    pub fn refresh_entries(&self, paths: &[&Path]) {
+        self.imp().refresh_entries(paths);
+    }
+
+    /// Start an application the way launchers do, telling the user if it fails. `app_info` is
+    /// `None` when GIO does not consider the entry an application that can be launched
+    pub fn launch_app_info(&self, app_info: Option<gio::DesktopAppInfo>) {
+        let message = match app_info {
+            Some(app_info) => {
+                let launch_context = self.display().app_launch_context();
+                match app_info.launch(&[], Some(&launch_context)) {
+                    Ok(()) => return,
+                    Err(e) => {
+                        eprintln!("Failed to launch {}: {e}", app_info.name());
+                        e.to_string()
+                    }
+                }
+            }
+            None => "The entry is not a launchable application, check its Type, Exec and TryExec \
+                     keys"
+                .to_string(),
+        };
+
+        let dialog = adw::AlertDialog::builder()
+            .heading("Failed to Launch")
+            .body(message)
+            .build();
+        dialog.add_response("close", "Close");
+        dialog.present(Some(self));
+    }
+
+    /// Open the file with the default application for plain text, changes made there are picked
+    /// up by the file watcher
+    pub fn open_in_text_editor(&self, path: &Path) {
+        let Some(app_info) = gio::AppInfo::default_for_type("text/plain", false) else {
+            let dialog = adw::AlertDialog::builder()
+                .heading("No Text Editor")
+                .body("There is no application set to open plain text files")
+                .build();
+            dialog.add_response("close", "Close");
+            dialog.present(Some(self));
+            return;
+        };
+
+        let launch_context = self.display().app_launch_context();
+        let file = gio::File::for_path(path);
+        if let Err(e) = app_info.launch(&[file], Some(&launch_context)) {
+            eprintln!("Failed to open {}: {e}", path.display());
+            let dialog = adw::AlertDialog::builder()
+                .heading("Failed to Open")
+                .body(e.to_string())
+                .build();
+            dialog.add_response("close", "Close");
+            dialog.present(Some(self));
+        }
+    }
+
+    /// Copy a system entry to the user applications directory, where it takes the place of the
+    /// original, and open the copy. The copy is written with `contents` when given, so changes
+    /// that could not be saved to the original carry over
+    pub fn override_in_user_dir(&self, path: &Path, contents: Option<&str>) {
+        let Some(target) = overrides::user_override_target(path) else {
+            return;
+        };
+
+        // An override made earlier is opened as it is, with the changes brought along on top of it
+        // as unsaved changes
+        if let (true, Some(contents)) = (target.exists(), contents) {
+            self.open_desktop_file_with_changes(&target, contents);
+            self.add_toast(adw::Toast::new(
+                "The launcher was already overridden, your changes are applied to the override",
+            ));
+            return;
+        }
+        if !target.exists() {
+            let result = target
+                .parent()
+                .map_or(Ok(()), std::fs::create_dir_all)
+                .and_then(|()| match contents {
+                    Some(contents) => std::fs::write(&target, contents),
+                    None => std::fs::copy(path, &target).map(|_| ()),
+                });
+            if let Err(e) = result {
+                eprintln!("Failed to override {}: {e}", path.to_string_lossy());
+                let dialog = adw::AlertDialog::builder()
+                    .heading("Failed to Override Launcher")
+                    .body(format!("The launcher could not be copied: {e}"))
+                    .build();
+                dialog.add_response("close", "Close");
+                dialog.present(Some(self));
+                return;
+            }
+            edited_files::mark_edited(&target);
+        }
+
+        self.open_created_file(&target);
+    }
+
+    /// Ask for the name of a copy of the entry, then write the copy in the folder for new
+    /// launchers and open it
+    pub fn duplicate_entry(&self, path: &Path, name: &str) {
+        let contents = match std::fs::read_to_string(path) {
+            Ok(contents) => contents,
+            Err(e) => {
+                eprintln!("Failed to read {}: {e}", path.to_string_lossy());
+                let dialog = adw::AlertDialog::builder()
+                    .heading("Failed to Duplicate Launcher")
+                    .body(format!("The launcher could not be read: {e}"))
+                    .build();
+                dialog.add_response("close", "Close");
+                dialog.present(Some(self));
+                return;
+            }
+        };
+
+        let name_row = adw::EntryRow::builder()
+            .title("Name")
+            .text(format!("{name} (Copy)"))
+            .build();
+        let list = gtk::ListBox::builder()
+            .selection_mode(gtk::SelectionMode::None)
+            .css_classes(["boxed-list"])
+            .build();
+        list.append(&name_row);
+
+        let dialog = adw::AlertDialog::builder()
+            .heading("Duplicate Launcher")
+            .body("The copy is saved in the folder for new launchers with its own file name")
+            .extra_child(&list)
+            .build();
+        dialog.add_responses(&[("cancel", "Cancel"), ("duplicate", "Duplicate")]);
+        dialog.set_response_appearance("duplicate", adw::ResponseAppearance::Suggested);
+        dialog.set_default_response(Some("duplicate"));
+        dialog.set_close_response("cancel");
+
+        name_row.connect_changed(glib::clone!(
+            #[weak]
+            dialog,
+            move |row| dialog.set_response_enabled("duplicate", !row.text().trim().is_empty())
+        ));
+        dialog.connect_response(
+            Some("duplicate"),
+            glib::clone!(
+                #[weak(rename_to = window)]
+                self,
+                #[weak]
+                name_row,
+                move |_, _| {
+                    let launcher = NewLauncher::from_template(name_row.text().trim(), &contents);
+                    match launcher.write() {
+                        Ok(path) => {
+                            edited_files::mark_edited(&path);
+                            window.open_created_file(&path);
+                        }
+                        Err(e) => {
+                            eprintln!("Failed to duplicate launcher: {e}");
+                            let dialog = adw::AlertDialog::builder()
+                                .heading("Failed to Duplicate Launcher")
+                                .body(format!("The copy could not be written: {e}"))
+                                .build();
+                            dialog.add_response("close", "Close");
+                            dialog.present(Some(&window));
+                        }
+                    }
+                }
+            ),
+        );
+        dialog.present(Some(self));
+    }
+
+    /// Move the file of an entry to the trash, after asking for confirmation
+    pub async fn trash_entry(&self, path: &Path, name: &str) {
+        let dialog = adw::AlertDialog::builder()
+            .heading(format!("Move {name} to the Trash?"))
+            .body(format!(
+                "The launcher {} will be moved to the trash, it can be restored from there",
+                util::display_path(path).to_string_lossy()
+            ))
+            .close_response("cancel")
+            .default_response("cancel")
+            .build();
+        dialog.add_response("cancel", "Cancel");
+        dialog.add_response("trash", "Move to Trash");
+        dialog.set_response_appearance("trash", adw::ResponseAppearance::Destructive);
+        if dialog.choose_future(self).await != "trash" {
+            return;
+        }
+
+        if let Err(e) = trash::delete(path) {
+            eprintln!("Failed to move {} to trash: {e}", path.to_string_lossy());
+            let dialog = adw::AlertDialog::builder()
+                .heading("Failed to Move to Trash")
+                .body(e.to_string())
+                .build();
+            dialog.add_response("close", "Close");
+            dialog.present(Some(self));
+            return;
+        }
+
+        // Don't wait for the watcher, the removal was requested from the list itself
+        self.refresh_entries(&[path]);
+    }
+
     fn entries(&self) -> ListStore {
         self.imp()
             .entries
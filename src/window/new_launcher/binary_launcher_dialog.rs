@@ -0,0 +1,409 @@
+/*
+* Copyright © 2025 Alessandro Balducci
+*
+* This file is part of Desktop File Editor.
+* Desktop File Editor is free software: you can redistribute it and/or modify it under the terms of the
+* GNU General Public License as published by the Free Software Foundation,
+* either version 3 of the License, or (at your option) any later version.
+* Desktop File Editor is distributed in the hope that it will be useful, but WITHOUT ANY WARRANTY;
+* without even the implied warranty of MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.
+* See the GNU General Public License for more details.
+* You should have received a copy of the GNU General Public License along with Desktop File Editor. If not, see <https://www.gnu.org/licenses/>.
+*/
+
+use std::path::Path;
+
+use adw::prelude::*;
+use gtk::glib::{self, closure_local};
+
+mod imp {
+    use std::collections::HashSet;
+    use std::fs;
+    use std::os::unix::fs::PermissionsExt;
+    use std::path::Path;
+    use std::sync::OnceLock;
+
+    use adw::{prelude::*, subclass::prelude::*};
+    use gtk::{
+        gio,
+        glib::{self, clone, closure_local, subclass::Signal},
+        ClosureExpression, Expression, FilterListModel, ListItem, ListView, NoSelection,
+        SignalListItemFactory, StringFilter, StringFilterMatchMode, StringList, StringObject,
+    };
+
+    use crate::shellparse::quote_exec_arg;
+    use crate::util::{self, display_path};
+
+    use super::super::{pretty_name, NewLauncher};
+
+    #[derive(Default)]
+    pub struct BinaryLauncherDialog {
+        navigation_view: adw::NavigationView,
+        executables: StringList,
+        details_page: adw::NavigationPage,
+        name_row: adw::EntryRow,
+        exec_row: adw::EntryRow,
+        terminal_row: adw::SwitchRow,
+    }
+
+    #[glib::object_subclass]
+    impl ObjectSubclass for BinaryLauncherDialog {
+        const NAME: &'static str = "BinaryLauncherDialog";
+        type Type = super::BinaryLauncherDialog;
+        type ParentType = adw::Dialog;
+    }
+
+    impl ObjectImpl for BinaryLauncherDialog {
+        fn constructed(&self) {
+            self.parent_constructed();
+            let obj = self.obj();
+
+            obj.set_title("New Launcher");
+            obj.set_content_width(420);
+            obj.set_content_height(560);
+
+            self.navigation_view.push(&self.make_choose_page());
+            self.make_details_page();
+            obj.set_child(Some(&self.navigation_view));
+
+            // Scanning PATH touches a lot of files, keep it off the main thread
+            glib::spawn_future_local(clone!(
+                #[weak]
+                obj,
+                async move {
+                    let executables = gio::spawn_blocking(find_executables)
+                        .await
+                        .unwrap_or_default();
+                    let executables: Vec<&str> =
+                        executables.iter().map(String::as_str).collect();
+                    obj.imp().executables.splice(0, 0, &executables);
+                }
+            ));
+        }
+
+        fn signals() -> &'static [Signal] {
+            static SIGNALS: OnceLock<Vec<Signal>> = OnceLock::new();
+            SIGNALS.get_or_init(|| {
+                vec![Signal::builder("launcher-created")
+                    .param_types([String::static_type()])
+                    .build()]
+            })
+        }
+    }
+
+    impl AdwDialogImpl for BinaryLauncherDialog {}
+    impl WidgetImpl for BinaryLauncherDialog {}
+
+    impl BinaryLauncherDialog {
+        fn make_choose_page(&self) -> adw::NavigationPage {
+            let empty_arr: &[Expression] = &[];
+            let file_name_expr = ClosureExpression::new::<String>(
+                empty_arr,
+                closure_local!(|entry: Option<StringObject>| {
+                    entry
+                        .map(|ent| file_name(Path::new(&ent.string())))
+                        .unwrap_or_default()
+                }),
+            );
+
+            let filter = StringFilter::builder()
+                .match_mode(StringFilterMatchMode::Substring)
+                .expression(file_name_expr)
+                .ignore_case(true)
+                .build();
+
+            let search_entry = gtk::SearchEntry::builder()
+                .placeholder_text("Search programs")
+                .hexpand(true)
+                .build();
+            search_entry.connect_search_changed(clone!(
+                #[weak]
+                filter,
+                move |search_entry| {
+                    filter.set_search(Some(&search_entry.text()));
+                }
+            ));
+
+            let factory = SignalListItemFactory::new();
+            factory.connect_setup(move |_, list_item| {
+                let name_label = gtk::Label::builder().halign(gtk::Align::Start).build();
+                let path_label = gtk::Label::builder()
+                    .halign(gtk::Align::Start)
+                    .ellipsize(gtk::pango::EllipsizeMode::Middle)
+                    .css_classes(["caption", "dim-label"])
+                    .build();
+
+                let item_box = gtk::Box::builder()
+                    .orientation(gtk::Orientation::Vertical)
+                    .margin_top(6)
+                    .margin_bottom(6)
+                    .build();
+                item_box.append(&name_label);
+                item_box.append(&path_label);
+
+                list_item
+                    .downcast_ref::<ListItem>()
+                    .expect("Needs to be ListItem")
+                    .set_child(Some(&item_box));
+            });
+
+            factory.connect_bind(move |_, list_item| {
+                let list_item = list_item
+                    .downcast_ref::<ListItem>()
+                    .expect("Needs to be ListItem");
+                let path = list_item
+                    .item()
+                    .and_downcast::<StringObject>()
+                    .expect("Needs to be StringObject")
+                    .string();
+                let item_box = list_item.child().expect("ListItem has no child");
+
+                let name_label = item_box
+                    .first_child()
+                    .and_downcast::<gtk::Label>()
+                    .expect("Needs to be Label");
+                let path_label = item_box
+                    .last_child()
+                    .and_downcast::<gtk::Label>()
+                    .expect("Needs to be Label");
+
+                name_label.set_label(&file_name(Path::new(&path)));
+                path_label.set_label(&path);
+            });
+
+            let filter_model = FilterListModel::new(Some(self.executables.clone()), Some(filter));
+            filter_model.set_incremental(true);
+
+            let list_view = ListView::builder()
+                .model(&NoSelection::new(Some(filter_model)))
+                .factory(&factory)
+                .single_click_activate(true)
+                .css_classes(["navigation-sidebar"])
+                .build();
+
+            list_view.connect_activate(clone!(
+                #[weak(rename_to = dialog)]
+                self.obj(),
+                move |list_view, position| {
+                    let model = list_view.model().expect("No model present");
+                    let path = model
+                        .item(position)
+                        .and_downcast::<StringObject>()
+                        .expect("Needs to be StringObject")
+                        .string();
+                    dialog.imp().show_details(Path::new(&path));
+                }
+            ));
+
+            let scrolled_window = gtk::ScrolledWindow::builder()
+                .hscrollbar_policy(gtk::PolicyType::Never)
+                .vexpand(true)
+                .child(&list_view)
+                .build();
+
+            let header_bar = adw::HeaderBar::builder()
+                .title_widget(&search_entry)
+                .build();
+
+            let toolbar_view = adw::ToolbarView::new();
+            toolbar_view.add_top_bar(&header_bar);
+            toolbar_view.set_content(Some(&scrolled_window));
+
+            search_entry.set_key_capture_widget(Some(&toolbar_view));
+
+            adw::NavigationPage::builder()
+                .title("Choose Program")
+                .child(&toolbar_view)
+                .build()
+        }
+
+        fn make_details_page(&self) {
+            self.name_row.set_title("Name");
+            self.exec_row.set_title("Exec");
+            self.terminal_row.set_title("Terminal");
+            self.terminal_row
+                .set_subtitle("Run the program in a terminal window");
+
+            let group = adw::PreferencesGroup::new();
+            group.add(&self.name_row);
+            group.add(&self.exec_row);
+            group.add(&self.terminal_row);
+
+            let preferences_page = adw::PreferencesPage::new();
+            preferences_page.add(&group);
+
+            let create_button = gtk::Button::builder()
+                .label("Create")
+                .css_classes(["suggested-action"])
+                .build();
+            create_button.connect_clicked(clone!(
+                #[weak(rename_to = dialog)]
+                self.obj(),
+                move |_| {
+                    glib::spawn_future_local(async move {
+                        dialog.imp().create_launcher().await;
+                    });
+                }
+            ));
+
+            // Name and Exec are required
+            let update_create_sensitivity = clone!(
+                #[weak]
+                create_button,
+                #[weak(rename_to = dialog)]
+                self.obj(),
+                move || {
+                    let imp = dialog.imp();
+                    create_button.set_sensitive(
+                        !imp.name_row.text().trim().is_empty()
+                            && !imp.exec_row.text().trim().is_empty(),
+                    );
+                }
+            );
+            self.name_row.connect_changed(clone!(
+                #[strong]
+                update_create_sensitivity,
+                move |_| update_create_sensitivity()
+            ));
+            self.exec_row
+                .connect_changed(move |_| update_create_sensitivity());
+
+            let header_bar = adw::HeaderBar::new();
+            header_bar.pack_end(&create_button);
+
+            let toolbar_view = adw::ToolbarView::new();
+            toolbar_view.add_top_bar(&header_bar);
+            toolbar_view.set_content(Some(&preferences_page));
+
+            self.details_page.set_title("Launcher Details");
+            self.details_page.set_child(Some(&toolbar_view));
+        }
+
+        fn show_details(&self, path: &Path) {
+            let file_name = file_name(path);
+            self.name_row.set_text(&pretty_name(&file_name));
+            self.exec_row.set_text(&exec_for(path));
+
+            // Programs that don't ship a desktop file are most likely command line tools
+            self.terminal_row.set_active(!has_desktop_file(&file_name));
+
+            self.navigation_view.push(&self.details_page);
+        }
+
+        async fn create_launcher(&self) {
+            let obj = self.obj();
+
+            let mut launcher = NewLauncher::new(self.name_row.text().trim());
+            launcher.set("Exec", self.exec_row.text().trim());
+            launcher.set("Terminal", self.terminal_row.is_active().to_string());
+
+            if let Some(path) = launcher.create_or_report(&*obj).await {
+                let path = path.to_string_lossy().to_string();
+                obj.emit_by_name::<()>("launcher-created", &[&path]);
+                obj.close();
+            }
+        }
+    }
+
+    fn file_name(path: &Path) -> String {
+        path.file_name()
+            .map(|name| name.to_string_lossy().to_string())
+            .unwrap_or_default()
+    }
+
+    /// All executables found in PATH, sorted by file name. When two directories contain an
+    /// executable with the same name, only the one that would be run is kept
+    fn find_executables() -> Vec<String> {
+        let Some(search_paths) = util::binary_search_paths() else {
+            return Vec::new();
+        };
+
+        let mut names = HashSet::new();
+        let mut executables = Vec::new();
+        for dir in std::env::split_paths(&search_paths) {
+            let Ok(read_dir) = fs::read_dir(&dir) else {
+                continue;
+            };
+
+            for entry in read_dir.filter_map(Result::ok) {
+                let path = entry.path();
+                let is_executable = fs::metadata(&path).is_ok_and(|metadata| {
+                    metadata.is_file() && metadata.permissions().mode() & 0o111 != 0
+                });
+
+                if is_executable && names.insert(entry.file_name()) {
+                    executables.push(path);
+                }
+            }
+        }
+
+        executables.sort_by_key(|path| path.file_name().map(|name| name.to_os_string()));
+        executables
+            .into_iter()
+            .map(|path| path.to_string_lossy().to_string())
+            .collect()
+    }
+
+    /// Programs installed in the home directory (e.g. ~/.cargo/bin) are often not in the PATH of
+    /// the graphical session, so they are referenced by their absolute path
+    pub fn exec_for(path: &Path) -> String {
+        let exec = if path.starts_with(glib::home_dir()) {
+            display_path(path).to_string_lossy().to_string()
+        } else {
+            file_name(path)
+        };
+
+        quote_exec_arg(&exec)
+    }
+
+    fn has_desktop_file(file_name: &str) -> bool {
+        gio::AppInfo::all().iter().any(|app_info| {
+            app_info
+                .executable()
+                .file_name()
+                .is_some_and(|name| name == file_name)
+        })
+    }
+}
+
+glib::wrapper! {
+    pub struct BinaryLauncherDialog(ObjectSubclass<imp::BinaryLauncherDialog>)
+        @extends adw::Dialog, gtk::Widget,
+        @implements gtk::Accessible, gtk::Buildable, gtk::ConstraintTarget, gtk::ShortcutManager;
+}
+
+impl BinaryLauncherDialog {
+    pub fn new() -> Self {
+        glib::Object::builder().build()
+    }
+
+    pub fn connect_launcher_created<F: Fn(&Path) + 'static>(&self, f: F) {
+        self.connect_closure(
+            "launcher-created",
+            false,
+            closure_local!(move |_: BinaryLauncherDialog, path: String| f(Path::new(&path))),
+        );
+    }
+}
+
+impl Default for BinaryLauncherDialog {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::path::Path;
+
+    use super::imp::exec_for;
+
+    #[test]
+    fn exec_quoting() {
+        assert_eq!(exec_for(Path::new("/usr/bin/tool")), "tool");
+        assert_eq!(
+            exec_for(Path::new("/opt/bin/100%\"tool\"")),
+            r#""100%%\"tool\"""#
+        );
+    }
+}
@@ -0,0 +1,325 @@
+/*
+* Copyright © 2025 Alessandro Balducci
+*
+* This file is part of Desktop File Editor.
+* Desktop File Editor is free software: you can redistribute it and/or modify it under the terms of the
+* GNU General Public License as published by the Free Software Foundation,
+* either version 3 of the License, or (at your option) any later version.
+* Desktop File Editor is distributed in the hope that it will be useful, but WITHOUT ANY WARRANTY;
+* without even the implied warranty of MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.
+* See the GNU General Public License for more details.
+* You should have received a copy of the GNU General Public License along with Desktop File Editor. If not, see <https://www.gnu.org/licenses/>.
+*/
+
+mod binary_launcher_dialog;
+mod new_file_dialog;
+mod script_launcher_dialog;
+mod templates;
+mod web_app_dialog;
+
+use std::{
+    fs, io,
+    path::{Path, PathBuf},
+};
+
+use adw::prelude::*;
+use gtk::glib;
+#[cfg(feature = "flatpak")]
+use gtk::{gdk, gio};
+
+#[cfg(feature = "flatpak")]
+use crate::dynamic_launcher;
+use crate::settings::settings;
+
+pub use binary_launcher_dialog::BinaryLauncherDialog;
+pub use new_file_dialog::NewFileDialog;
+pub use script_launcher_dialog::ScriptLauncherDialog;
+pub use templates::save_template;
+pub use web_app_dialog::WebAppDialog;
+
+/// Contents of the main group of a launcher created by a wizard
+#[derive(Clone)]
+pub struct NewLauncher {
+    name: String,
+    entries: Vec<(&'static str, String)>,
+    /// Text of the template the launcher starts from, its keys are replaced by the entries
+    template: Option<String>,
+}
+
+impl NewLauncher {
+    pub fn new(name: &str) -> Self {
+        Self {
+            name: name.to_string(),
+            entries: vec![("Type", "Application".to_string()), ("Name", name.to_string())],
+            template: None,
+        }
+    }
+
+    /// A launcher made from the text of a template, its Type is kept
+    pub fn from_template(name: &str, template: &str) -> Self {
+        Self {
+            name: name.to_string(),
+            entries: vec![("Name", name.to_string())],
+            template: Some(template.to_string()),
+        }
+    }
+
+    /// Sets the value of a key, replacing the previous one if present
+    pub fn set(&mut self, key: &'static str, value: impl Into<String>) {
+        let value = value.into();
+        match self.entries.iter_mut().find(|(k, _)| *k == key) {
+            Some((_, old_value)) => *old_value = value,
+            None => self.entries.push((key, value)),
+        }
+    }
+
+    pub fn to_entry_string(&self) -> String {
+        if let Some(template) = &self.template {
+            return templates::fill_template(template, &self.entries);
+        }
+
+        let mut result = String::from("[Desktop Entry]\n");
+        for (key, value) in self.entries.iter() {
+            result.push_str(&format!("{key}={}\n", escape_value(value)));
+        }
+        result
+    }
+
+    /// Writes the launcher in the folder chosen for new launchers, without overwriting existing
+    /// files, and returns its path
+    pub fn write(&self) -> io::Result<PathBuf> {
+        self.write_in(&new_files_dir())
+    }
+
+    /// Writes the launcher in `dir`, without overwriting existing files, and returns its path
+    pub fn write_in(&self, dir: &Path) -> io::Result<PathBuf> {
+        fs::create_dir_all(dir)?;
+
+        let path = available_path(dir, &launcher_file_stem(&self.name), "desktop");
+        fs::write(&path, self.to_entry_string())?;
+        Ok(path)
+    }
+
+    /// Creates the launcher, showing an error dialog on `parent` on failure, and returns its path.
+    ///
+    /// When the DynamicLauncher portal is available the Flatpak build installs the launcher
+    /// through it instead of writing the file directly, the user may cancel the portal's dialog.
+    pub async fn create_or_report(&self, parent: &impl IsA<gtk::Widget>) -> Option<PathBuf> {
+        #[cfg(feature = "flatpak")]
+        let result = if dynamic_launcher::is_available().await {
+            self.install_with_portal().await.map_err(|e| e.to_string())
+        } else {
+            self.write().map(Some).map_err(|e| e.to_string())
+        };
+        #[cfg(not(feature = "flatpak"))]
+        let result = self.write().map(Some).map_err(|e| e.to_string());
+
+        match result {
+            Ok(path) => path,
+            Err(e) => {
+                eprintln!("Failed to create new launcher: {e}");
+                let dialog =
+                    adw::AlertDialog::new(Some("Failed to Create Launcher"), Some(&e));
+                dialog.add_response("close", "Close");
+                dialog.present(Some(parent));
+                None
+            }
+        }
+    }
+
+    #[cfg(feature = "flatpak")]
+    async fn install_with_portal(&self) -> zbus::Result<Option<PathBuf>> {
+        let stem = launcher_file_stem(&self.name);
+        let icon = self.icon_bytes().await;
+
+        let installed = dynamic_launcher::install(
+            &self.name,
+            icon,
+            &dynamic_launcher::desktop_file_id(&stem),
+            |name| {
+                // The portal writes the Icon key itself, pointing to its own copy of the icon
+                let mut launcher = self.clone();
+                launcher.entries.retain(|(key, _)| *key != "Icon");
+                launcher.set("Name", name);
+                launcher.to_entry_string()
+            },
+        )
+        .await?;
+
+        // The portal installs in the host's data home, not in the one of the sandbox
+        Ok(installed.map(|installed| {
+            glib::home_dir()
+                .join(".local/share/applications")
+                .join(installed.desktop_file_id)
+        }))
+    }
+
+    /// Contents of the icon file, either the one set in the Icon key or the one it resolves to in
+    /// the icon theme
+    #[cfg(feature = "flatpak")]
+    async fn icon_bytes(&self) -> Vec<u8> {
+        const FALLBACK_ICON: &str = "application-x-executable";
+
+        let icon = self
+            .entries
+            .iter()
+            .find(|(key, _)| *key == "Icon")
+            .map(|(_, value)| value.as_str())
+            .unwrap_or(FALLBACK_ICON);
+
+        let file = if Path::new(icon).is_absolute() {
+            Some(gio::File::for_path(icon))
+        } else {
+            gdk::Display::default().and_then(|display| {
+                gtk::IconTheme::for_display(&display)
+                    .lookup_icon(
+                        icon,
+                        &[FALLBACK_ICON],
+                        128,
+                        1,
+                        gtk::TextDirection::None,
+                        gtk::IconLookupFlags::empty(),
+                    )
+                    .file()
+            })
+        };
+
+        let Some(file) = file else {
+            return Vec::new();
+        };
+        match file.load_contents_future().await {
+            Ok((contents, _)) => contents.to_vec(),
+            Err(e) => {
+                eprintln!("Failed to read launcher icon {icon}: {e}");
+                Vec::new()
+            }
+        }
+    }
+}
+
+pub fn user_applications_dir() -> PathBuf {
+    glib::user_data_dir().join("applications")
+}
+
+/// Folder new launchers are written to, the applications directory of the user unless another
+/// one was chosen in the preferences
+pub fn new_files_dir() -> PathBuf {
+    let dir = settings().new_files_dir();
+    if dir.is_empty() {
+        user_applications_dir()
+    } else {
+        PathBuf::from(dir)
+    }
+}
+
+/// Escapes the characters that cannot appear verbatim in a desktop entry value
+fn escape_value(value: &str) -> String {
+    let mut escaped = String::with_capacity(value.len());
+    for c in value.chars() {
+        match c {
+            '\\' => escaped.push_str("\\\\"),
+            '\n' => escaped.push_str("\\n"),
+            '\t' => escaped.push_str("\\t"),
+            '\r' => escaped.push_str("\\r"),
+            c => escaped.push(c),
+        }
+    }
+    escaped
+}
+
+/// Turns a launcher name into something suitable for a file name, e.g. "My App" -> "my-app"
+pub fn launcher_file_stem(name: &str) -> String {
+    let stem = name
+        .to_lowercase()
+        .split(|c: char| !c.is_alphanumeric() && c != '.' && c != '_')
+        .filter(|part| !part.is_empty())
+        .collect::<Vec<_>>()
+        .join("-");
+
+    if stem.is_empty() {
+        "launcher".to_string()
+    } else {
+        stem
+    }
+}
+
+/// New file stem for a launcher whose Name changed from `old_name` to `new_name`, if its file is
+/// named after the old Name and the new one gives a different file name
+pub fn renamed_file_stem(file_stem: &str, old_name: &str, new_name: &str) -> Option<String> {
+    let old_stem = launcher_file_stem(old_name);
+    let new_stem = launcher_file_stem(new_name);
+    (file_stem.to_lowercase() == old_stem && new_stem != old_stem).then_some(new_stem)
+}
+
+/// Guesses a human readable name from a file name, e.g. "my-tool" -> "My Tool"
+fn pretty_name(file_name: &str) -> String {
+    file_name
+        .split(['-', '_', ' '])
+        .filter(|word| !word.is_empty())
+        .map(|word| {
+            let mut chars = word.chars();
+            match chars.next() {
+                Some(first) => first.to_uppercase().chain(chars).collect(),
+                None => String::new(),
+            }
+        })
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+/// First path in `dir` named after `stem` with the given extension that does not exist yet
+fn available_path(dir: &Path, stem: &str, extension: &str) -> PathBuf {
+    let mut path = dir.join(format!("{stem}.{extension}"));
+    let mut n = 2;
+    while path.exists() {
+        path = dir.join(format!("{stem}-{n}.{extension}"));
+        n += 1;
+    }
+    path
+}
+
+#[cfg(test)]
+mod test {
+    use super::{escape_value, launcher_file_stem, pretty_name, renamed_file_stem, NewLauncher};
+
+    #[test]
+    fn file_stem() {
+        assert_eq!(launcher_file_stem("My App"), "my-app");
+        assert_eq!(launcher_file_stem("  Tool (nightly) "), "tool-nightly");
+        assert_eq!(launcher_file_stem("org.example_app"), "org.example_app");
+        assert_eq!(launcher_file_stem("!!!"), "launcher");
+    }
+
+    #[test]
+    fn renamed_stem() {
+        assert_eq!(
+            renamed_file_stem("my-app", "My App", "My Tool"),
+            Some("my-tool".to_string())
+        );
+        assert_eq!(renamed_file_stem("my-app", "My App", "my app!"), None);
+        assert_eq!(renamed_file_stem("org.example.App", "App", "Tool"), None);
+    }
+
+    #[test]
+    fn name_from_file() {
+        assert_eq!(pretty_name("my-tool"), "My Tool");
+        assert_eq!(pretty_name("backup_photos"), "Backup Photos");
+    }
+
+    #[test]
+    fn escape() {
+        assert_eq!(escape_value("a\\b\nc"), "a\\\\b\\nc");
+    }
+
+    #[test]
+    fn entry_string() {
+        let mut launcher = NewLauncher::new("Tool");
+        launcher.set("Exec", "tool");
+        launcher.set("Terminal", "true");
+        launcher.set("Exec", "tool --flag");
+        assert_eq!(
+            launcher.to_entry_string(),
+            "[Desktop Entry]\nType=Application\nName=Tool\nExec=tool --flag\nTerminal=true\n"
+        );
+    }
+}
@@ -0,0 +1,282 @@
+/*
+* Copyright © 2025 Alessandro Balducci
+*
+* This file is part of Desktop File Editor.
+* Desktop File Editor is free software: you can redistribute it and/or modify it under the terms of the
+* GNU General Public License as published by the Free Software Foundation,
+* either version 3 of the License, or (at your option) any later version.
+* Desktop File Editor is distributed in the hope that it will be useful, but WITHOUT ANY WARRANTY;
+* without even the implied warranty of MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.
+* See the GNU General Public License for more details.
+* You should have received a copy of the GNU General Public License along with Desktop File Editor. If not, see <https://www.gnu.org/licenses/>.
+*/
+
+use std::path::Path;
+
+use adw::prelude::*;
+use gtk::glib::{self, closure_local};
+
+mod imp {
+    use std::cell::RefCell;
+    use std::path::PathBuf;
+    use std::sync::OnceLock;
+
+    use adw::{prelude::*, subclass::prelude::*};
+    use gtk::{
+        gio,
+        glib::{self, clone, subclass::Signal},
+        FileDialog,
+    };
+
+    use crate::util::display_path;
+
+    use super::super::templates::{templates, Template};
+    use super::super::{new_files_dir, NewLauncher};
+
+    #[derive(Default)]
+    pub struct NewFileDialog {
+        name_row: adw::EntryRow,
+        template_row: adw::ComboRow,
+        templates: RefCell<Vec<Template>>,
+        /// Template the rows were last filled from
+        shown_template: RefCell<Option<Template>>,
+        exec_row: adw::EntryRow,
+        url_row: adw::EntryRow,
+        dir_row: adw::ActionRow,
+        create_button: gtk::Button,
+        dir: RefCell<PathBuf>,
+    }
+
+    #[glib::object_subclass]
+    impl ObjectSubclass for NewFileDialog {
+        const NAME: &'static str = "NewFileDialog";
+        type Type = super::NewFileDialog;
+        type ParentType = adw::Dialog;
+    }
+
+    impl ObjectImpl for NewFileDialog {
+        fn constructed(&self) {
+            self.parent_constructed();
+            let obj = self.obj();
+
+            obj.set_title("New Desktop File");
+            obj.set_content_width(420);
+
+            self.name_row.set_title("Name");
+            self.exec_row.set_title("Command");
+            self.url_row.set_title("URL");
+
+            let templates = templates();
+            let names: Vec<&str> = templates.iter().map(|t| t.name.as_str()).collect();
+            self.template_row.set_title("Template");
+            self.template_row
+                .set_model(Some(&gtk::StringList::new(&names)));
+            self.templates.replace(templates);
+            self.template_row.connect_selected_notify(clone!(
+                #[weak(rename_to = dialog)]
+                obj,
+                move |_| dialog.imp().update_template()
+            ));
+
+            self.dir_row.set_title("Folder");
+            self.dir_row.add_suffix(&self.make_choose_button());
+            self.set_dir(new_files_dir());
+
+            for row in [&self.name_row, &self.exec_row, &self.url_row] {
+                row.connect_changed(clone!(
+                    #[weak(rename_to = dialog)]
+                    obj,
+                    move |_| dialog.imp().update_create_sensitivity()
+                ));
+            }
+
+            let entry_group = adw::PreferencesGroup::new();
+            entry_group.add(&self.name_row);
+            entry_group.add(&self.template_row);
+            entry_group.add(&self.exec_row);
+            entry_group.add(&self.url_row);
+
+            let file_group = adw::PreferencesGroup::new();
+            file_group.add(&self.dir_row);
+
+            let preferences_page = adw::PreferencesPage::new();
+            preferences_page.add(&entry_group);
+            preferences_page.add(&file_group);
+
+            self.create_button.set_label("Create");
+            self.create_button.add_css_class("suggested-action");
+            self.create_button.connect_clicked(clone!(
+                #[weak(rename_to = dialog)]
+                obj,
+                move |_| dialog.imp().create_file()
+            ));
+
+            let header_bar = adw::HeaderBar::new();
+            header_bar.pack_end(&self.create_button);
+
+            let toolbar_view = adw::ToolbarView::new();
+            toolbar_view.add_top_bar(&header_bar);
+            toolbar_view.set_content(Some(&preferences_page));
+            obj.set_child(Some(&toolbar_view));
+
+            self.update_template();
+        }
+
+        fn signals() -> &'static [Signal] {
+            static SIGNALS: OnceLock<Vec<Signal>> = OnceLock::new();
+            SIGNALS.get_or_init(|| {
+                vec![Signal::builder("launcher-created")
+                    .param_types([String::static_type()])
+                    .build()]
+            })
+        }
+    }
+
+    impl AdwDialogImpl for NewFileDialog {}
+    impl WidgetImpl for NewFileDialog {}
+
+    impl NewFileDialog {
+        fn make_choose_button(&self) -> gtk::Button {
+            let button = gtk::Button::builder()
+                .label("Choose…")
+                .valign(gtk::Align::Center)
+                .build();
+
+            button.connect_clicked(clone!(
+                #[weak(rename_to = dialog)]
+                self.obj(),
+                move |button| {
+                    let file_dialog = FileDialog::builder()
+                        .title("Choose Folder")
+                        .initial_folder(&gio::File::for_path(&*dialog.imp().dir.borrow()))
+                        .build();
+
+                    let parent = button.root().and_downcast::<gtk::Window>();
+                    file_dialog.select_folder(
+                        parent.as_ref(),
+                        gio::Cancellable::NONE,
+                        clone!(
+                            #[weak]
+                            dialog,
+                            move |res: Result<gio::File, glib::Error>| {
+                                if let Some(path) = res.ok().and_then(|file| file.path()) {
+                                    dialog.imp().set_dir(path);
+                                }
+                            }
+                        ),
+                    );
+                }
+            ));
+
+            button
+        }
+
+        fn set_dir(&self, path: PathBuf) {
+            self.dir_row
+                .set_subtitle(&display_path(&path).to_string_lossy());
+            self.dir.replace(path);
+        }
+
+        fn selected_template(&self) -> Option<Template> {
+            let templates = self.templates.borrow();
+            templates
+                .get(self.template_row.selected() as usize)
+                .cloned()
+        }
+
+        /// Applications run a command, links open an address and directories need neither. The
+        /// values of the template are examples, they replace the text unless it was typed
+        fn update_template(&self) {
+            let Some(template) = self.selected_template() else {
+                return;
+            };
+            let entry_type = template.value("Type").unwrap_or("Application");
+            self.exec_row.set_visible(entry_type == "Application");
+            self.url_row.set_visible(entry_type == "Link");
+
+            let shown_template = self.shown_template.replace(Some(template.clone()));
+            for (row, key) in [(&self.exec_row, "Exec"), (&self.url_row, "URL")] {
+                let text = row.text();
+                let shown_value = shown_template.as_ref().and_then(|t| t.value(key));
+                if text.is_empty() || shown_value == Some(text.as_str()) {
+                    row.set_text(template.value(key).unwrap_or_default());
+                }
+            }
+            self.update_create_sensitivity();
+        }
+
+        /// The row with the command or the address, if the template needs one
+        fn target_row(&self) -> Option<&adw::EntryRow> {
+            [&self.exec_row, &self.url_row]
+                .into_iter()
+                .find(|row| row.is_visible())
+        }
+
+        fn update_create_sensitivity(&self) {
+            let target_filled = self
+                .target_row()
+                .is_none_or(|row| !row.text().trim().is_empty());
+            self.create_button
+                .set_sensitive(!self.name_row.text().trim().is_empty() && target_filled);
+        }
+
+        fn create_file(&self) {
+            let name = self.name_row.text().trim().to_string();
+            let Some(template) = self.selected_template() else {
+                return;
+            };
+
+            let mut launcher = NewLauncher::from_template(&name, &template.contents);
+            if self.exec_row.is_visible() {
+                launcher.set("Exec", self.exec_row.text().trim());
+            }
+            if self.url_row.is_visible() {
+                launcher.set("URL", self.url_row.text().trim());
+            }
+
+            let obj = self.obj();
+            match launcher.write_in(&self.dir.borrow()) {
+                Ok(path) => {
+                    let path = path.to_string_lossy().to_string();
+                    obj.emit_by_name::<()>("launcher-created", &[&path]);
+                    obj.close();
+                }
+                Err(e) => {
+                    eprintln!("Failed to create new desktop file: {e}");
+                    let dialog = adw::AlertDialog::new(
+                        Some("Failed to Create Desktop File"),
+                        Some(&e.to_string()),
+                    );
+                    dialog.add_response("close", "Close");
+                    dialog.present(Some(&*obj));
+                }
+            }
+        }
+    }
+}
+
+glib::wrapper! {
+    pub struct NewFileDialog(ObjectSubclass<imp::NewFileDialog>)
+        @extends adw::Dialog, gtk::Widget,
+        @implements gtk::Accessible, gtk::Buildable, gtk::ConstraintTarget, gtk::ShortcutManager;
+}
+
+impl NewFileDialog {
+    pub fn new() -> Self {
+        glib::Object::builder().build()
+    }
+
+    pub fn connect_launcher_created<F: Fn(&Path) + 'static>(&self, f: F) {
+        self.connect_closure(
+            "launcher-created",
+            false,
+            closure_local!(move |_: NewFileDialog, path: String| f(Path::new(&path))),
+        );
+    }
+}
+
+impl Default for NewFileDialog {
+    fn default() -> Self {
+        Self::new()
+    }
+}
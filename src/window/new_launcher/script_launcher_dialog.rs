@@ -0,0 +1,340 @@
+/*
+* Copyright © 2025 Alessandro Balducci
+*
+* This file is part of Desktop File Editor.
+* Desktop File Editor is free software: you can redistribute it and/or modify it under the terms of the
+* GNU General Public License as published by the Free Software Foundation,
+* either version 3 of the License, or (at your option) any later version.
+* Desktop File Editor is distributed in the hope that it will be useful, but WITHOUT ANY WARRANTY;
+* without even the implied warranty of MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.
+* See the GNU General Public License for more details.
+* You should have received a copy of the GNU General Public License along with Desktop File Editor. If not, see <https://www.gnu.org/licenses/>.
+*/
+
+use std::path::Path;
+
+use adw::prelude::*;
+use gtk::glib::{self, closure_local};
+
+mod imp {
+    use std::cell::RefCell;
+    use std::fs;
+    use std::io::{BufRead, BufReader};
+    use std::os::unix::fs::PermissionsExt;
+    use std::path::{Path, PathBuf};
+    use std::sync::OnceLock;
+
+    use adw::{prelude::*, subclass::prelude::*};
+    use gtk::{
+        gio::Cancellable,
+        glib::{self, clone, subclass::Signal},
+        FileDialog,
+    };
+
+    use crate::shellparse::Command;
+    use crate::util::display_path;
+
+    use super::super::{pretty_name, NewLauncher};
+
+    /// Interpreters offered in the interpreter row, after running the script directly
+    const INTERPRETERS: [&str; 3] = ["sh", "bash", "python3"];
+
+    #[derive(Default)]
+    pub struct ScriptLauncherDialog {
+        script_row: adw::ActionRow,
+        interpreter_row: adw::ComboRow,
+        name_row: adw::EntryRow,
+        terminal_row: adw::SwitchRow,
+        working_dir_row: adw::ActionRow,
+        create_button: gtk::Button,
+        script: RefCell<Option<PathBuf>>,
+        working_dir: RefCell<Option<PathBuf>>,
+    }
+
+    #[glib::object_subclass]
+    impl ObjectSubclass for ScriptLauncherDialog {
+        const NAME: &'static str = "ScriptLauncherDialog";
+        type Type = super::ScriptLauncherDialog;
+        type ParentType = adw::Dialog;
+    }
+
+    impl ObjectImpl for ScriptLauncherDialog {
+        fn constructed(&self) {
+            self.parent_constructed();
+            let obj = self.obj();
+
+            obj.set_title("New Script Launcher");
+            obj.set_content_width(420);
+
+            self.script_row.set_title("Script");
+            self.script_row.set_subtitle("No script selected");
+            self.script_row.add_suffix(&self.make_choose_button(false));
+
+            let mut interpreters = vec!["Run Directly"];
+            interpreters.extend(INTERPRETERS);
+            self.interpreter_row.set_title("Interpreter");
+            self.interpreter_row
+                .set_model(Some(&gtk::StringList::new(&interpreters)));
+            self.interpreter_row.connect_selected_notify(clone!(
+                #[weak(rename_to = dialog)]
+                obj,
+                move |_| dialog.imp().update_interpreter_subtitle()
+            ));
+
+            self.name_row.set_title("Name");
+            self.name_row.connect_changed(clone!(
+                #[weak(rename_to = dialog)]
+                obj,
+                move |_| dialog.imp().update_create_sensitivity()
+            ));
+
+            self.terminal_row.set_title("Terminal");
+            self.terminal_row
+                .set_subtitle("Run the script in a terminal window");
+
+            self.working_dir_row.set_title("Working Directory");
+            self.working_dir_row.set_subtitle("Not set");
+            self.working_dir_row
+                .add_suffix(&self.make_choose_button(true));
+
+            let script_group = adw::PreferencesGroup::new();
+            script_group.add(&self.script_row);
+            script_group.add(&self.interpreter_row);
+
+            let launcher_group = adw::PreferencesGroup::new();
+            launcher_group.add(&self.name_row);
+            launcher_group.add(&self.terminal_row);
+            launcher_group.add(&self.working_dir_row);
+
+            let preferences_page = adw::PreferencesPage::new();
+            preferences_page.add(&script_group);
+            preferences_page.add(&launcher_group);
+
+            self.create_button.set_label("Create");
+            self.create_button.add_css_class("suggested-action");
+            self.create_button.set_sensitive(false);
+            self.create_button.connect_clicked(clone!(
+                #[weak(rename_to = dialog)]
+                obj,
+                move |_| {
+                    glib::spawn_future_local(async move {
+                        dialog.imp().create_launcher().await;
+                    });
+                }
+            ));
+
+            let header_bar = adw::HeaderBar::new();
+            header_bar.pack_end(&self.create_button);
+
+            let toolbar_view = adw::ToolbarView::new();
+            toolbar_view.add_top_bar(&header_bar);
+            toolbar_view.set_content(Some(&preferences_page));
+            obj.set_child(Some(&toolbar_view));
+        }
+
+        fn signals() -> &'static [Signal] {
+            static SIGNALS: OnceLock<Vec<Signal>> = OnceLock::new();
+            SIGNALS.get_or_init(|| {
+                vec![Signal::builder("launcher-created")
+                    .param_types([String::static_type()])
+                    .build()]
+            })
+        }
+    }
+
+    impl AdwDialogImpl for ScriptLauncherDialog {}
+    impl WidgetImpl for ScriptLauncherDialog {}
+
+    impl ScriptLauncherDialog {
+        fn make_choose_button(&self, folder: bool) -> gtk::Button {
+            let button = gtk::Button::builder()
+                .label("Choose…")
+                .valign(gtk::Align::Center)
+                .build();
+
+            button.connect_clicked(clone!(
+                #[weak(rename_to = dialog)]
+                self.obj(),
+                move |button| {
+                    let file_dialog = FileDialog::builder()
+                        .title(if folder {
+                            "Choose Working Directory"
+                        } else {
+                            "Choose Script"
+                        })
+                        .build();
+
+                    let parent = button.root().and_downcast::<gtk::Window>();
+                    let on_chosen = clone!(
+                        #[weak]
+                        dialog,
+                        move |res: Result<gtk::gio::File, glib::Error>| {
+                            let Some(path) = res.ok().and_then(|file| file.path()) else {
+                                return;
+                            };
+
+                            if folder {
+                                dialog.imp().set_working_dir(path);
+                            } else {
+                                dialog.imp().set_script(path);
+                            }
+                        }
+                    );
+
+                    if folder {
+                        file_dialog.select_folder(parent.as_ref(), Cancellable::NONE, on_chosen);
+                    } else {
+                        file_dialog.open(parent.as_ref(), Cancellable::NONE, on_chosen);
+                    }
+                }
+            ));
+
+            button
+        }
+
+        fn set_script(&self, path: PathBuf) {
+            self.script_row
+                .set_subtitle(&display_path(&path).to_string_lossy());
+
+            let interpreter = guess_interpreter(&path);
+            let selected = interpreter
+                .and_then(|interpreter| INTERPRETERS.iter().position(|i| *i == interpreter))
+                .map_or(0, |i| i + 1);
+            self.interpreter_row.set_selected(selected as u32);
+
+            if let Some(stem) = path.file_stem() {
+                self.name_row.set_text(&pretty_name(&stem.to_string_lossy()));
+            }
+
+            if self.working_dir.borrow().is_none() {
+                if let Some(parent) = path.parent() {
+                    self.set_working_dir(parent.to_path_buf());
+                }
+            }
+
+            self.script.replace(Some(path));
+            self.update_interpreter_subtitle();
+            self.update_create_sensitivity();
+        }
+
+        fn set_working_dir(&self, path: PathBuf) {
+            self.working_dir_row
+                .set_subtitle(&display_path(&path).to_string_lossy());
+            self.working_dir.replace(Some(path));
+        }
+
+        fn update_interpreter_subtitle(&self) {
+            let runs_directly = self.interpreter_row.selected() == 0;
+            let not_executable = self
+                .script
+                .borrow()
+                .as_deref()
+                .is_some_and(|script| !is_executable(script));
+
+            self.interpreter_row
+                .set_subtitle(if runs_directly && not_executable {
+                    "The script is not executable"
+                } else {
+                    ""
+                });
+        }
+
+        fn update_create_sensitivity(&self) {
+            self.create_button.set_sensitive(
+                self.script.borrow().is_some() && !self.name_row.text().trim().is_empty(),
+            );
+        }
+
+        async fn create_launcher(&self) {
+            let Some(script) = self.script.borrow().as_deref().map(display_path) else {
+                return;
+            };
+            let script = script.to_string_lossy().to_string();
+
+            let command = match self.interpreter_row.selected() as usize {
+                0 => Command {
+                    command: script,
+                    args: Vec::new(),
+                    variables: Vec::new(),
+                },
+                i => Command {
+                    command: INTERPRETERS[i - 1].to_string(),
+                    args: vec![script],
+                    variables: Vec::new(),
+                },
+            };
+
+            let mut launcher = NewLauncher::new(self.name_row.text().trim());
+            launcher.set("Exec", command.to_exec_string());
+            launcher.set("Terminal", self.terminal_row.is_active().to_string());
+            if let Some(working_dir) = self.working_dir.borrow().as_deref() {
+                launcher.set("Path", display_path(working_dir).to_string_lossy());
+            }
+
+            let obj = self.obj();
+            if let Some(path) = launcher.create_or_report(&*obj).await {
+                let path = path.to_string_lossy().to_string();
+                obj.emit_by_name::<()>("launcher-created", &[&path]);
+                obj.close();
+            }
+        }
+    }
+
+    fn is_executable(path: &Path) -> bool {
+        fs::metadata(path).is_ok_and(|metadata| metadata.permissions().mode() & 0o111 != 0)
+    }
+
+    /// Picks the interpreter from the shebang, or from the extension when the script can't be run
+    /// directly. None means the script should be run directly
+    fn guess_interpreter(path: &Path) -> Option<&'static str> {
+        let shebang = fs::File::open(path).ok().and_then(|file| {
+            let mut first_line = String::new();
+            BufReader::new(file).read_line(&mut first_line).ok()?;
+            first_line.starts_with("#!").then_some(first_line)
+        });
+
+        if let Some(shebang) = shebang {
+            if is_executable(path) {
+                return None;
+            }
+
+            return INTERPRETERS
+                .into_iter()
+                .rev()
+                .find(|interpreter| shebang.contains(interpreter));
+        }
+
+        match path.extension()?.to_str()? {
+            "py" => Some("python3"),
+            "bash" => Some("bash"),
+            "sh" => Some("sh"),
+            _ => None,
+        }
+    }
+}
+
+glib::wrapper! {
+    pub struct ScriptLauncherDialog(ObjectSubclass<imp::ScriptLauncherDialog>)
+        @extends adw::Dialog, gtk::Widget,
+        @implements gtk::Accessible, gtk::Buildable, gtk::ConstraintTarget, gtk::ShortcutManager;
+}
+
+impl ScriptLauncherDialog {
+    pub fn new() -> Self {
+        glib::Object::builder().build()
+    }
+
+    pub fn connect_launcher_created<F: Fn(&Path) + 'static>(&self, f: F) {
+        self.connect_closure(
+            "launcher-created",
+            false,
+            closure_local!(move |_: ScriptLauncherDialog, path: String| f(Path::new(&path))),
+        );
+    }
+}
+
+impl Default for ScriptLauncherDialog {
+    fn default() -> Self {
+        Self::new()
+    }
+}
@@ -0,0 +1,222 @@
+/*
+* Copyright © 2025 Alessandro Balducci
+*
+* This file is part of Desktop File Editor.
+* Desktop File Editor is free software: you can redistribute it and/or modify it under the terms of the
+* GNU General Public License as published by the Free Software Foundation,
+* either version 3 of the License, or (at your option) any later version.
+* Desktop File Editor is distributed in the hope that it will be useful, but WITHOUT ANY WARRANTY;
+* without even the implied warranty of MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.
+* See the GNU General Public License for more details.
+* You should have received a copy of the GNU General Public License along with Desktop File Editor. If not, see <https://www.gnu.org/licenses/>.
+*/
+
+//! Starting points for new desktop files: the built-in ones cover the common kinds of entries,
+//! the user can add their own from any file open in the editor
+
+use std::{
+    fs, io,
+    path::{Path, PathBuf},
+};
+
+use gtk::glib;
+
+use super::{available_path, escape_value, launcher_file_stem, pretty_name};
+
+/// A desktop file new ones can start from
+#[derive(Debug, Clone, PartialEq)]
+pub struct Template {
+    pub name: String,
+    pub contents: String,
+    /// File of a template saved by the user, built-in templates have none
+    pub path: Option<PathBuf>,
+}
+
+impl Template {
+    /// Value of `key` in the main group of the template, if set
+    pub fn value(&self, key: &str) -> Option<&str> {
+        main_group_lines(&self.contents)
+            .find_map(|line| line.strip_prefix(key)?.strip_prefix('='))
+            .map(str::trim)
+            .filter(|value| !value.is_empty())
+    }
+}
+
+const BUILTIN_TEMPLATES: [(&str, &str); 6] = [
+    (
+        "Application",
+        "[Desktop Entry]\nType=Application\nName=\nComment=\nExec=\n\
+         Icon=application-x-executable\nTerminal=false\nCategories=Utility;\n",
+    ),
+    (
+        "Link",
+        "[Desktop Entry]\nType=Link\nName=\nComment=\nURL=https://\nIcon=text-html\n",
+    ),
+    (
+        "Directory",
+        "[Desktop Entry]\nType=Directory\nName=\nComment=\nIcon=folder\n",
+    ),
+    (
+        "Terminal Application",
+        "[Desktop Entry]\nType=Application\nName=\nComment=\nExec=\n\
+         Icon=utilities-terminal\nTerminal=true\nCategories=ConsoleOnly;Utility;\n",
+    ),
+    (
+        "Wine Application",
+        "[Desktop Entry]\nType=Application\nName=\nComment=\n\
+         Exec=wine start /unix /path/to/app.exe\nIcon=wine\nTerminal=false\nCategories=Wine;\n\
+         StartupWMClass=app.exe\n",
+    ),
+    (
+        "Flatpak Application",
+        "[Desktop Entry]\nType=Application\nName=\nComment=\n\
+         Exec=flatpak run --branch=stable org.example.App\nIcon=org.example.App\n\
+         Terminal=false\nTryExec=flatpak\n",
+    ),
+];
+
+/// Directory of the templates saved by the user
+fn user_templates_dir() -> PathBuf {
+    glib::user_config_dir()
+        .join("desktop-file-editor")
+        .join("templates")
+}
+
+/// The built-in templates, followed by those saved by the user in the order of their names
+pub fn templates() -> Vec<Template> {
+    let builtin = BUILTIN_TEMPLATES.iter().map(|(name, contents)| Template {
+        name: name.to_string(),
+        contents: contents.to_string(),
+        path: None,
+    });
+
+    let mut user_templates: Vec<Template> = fs::read_dir(user_templates_dir())
+        .into_iter()
+        .flatten()
+        .filter_map(Result::ok)
+        .map(|dir_entry| dir_entry.path())
+        .filter(|path| {
+            path.extension()
+                .is_some_and(|extension| extension == "desktop")
+        })
+        .filter_map(|path| {
+            let contents = fs::read_to_string(&path)
+                .inspect_err(|e| eprintln!("Failed to read template {}: {e}", path.display()))
+                .ok()?;
+            let name = pretty_name(&path.file_stem()?.to_string_lossy());
+            Some(Template {
+                name,
+                contents,
+                path: Some(path),
+            })
+        })
+        .collect();
+    user_templates.sort_by(|a, b| a.name.cmp(&b.name));
+
+    builtin.chain(user_templates).collect()
+}
+
+/// Save `contents` as a template named `name`, and return the path of its file
+pub fn save_template(name: &str, contents: &str) -> io::Result<PathBuf> {
+    let dir = user_templates_dir();
+    fs::create_dir_all(&dir)?;
+    let path = available_path(&dir, &launcher_file_stem(name), "desktop");
+    fs::write(&path, contents)?;
+    Ok(path)
+}
+
+/// Lines of the `Desktop Entry` group of `contents`
+fn main_group_lines(contents: &str) -> impl Iterator<Item = &str> {
+    contents
+        .lines()
+        .skip_while(|line| line.trim() != "[Desktop Entry]")
+        .skip(1)
+        .take_while(|line| !line.starts_with('['))
+}
+
+/// The text of `template` with the values of the main group replaced by `entries`, the keys it
+/// does not have are added at the end of the group
+pub fn fill_template(template: &str, entries: &[(&str, String)]) -> String {
+    let mut result = String::with_capacity(template.len());
+    let mut written = vec![false; entries.len()];
+    let mut in_main_group = false;
+    let mut found_main_group = false;
+
+    // Added keys go before the blank lines separating the group from the next one
+    let write_missing = |result: &mut String, written: &mut [bool]| {
+        let mut blank_lines = 0;
+        while result.ends_with("\n\n") {
+            result.pop();
+            blank_lines += 1;
+        }
+        for ((key, value), written) in entries.iter().zip(written.iter_mut()) {
+            if !*written {
+                result.push_str(&format!("{key}={}\n", escape_value(value)));
+                *written = true;
+            }
+        }
+        result.push_str(&"\n".repeat(blank_lines));
+    };
+
+    for line in template.lines() {
+        if line.starts_with('[') {
+            if in_main_group {
+                write_missing(&mut result, &mut written);
+            }
+            in_main_group = line.trim() == "[Desktop Entry]";
+            found_main_group |= in_main_group;
+        } else if in_main_group {
+            let key = line.split_once('=').map(|(key, _)| key.trim());
+            if let Some(index) = entries.iter().position(|(k, _)| Some(*k) == key) {
+                if !written[index] {
+                    let (key, value) = &entries[index];
+                    result.push_str(&format!("{key}={}\n", escape_value(value)));
+                    written[index] = true;
+                }
+                continue;
+            }
+        }
+        result.push_str(line);
+        result.push('\n');
+    }
+
+    if in_main_group {
+        write_missing(&mut result, &mut written);
+    } else if !found_main_group {
+        let mut main_group = String::from("[Desktop Entry]\n");
+        write_missing(&mut main_group, &mut written);
+        if !result.is_empty() {
+            main_group.push('\n');
+        }
+        result.insert_str(0, &main_group);
+    }
+    result
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn fill() {
+        let template =
+            "[Desktop Entry]\nType=Application\nName=\nExec=old\n\n[Desktop Action a]\nName=A\n";
+        let entries = [("Name", "Tool".to_string()), ("Icon", "tool".to_string())];
+        assert_eq!(
+            fill_template(template, &entries),
+            "[Desktop Entry]\nType=Application\nName=Tool\nExec=old\nIcon=tool\n\n\
+             [Desktop Action a]\nName=A\n"
+        );
+        assert_eq!(
+            fill_template("", &entries),
+            "[Desktop Entry]\nName=Tool\nIcon=tool\n"
+        );
+    }
+
+    #[test]
+    fn template_values() {
+        let template = &templates()[0];
+        assert_eq!(template.value("Type"), Some("Application"));
+        assert_eq!(template.value("Name"), None);
+    }
+}
@@ -0,0 +1,285 @@
+/*
+* Copyright © 2025 Alessandro Balducci
+*
+* This file is part of Desktop File Editor.
+* Desktop File Editor is free software: you can redistribute it and/or modify it under the terms of the
+* GNU General Public License as published by the Free Software Foundation,
+* either version 3 of the License, or (at your option) any later version.
+* Desktop File Editor is distributed in the hope that it will be useful, but WITHOUT ANY WARRANTY;
+* without even the implied warranty of MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.
+* See the GNU General Public License for more details.
+* You should have received a copy of the GNU General Public License along with Desktop File Editor. If not, see <https://www.gnu.org/licenses/>.
+*/
+
+use std::path::Path;
+
+use adw::prelude::*;
+use gtk::glib::{self, closure_local};
+
+mod imp {
+    use std::cell::RefCell;
+    use std::sync::OnceLock;
+
+    use adw::{prelude::*, subclass::prelude::*};
+    use gtk::{
+        gdk, gio,
+        glib::{self, clone, subclass::Signal, Uri, UriFlags},
+    };
+
+    use crate::shellparse::quote_exec_arg;
+    use crate::util;
+
+    use super::super::{launcher_file_stem, NewLauncher};
+
+    /// Browsers supporting the `--app=URL` flag, which opens a page in its own window
+    const APP_MODE_BROWSERS: [(&str, &str); 7] = [
+        ("chromium", "Chromium"),
+        ("chromium-browser", "Chromium"),
+        ("google-chrome", "Google Chrome"),
+        ("google-chrome-stable", "Google Chrome"),
+        ("brave-browser", "Brave"),
+        ("microsoft-edge", "Microsoft Edge"),
+        ("vivaldi", "Vivaldi"),
+    ];
+
+    #[derive(Default)]
+    pub struct WebAppDialog {
+        url_row: adw::EntryRow,
+        name_row: adw::EntryRow,
+        browser_row: adw::ComboRow,
+        favicon_row: adw::SwitchRow,
+        create_button: gtk::Button,
+        // Binaries of the browsers listed in the browser row, after the default browser
+        browsers: RefCell<Vec<&'static str>>,
+    }
+
+    #[glib::object_subclass]
+    impl ObjectSubclass for WebAppDialog {
+        const NAME: &'static str = "WebAppDialog";
+        type Type = super::WebAppDialog;
+        type ParentType = adw::Dialog;
+    }
+
+    impl ObjectImpl for WebAppDialog {
+        fn constructed(&self) {
+            self.parent_constructed();
+            let obj = self.obj();
+
+            obj.set_title("New Web App");
+            obj.set_content_width(420);
+
+            self.url_row.set_title("URL");
+            self.name_row.set_title("Name");
+
+            let installed_browsers: Vec<(&str, &str)> = APP_MODE_BROWSERS
+                .into_iter()
+                .filter(|(binary, _)| is_installed(binary))
+                .collect();
+
+            let mut browser_names = vec!["Default Browser"];
+            browser_names.extend(installed_browsers.iter().map(|(_, name)| *name));
+            self.browser_row.set_title("Open With");
+            self.browser_row
+                .set_model(Some(&gtk::StringList::new(&browser_names)));
+            self.browsers
+                .replace(installed_browsers.iter().map(|(binary, _)| *binary).collect());
+
+            // Only app mode browsers open the site in a window of its own
+            self.browser_row.connect_selected_notify(|browser_row| {
+                browser_row.set_subtitle(if browser_row.selected() == 0 {
+                    "Opens the site as a link in a browser tab"
+                } else {
+                    "Opens the site in its own window"
+                });
+            });
+            self.browser_row
+                .set_subtitle("Opens the site as a link in a browser tab");
+
+            self.favicon_row.set_title("Use Site Icon");
+            self.favicon_row
+                .set_subtitle("Download the site's favicon and use it as the icon");
+            self.favicon_row.set_active(true);
+
+            let group = adw::PreferencesGroup::new();
+            group.add(&self.url_row);
+            group.add(&self.name_row);
+            group.add(&self.browser_row);
+            group.add(&self.favicon_row);
+
+            let preferences_page = adw::PreferencesPage::new();
+            preferences_page.add(&group);
+
+            self.create_button.set_label("Create");
+            self.create_button.add_css_class("suggested-action");
+            self.create_button.set_sensitive(false);
+            self.create_button.connect_clicked(clone!(
+                #[weak(rename_to = dialog)]
+                obj,
+                move |_| {
+                    glib::spawn_future_local(async move {
+                        dialog.imp().create_launcher().await;
+                    });
+                }
+            ));
+
+            self.url_row.connect_changed(clone!(
+                #[weak(rename_to = dialog)]
+                obj,
+                move |_| dialog.imp().update_create_sensitivity()
+            ));
+            self.name_row.connect_changed(clone!(
+                #[weak(rename_to = dialog)]
+                obj,
+                move |_| dialog.imp().update_create_sensitivity()
+            ));
+
+            let header_bar = adw::HeaderBar::new();
+            header_bar.pack_end(&self.create_button);
+
+            let toolbar_view = adw::ToolbarView::new();
+            toolbar_view.add_top_bar(&header_bar);
+            toolbar_view.set_content(Some(&preferences_page));
+            obj.set_child(Some(&toolbar_view));
+        }
+
+        fn signals() -> &'static [Signal] {
+            static SIGNALS: OnceLock<Vec<Signal>> = OnceLock::new();
+            SIGNALS.get_or_init(|| {
+                vec![Signal::builder("launcher-created")
+                    .param_types([String::static_type()])
+                    .build()]
+            })
+        }
+    }
+
+    impl AdwDialogImpl for WebAppDialog {}
+    impl WidgetImpl for WebAppDialog {}
+
+    impl WebAppDialog {
+        fn update_create_sensitivity(&self) {
+            let url = normalize_url(&self.url_row.text());
+            if url.is_some() {
+                self.url_row.remove_css_class("error");
+            } else {
+                self.url_row.add_css_class("error");
+            }
+
+            self.create_button
+                .set_sensitive(url.is_some() && !self.name_row.text().trim().is_empty());
+        }
+
+        async fn create_launcher(&self) {
+            let Some(url) = normalize_url(&self.url_row.text()) else {
+                return;
+            };
+            let name = self.name_row.text().trim().to_string();
+
+            self.create_button.set_sensitive(false);
+
+            let mut launcher = NewLauncher::new(&name);
+            let selected = self.browser_row.selected() as usize;
+            match selected.checked_sub(1).and_then(|i| self.browsers.borrow().get(i).copied()) {
+                Some(browser) => {
+                    let app_arg = quote_exec_arg(&format!("--app={}", url.to_str()));
+                    launcher.set("Exec", format!("{browser} {app_arg}"));
+                }
+                None => {
+                    launcher.set("Type", "Link");
+                    launcher.set("URL", url.to_str());
+                }
+            }
+
+            if self.favicon_row.is_active() {
+                match install_favicon(&url, &launcher_file_stem(&name)).await {
+                    Ok(icon_name) => launcher.set("Icon", icon_name),
+                    Err(e) => eprintln!("Failed to install favicon for {url}: {e}"),
+                }
+            }
+
+            let obj = self.obj();
+            if let Some(path) = launcher.create_or_report(&*obj).await {
+                let path = path.to_string_lossy().to_string();
+                obj.emit_by_name::<()>("launcher-created", &[&path]);
+                obj.close();
+            } else {
+                self.update_create_sensitivity();
+            }
+        }
+    }
+
+    /// Parses a web address, assuming https if the scheme is missing
+    fn normalize_url(input: &str) -> Option<Uri> {
+        let input = input.trim();
+        let input = if input.contains("://") {
+            input.to_string()
+        } else {
+            format!("https://{input}")
+        };
+
+        let uri = Uri::parse(&input, UriFlags::NONE).ok()?;
+        let is_web = matches!(uri.scheme().as_str(), "http" | "https");
+        let has_host = uri.host().is_some_and(|host| !host.is_empty());
+        (is_web && has_host).then_some(uri)
+    }
+
+    /// Downloads the favicon of the site and installs it as a PNG in the user's hicolor icon
+    /// theme, returns the name the icon is looked up with
+    async fn install_favicon(url: &Uri, stem: &str) -> Result<String, glib::Error> {
+        let favicon_uri = url.parse_relative("/favicon.ico", UriFlags::NONE)?;
+        let (contents, _) = gio::File::for_uri(&favicon_uri.to_str())
+            .load_contents_future()
+            .await?;
+
+        let texture = gdk::Texture::from_bytes(&glib::Bytes::from(&contents[..]))?;
+
+        let dir = util::hicolor_dir()
+            .join(util::hicolor_size_dir(Some(texture.width())))
+            .join("apps");
+        std::fs::create_dir_all(&dir)
+            .map_err(|e| glib::Error::new(gio::IOErrorEnum::Failed, &e.to_string()))?;
+
+        // The theme would mix the favicon up with another icon of the same name in another size
+        let mut name = stem.to_string();
+        let mut n = 2;
+        while util::hicolor_icon_exists(&name) {
+            name = format!("{stem}-{n}");
+            n += 1;
+        }
+
+        texture
+            .save_to_png(dir.join(format!("{name}.png")))
+            .map_err(|e| glib::Error::new(gio::IOErrorEnum::Failed, &e.to_string()))?;
+        Ok(name)
+    }
+
+    fn is_installed(binary: &str) -> bool {
+        which::which_in_global(binary, util::binary_search_paths())
+            .is_ok_and(|mut paths| paths.next().is_some())
+    }
+}
+
+glib::wrapper! {
+    pub struct WebAppDialog(ObjectSubclass<imp::WebAppDialog>)
+        @extends adw::Dialog, gtk::Widget,
+        @implements gtk::Accessible, gtk::Buildable, gtk::ConstraintTarget, gtk::ShortcutManager;
+}
+
+impl WebAppDialog {
+    pub fn new() -> Self {
+        glib::Object::builder().build()
+    }
+
+    pub fn connect_launcher_created<F: Fn(&Path) + 'static>(&self, f: F) {
+        self.connect_closure(
+            "launcher-created",
+            false,
+            closure_local!(move |_: WebAppDialog, path: String| f(Path::new(&path))),
+        );
+    }
+}
+
+impl Default for WebAppDialog {
+    fn default() -> Self {
+        Self::new()
+    }
+}
@@ -0,0 +1,111 @@
+/*
+* Copyright © 2025 Alessandro Balducci
+*
+* This file is part of Desktop File Editor.
+* Desktop File Editor is free software: you can redistribute it and/or modify it under the terms of the
+* GNU General Public License as published by the Free Software Foundation,
+* either version 3 of the License, or (at your option) any later version.
+* Desktop File Editor is distributed in the hope that it will be useful, but WITHOUT ANY WARRANTY;
+* without even the implied warranty of MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.
+* See the GNU General Public License for more details.
+* You should have received a copy of the GNU General Public License along with Desktop File Editor. If not, see <https://www.gnu.org/licenses/>.
+*/
+
+//! Moving the customizations of the user, the entries in their applications directory that
+//! override entries installed on the system, to another machine
+
+use std::{
+    collections::HashSet,
+    fs, io,
+    path::{Path, PathBuf},
+};
+
+use gtk::gio::ListStore;
+
+use super::{file_entry::FileEntry, new_launcher::user_applications_dir};
+use crate::util;
+
+/// Files of the user applications directory that take the place of another entry with the same
+/// desktop file ID
+pub fn user_overrides(entries: &ListStore) -> Vec<PathBuf> {
+    let user_dir = user_applications_dir();
+    let user_dir = fs::canonicalize(&user_dir).unwrap_or(user_dir);
+
+    let overrides: HashSet<PathBuf> = entries
+        .iter::<FileEntry>()
+        .filter_map(Result::ok)
+        .filter_map(|entry| entry.shadowed_by())
+        .filter(|path| path.starts_with(&user_dir))
+        .collect();
+
+    let mut overrides: Vec<PathBuf> = overrides.into_iter().collect();
+    overrides.sort();
+    overrides
+}
+
+/// Where each of `files`, found under `from`, goes when copied to `to`. The path relative to
+/// the directory is kept, it is part of the desktop file ID. Files outside of `from` are skipped
+pub fn relative_targets(files: &[PathBuf], from: &Path, to: &Path) -> Vec<(PathBuf, PathBuf)> {
+    files
+        .iter()
+        .filter_map(|file| {
+            let relative = file.strip_prefix(from).ok()?;
+            Some((file.clone(), to.join(relative)))
+        })
+        .collect()
+}
+
+/// Where a copy of the file at `path` goes to take its place: the user applications directory,
+/// under the same desktop file ID. `None` for files that are already in that directory
+pub fn user_override_target(path: &Path) -> Option<PathBuf> {
+    let user_dir = user_applications_dir();
+    let canonical_user_dir = fs::canonicalize(&user_dir).unwrap_or(user_dir.clone());
+    if path.starts_with(&canonical_user_dir) {
+        return None;
+    }
+
+    // Files outside of the applications directories have no ID to keep
+    let relative = util::canonical_application_paths()
+        .iter()
+        .find_map(|dir| path.strip_prefix(dir).ok().map(Path::to_path_buf))
+        .or_else(|| path.file_name().map(PathBuf::from))?;
+    Some(user_dir.join(relative))
+}
+
+/// Copy each file to its target, creating the directories that are missing
+pub fn copy_files(targets: &[(PathBuf, PathBuf)]) -> io::Result<()> {
+    for (source, target) in targets {
+        if let Some(parent) = target.parent() {
+            fs::create_dir_all(parent)?;
+        }
+        fs::copy(source, target)?;
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn keeps_relative_paths() {
+        let files = [
+            PathBuf::from("/home/user/.local/share/applications/firefox.desktop"),
+            PathBuf::from("/home/user/.local/share/applications/kde/kate.desktop"),
+            PathBuf::from("/usr/share/applications/gedit.desktop"),
+        ];
+        let targets = relative_targets(
+            &files,
+            Path::new("/home/user/.local/share/applications"),
+            Path::new("/media/backup"),
+        );
+        let copied: Vec<&Path> = targets.iter().map(|(_, target)| target.as_path()).collect();
+        assert_eq!(
+            copied,
+            [
+                Path::new("/media/backup/firefox.desktop"),
+                Path::new("/media/backup/kde/kate.desktop"),
+            ]
+        );
+    }
+}
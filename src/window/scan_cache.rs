@@ -0,0 +1,247 @@
+/*
+* Copyright © 2025 Alessandro Balducci
+*
+* This file is part of Desktop File Editor.
+* Desktop File Editor is free software: you can redistribute it and/or modify it under the terms of the
+* GNU General Public License as published by the Free Software Foundation,
+* either version 3 of the License, or (at your option) any later version.
+* Desktop File Editor is distributed in the hope that it will be useful, but WITHOUT ANY WARRANTY;
+* without even the implied warranty of MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.
+* See the GNU General Public License for more details.
+* You should have received a copy of the GNU General Public License along with Desktop File Editor. If not, see <https://www.gnu.org/licenses/>.
+*/
+
+use std::{
+    collections::HashMap,
+    fs,
+    path::{Path, PathBuf},
+    sync::Arc,
+    time::UNIX_EPOCH,
+};
+
+use freedesktop_desktop_entry::{get_languages_from_env, DecodeError};
+use gtk::glib;
+
+use super::file_entry::{FileEntry, Problem};
+
+const CACHE_GROUP: &str = "Scan Cache";
+/// Bumped whenever the cached fields change, older caches are then ignored
+const CACHE_VERSION: u64 = 5;
+
+/// Metadata of a scanned desktop file, enough to list it without decoding the file again
+#[derive(Debug, Clone)]
+pub struct CachedEntry {
+    /// Modification time of the file in nanoseconds since the epoch
+    pub modified: u64,
+    /// Canonical path of the file
+    pub target: PathBuf,
+    pub name: Option<String>,
+    pub localized_name: Option<String>,
+    pub icon: String,
+    pub no_display: bool,
+    pub hidden: bool,
+    pub comment: Option<String>,
+    pub generic_name: Option<String>,
+    pub categories: Option<String>,
+    pub only_show_in: Option<String>,
+    pub not_show_in: Option<String>,
+    pub exec: Option<String>,
+    pub note: Option<String>,
+    /// Names of the Desktop Actions
+    pub actions: Vec<String>,
+    pub warnings: Vec<Problem>,
+}
+
+/// Entries found by the previous scan, so that only the files modified since then need to be
+/// decoded again. The checks depending on the rest of the system, like whether the binary is
+/// installed, are still run for every entry
+pub struct ScanCache {
+    previous: PreviousScan,
+    current: HashMap<PathBuf, CachedEntry>,
+    locales: String,
+}
+
+/// The entries of the previous scan, shared with the threads decoding the files
+#[derive(Clone, Default)]
+pub struct PreviousScan(Arc<HashMap<PathBuf, CachedEntry>>);
+
+impl PreviousScan {
+    /// The cached entry of the file at `path`, if the file wasn't modified since
+    pub fn unchanged(&self, path: &Path, modified: u64) -> Option<&CachedEntry> {
+        let target = fs::canonicalize(path).ok();
+        self.0
+            .get(path)
+            .filter(|cached| cached.modified == modified && target.as_ref() == Some(&cached.target))
+    }
+
+    /// Metadata of the file at `path`, taken from the previous scan if the file wasn't modified
+    /// since. This does not create any object, so it can run on another thread
+    pub fn cached_entry(&self, path: &Path) -> Result<CachedEntry, DecodeError> {
+        let Some(modified) = modified_nanos(path) else {
+            return FileEntry::read_cached(path, 0);
+        };
+        match self.unchanged(path, modified) {
+            Some(cached) => Ok(cached.clone()),
+            None => FileEntry::read_cached(path, modified),
+        }
+    }
+}
+
+impl ScanCache {
+    pub fn load() -> Self {
+        let mut cache = Self {
+            previous: PreviousScan::default(),
+            current: HashMap::new(),
+            locales: get_languages_from_env().join(";"),
+        };
+
+        let key_file = glib::KeyFile::new();
+        if let Err(e) = key_file.load_from_file(Self::file_path(), glib::KeyFileFlags::NONE) {
+            if !e.matches(glib::FileError::Noent) {
+                eprintln!("Failed to load scan cache: {e}");
+            }
+            return cache;
+        }
+
+        // Names and comments are cached already translated, a different language needs a full
+        // scan
+        let version = key_file.uint64(CACHE_GROUP, "Version").ok();
+        let locales = key_file.string(CACHE_GROUP, "Locales").ok();
+        if version != Some(CACHE_VERSION) || locales.as_deref() != Some(cache.locales.as_str()) {
+            return cache;
+        }
+
+        cache.previous = PreviousScan(Arc::new(
+            key_file
+                .groups()
+                .iter()
+                .filter(|group| group.as_str() != CACHE_GROUP)
+                .filter_map(|group| read_entry(&key_file, group.as_str()))
+                .collect(),
+        ));
+        cache
+    }
+
+    pub fn previous(&self) -> PreviousScan {
+        self.previous.clone()
+    }
+
+    /// Keep `cached` for the next scan, as the entry of the file at `path`
+    pub fn insert(&mut self, path: PathBuf, cached: CachedEntry) {
+        self.current.insert(path, cached);
+    }
+
+    /// Save the entries obtained since the cache was loaded, the ones that were not asked for
+    /// are dropped
+    pub fn save(&self) {
+        let key_file = glib::KeyFile::new();
+        key_file.set_uint64(CACHE_GROUP, "Version", CACHE_VERSION);
+        key_file.set_string(CACHE_GROUP, "Locales", &self.locales);
+
+        for (i, (path, cached)) in self.current.iter().enumerate() {
+            write_entry(&key_file, &format!("Entry {i}"), path, cached);
+        }
+
+        let path = Self::file_path();
+        let result = path
+            .parent()
+            .map_or(Ok(()), fs::create_dir_all)
+            .map_err(|e| e.to_string())
+            .and_then(|_| key_file.save_to_file(&path).map_err(|e| e.to_string()));
+
+        if let Err(e) = result {
+            eprintln!("Failed to save scan cache: {e}");
+        }
+    }
+
+    fn file_path() -> PathBuf {
+        glib::user_cache_dir()
+            .join("desktop-file-editor")
+            .join("scan-cache.ini")
+    }
+}
+
+pub fn modified_nanos(path: &Path) -> Option<u64> {
+    let modified = fs::metadata(path).and_then(|m| m.modified()).ok()?;
+    let nanos = modified.duration_since(UNIX_EPOCH).ok()?.as_nanos();
+    u64::try_from(nanos).ok()
+}
+
+fn read_entry(key_file: &glib::KeyFile, group: &str) -> Option<(PathBuf, CachedEntry)> {
+    let string = |key: &str| key_file.string(group, key).ok().map(String::from);
+    let boolean = |key: &str| key_file.boolean(group, key).unwrap_or(false);
+
+    let warnings = (0..key_file.uint64(group, "Warnings").ok()?)
+        .map(|i| {
+            Some(Problem {
+                key: string(&format!("Warning{i}Key")),
+                message: string(&format!("Warning{i}"))?,
+                suggestion: string(&format!("Warning{i}Suggestion")),
+            })
+        })
+        .collect::<Option<Vec<_>>>()?;
+    let actions = (0..key_file.uint64(group, "Actions").unwrap_or(0))
+        .map(|i| string(&format!("Action{i}")))
+        .collect::<Option<Vec<_>>>()?;
+
+    let cached = CachedEntry {
+        modified: key_file.uint64(group, "Modified").ok()?,
+        target: PathBuf::from(string("Target")?),
+        name: string("Name"),
+        localized_name: string("LocalizedName"),
+        icon: string("Icon")?,
+        no_display: boolean("NoDisplay"),
+        hidden: boolean("Hidden"),
+        comment: string("Comment"),
+        generic_name: string("GenericName"),
+        categories: string("Categories"),
+        only_show_in: string("OnlyShowIn"),
+        not_show_in: string("NotShowIn"),
+        exec: string("Exec"),
+        note: string("Note"),
+        actions,
+        warnings,
+    };
+    Some((PathBuf::from(string("Path")?), cached))
+}
+
+fn write_entry(key_file: &glib::KeyFile, group: &str, path: &Path, cached: &CachedEntry) {
+    // Files with paths that can't be written in the cache are simply decoded every time
+    let (Some(path), Some(target)) = (path.to_str(), cached.target.to_str()) else {
+        return;
+    };
+
+    let set_optional = |key: &str, value: &Option<String>| {
+        if let Some(value) = value {
+            key_file.set_string(group, key, value);
+        }
+    };
+
+    key_file.set_string(group, "Path", path);
+    key_file.set_string(group, "Target", target);
+    key_file.set_uint64(group, "Modified", cached.modified);
+    set_optional("Name", &cached.name);
+    set_optional("LocalizedName", &cached.localized_name);
+    key_file.set_string(group, "Icon", &cached.icon);
+    key_file.set_boolean(group, "NoDisplay", cached.no_display);
+    key_file.set_boolean(group, "Hidden", cached.hidden);
+    set_optional("Comment", &cached.comment);
+    set_optional("GenericName", &cached.generic_name);
+    set_optional("Categories", &cached.categories);
+    set_optional("OnlyShowIn", &cached.only_show_in);
+    set_optional("NotShowIn", &cached.not_show_in);
+    set_optional("Exec", &cached.exec);
+    set_optional("Note", &cached.note);
+
+    key_file.set_uint64(group, "Actions", cached.actions.len() as u64);
+    for (i, action) in cached.actions.iter().enumerate() {
+        key_file.set_string(group, &format!("Action{i}"), action);
+    }
+
+    key_file.set_uint64(group, "Warnings", cached.warnings.len() as u64);
+    for (i, warning) in cached.warnings.iter().enumerate() {
+        key_file.set_string(group, &format!("Warning{i}"), &warning.message);
+        set_optional(&format!("Warning{i}Key"), &warning.key);
+        set_optional(&format!("Warning{i}Suggestion"), &warning.suggestion);
+    }
+}
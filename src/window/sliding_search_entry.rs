@@ -48,6 +48,9 @@ mod imp {
         #[template_child]
         pub button: TemplateChild<Button>,
 
+        #[template_child]
+        pub chips_box: TemplateChild<gtk::Box>,
+
         pub capture_widget: RefCell<Option<Widget>>,
         pub capture_widget_controller: RefCell<Option<EventControllerKey>>,
     }
@@ -100,7 +103,7 @@ mod imp {
 
         #[template_callback]
         fn on_eventcontrollerfocus_leave(&self, _event_controller_focus: &EventControllerFocus) {
-            if self.search_entry.text().is_empty() {
+            if self.search_entry.text().is_empty() && !self.has_chips() {
                 self.set_reveal(false);
             }
         }
@@ -125,6 +128,12 @@ mod imp {
         }
 
         pub fn set_reveal(&self, reveal: bool) {
+            // Active filters stay visible, only the text is cleared
+            if !reveal && self.has_chips() {
+                self.search_entry.set_text("");
+                return;
+            }
+
             self.revealer.set_reveal_child(reveal);
             if !reveal {
                 self.button
@@ -132,6 +141,10 @@ mod imp {
                 self.search_entry.set_text("");
             }
         }
+
+        pub fn has_chips(&self) -> bool {
+            self.chips_box.first_child().is_some()
+        }
     }
 }
 
@@ -154,6 +167,36 @@ impl SlidingSearchEntry {
         self.imp().revealer.reveals_child()
     }
 
+    pub fn clear_chips(&self) {
+        let chips_box = &self.imp().chips_box;
+        while let Some(chip) = chips_box.first_child() {
+            chips_box.remove(&chip);
+        }
+    }
+
+    /// Show a removable chip next to the search text, `on_remove` is called when the user
+    /// removes it
+    pub fn add_chip(&self, label: &str, on_remove: impl Fn() + 'static) {
+        let chip = gtk::Box::new(gtk::Orientation::Horizontal, 3);
+        chip.add_css_class("filter-chip");
+
+        let label = gtk::Label::new(Some(label));
+        chip.append(&label);
+
+        let remove_button = gtk::Button::from_icon_name("window-close-symbolic");
+        remove_button.add_css_class("flat");
+        remove_button.add_css_class("circular");
+        remove_button.set_valign(gtk::Align::Center);
+        remove_button.set_tooltip_text(Some("Remove Filter"));
+        remove_button.connect_clicked(move |_| on_remove());
+        chip.append(&remove_button);
+
+        self.imp().chips_box.append(&chip);
+        if !self.reveals_child() {
+            self.set_reveal(true);
+        }
+    }
+
     // The key capture widget implementation is heavily inspired by the same feature in the
     // SearchBar widget, although adapted and translated to Rust:
     // https://gitlab.gnome.org/GNOME/gtk/-/blob/main/gtk/gtksearchbar.c
@@ -0,0 +1,272 @@
+/*
+* Copyright © 2025 Alessandro Balducci
+*
+* This file is part of Desktop File Editor.
+* Desktop File Editor is free software: you can redistribute it and/or modify it under the terms of the
+* GNU General Public License as published by the Free Software Foundation,
+* either version 3 of the License, or (at your option) any later version.
+* Desktop File Editor is distributed in the hope that it will be useful, but WITHOUT ANY WARRANTY;
+* without even the implied warranty of MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.
+* See the GNU General Public License for more details.
+* You should have received a copy of the GNU General Public License along with Desktop File Editor. If not, see <https://www.gnu.org/licenses/>.
+*/
+
+use std::time::Duration;
+
+use adw::prelude::*;
+use gtk::glib::{self, clone, subclass::types::ObjectSubclassIsExt};
+
+use crate::{desktop_file_view::DesktopFileView, settings::settings};
+
+use super::DMWindow;
+
+/// How long the navigation view takes to slide to another page. The popover of a step waits for
+/// it, so that it points at the widget where it ends up
+const PAGE_TRANSITION: Duration = Duration::from_millis(300);
+
+/// What moves the tour past a step
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Advance {
+    /// The Next button of the popover
+    Next,
+    /// Opening an entry in the editor
+    EditorOpened,
+}
+
+struct Step {
+    title: &'static str,
+    text: &'static str,
+    advance: Advance,
+    /// The widget the popover points at, `None` while it is not on screen
+    target: fn(&DMWindow) -> Option<gtk::Widget>,
+}
+
+const STEPS: &[Step] = &[
+    Step {
+        title: "Filter the List",
+        text: "Show only the launchers with errors or warnings, or bring back the hidden ones. \
+               Filters can also be typed in the search, like category:Game",
+        advance: Advance::Next,
+        target: |window| Some(window.imp().filters_button.get().upcast()),
+    },
+    Step {
+        title: "Open an Entry",
+        text: "Click a launcher to open it in the editor",
+        advance: Advance::EditorOpened,
+        target: |window| Some(window.imp().entries_list.get().upcast()),
+    },
+    Step {
+        title: "Edit a Value",
+        text: "Change any value, the launcher preview and the list follow along. \
+               Other keys can be added with Add New Entry",
+        advance: Advance::Next,
+        target: |window| editor(window).map(|view| view.imp().desktop_entry_group.get().upcast()),
+    },
+    Step {
+        title: "Add a Locale",
+        text: "Translate the texts for another language, \
+               the menu next to the button picks the locale being edited",
+        advance: Advance::Next,
+        target: |window| editor(window).map(|view| view.imp().add_locale_button.get().upcast()),
+    },
+    Step {
+        title: "Save",
+        text: "Write the changes to the file with Ctrl+S, or discard them with Reset, Ctrl+Shift+R",
+        advance: Advance::Next,
+        target: |window| editor(window).map(|view| view.imp().save_button.get().upcast()),
+    },
+];
+
+/// A tour in progress
+#[derive(Debug, Default)]
+pub struct Tour {
+    step: usize,
+    popover: Option<gtk::Popover>,
+}
+
+fn editor(window: &DMWindow) -> Option<DesktopFileView> {
+    window
+        .imp()
+        .navigation_view
+        .visible_page()
+        .and_downcast::<DesktopFileView>()
+}
+
+/// Start the tour from its first step, replacing one already in progress
+pub fn start(window: &DMWindow) {
+    hide_popover(window);
+    window.imp().tour.replace(Some(Tour::default()));
+    show_step(window);
+}
+
+/// Start the tour if the user never went through it
+pub fn start_if_new(window: &DMWindow) {
+    if !settings().tour_completed() {
+        start(window);
+    }
+}
+
+/// Follow the user to another page, where the current step may point at a different widget or
+/// be completed by an editor being opened
+pub fn on_page_changed(window: &DMWindow) {
+    if window.imp().tour.borrow().is_none() {
+        return;
+    }
+    hide_popover(window);
+
+    if editor(window).is_some() {
+        let mut tour = window.imp().tour.borrow_mut();
+        if let Some(tour) = tour.as_mut() {
+            if STEPS[tour.step].advance == Advance::EditorOpened {
+                tour.step += 1;
+            }
+        }
+    }
+
+    glib::timeout_add_local_once(
+        PAGE_TRANSITION,
+        clone!(
+            #[weak]
+            window,
+            move || show_step(&window)
+        ),
+    );
+}
+
+fn hide_popover(window: &DMWindow) {
+    let popover = window
+        .imp()
+        .tour
+        .borrow_mut()
+        .as_mut()
+        .and_then(|tour| tour.popover.take());
+    if let Some(popover) = popover {
+        popover.popdown();
+        popover.unparent();
+    }
+}
+
+fn next_step(window: &DMWindow) {
+    hide_popover(window);
+    let finished = match window.imp().tour.borrow_mut().as_mut() {
+        Some(tour) => {
+            tour.step += 1;
+            tour.step >= STEPS.len()
+        }
+        None => return,
+    };
+
+    if finished {
+        finish(window);
+    } else {
+        show_step(window);
+    }
+}
+
+fn finish(window: &DMWindow) {
+    hide_popover(window);
+    window.imp().tour.replace(None);
+    settings().set_tour_completed(true);
+}
+
+/// Show the popover of the current step, unless its widget is not on screen. The step is shown
+/// later, once the user goes back to the page it is on
+fn show_step(window: &DMWindow) {
+    let Some(index) = window
+        .imp()
+        .tour
+        .borrow()
+        .as_ref()
+        .filter(|tour| tour.popover.is_none())
+        .map(|tour| tour.step)
+    else {
+        return;
+    };
+    let step = &STEPS[index];
+    let Some(target) = (step.target)(window).filter(|target| target.is_mapped()) else {
+        return;
+    };
+
+    let popover = step_popover(window, index, step);
+    popover.set_parent(&target);
+    popover.popup();
+    if let Some(tour) = window.imp().tour.borrow_mut().as_mut() {
+        tour.popover = Some(popover);
+    }
+}
+
+fn step_popover(window: &DMWindow, index: usize, step: &Step) -> gtk::Popover {
+    let content = gtk::Box::builder()
+        .orientation(gtk::Orientation::Vertical)
+        .spacing(6)
+        .margin_top(6)
+        .margin_bottom(6)
+        .margin_start(6)
+        .margin_end(6)
+        .build();
+
+    content.append(
+        &gtk::Label::builder()
+            .label(step.title)
+            .halign(gtk::Align::Start)
+            .css_classes(["heading"])
+            .build(),
+    );
+    content.append(
+        &gtk::Label::builder()
+            .label(step.text)
+            .halign(gtk::Align::Start)
+            .xalign(0.0)
+            .wrap(true)
+            .max_width_chars(36)
+            .build(),
+    );
+
+    let buttons = gtk::Box::builder().spacing(6).margin_top(6).build();
+    buttons.append(
+        &gtk::Label::builder()
+            .label(format!("{} of {}", index + 1, STEPS.len()))
+            .hexpand(true)
+            .halign(gtk::Align::Start)
+            .css_classes(["dim-label", "caption"])
+            .build(),
+    );
+
+    let skip_button = gtk::Button::builder()
+        .label("Skip Tour")
+        .css_classes(["flat"])
+        .build();
+    skip_button.connect_clicked(clone!(
+        #[weak]
+        window,
+        move |_| finish(&window)
+    ));
+    buttons.append(&skip_button);
+
+    if step.advance == Advance::Next {
+        let last = index + 1 == STEPS.len();
+        let next_button = gtk::Button::builder()
+            .label(if last { "Done" } else { "Next" })
+            .css_classes(["suggested-action"])
+            .build();
+        next_button.connect_clicked(clone!(
+            #[weak]
+            window,
+            move |_| next_step(&window)
+        ));
+        buttons.append(&next_button);
+    }
+    content.append(&buttons);
+
+    // The popover stays open while the user tries out what it explains
+    gtk::Popover::builder()
+        .child(&content)
+        .autohide(false)
+        .build()
+}
+
+/// Drop the tour without marking it as completed, so that it starts again next time
+pub fn abandon(window: &DMWindow) {
+    hide_popover(window);
+    window.imp().tour.replace(None);
+}
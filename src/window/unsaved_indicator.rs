@@ -0,0 +1,119 @@
+/*
+* Copyright © 2025 Alessandro Balducci
+*
+* This file is part of Desktop File Editor.
+* Desktop File Editor is free software: you can redistribute it and/or modify it under the terms of the
+* GNU General Public License as published by the Free Software Foundation,
+* either version 3 of the License, or (at your option) any later version.
+* Desktop File Editor is distributed in the hope that it will be useful, but WITHOUT ANY WARRANTY;
+* without even the implied warranty of MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.
+* See the GNU General Public License for more details.
+* You should have received a copy of the GNU General Public License along with Desktop File Editor. If not, see <https://www.gnu.org/licenses/>.
+*/
+
+use adw::prelude::*;
+use gtk::glib::{self, clone};
+
+use crate::desktop_file_view::DesktopFileView;
+use crate::util::display_path;
+
+use super::DMWindow;
+
+/// Header bar button of `editor` telling how many other editors of the window have unsaved
+/// changes, its popover lists them with a button to save each one. It is hidden while there are
+/// none, the editor showing it has its own save button
+pub fn unsaved_indicator(window: &DMWindow, editor: &DesktopFileView) -> gtk::MenuButton {
+    let filter = gtk::CustomFilter::new(clone!(
+        #[weak]
+        editor,
+        #[upgrade_or]
+        false,
+        move |item| item.downcast_ref::<DesktopFileView>() != Some(&editor)
+    ));
+    let others = gtk::FilterListModel::new(Some(window.unsaved_views()), Some(filter));
+
+    let list_box = gtk::ListBox::builder()
+        .selection_mode(gtk::SelectionMode::None)
+        .css_classes(["boxed-list"])
+        .build();
+    list_box.bind_model(Some(&others), |item| {
+        let desktop_file_view = item
+            .downcast_ref::<DesktopFileView>()
+            .expect("Item is not a DesktopFileView");
+        unsaved_row(desktop_file_view).upcast()
+    });
+
+    let heading = gtk::Label::builder()
+        .label("Unsaved Changes")
+        .halign(gtk::Align::Start)
+        .css_classes(["heading"])
+        .build();
+    let content = gtk::Box::builder()
+        .orientation(gtk::Orientation::Vertical)
+        .spacing(12)
+        .margin_top(6)
+        .margin_bottom(6)
+        .margin_start(6)
+        .margin_end(6)
+        .width_request(300)
+        .build();
+    content.append(&heading);
+    content.append(&list_box);
+
+    let button_content = adw::ButtonContent::builder()
+        .icon_name("document-save-symbolic")
+        .build();
+    let button = gtk::MenuButton::builder()
+        .child(&button_content)
+        .tooltip_text("Other Files With Unsaved Changes")
+        .popover(&gtk::Popover::builder().child(&content).build())
+        .build();
+
+    let update = clone!(
+        #[weak]
+        button,
+        move |others: &gtk::FilterListModel| {
+            let count = others.n_items();
+            button_content.set_label(&format!("{count} Unsaved"));
+            button.set_visible(count > 0);
+            if count == 0 {
+                button.popdown();
+            }
+        }
+    );
+    update(&others);
+    others.connect_items_changed(move |others, _, _, _| update(others));
+
+    button
+}
+
+fn unsaved_row(desktop_file_view: &DesktopFileView) -> adw::ActionRow {
+    let subtitle = if desktop_file_view.scratch() {
+        "Not saved to a file yet".to_string()
+    } else {
+        display_path(&desktop_file_view.path())
+            .to_string_lossy()
+            .into_owned()
+    };
+    let row = adw::ActionRow::builder()
+        .title(desktop_file_view.title())
+        .subtitle(subtitle)
+        .build();
+
+    let save_button = gtk::Button::builder()
+        .label("Save")
+        .valign(gtk::Align::Center)
+        .build();
+    save_button.connect_clicked(clone!(
+        #[weak]
+        desktop_file_view,
+        move |_| {
+            // The row goes away once the editor has no unsaved changes anymore
+            glib::spawn_future_local(async move {
+                desktop_file_view.save().await;
+            });
+        }
+    ));
+    row.add_suffix(&save_button);
+    row
+}
@@ -0,0 +1,172 @@
+/*
+* Copyright © 2025 Alessandro Balducci
+*
+* This file is part of Desktop File Editor.
+* Desktop File Editor is free software: you can redistribute it and/or modify it under the terms of the
+* GNU General Public License as published by the Free Software Foundation,
+* either version 3 of the License, or (at your option) any later version.
+* Desktop File Editor is distributed in the hope that it will be useful, but WITHOUT ANY WARRANTY;
+* without even the implied warranty of MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.
+* See the GNU General Public License for more details.
+* You should have received a copy of the GNU General Public License along with Desktop File Editor. If not, see <https://www.gnu.org/licenses/>.
+*/
+
+use std::rc::Rc;
+
+use adw::prelude::*;
+use gtk::glib::clone;
+
+use super::file_entry::{make_executable, FileEntry, Problem};
+
+/// Shows every check the entry failed along with how its command was resolved. `open_at` is
+/// called with the key of a problem to open the editor on it
+pub fn show_validity_dialog<F>(parent: &impl IsA<gtk::Widget>, entry: &FileEntry, open_at: F)
+where
+    F: Fn(&str) + 'static,
+{
+    let status = entry.validity_status();
+    let open_at = Rc::new(open_at);
+
+    let dialog = adw::Dialog::builder()
+        .title(entry.display_name().unwrap_or_default())
+        .content_width(480)
+        .build();
+    let page = adw::PreferencesPage::new();
+
+    let problems_group = adw::PreferencesGroup::builder().title("Problems").build();
+    let errors = status.errors();
+    let problems = errors
+        .iter()
+        .map(|problem| (problem, "dialog-error-symbolic", "error"))
+        .chain(
+            status
+                .warnings()
+                .iter()
+                .map(|problem| (problem, "dialog-warning-symbolic", "warning")),
+        );
+    for (problem, icon_name, css_class) in problems {
+        let row = problem_row(problem, icon_name, css_class);
+        if let (Some("Exec"), Some(path)) =
+            (problem.key.as_deref(), status.non_executable_command())
+        {
+            let path = path.to_path_buf();
+            let button = gtk::Button::builder()
+                .label("Make Executable")
+                .tooltip_text("Allow the file to be run as a program")
+                .valign(gtk::Align::Center)
+                .build();
+            button.connect_clicked(clone!(
+                #[weak]
+                dialog,
+                #[weak]
+                entry,
+                move |_| match make_executable(&path) {
+                    Ok(()) => {
+                        entry.recheck();
+                        dialog.close();
+                    }
+                    Err(e) => {
+                        let alert = adw::AlertDialog::builder()
+                            .heading("Failed to Make Executable")
+                            .body(e.message())
+                            .build();
+                        alert.add_response("close", "Close");
+                        alert.present(Some(&dialog));
+                    }
+                }
+            ));
+            row.add_suffix(&button);
+        }
+        if let Some(key) = problem.key.clone() {
+            let button = gtk::Button::builder()
+                .icon_name("document-edit-symbolic")
+                .tooltip_text("Open in Editor")
+                .valign(gtk::Align::Center)
+                .css_classes(["flat"])
+                .build();
+            button.connect_clicked(clone!(
+                #[weak]
+                dialog,
+                #[strong]
+                open_at,
+                move |_| {
+                    dialog.close();
+                    open_at(&key);
+                }
+            ));
+            row.add_suffix(&button);
+            row.set_activatable_widget(Some(&button));
+        }
+        problems_group.add(&row);
+    }
+    if errors.is_empty() && status.warnings().is_empty() {
+        let row = adw::ActionRow::builder().title("No problems found").build();
+        row.add_prefix(&gtk::Image::from_icon_name("emblem-ok-symbolic"));
+        problems_group.add(&row);
+    }
+    page.add(&problems_group);
+
+    let command_group = adw::PreferencesGroup::builder().title("Command").build();
+    let command = match (status.command(), entry.exec()) {
+        (Some(command), _) => command.to_string(),
+        (None, Some(exec)) => exec,
+        (None, None) => "There is no Exec key".to_string(),
+    };
+    command_group.add(&property_row("Parsed Command", &command));
+
+    let binary = status
+        .binary_path()
+        .map(|path| path.to_string_lossy().into_owned())
+        .unwrap_or_else(|| "Not found".to_string());
+    command_group.add(&property_row("Binary", &binary));
+
+    let search_paths = status
+        .search_paths()
+        .iter()
+        .map(|path| path.to_string_lossy())
+        .collect::<Vec<_>>()
+        .join("\n");
+    let search_paths = if search_paths.is_empty() {
+        "PATH is not set".to_string()
+    } else {
+        search_paths
+    };
+    command_group.add(&property_row("Searched Directories", &search_paths));
+    page.add(&command_group);
+
+    let toolbar_view = adw::ToolbarView::new();
+    toolbar_view.add_top_bar(&adw::HeaderBar::new());
+    toolbar_view.set_content(Some(&page));
+    dialog.set_child(Some(&toolbar_view));
+
+    dialog.present(Some(parent));
+}
+
+fn problem_row(problem: &Problem, icon_name: &str, css_class: &str) -> adw::ActionRow {
+    // The suggestion is more useful than the key, which the edit button leads to anyway
+    let subtitle = problem
+        .suggestion
+        .as_deref()
+        .or(problem.key.as_deref())
+        .unwrap_or_default();
+    let row = adw::ActionRow::builder()
+        .title(&problem.message)
+        .subtitle(subtitle)
+        .use_markup(false)
+        .build();
+
+    let icon = gtk::Image::from_icon_name(icon_name);
+    icon.add_css_class(css_class);
+    row.add_prefix(&icon);
+    row
+}
+
+fn property_row(title: &str, value: &str) -> adw::ActionRow {
+    adw::ActionRow::builder()
+        .title(title)
+        .subtitle(value)
+        .subtitle_selectable(true)
+        .use_markup(false)
+        .css_classes(["property"])
+        .build()
+}